@@ -0,0 +1,88 @@
+//! Constructing, calling, downcasting and dropping thin objects, against `Box<dyn Trait>` and
+//! `Box<dyn Any>`, for a small and a large payload. Run with `cargo bench --bench dispatch`.
+
+use std::any::Any;
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use thin_trait_objects::prelude::*;
+
+#[thin(any)]
+trait Shape: 'static {
+    fn area(&self) -> f32;
+}
+
+struct Small([f32; 2]);
+
+impl Shape for Small {
+    fn area(&self) -> f32 {
+        self.0[0] * self.0[1]
+    }
+}
+
+struct Large([f32; 64]);
+
+impl Shape for Large {
+    fn area(&self) -> f32 {
+        self.0[0] * self.0[63]
+    }
+}
+
+fn small() -> Small {
+    Small(black_box([2.0, 3.0]))
+}
+
+fn large() -> Large {
+    Large(black_box([2.0; 64]))
+}
+
+fn construct(c: &mut Criterion) {
+    let mut group = c.benchmark_group("construct");
+    group.bench_function("thin/small", |b| b.iter_with_large_drop(|| Thin::<dyn Shape>::new(small())));
+    group.bench_function("box/small", |b| b.iter_with_large_drop(|| Box::new(small()) as Box<dyn Shape>));
+    group.bench_function("thin/large", |b| b.iter_with_large_drop(|| Thin::<dyn Shape>::new(large())));
+    group.bench_function("box/large", |b| b.iter_with_large_drop(|| Box::new(large()) as Box<dyn Shape>));
+    group.finish();
+}
+
+fn dispatch(c: &mut Criterion) {
+    let thin = [Thin::<dyn Shape>::new(small()), Thin::<dyn Shape>::new(large())];
+    let boxed: [Box<dyn Shape>; 2] = [Box::new(small()), Box::new(large())];
+
+    let mut group = c.benchmark_group("dispatch");
+    for (i, payload) in ["small", "large"].into_iter().enumerate() {
+        group.bench_function(format!("thin/{}", payload), |b| b.iter(|| black_box(&thin[i]).area()));
+        group.bench_function(format!("box/{}", payload), |b| b.iter(|| black_box(&boxed[i]).area()));
+    }
+    group.finish();
+}
+
+fn downcast(c: &mut Criterion) {
+    let thin = Thin::<dyn Shape>::new(small());
+    let boxed: Box<dyn Any> = Box::new(small());
+
+    let mut group = c.benchmark_group("downcast");
+    group.bench_function("thin/hit", |b| b.iter(|| black_box(&thin).downcast_ref::<Small>().is_some()));
+    group.bench_function("box_any/hit", |b| b.iter(|| black_box(&boxed).downcast_ref::<Small>().is_some()));
+    group.bench_function("thin/miss", |b| b.iter(|| black_box(&thin).downcast_ref::<Large>().is_some()));
+    group.bench_function("box_any/miss", |b| b.iter(|| black_box(&boxed).downcast_ref::<Large>().is_some()));
+    group.bench_function("thin/by_value", |b| {
+        b.iter_batched(|| Thin::<dyn Shape>::new(small()), |thin| thin.downcast::<Small>().ok(), BatchSize::SmallInput)
+    });
+    group.bench_function("box_any/by_value", |b| {
+        b.iter_batched(|| Box::new(small()) as Box<dyn Any>, |boxed| boxed.downcast::<Small>().ok(), BatchSize::SmallInput)
+    });
+    group.finish();
+}
+
+fn drop_objects(c: &mut Criterion) {
+    let mut group = c.benchmark_group("drop");
+    group.bench_function("thin/small", |b| b.iter_batched(|| Thin::<dyn Shape>::new(small()), drop, BatchSize::SmallInput));
+    group.bench_function("box/small", |b| b.iter_batched(|| Box::new(small()) as Box<dyn Shape>, drop, BatchSize::SmallInput));
+    group.bench_function("thin/large", |b| b.iter_batched(|| Thin::<dyn Shape>::new(large()), drop, BatchSize::SmallInput));
+    group.bench_function("box/large", |b| b.iter_batched(|| Box::new(large()) as Box<dyn Shape>, drop, BatchSize::SmallInput));
+    group.finish();
+}
+
+criterion_group!(benches, construct, dispatch, downcast, drop_objects);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Creating and dropping a frame's worth of thin objects, with and without recycling their
+//! allocations. Run with `cargo bench --features recycle`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use thin_trait_objects::prelude::*;
+use thin_trait_objects::recycle;
+
+#[thin]
+trait Particle: 'static {
+    fn step(&mut self, dt: f32);
+}
+
+#[derive(Clone, Copy)]
+struct Point([f32; 4]);
+
+impl Particle for Point {
+    fn step(&mut self, dt: f32) {
+        self.0[0] += self.0[2] * dt;
+        self.0[1] += self.0[3] * dt;
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Trail([f32; 16]);
+
+impl Particle for Trail {
+    fn step(&mut self, dt: f32) {
+        self.0.rotate_right(2);
+        self.0[0] += dt;
+    }
+}
+
+const FRAME: usize = 1000;
+
+fn frame(particles: &mut Vec<Thin<dyn Particle>>) {
+    for i in 0..FRAME {
+        match i % 4 {
+            0 => particles.push(Thin::<dyn Particle>::new(Trail([0.0; 16]))),
+            _ => particles.push(Thin::<dyn Particle>::new(Point([0.0, 0.0, 1.0, 1.0]))),
+        }
+    }
+    for particle in particles.iter_mut() {
+        particle.step(1.0 / 60.0);
+    }
+    particles.clear();
+}
+
+fn churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("churn");
+    for (name, capacity) in [("box", 0), ("recycled", FRAME)] {
+        group.bench_function(name, |b| {
+            recycle::set_capacity(capacity);
+            let mut particles = Vec::with_capacity(FRAME);
+            b.iter(|| frame(&mut particles));
+        });
+    }
+    recycle::set_capacity(recycle::DEFAULT_CAPACITY);
+    group.finish();
+}
+
+criterion_group!(benches, churn);
+criterion_main!(benches);
@@ -1,103 +1,188 @@
 use std::any::{Any, TypeId};
+use std::ptr::NonNull;
 use crate::prelude::*;
+use crate::__private::*;
 
+// the implementation shared by every set of bounds, which `impl_thin_dyn_any!` delegates to, so
+// that e.g. `dyn Any` and `dyn Any + Send` objects of the same type share a vtable
+
+#[repr(C)]
+struct VTable {
+    drop: extern "C-unwind" fn(*mut ()),
+    type_id: TypeId,
+    #[cfg(feature = "type-name")]
+    type_name: fn() -> &'static str,
+}
+
+extern "C-unwind" fn drop<T>(ptr: *mut ()) {
+    unsafe { drop_bundle::<RawBundle<VTable, T>>(ptr) }
+}
+
+fn vtable<K: Any>() -> &'static VTable {
+    const {
+        &VTable {
+            drop: drop::<K>,
+            type_id: TypeId::of::<K>(),
+            #[cfg(feature = "type-name")]
+            type_name: ::std::any::type_name::<K>,
+        }
+    }
+}
+
+fn erase<U: ?Sized + SpecialAssoc + 'static, K: Any>(value: K) -> Thin<U> {
+    let bundle = RawBundle { vtable: vtable::<K>(), value };
+    unsafe { Thin::from_raw(alloc_bundle(bundle)) }
+}
+
+fn erased_type_id(ptr: NonNull<()>) -> TypeId {
+    let vtable = unsafe { *(ptr.as_ptr() as *const &'static VTable) };
+    vtable.type_id
+}
+
+fn vtable_of<U: ?Sized + SpecialAssoc>(thin: &Thin<U>) -> &'static VTable {
+    check_poison(thin.ptr.as_ptr());
+    unsafe { *(thin.ptr.as_ptr() as *const &'static VTable) }
+}
+
+fn is_type<U: ?Sized + SpecialAssoc, T: 'static>(thin: &Thin<U>) -> bool {
+    check_dropped_as::<Thin<U>>(thin.ptr.as_ptr(), "downcast");
+    vtable_of(thin).type_id == TypeId::of::<T>()
+}
+
+fn downcast<U: ?Sized + SpecialAssoc, T: 'static>(thin: Thin<U>) -> Option<T> {
+    if !is_type::<U, T>(&thin) {
+        return None;
+    }
+    let ptr = thin.ptr.as_ptr() as *mut RawBundle<VTable, T>;
+    ::std::mem::forget(thin);
+    let bundle = unsafe { Box::from_raw(ptr) };
+    Some(bundle.value)
+}
+
+fn downcast_thin<U: ?Sized + SpecialAssoc, T: 'static>(thin: Thin<U>) -> Result<ThinBox<T>, Thin<U>> {
+    if !is_type::<U, T>(&thin) {
+        return Err(thin);
+    }
+    let ptr = thin.ptr.as_ptr();
+    ::std::mem::forget(thin);
+    Ok(unsafe { ThinBox::from_raw(ptr) })
+}
+
+fn downcast_ref<U: ?Sized + SpecialAssoc, T: 'static>(thin: &Thin<U>) -> Option<&T> {
+    if !is_type::<U, T>(thin) {
+        return None;
+    }
+    let bundle = unsafe { &*(thin.ptr.as_ptr() as *const RawBundle<VTable, T>) };
+    Some(&bundle.value)
+}
+
+fn downcast_mut<U: ?Sized + SpecialAssoc, T: 'static>(thin: &mut Thin<U>) -> Option<&mut T> {
+    if !is_type::<U, T>(thin) {
+        return None;
+    }
+    let bundle = unsafe { &mut *(thin.ptr.as_ptr() as *mut RawBundle<VTable, T>) };
+    Some(&mut bundle.value)
+}
+
+// a generic impl for every set of bounds would overlap with those of `dyn StableAny`, so each gets
+// its own, which only delegates
 macro_rules! impl_thin_dyn_any {
     ($($bounds: path),*) => {
-        const _: () = {
-            #[repr(C)]
-            struct VTable {
-                drop: extern "C" fn(*mut ()),
-                type_id: TypeId,
+        unsafe impl<K: Any $(+ $bounds)*> ThinVTable<dyn Any $(+ $bounds)*, K> for Thin<dyn Any $(+ $bounds)*> {
+            fn vtable() -> *const () {
+                vtable::<K>() as *const VTable as *const ()
             }
+        }
 
-            extern "C" fn drop<T>(ptr: *mut ()) {
-                let bundle = ptr as *mut Bundle<T>;
-                let _ = unsafe { Box::from_raw(bundle) };
+        impl<K: Any $(+ $bounds)*> ThinExt<dyn Any $(+ $bounds)*, K> for Thin<dyn Any $(+ $bounds)*> {
+            fn new(value: K) -> Self {
+                erase(value)
             }
+        }
 
-            #[repr(C)]
-            struct Bundle<T> {
-                vtable: VTable,
-                value: T,
+        impl SpecialAssoc for dyn Any $(+ $bounds)* {
+            type Kind = Own;
+
+            fn same_type(a: NonNull<()>, b: NonNull<()>) -> bool {
+                erased_type_id(a) == erased_type_id(b)
+            }
+        }
+
+        unsafe impl ThinAny for dyn Any $(+ $bounds)* {
+            fn erased_type_id(ptr: NonNull<()>) -> TypeId {
+                erased_type_id(ptr)
+            }
+        }
+
+        impl Thin<dyn Any $(+ $bounds)*> {
+            /// Returns the `TypeId` of the concrete type, as `Any::type_id` would through a `Box<dyn Any>`
+            /// (rather than that of the `Thin` itself).
+            pub fn type_id(&self) -> TypeId {
+                vtable_of(self).type_id
+            }
+
+            /// Returns the name of the concrete type, as `std::any::type_name_of_val` would through a
+            /// `Box<dyn Any>`.
+            #[cfg(feature = "type-name")]
+            pub fn type_name(&self) -> &'static str {
+                (vtable_of(self).type_name)()
             }
 
-            impl<K: Any $(+ $bounds)*> ThinExt<dyn Any $(+ $bounds)*, K> for Thin<dyn Any $(+ $bounds)*> {
-                fn new(value: K) -> Self {
-                    let vtable = VTable { drop: drop::<K>, type_id: TypeId::of::<K>() };
-                    let bundle = Bundle { vtable, value };
-                    let ptr = Box::into_raw(Box::new(bundle));
-                    unsafe { Thin::from_raw(ptr as *mut ()) }
-                }
+            pub fn is<T: 'static>(&self) -> bool {
+                is_type::<_, T>(self)
             }
 
-            impl SpecialAssoc for dyn Any $(+ $bounds)* {
-                type Kind = Own;
+            pub fn downcast<T: 'static>(self) -> Option<T> {
+                downcast(self)
             }
 
-            impl Thin<dyn Any $(+ $bounds)*> {
-                unsafe fn downcast_unchecked<T>(self) -> T {
-                    let ptr = self.ptr.as_ptr() as *mut Bundle<T>;
-                    ::std::mem::forget(self);
-                    let bundle = unsafe { Box::from_raw(ptr) };
-                    bundle.value
-                }
-
-                unsafe fn downcast_ref_unchecked<T>(&self) -> &T {
-                    let ptr = self.ptr.as_ptr() as *const Bundle<T>;
-                    let bundle = unsafe { &*ptr };
-                    &bundle.value
-                }
-
-                unsafe fn downcast_mut_unchecked<T>(&mut self) -> &mut T {
-                    let ptr = self.ptr.as_ptr() as *mut Bundle<T>;
-                    let bundle = unsafe { &mut *ptr };
-                    &mut bundle.value
-                }
-
-                pub fn is<T: 'static>(&self) -> bool {
-                    let vtable = unsafe { &*(self.ptr.as_ptr() as *const VTable) };
-                    vtable.type_id == TypeId::of::<T>()
-                }
-
-                pub fn downcast<T: 'static>(self) -> Option<T> {
-                    if self.is::<T>() {
-                        let val: T = unsafe { self.downcast_unchecked::<T>() };
-                        return Some(val);
-                    }
-                    None
-                }
-
-                pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
-                    if self.is::<T>() {
-                        let val: &T = unsafe { self.downcast_ref_unchecked::<T>() };
-                        return Some(val);
-                    }
-                    None
-                }
-
-                pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
-                    if self.is::<T>() {
-                        let val: &mut T = unsafe { self.downcast_mut_unchecked::<T>() };
-                        return Some(val);
-                    }
-                    None
-                }
+            /// Downcasts to a `ThinBox<T>`, which keeps the value in its current allocation.
+            pub fn downcast_thin<T: 'static>(self) -> Result<ThinBox<T>, Self> {
+                downcast_thin(self)
             }
-        };
+
+            pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+                downcast_ref(self)
+            }
+
+            pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+                downcast_mut(self)
+            }
+        }
+
+        impl ThinMut<dyn Any $(+ $bounds)*> {
+            pub fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+                // only the value is borrowed mutably
+                downcast_mut(unsafe { self.as_thin_mut() })
+            }
+        }
     };
 }
 
 impl_thin_dyn_any!();
 impl_thin_dyn_any!(Send);
+impl_thin_dyn_any!(Sync);
 impl_thin_dyn_any!(Send, Sync);
 
+// values are only ever created from `K: Any + Send` and the like
+impl ThinSend for dyn Any + Send {}
+impl ThinSend for dyn Any + Send + Sync {}
+impl ThinSync for dyn Any + Sync {}
+impl ThinSync for dyn Any + Send + Sync {}
+
 #[cfg(test)]
 #[allow(dead_code)]
 mod tests {
-    use std::any::Any;
+    use std::any::{Any, TypeId};
 
     use crate::prelude::*;
 
+    #[test]
+    fn sync_only() {
+        let thin = Thin::<dyn Any + Sync>::new(8u8);
+        assert_eq!(thin.downcast_ref::<u8>(), Some(&8u8));
+    }
+
     #[test]
     fn downcasting() {
         let mut thin = Thin::<dyn Any>::new(8u8);
@@ -111,4 +196,34 @@ mod tests {
         let val = thin.downcast::<u8>().unwrap();
         assert_eq!(val, 9u8);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn downcast_thin() {
+        let thin = Thin::<dyn Any>::new(vec![1u8, 2, 3]);
+        let Err(thin) = thin.downcast_thin::<String>() else { panic!("downcast to the wrong type") };
+
+        let mut boxed = thin.downcast_thin::<Vec<u8>>().ok().unwrap();
+        boxed.push(4);
+        assert_eq!(*boxed, [1, 2, 3, 4]);
+        assert_eq!(boxed.into_inner(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn same_type() {
+        let a = Thin::<dyn Any>::new(8u8);
+        let b = Thin::<dyn Any>::new(9u8);
+        let c = Thin::<dyn Any>::new(8u16);
+
+        assert!(Thin::is_same_type(&a, &b));
+        assert!(!Thin::is_same_type(&a, &c));
+    }
+
+    #[test]
+    fn type_info() {
+        let thin = Thin::<dyn Any + Send>::new(8u8);
+        assert_eq!(thin.type_id(), TypeId::of::<u8>());
+        assert_ne!(thin.type_id(), TypeId::of::<Thin<dyn Any + Send>>());
+        #[cfg(feature = "type-name")]
+        assert_eq!(thin.type_name(), "u8");
+    }
+}
@@ -0,0 +1,290 @@
+//! Thin objects allocated together, for creating many at once without an allocation each.
+//!
+//! A [`ThinBatch`] carves bundles of any concrete type out of large, shared chunks, each of which is
+//! freed once the batch and every object in it have been dropped. [`Thin::new_many`] allocates a
+//! single chunk for the whole iterator:
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Shape: 'static {
+//!     fn area(&self) -> u32;
+//! }
+//!
+//! struct Square(u32);
+//! impl Shape for Square {
+//!     fn area(&self) -> u32 { self.0 * self.0 }
+//! }
+//!
+//! struct Rect(u32, u32);
+//! impl Shape for Rect {
+//!     fn area(&self) -> u32 { self.0 * self.1 }
+//! }
+//!
+//! let squares = Thin::<dyn Shape>::new_many((1..=3).map(Square));
+//! assert_eq!(squares.iter().map(|shape| shape.area()).sum::<u32>(), 14);
+//!
+//! let mut batch = ThinBatch::<dyn Shape>::new();
+//! let shapes = vec![batch.push(Square(2)), batch.push(Rect(2, 3))];
+//! assert_eq!(shapes[1].area(), 6);
+//! ```
+//!
+//! Only `#[thin]` traits without `any` can be batched, as downcasting by value frees the bundle on
+//! its own.
+
+use std::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+#[cfg(not(loom))]
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+
+// the chunks' reference counts are modelled with `RUSTFLAGS="--cfg loom" cargo test --lib batch`
+#[cfg(loom)]
+use loom::sync::atomic::{fence, AtomicUsize, Ordering};
+
+use crate::drop_panic::with_drop_policy;
+use crate::prelude::*;
+use crate::__private::*;
+
+/// The size of the chunks allocated by [`ThinBatch::new`], unless a bundle needs more.
+pub const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+// each bundle is preceded by a pointer to its chunk
+const CHUNK_PTR: usize = size_of::<*const Chunk>();
+
+#[repr(C)]
+struct Chunk {
+    // one for each live object in the chunk, and one while it's the batch's current chunk
+    refs: AtomicUsize,
+    layout: Layout,
+}
+
+/// Provides the vtable of batched `Thin<U>`s created from a `K`, whose drop slot leaves freeing the
+/// bundle to its chunk.
+///
+/// `#[thin]` implements this alongside [`ThinExt`], unless the trait is `any`.
+///
+/// # Safety
+/// As for [`ThinVTable`], and the vtable's drop slot must drop the bundle with
+/// [`drop_batched_bundle`].
+pub unsafe trait ThinBatched<U: ?Sized + SpecialAssoc + 'static, K> {
+    fn batched_vtable() -> *const ();
+}
+
+/// Allocates the bundles of `Thin<U>`s in shared chunks.
+pub struct ThinBatch<U: ?Sized + SpecialAssoc + 'static> {
+    chunk: Option<NonNull<Chunk>>,
+    // the offset of the first free byte in the chunk
+    cursor: usize,
+    chunk_size: usize,
+    phantom: PhantomData<fn() -> Thin<U>>,
+}
+
+// the batch only shares its chunks' reference counts, which are atomic
+unsafe impl<U: ?Sized + SpecialAssoc + 'static> Send for ThinBatch<U> {}
+unsafe impl<U: ?Sized + SpecialAssoc + 'static> Sync for ThinBatch<U> {}
+
+impl<U: ?Sized + SpecialAssoc + 'static> ThinBatch<U> {
+    pub const fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a batch which allocates chunks of (at least) `chunk_size` bytes.
+    pub const fn with_chunk_size(chunk_size: usize) -> Self {
+        ThinBatch { chunk: None, cursor: 0, chunk_size, phantom: PhantomData }
+    }
+
+    /// Creates a thin object from the given value, in the current chunk if it has room.
+    pub fn push<K>(&mut self, value: K) -> Thin<U> where Thin<U>: ThinBatched<U, K> {
+        let (chunk, bundle) = self.reserve(Layout::new::<RawBundle<(), K>>());
+        let vtable = <Thin<U> as ThinBatched<U, K>>::batched_vtable();
+        unsafe {
+            (bundle.sub(CHUNK_PTR) as *mut *const Chunk).write(chunk.as_ptr());
+            // the vtable's type is erased, as `()`
+            (bundle as *mut RawBundle<(), K>).write(RawBundle { vtable: &*vtable, value });
+            Thin::from_raw(bundle as *mut ())
+        }
+    }
+
+    // returns the chunk and the address of a bundle with the given layout, counting it as live
+    fn reserve(&mut self, layout: Layout) -> (NonNull<Chunk>, *mut u8) {
+        if let Some(chunk) = self.chunk {
+            let base = chunk.as_ptr() as *mut u8;
+            // aligns the bundle's address, as the chunk may be less aligned than the bundle
+            let start = (base as usize + self.cursor + CHUNK_PTR).next_multiple_of(layout.align()) - base as usize;
+            if start + layout.size() <= unsafe { chunk.as_ref() }.layout.size() {
+                self.cursor = start + layout.size();
+                unsafe { chunk.as_ref() }.refs.fetch_add(1, Ordering::Relaxed);
+                return (chunk, unsafe { base.add(start) });
+            }
+        }
+
+        let needed = size_of::<Chunk>() + CHUNK_PTR + layout.align() + layout.size();
+        self.new_chunk(needed.max(self.chunk_size));
+        self.reserve(layout)
+    }
+
+    fn new_chunk(&mut self, size: usize) {
+        let layout = Layout::from_size_align(size, align_of::<Chunk>()).expect("chunk too large");
+        let chunk = unsafe { alloc(layout) } as *mut Chunk;
+        let Some(chunk) = NonNull::new(chunk) else { handle_alloc_error(layout) };
+        unsafe { chunk.write(Chunk { refs: AtomicUsize::new(1), layout }) };
+        if let Some(old) = self.chunk.replace(chunk) {
+            unsafe { release(old) };
+        }
+        self.cursor = size_of::<Chunk>();
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> Default for ThinBatch<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> Drop for ThinBatch<U> {
+    fn drop(&mut self) {
+        if let Some(chunk) = self.chunk {
+            unsafe { release(chunk) };
+        }
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc<Kind = Own> + 'static> Thin<U> {
+    /// Creates thin objects from every value of `values`, in a single allocation.
+    pub fn new_many<K>(values: impl IntoIterator<Item = K>) -> Vec<Thin<U>> where Thin<U>: ThinBatched<U, K> {
+        let values = values.into_iter().collect::<Vec<_>>();
+        let layout = Layout::new::<RawBundle<(), K>>();
+        let stride = CHUNK_PTR + layout.align() + layout.size();
+        let mut batch = ThinBatch::with_chunk_size(size_of::<Chunk>() + values.len() * stride);
+        values.into_iter().map(|value| batch.push(value)).collect()
+    }
+}
+
+// frees the chunk if this was its last reference
+unsafe fn release(chunk: NonNull<Chunk>) {
+    let refs = &unsafe { chunk.as_ref() }.refs;
+    if refs.fetch_sub(1, Ordering::Release) == 1 {
+        // sees every write to the chunk made before the other references were released
+        fence(Ordering::Acquire);
+        let layout = unsafe { chunk.as_ref() }.layout;
+        unsafe { dealloc(chunk.as_ptr() as *mut u8, layout) };
+    }
+}
+
+/// Drops the bundle (of type `B`) at `ptr` in place, according to the current
+/// [`DropPanicPolicy`](crate::drop_panic::DropPanicPolicy), then releases its chunk.
+///
+/// # Safety
+/// `ptr` must have been created by [`ThinBatch::push`], and not be used afterwards.
+#[doc(hidden)]
+pub unsafe fn drop_batched_bundle<B>(ptr: *mut ()) {
+    crate::poison::check_dropped(ptr, "double drop");
+    // poisoned bundles are leaked, along with their chunk
+    #[cfg(feature = "debug-poison")]
+    with_drop_policy(|| unsafe { crate::poison::poison::<B>(ptr) });
+    #[cfg(not(feature = "debug-poison"))]
+    {
+        let chunk = unsafe { *((ptr as *const u8).sub(CHUNK_PTR) as *const NonNull<Chunk>) };
+        with_drop_policy(|| unsafe { std::ptr::drop_in_place(ptr as *mut B) });
+        unsafe { release(chunk) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[thin]
+    trait Animal: 'static {
+        fn legs(&self) -> u32;
+    }
+
+    struct Bird(#[allow(dead_code)] Rc<()>);
+    impl Animal for Bird {
+        fn legs(&self) -> u32 {
+            2
+        }
+    }
+
+    #[repr(align(64))]
+    struct Spider([u8; 100]);
+    impl Animal for Spider {
+        fn legs(&self) -> u32 {
+            self.0.len() as u32 / 12
+        }
+    }
+
+    // loom's atomics can only be used within a model
+    #[cfg(not(loom))]
+    #[test]
+    fn batched_bundles() {
+        let rc = Rc::new(());
+        let birds = Thin::<dyn Animal>::new_many((0..10).map(|_| Bird(rc.clone())));
+        assert_eq!(birds.iter().map(|bird| bird.legs()).sum::<u32>(), 20);
+        assert_eq!(Rc::strong_count(&rc), 11);
+        drop(birds);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        // mixed types and alignments, spilling over into new chunks
+        let mut batch = ThinBatch::<dyn Animal>::with_chunk_size(256);
+        let mut animals = (0..20)
+            .map(|n| match n % 2 {
+                0 => batch.push(Bird(rc.clone())),
+                _ => batch.push(Spider([0; 100])),
+            })
+            .collect::<Vec<_>>();
+        drop(batch);
+        assert!(animals.iter().all(|animal| (animal.ptr.as_ptr() as usize).is_multiple_of(align_of::<*const ()>())));
+        assert!(animals.iter().skip(1).step_by(2).all(|spider| (spider.ptr.as_ptr() as usize).is_multiple_of(64)));
+        assert_eq!(animals.iter().map(|animal| animal.legs()).sum::<u32>(), 100);
+
+        // the chunks outlive the batch until every object in them is dropped
+        animals.truncate(5);
+        assert_eq!(Rc::strong_count(&rc), 4);
+        let first = animals.swap_remove(0);
+        drop(animals);
+        assert_eq!(first.legs(), 2);
+        drop(first);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn chunk_released_across_threads() {
+        use std::sync::Arc;
+        use loom::sync::atomic::AtomicUsize;
+
+        #[thin]
+        trait Counted: Send + Sync + 'static {
+            fn id(&self) -> usize;
+        }
+
+        struct Tracked(usize, Arc<AtomicUsize>);
+        impl Counted for Tracked {
+            fn id(&self) -> usize {
+                self.0
+            }
+        }
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.1.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        // the batch and its two objects release the chunk in every order
+        loom::model(|| {
+            let drops = Arc::new(AtomicUsize::new(0));
+            let mut batch = ThinBatch::<dyn Counted>::new();
+            let objects = [batch.push(Tracked(0, drops.clone())), batch.push(Tracked(1, drops.clone()))];
+            let threads = objects.map(|object| loom::thread::spawn(move || object.id()));
+            drop(batch);
+            let ids = threads.map(|thread| thread.join().unwrap());
+            assert_eq!(ids, [0, 1]);
+            assert_eq!(drops.load(Ordering::Relaxed), 2);
+        });
+    }
+}
@@ -0,0 +1,91 @@
+//! `ThinBox<T>`, a typed handle to the allocation of a downcast thin object.
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+use crate::__private::*;
+
+/// An owned `T`, still in the allocation of the thin object it was downcast from.
+///
+/// Created by `downcast_thin` on `Thin<dyn Any>` and `Thin<dyn StableAny>`, which (unlike `downcast`)
+/// don't move the value out of its allocation, making them cheap for large values.
+///
+/// ```rust
+/// use std::any::Any;
+/// use thin_trait_objects::prelude::*;
+///
+/// # #[cfg(not(feature = "any"))] fn main() {}
+/// # #[cfg(feature = "any")]
+/// # fn main() {
+/// let thin = Thin::<dyn Any>::new([7u8; 4096]);
+/// let mut boxed = thin.downcast_thin::<[u8; 4096]>().ok().unwrap();
+/// boxed[0] = 8;
+/// assert_eq!(boxed[..2], [8, 7]);
+/// # }
+/// ```
+#[repr(transparent)]
+pub struct ThinBox<T> {
+    ptr: NonNull<()>,
+    phantom: PhantomData<T>,
+}
+
+unsafe impl<T: Send> Send for ThinBox<T> {}
+unsafe impl<T: Sync> Sync for ThinBox<T> {}
+
+impl<T> ThinBox<T> {
+    /// # Safety
+    /// `ptr` must point to the bundle of an owned thin object, created with `Box::into_raw`,
+    /// whose value is a `T`.
+    #[cfg_attr(not(any(feature = "any", feature = "stable_any")), allow(dead_code))]
+    pub(crate) unsafe fn from_raw(ptr: *mut ()) -> Self {
+        ThinBox { ptr: NonNull::new(ptr).unwrap(), phantom: PhantomData }
+    }
+
+    fn bundle(&self) -> *mut RawBundle<(), T> {
+        check_poison(self.ptr.as_ptr());
+        self.ptr.as_ptr() as *mut RawBundle<(), T>
+    }
+
+    /// Moves the value out of its allocation.
+    pub fn into_inner(self) -> T {
+        let ptr = self.bundle();
+        std::mem::forget(self);
+        let bundle = unsafe { Box::from_raw(ptr) };
+        bundle.value
+    }
+}
+
+impl<T> Deref for ThinBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &(*self.bundle()).value }
+    }
+}
+
+impl<T> DerefMut for ThinBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut (*self.bundle()).value }
+    }
+}
+
+impl<T> Drop for ThinBox<T> {
+    fn drop(&mut self) {
+        // goes through the same path as dropping the thin object would
+        unsafe { drop_bundle::<RawBundle<(), T>>(self.ptr.as_ptr()) }
+    }
+}
+
+impl<T: Debug> Debug for ThinBox<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Display> Display for ThinBox<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,155 @@
+//! `Display`, `Debug` and `Error` for the thin objects of `#[thin]` traits with those supertraits.
+//!
+//! A trait's supertraits must be implemented by `Thin<dyn Trait>` as well, so `#[thin]` bridges these
+//! std traits through vtable slots of their own, following the method slots, and implements
+//! [`DisplaySlot`], [`DebugSlot`] and [`ErrorSlot`] for `dyn Trait`, from which `Thin<dyn Trait>`
+//! and `ThinMut<dyn Trait>` get the std impls (which a downstream crate couldn't write itself).
+//!
+//! ```rust
+//! use std::error::Error;
+//! use std::fmt;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait PluginError: Error + Send + Sync + 'static {
+//!     fn code(&self) -> u32;
+//! }
+//!
+//! #[derive(Debug)]
+//! struct Timeout(u32);
+//!
+//! impl fmt::Display for Timeout {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         write!(f, "timed out after {}ms", self.0)
+//!     }
+//! }
+//!
+//! impl Error for Timeout {}
+//!
+//! impl PluginError for Timeout {
+//!     fn code(&self) -> u32 { 408 }
+//! }
+//!
+//! let err: Box<dyn Error + Send + Sync> = Box::new(Thin::<dyn PluginError>::new(Timeout(50)));
+//! assert_eq!(err.to_string(), "timed out after 50ms");
+//! assert_eq!(format!("{:?}", err), "Timeout(50)");
+//! ```
+
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
+
+use crate::prelude::*;
+
+/// An object type whose vtables have a slot for the `Display` impl of the concrete type.
+pub trait DisplaySlot: SpecialAssoc {
+    #[doc(hidden)]
+    fn fmt_display(thin: &Thin<Self>, f: &mut Formatter<'_>) -> fmt::Result;
+}
+
+/// An object type whose vtables have a slot for the `Debug` impl of the concrete type.
+pub trait DebugSlot: SpecialAssoc {
+    #[doc(hidden)]
+    fn fmt_debug(thin: &Thin<Self>, f: &mut Formatter<'_>) -> fmt::Result;
+}
+
+/// An object type whose vtables have a slot for `Error::source` of the concrete type.
+pub trait ErrorSlot: DisplaySlot + DebugSlot {
+    #[doc(hidden)]
+    fn error_source(thin: &Thin<Self>) -> Option<&(dyn Error + 'static)>;
+}
+
+impl<U: ?Sized + DisplaySlot> Display for Thin<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        U::fmt_display(self, f)
+    }
+}
+
+impl<U: ?Sized + DebugSlot> Debug for Thin<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        U::fmt_debug(self, f)
+    }
+}
+
+impl<U: ?Sized + ErrorSlot> Error for Thin<U> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        U::error_source(self)
+    }
+}
+
+impl<U: ?Sized + DisplaySlot + 'static> Display for ThinMut<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        U::fmt_display(self, f)
+    }
+}
+
+impl<U: ?Sized + DebugSlot + 'static> Debug for ThinMut<U> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        U::fmt_debug(self, f)
+    }
+}
+
+impl<U: ?Sized + ErrorSlot + 'static> Error for ThinMut<U> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        U::error_source(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[thin]
+    trait Labelled: Display + Debug + 'static {
+        fn id(&self) -> u32;
+    }
+
+    #[thin]
+    trait Failure: Error + 'static {}
+
+    #[derive(Debug)]
+    struct Label(u32);
+
+    impl Display for Label {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "#{}", self.0)
+        }
+    }
+
+    impl Labelled for Label {
+        fn id(&self) -> u32 {
+            self.0
+        }
+    }
+
+    #[derive(Debug)]
+    struct Wrapped(std::io::Error);
+
+    impl Display for Wrapped {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("wrapped")
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    impl Failure for Wrapped {}
+
+    #[test]
+    fn bridges() {
+        let label = Thin::<dyn Labelled>::new(Label(7));
+        assert_eq!((format!("{}", label), format!("{:?}", label), label.id()), ("#7".into(), "Label(7)".into(), 7));
+        // the formatter's flags reach the concrete type
+        assert_eq!(format!("{:#?}", Thin::<dyn Labelled>::new(Label(1))), "Label(\n    1,\n)");
+
+        let failure = Thin::<dyn Failure>::new(Wrapped(std::io::ErrorKind::NotFound.into()));
+        assert_eq!(failure.to_string(), "wrapped");
+        assert_eq!(failure.source().unwrap().to_string(), "entity not found");
+        // `Box<dyn Failure>` isn't an `Error`, so it doesn't implement `Failure` either
+        let boxed: Box<dyn Error> = Box::new(failure);
+        assert!(boxed.source().is_some());
+    }
+}
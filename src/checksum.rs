@@ -0,0 +1,168 @@
+//! Vtable checksums, enabled by the `debug-checksum` feature.
+//!
+//! `#[thin]` vtables store a checksum of their size and method slots after their size, which is
+//! verified before every dispatch, so that a corrupted object (or one created by code built without
+//! the feature) aborts with a diagnostic at the first call, rather than jumping to a garbage slot.
+//!
+//! Vtables are built in const contexts, which can't read the addresses of their slots, so they're
+//! created with [`VTableChecksum::UNSEALED`], and [`RawVTableHeader::seal`](crate::raw::RawVTableHeader::seal)
+//! makes a copy with the checksum filled in the first time each is used.
+
+/// The checksum slot of a `#[thin]` vtable. Zero-sized without the `debug-checksum` feature.
+#[doc(hidden)]
+#[repr(transparent)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VTableChecksum(#[cfg(feature = "debug-checksum")] usize);
+
+/// Whether `#[thin]` vtables have a checksum slot.
+#[doc(hidden)]
+pub const VTABLE_CHECKSUM: bool = cfg!(feature = "debug-checksum");
+
+// vtables which claim to be larger are taken to be corrupt, rather than read to their end
+#[cfg(feature = "debug-checksum")]
+const MAX_SIZE: usize = 1 << 16;
+
+// any bijective mix will do, as long as small sizes don't map to plausible pointers
+#[cfg(feature = "debug-checksum")]
+const fn mix(word: u64) -> u64 {
+    let mixed = (word ^ 0x9E37_79B9_7F4A_7C15).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    mixed ^ (mixed >> 31)
+}
+
+impl VTableChecksum {
+    /// The checksum slot of a vtable which hasn't been sealed yet, which never verifies.
+    #[cfg(feature = "debug-checksum")]
+    pub const UNSEALED: VTableChecksum = VTableChecksum(0);
+    #[cfg(not(feature = "debug-checksum"))]
+    pub const UNSEALED: VTableChecksum = VTableChecksum();
+
+    /// The checksum of the vtable at `vtable`, whose method slots extend `size` bytes: a mix of the
+    /// size and of every slot following the checksum.
+    ///
+    /// # Safety
+    /// `vtable` must point to at least `size` readable bytes, starting with a `RawVTableHeader`.
+    #[cfg(feature = "debug-checksum")]
+    pub(crate) unsafe fn of(vtable: *const (), size: usize) -> Self {
+        let words = vtable as *const usize;
+        let mut checksum = mix(size as u64);
+        for index in 3..size / size_of::<usize>() {
+            checksum = mix(checksum ^ unsafe { *words.add(index) } as u64);
+        }
+        // so that it's never `UNSEALED`
+        VTableChecksum(checksum as usize | 1)
+    }
+
+    /// Aborts if this isn't the checksum of the vtable at `vtable`, whose method slots extend
+    /// `size` bytes. Does nothing without the `debug-checksum` feature.
+    ///
+    /// # Safety
+    /// `vtable` must point to a vtable starting with a `RawVTableHeader`, whose size is `size`.
+    #[inline(always)]
+    pub unsafe fn verify(self, vtable: *const (), size: usize) {
+        #[cfg(feature = "debug-checksum")]
+        {
+            let word = size_of::<usize>();
+            let plausible = size.is_multiple_of(word) && (3 * word..=MAX_SIZE).contains(&size);
+            if !plausible || self != unsafe { VTableChecksum::of(vtable, size) } {
+                eprintln!(
+                    "the vtable at {:p} failed its checksum, and is corrupt, has a mismatched layout or wasn't sealed, aborting",
+                    vtable,
+                );
+                std::process::abort();
+            }
+        }
+        #[cfg(not(feature = "debug-checksum"))]
+        let _ = (vtable, size);
+    }
+}
+
+#[cfg(all(test, feature = "debug-checksum"))]
+mod tests {
+    use std::process::Command;
+
+    use crate::prelude::*;
+    use super::VTableChecksum;
+
+    #[thin]
+    trait Foo: 'static {
+        fn get(&self) -> u8;
+        fn double(&self) -> u8;
+    }
+
+    impl Foo for u8 {
+        fn get(&self) -> u8 {
+            *self
+        }
+        fn double(&self) -> u8 {
+            *self * 2
+        }
+    }
+
+    #[test]
+    fn checksum_slot() {
+        let thin = Thin::<dyn Foo>::new(8u8);
+        assert_eq!(<dyn Foo as SlotLayout>::SLOTS, ["drop", "size", "checksum", "get", "double"]);
+        assert_eq!(thin.get(), 8);
+
+        let size = thin.vtable_size().unwrap();
+        let words = unsafe { *(thin.ptr.as_ptr() as *const *const usize) };
+        let checksum = unsafe { *(words.add(2) as *const VTableChecksum) };
+        assert_eq!(checksum, unsafe { VTableChecksum::of(words as *const (), size) });
+        assert_ne!(checksum, VTableChecksum::UNSEALED);
+
+        // objects of the same type share the sealed copy of their vtable
+        assert!(Thin::same_vtable(&thin, &Thin::<dyn Foo>::new(9u8)));
+    }
+
+    // runs `test` in a child process, which is expected to abort with the checksum's diagnostic
+    fn assert_aborts(test: &str) {
+        let output = Command::new(std::env::current_exe().unwrap())
+            .args([test, "--exact", "--nocapture", "--test-threads=1"])
+            .env("THIN_CHECKSUM_ABORT", "1")
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("failed its checksum"));
+    }
+
+    #[test]
+    fn flipped_slot() {
+        let thin = Thin::<dyn Foo>::new(8u8);
+        if std::env::var_os("THIN_CHECKSUM_ABORT").is_none() {
+            return assert_aborts("checksum::tests::flipped_slot");
+        }
+
+        // a copy of the vtable whose `get` slot is `double`'s
+        let vtable = unsafe { *(thin.ptr.as_ptr() as *const *const usize) };
+        let mut copy = unsafe { std::slice::from_raw_parts(vtable, <dyn Foo as SlotLayout>::SLOTS.len()) }.to_vec();
+        copy[<dyn Foo>::SLOT_GET] = copy[<dyn Foo>::SLOT_DOUBLE];
+        unsafe { *(thin.ptr.as_ptr() as *mut *const usize) = copy.as_ptr() };
+        thin.get();
+    }
+
+    #[test]
+    fn unsealed() {
+        if std::env::var_os("THIN_CHECKSUM_ABORT").is_none() {
+            return assert_aborts("checksum::tests::unsealed");
+        }
+
+        #[repr(C)]
+        struct FooVTable {
+            header: crate::raw::RawVTableHeader,
+            get: extern "C" fn(RefSelf<'_>) -> u8,
+        }
+
+        extern "C-unwind" fn drop_u8(ptr: *mut ()) {
+            drop(unsafe { Box::from_raw(ptr as *mut (*const FooVTable, u8)) });
+        }
+
+        extern "C" fn get(recv: RefSelf<'_>) -> u8 {
+            unsafe { (*(recv.ptr as *const (*const FooVTable, u8))).1 }
+        }
+
+        static VTABLE: FooVTable = FooVTable { header: crate::raw::RawVTableHeader::new(drop_u8, size_of::<FooVTable>()), get };
+        let object = Box::into_raw(Box::new((&VTABLE as *const FooVTable, 8u8)));
+        let thin = unsafe { Thin::<dyn Foo>::from_raw(object.cast()) };
+        thin.get();
+    }
+}
@@ -0,0 +1,409 @@
+//! Generation of C headers for `#[thin]` traits, from the `<dyn Trait>::DESCRIPTOR` constants the
+//! macro emits, so that headers can be regenerated (e.g. in a build script) rather than drift from
+//! the Rust definitions.
+//!
+//! For each trait `Foo`, the header declares:
+//! - `Foo`, an opaque type: thin objects are `Foo *`, pointing to a bundle which starts with a
+//!   pointer to the vtable,
+//! - `FooVTable`, the `repr(C)` vtable struct, whose `size` is the extent of the method slots, and
+//!   whose `rust` pointer to the Rust-ABI entries (see `#[thin(dual_abi)]`) foreign code leaves null,
+//! - `Foo_vtable`, `Foo_drop` and a `Foo_{method}` wrapper for every method,
+//! - `foo_object_free`, if the trait is `#[thin(c_free)]`.
+//!
+//! ```rust
+//! use thin_trait_objects::codegen::render_header;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Counter: 'static {
+//!     fn add(&mut self, n: u32) -> u64;
+//! }
+//!
+//! let header = render_header(&[<dyn Counter>::DESCRIPTOR], "COUNTER_H");
+//! assert!(header.contains("uint64_t (*add)(Counter *self, uint32_t n);"));
+//! assert!(header.contains("static inline uint64_t Counter_add(Counter *self, uint32_t n) {"));
+//! ```
+//!
+//! Types are translated by name: primitives to their `<stdint.h>` equivalents, references and raw
+//! pointers to C pointers, `()` and `!` to `void`, the types of the [`ffi`](crate::ffi) module to
+//! structs declared by the header (whose elements are untyped, as C has no generics), and anything
+//! else to the last segment of its path, which must then be declared elsewhere.
+//!
+//! For generators targeting other languages, [`TraitDescriptor::to_json`] describes a trait as JSON.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use crate::checksum::VTABLE_CHECKSUM;
+
+/// Describes the vtable of a `#[thin]` trait, as its `<dyn Trait>::DESCRIPTOR`.
+#[derive(Clone, Copy, Debug)]
+pub struct TraitDescriptor {
+    pub name: &'static str,
+    /// The version of the crate declaring the trait.
+    pub version: &'static str,
+    /// The methods, in vtable order.
+    pub methods: &'static [MethodDescriptor],
+    /// Whether the trait is `#[thin(any)]`, and its vtables end with a `TypeId`.
+    pub any: bool,
+    /// Whether the trait is `#[thin(extension)]`, and its vtables have an extension pointer after
+    /// the methods (see the `extension` module).
+    pub extension: bool,
+    /// Whether the trait is `#[thin(dual_abi)]`, and its vtables end with a pointer to the Rust-ABI
+    /// entries, which is null in vtables created by foreign code.
+    pub dual_abi: bool,
+    /// The name of the `extern "C"` destructor exported by `#[thin(c_free)]`, if any.
+    pub free: Option<&'static str>,
+}
+
+/// Describes a method of a `#[thin]` trait.
+///
+/// Types are recorded as written in the trait, with their tokens separated by spaces.
+#[derive(Clone, Copy, Debug)]
+pub struct MethodDescriptor {
+    pub name: &'static str,
+    pub mut_receiver: bool,
+    /// The names and types of the arguments, other than the receiver.
+    pub args: &'static [(&'static str, &'static str)],
+    pub ret: &'static str,
+}
+
+impl TraitDescriptor {
+    /// Describes the trait as a JSON object, for binding generators in other languages:
+    ///
+    /// ```json
+    /// {
+    ///   "name": "Counter",
+    ///   "version": "0.1.0",
+    ///   "abi_hash": "9a3f...",
+    ///   "any": false,
+    ///   "extension": false,
+    ///   "dual_abi": false,
+    ///   "free": null,
+    ///   "checksum": false,
+    ///   "methods": [
+    ///     {"name": "add", "mut_receiver": true, "args": [{"name": "n", "type": "u32"}], "ret": "u64"}
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Types are recorded as in [`MethodDescriptor`]. `abi_hash` is the trait's `trait_abi_hash`,
+    /// formatted as 16 hex digits, and is only present with the `stable_any` feature. `checksum` is
+    /// whether vtables have a checksum slot after the size (see `debug-checksum`). `free` is the name
+    /// of the `#[thin(c_free)]` destructor, or `null`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        write!(out, "{{\"name\":{},\"version\":{}", json_str(self.name), json_str(self.version)).unwrap();
+        #[cfg(feature = "stable_any")]
+        write!(out, ",\"abi_hash\":\"{:016x}\"", self.abi_hash()).unwrap();
+        write!(out, ",\"any\":{},\"extension\":{},\"dual_abi\":{}", self.any, self.extension, self.dual_abi).unwrap();
+        match self.free {
+            Some(free) => write!(out, ",\"free\":{}", json_str(free)).unwrap(),
+            None => out.push_str(",\"free\":null"),
+        }
+        write!(out, ",\"checksum\":{},\"methods\":[", VTABLE_CHECKSUM).unwrap();
+        for (i, method) in self.methods.iter().enumerate() {
+            let args = method.args.iter().map(|(name, ty)| {
+                format!("{{\"name\":{},\"type\":{}}}", json_str(name), json_str(ty))
+            }).collect::<Vec<_>>();
+            write!(
+                out, "{}{{\"name\":{},\"mut_receiver\":{},\"args\":[{}],\"ret\":{}}}",
+                if i == 0 { "" } else { "," }, json_str(method.name), method.mut_receiver, args.join(","), json_str(method.ret),
+            ).unwrap();
+        }
+        out.push_str("]}");
+        out
+    }
+
+    // the same as `trait_abi_hash`, from the names of the slots
+    #[cfg(feature = "stable_any")]
+    fn abi_hash(&self) -> u64 {
+        let header: &[&str] = match VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum"],
+            false => &["drop", "size"],
+        };
+        let slots = header.iter().copied().chain(self.methods.iter().map(|method| method.name)).collect::<Vec<_>>();
+        crate::protocol::hash_slots(&slots)
+    }
+}
+
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Renders a header for `traits` to `path`, with an include guard derived from the file name.
+pub fn write_header(traits: &[TraitDescriptor], path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let stem = path.file_name().and_then(|name| name.to_str()).unwrap_or("thin_traits.h");
+    let guard = stem.chars().map(|c| match c.is_ascii_alphanumeric() {
+        true => c.to_ascii_uppercase(),
+        false => '_',
+    }).collect::<String>();
+    std::fs::write(path, render_header(traits, &guard))
+}
+
+/// Renders a header for `traits`, with the given include guard.
+pub fn render_header(traits: &[TraitDescriptor], guard: &str) -> String {
+    let mut out = String::new();
+    writeln!(out, "// Generated by thin_trait_objects::codegen. Do not edit.").unwrap();
+    writeln!(out, "#ifndef {guard}\n#define {guard}\n").unwrap();
+    writeln!(out, "#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n").unwrap();
+
+    for (name, fields) in FFI_TYPES {
+        let used = traits.iter().flat_map(|descriptor| descriptor.methods).any(|method| {
+            std::iter::once(method.ret).chain(method.args.iter().map(|(_, ty)| *ty)).any(|ty| ty.split_whitespace().any(|token| token == name))
+        });
+        if used {
+            writeln!(out, "typedef struct {name} {{\n{fields}}} {name};\n").unwrap();
+        }
+    }
+
+    // declared up front, since vtables may refer to each other's handles
+    for descriptor in traits {
+        writeln!(out, "typedef struct {0} {0};", descriptor.name).unwrap();
+    }
+    for descriptor in traits {
+        render_trait(&mut out, descriptor);
+    }
+
+    writeln!(out, "#endif // {guard}").unwrap();
+    out
+}
+
+fn render_trait(out: &mut String, descriptor: &TraitDescriptor) {
+    let name = descriptor.name;
+    let params = |method: &MethodDescriptor| {
+        let recv = match method.mut_receiver {
+            true => format!("{name} *self"),
+            false => format!("const {name} *self"),
+        };
+        let args = method.args.iter().map(|(arg, ty)| format!("{} {arg}", c_type(ty)));
+        std::iter::once(recv).chain(args).collect::<Vec<_>>().join(", ")
+    };
+
+    writeln!(out, "\ntypedef struct {name}VTable {{").unwrap();
+    writeln!(out, "    void (*drop)({name} *self);").unwrap();
+    // fields following the methods are only read from vtables whose methods end at the same offset
+    let size = match descriptor.extension || descriptor.any || descriptor.dual_abi {
+        true => "the offset of the first field after the methods",
+        false => "sizeof the vtable",
+    };
+    writeln!(out, "    size_t size; /* {size} */").unwrap();
+    if VTABLE_CHECKSUM {
+        writeln!(out, "    size_t checksum;").unwrap();
+    }
+    for method in descriptor.methods {
+        writeln!(out, "    {} (*{})({});", c_type(method.ret), method.name, params(method)).unwrap();
+    }
+    if descriptor.extension {
+        writeln!(out, "    const void *extension;").unwrap();
+    }
+    if descriptor.any {
+        writeln!(out, "    unsigned char type_id[{}];", size_of::<std::any::TypeId>()).unwrap();
+    }
+    if descriptor.dual_abi {
+        writeln!(out, "    const void *rust; /* NULL */").unwrap();
+    }
+    writeln!(out, "}} {name}VTable;\n").unwrap();
+
+    writeln!(out, "static inline const {name}VTable *{name}_vtable(const {name} *self) {{").unwrap();
+    writeln!(out, "    return *(const {name}VTable *const *)self;\n}}\n").unwrap();
+    writeln!(out, "static inline void {name}_drop({name} *self) {{").unwrap();
+    writeln!(out, "    {name}_vtable(self)->drop(self);\n}}\n").unwrap();
+    if let Some(free) = descriptor.free {
+        writeln!(out, "void {free}(void *ptr);\n").unwrap();
+    }
+    for method in descriptor.methods {
+        let ret = c_type(method.ret);
+        let args = std::iter::once("self").chain(method.args.iter().map(|(arg, _)| *arg)).collect::<Vec<_>>();
+        let call = format!("{name}_vtable(self)->{}({})", method.name, args.join(", "));
+        writeln!(out, "static inline {ret} {name}_{}({}) {{", method.name, params(method)).unwrap();
+        match ret.as_str() {
+            "void" => writeln!(out, "    {call};\n}}\n").unwrap(),
+            _ => writeln!(out, "    return {call};\n}}\n").unwrap(),
+        }
+    }
+}
+
+/// The C declarations of the types of the `ffi` module, which share their layout.
+const FFI_TYPES: [(&str, &str); 4] = [
+    ("ThinStr", "    const uint8_t *ptr;\n    size_t len;\n"),
+    ("ThinString", "    uint8_t *ptr;\n    size_t len;\n    size_t cap;\n    void (*free)(uint8_t *ptr, size_t len, size_t cap);\n"),
+    ("ThinSlice", "    const void *ptr;\n    size_t len;\n"),
+    ("ThinVecFfi", "    void *ptr;\n    size_t len;\n    size_t cap;\n    void (*free)(void *ptr, size_t len, size_t cap);\n"),
+];
+
+/// Translates a Rust type, with its tokens separated by spaces, to C.
+fn c_type(ty: &str) -> String {
+    let tokens = ty.split_whitespace().collect::<Vec<_>>();
+    c_type_of(&tokens)
+}
+
+fn c_type_of(tokens: &[&str]) -> String {
+    match tokens {
+        ["&", rest @ ..] => {
+            let rest = match rest {
+                [lifetime, rest @ ..] if lifetime.starts_with('\'') => rest,
+                rest => rest,
+            };
+            match rest {
+                ["mut", pointee @ ..] => pointer(pointee, true),
+                pointee => pointer(pointee, false),
+            }
+        }
+        ["*", "const", pointee @ ..] => pointer(pointee, false),
+        ["*", "mut", pointee @ ..] => pointer(pointee, true),
+        ["()"] | ["(", ")"] | ["!"] => "void".to_string(),
+        // thin objects are a single opaque pointer
+        ["Thin", "<", ..] => "void *".to_string(),
+        path => {
+            let head = path.split(|token| *token == "<").next().unwrap_or_default();
+            if let Some((name, _)) = FFI_TYPES.iter().find(|(name, _)| head.last() == Some(name)) {
+                return name.to_string();
+            }
+            let name = path.rsplit(|token| *token == "::").next().unwrap_or_default().concat();
+            match name.as_str() {
+                "u8" => "uint8_t",
+                "u16" => "uint16_t",
+                "u32" => "uint32_t",
+                "u64" => "uint64_t",
+                "i8" => "int8_t",
+                "i16" => "int16_t",
+                "i32" => "int32_t",
+                "i64" => "int64_t",
+                "usize" => "size_t",
+                "isize" => "ptrdiff_t",
+                "f32" => "float",
+                "f64" => "double",
+                "bool" => "bool",
+                "c_char" => "char",
+                "c_int" => "int",
+                "c_uint" => "unsigned int",
+                "c_long" => "long",
+                "c_ulong" => "unsigned long",
+                "c_void" => "void",
+                _ => return name,
+            }.to_string()
+        }
+    }
+}
+
+fn pointer(pointee: &[&str], mutable: bool) -> String {
+    match mutable {
+        true => format!("{} *", c_type_of(pointee)),
+        false => format!("const {} *", c_type_of(pointee)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[thin(c_free)]
+    trait Shape: 'static {
+        fn area(&self) -> f64;
+        fn scale(&mut self, by: f32);
+        fn name(&self, buf: &mut u8, len: usize) -> bool;
+    }
+
+    #[thin]
+    trait Label: 'static {
+        fn rename(&mut self, name: ThinStr<'_>) -> ThinString;
+    }
+
+    struct Circle(#[allow(dead_code)] std::rc::Rc<()>);
+
+    impl Shape for Circle {
+        fn area(&self) -> f64 {
+            3.0
+        }
+        fn scale(&mut self, _by: f32) {}
+        fn name(&self, _buf: &mut u8, _len: usize) -> bool {
+            false
+        }
+    }
+
+    unsafe extern "C" {
+        fn shape_object_free(ptr: *mut std::ffi::c_void);
+    }
+
+    #[test]
+    fn c_free() {
+        let rc = std::rc::Rc::new(());
+        let ptr = Thin::<dyn Shape>::new(Circle(rc.clone())).into_c_void();
+        assert_eq!(unsafe { Thin::<dyn Shape>::from_c_void(ptr) }.area(), 3.0);
+
+        let ptr = Thin::<dyn Shape>::new(Circle(rc.clone())).into_c_void();
+        unsafe { shape_object_free(ptr) };
+        unsafe { shape_object_free(std::ptr::null_mut()) };
+        assert_eq!(std::rc::Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(c_type("u32"), "uint32_t");
+        assert_eq!(c_type("& 'a mut u8"), "uint8_t *");
+        assert_eq!(c_type("* const std :: ffi :: c_char"), "const char *");
+        assert_eq!(c_type("()"), "void");
+        assert_eq!(c_type("Point"), "Point");
+        assert_eq!(c_type("Thin < dyn Iterator < Item = u8 > >"), "void *");
+        assert_eq!(c_type("ThinSlice < '_ , u32 >"), "ThinSlice");
+        assert_eq!(c_type("thin_trait_objects :: ffi :: ThinString"), "ThinString");
+    }
+
+    #[test]
+    fn headers() {
+        let header = render_header(&[<dyn Shape>::DESCRIPTOR], "SHAPE_H");
+        assert!(header.starts_with("// Generated by thin_trait_objects::codegen. Do not edit.\n#ifndef SHAPE_H\n"));
+        assert!(header.contains("typedef struct Shape Shape;"));
+        assert!(header.contains("    double (*area)(const Shape *self);\n    void (*scale)(Shape *self, float by);\n"));
+        assert!(header.contains("    bool (*name)(const Shape *self, uint8_t * buf, size_t len);\n"));
+        assert!(header.contains("size_t len);\n} ShapeVTable;"));
+        assert!(header.contains("static inline void Shape_scale(Shape *self, float by) {\n    Shape_vtable(self)->scale(self, by);\n}"));
+        assert!(header.contains("    return Shape_vtable(self)->area(self);"));
+        assert!(header.contains("void shape_object_free(void *ptr);"));
+        assert!(header.ends_with("#endif // SHAPE_H\n"));
+        assert!(!header.contains("ThinStr"));
+
+        // only the `ffi` types in use are declared
+        let label = render_header(&[<dyn Label>::DESCRIPTOR], "LABEL_H");
+        assert!(label.contains("#include <stdint.h>\n\ntypedef struct ThinStr {\n    const uint8_t *ptr;\n    size_t len;\n} ThinStr;\n"));
+        assert!(label.contains("    void (*free)(uint8_t *ptr, size_t len, size_t cap);\n} ThinString;\n\ntypedef struct Label Label;"));
+        assert!(!label.contains("ThinSlice"));
+        assert!(label.contains("    ThinString (*rename)(Label *self, ThinStr name);\n"));
+
+        let dir = std::env::temp_dir().join(format!("thin_codegen_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shape.h");
+        write_header(&[<dyn Shape>::DESCRIPTOR], &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), header);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn json() {
+        let json = <dyn Shape>::DESCRIPTOR.to_json();
+        assert!(json.starts_with(&format!("{{\"name\":\"Shape\",\"version\":\"{}\",", env!("CARGO_PKG_VERSION"))));
+        #[cfg(feature = "stable_any")]
+        assert!(json.contains(&format!("\"abi_hash\":\"{:016x}\"", crate::protocol::trait_abi_hash::<dyn Shape>())));
+        assert!(json.contains(",\"free\":\"shape_object_free\","));
+        assert!(json.contains("{\"name\":\"area\",\"mut_receiver\":false,\"args\":[],\"ret\":\"f64\"},"));
+        assert!(json.ends_with(
+            "{\"name\":\"name\",\"mut_receiver\":false,\"args\":[{\"name\":\"buf\",\"type\":\"& mut u8\"},{\"name\":\"len\",\"type\":\"usize\"}],\"ret\":\"bool\"}]}"
+        ));
+        assert_eq!(json_str("a\"b\\c\n"), "\"a\\\"b\\\\c\\u000a\"");
+    }
+}
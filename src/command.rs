@@ -0,0 +1,206 @@
+//! `CommandQueue`, for deferring operations on a world of type `W`, and optionally recording them
+//! to be replayed later.
+//!
+//! Commands are `Thin<dyn Command<W>>`s, so a queue of them is a `Vec` of single pointers rather
+//! than of `Box<dyn FnOnce>` fat pointers, and commands may be created by plugins.
+//!
+//! ```rust
+//! use thin_trait_objects::command::{replay, CommandQueue};
+//!
+//! let mut world = Vec::new();
+//! let mut queue = CommandQueue::new();
+//! queue.start_recording();
+//! queue.push(|world: &mut Vec<u32>| world.push(1));
+//! queue.push(|world: &mut Vec<u32>| world.push(world.len() as u32 + 1));
+//! queue.execute_all(&mut world);
+//! assert_eq!(world, [1, 2]);
+//!
+//! let mut log = queue.stop_recording();
+//! replay(&mut log, &mut world);
+//! assert_eq!(world, [1, 2, 1, 4]);
+//! ```
+
+use crate::prelude::*;
+use crate::__private::*;
+
+/// An operation on a world of type `W`, which may be executed more than once if recorded.
+pub trait Command<W: 'static>: 'static {
+    fn execute(&mut self, world: &mut W);
+}
+
+impl<W: 'static, F: FnMut(&mut W) + 'static> Command<W> for F {
+    fn execute(&mut self, world: &mut W) {
+        self(world)
+    }
+}
+
+const _: () = {
+    #[repr(C)]
+    pub struct VTable<W: 'static> {
+        drop: extern "C-unwind" fn(*mut ()),
+        execute: extern "C" fn(MutSelf<'_>, &mut W),
+    }
+
+    extern "C-unwind" fn drop<W: 'static, T>(ptr: *mut ()) {
+        unsafe { drop_bundle::<RawBundle<VTable<W>, T>>(ptr) }
+    }
+
+    extern "C" fn execute<W: 'static, T: Command<W>>(recv: MutSelf<'_>, world: &mut W) {
+        let value = unsafe { RawBundle::<VTable<W>, T>::value_mut(recv) };
+        T::execute(value, world)
+    }
+
+    impl<W: 'static> SpecialAssoc for dyn Command<W> {
+        type Kind = Own;
+    }
+
+    impl<W: 'static> SlotLayout for dyn Command<W> {
+        const SLOTS: &'static [&'static str] = &["drop", "execute"];
+        type VTable = VTable<W>;
+    }
+
+    impl<W: 'static, K: Command<W>> ThinExt<dyn Command<W>, K> for Thin<dyn Command<W>> {
+        fn new(value: K) -> Self {
+            let vtable: &'static VTable<W> = const {
+                &VTable {
+                    drop: drop::<W, K>,
+                    execute: execute::<W, K>,
+                }
+            };
+            let bundle = RawBundle { vtable, value };
+            let ptr = Box::into_raw(Box::new(bundle));
+            unsafe { Thin::from_raw(ptr as *mut ()) }
+        }
+    }
+
+    impl<W: 'static> Command<W> for Thin<dyn Command<W>> {
+        fn execute(&mut self, world: &mut W) {
+            let shim = {
+                check_poison(self.ptr.as_ptr());
+                let vtable = unsafe { *(self.ptr.as_ptr() as *const &'static VTable<W>) };
+                vtable.execute
+            };
+            shim(MutSelf::new(self), world)
+        }
+    }
+};
+
+/// Executes every command in `commands` in order, without consuming them.
+pub fn replay<W: 'static>(commands: &mut ThinVec<dyn Command<W>>, world: &mut W) {
+    for command in commands.as_mut_slice() {
+        command.execute(world);
+    }
+}
+
+/// A queue of commands, executed in the order they were pushed.
+pub struct CommandQueue<W: 'static> {
+    pending: ThinVec<dyn Command<W>>,
+    // executed commands, while recording
+    recording: Option<ThinVec<dyn Command<W>>>,
+}
+
+impl<W: 'static> Default for CommandQueue<W> {
+    fn default() -> Self {
+        CommandQueue::new()
+    }
+}
+
+impl<W: 'static> CommandQueue<W> {
+    pub const fn new() -> Self {
+        CommandQueue { pending: ThinVec::new(), recording: None }
+    }
+
+    pub fn push<C: Command<W>>(&mut self, command: C) {
+        self.pending.push(Thin::<dyn Command<W>>::new(command))
+    }
+
+    pub fn push_thin(&mut self, command: Thin<dyn Command<W>>) {
+        self.pending.push(command)
+    }
+
+    /// Returns the number of commands waiting to be executed.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Executes and removes every pending command in order, recording them if recording.
+    pub fn execute_all(&mut self, world: &mut W) {
+        for mut command in self.pending.drain(..) {
+            command.execute(world);
+            if let Some(recording) = &mut self.recording {
+                recording.push(command);
+            }
+        }
+    }
+
+    /// Removes the pending commands without executing them.
+    pub fn drain(&mut self) -> ThinVec<dyn Command<W>> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Starts recording executed commands, discarding any previous recording.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(ThinVec::new());
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Stops recording, returning the commands executed since recording started, for [`replay`].
+    pub fn stop_recording(&mut self) -> ThinVec<dyn Command<W>> {
+        self.recording.take().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Append(&'static str);
+
+    impl Command<String> for Append {
+        fn execute(&mut self, world: &mut String) {
+            world.push_str(self.0);
+        }
+    }
+
+    #[test]
+    fn deferred_execution() {
+        let mut world = String::new();
+        let mut queue = CommandQueue::new();
+        queue.push(Append("a"));
+        queue.push(|world: &mut String| world.make_ascii_uppercase());
+        queue.push(Append("b"));
+        assert_eq!(queue.len(), 3);
+        assert!(world.is_empty());
+
+        queue.execute_all(&mut world);
+        assert_eq!(world, "Ab");
+        assert!(queue.is_empty());
+        assert_eq!(queue.stop_recording().len(), 0);
+    }
+
+    #[test]
+    fn draining_and_recording() {
+        let mut world = String::new();
+        let mut queue = CommandQueue::new();
+        queue.push(Append("skipped"));
+        let drained = queue.drain();
+        assert_eq!(drained.len(), 1);
+
+        queue.start_recording();
+        queue.push(Append("x"));
+        queue.push_thin(Thin::<dyn Command<String>>::new(Append("y")));
+        queue.execute_all(&mut world);
+        let mut log = queue.stop_recording();
+        assert!(!queue.is_recording());
+
+        replay(&mut log, &mut world);
+        assert_eq!(world, "xyxy");
+    }
+}
@@ -0,0 +1,254 @@
+//! ECS-style storage of components, keyed by their `StableTypeId`.
+//!
+//! Each component type gets a column: a sparse set mapping entities to indices into a dense
+//! `Vec` of its values. A column's values live in a single `Thin<dyn StableAny>`, so accessing a
+//! column costs one id comparison, and iterating over it touches contiguous memory.
+//!
+//! ```rust
+//! use thin_trait_objects::components::Components;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[derive(StableAny, Debug, PartialEq)]
+//! struct Position(i32, i32);
+//!
+//! #[derive(StableAny, Debug, PartialEq)]
+//! struct Velocity(i32, i32);
+//!
+//! let mut world = Components::new();
+//! let a = world.spawn();
+//! let b = world.spawn();
+//! world.insert(a, Position(0, 0));
+//! world.insert(a, Velocity(1, 2));
+//! world.insert(b, Position(5, 5));
+//!
+//! for (entity, velocity) in world.iter::<Velocity>().map(|(e, v)| (e, (v.0, v.1))).collect::<Vec<_>>() {
+//!     let position = world.get_mut::<Position>(entity).unwrap();
+//!     position.0 += velocity.0;
+//!     position.1 += velocity.1;
+//! }
+//!
+//! assert_eq!(world.get::<Position>(a), Some(&Position(1, 2)));
+//! assert_eq!(world.get::<Position>(b), Some(&Position(5, 5)));
+//! ```
+
+use std::collections::HashMap;
+
+use crate::prelude::*;
+
+/// An entity, to which at most one component of each type may be attached.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Entity(u32);
+
+impl Entity {
+    /// The index of this entity, unique within the `Components` which spawned it.
+    pub fn index(self) -> u32 {
+        self.0
+    }
+}
+
+/// The dense values of a column.
+#[derive(StableAny)]
+struct Dense<T>(Vec<T>);
+
+const VACANT: u32 = u32::MAX;
+
+struct Column {
+    // indexed by entity, `VACANT` where the entity has no component
+    sparse: Vec<u32>,
+    entities: Vec<Entity>,
+    // a `Dense<T>`
+    values: Thin<dyn StableAny>,
+    // swap-removes (and drops) the value at an index, without knowing its type
+    swap_remove: fn(&mut Thin<dyn StableAny>, usize),
+}
+
+impl Column {
+    fn new<T: UUID>() -> Self {
+        Column {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            values: Thin::<dyn StableAny>::new(Dense::<T>(Vec::new())),
+            swap_remove: |values, index| {
+                values.downcast_mut::<Dense<T>>().unwrap().0.swap_remove(index);
+            },
+        }
+    }
+
+    fn dense_index(&self, entity: Entity) -> Option<usize> {
+        match self.sparse.get(entity.0 as usize) {
+            Some(&index) if index != VACANT => Some(index as usize),
+            _ => None,
+        }
+    }
+
+    fn values<T: UUID>(&self) -> &Vec<T> {
+        &self.values.downcast_ref::<Dense<T>>().expect("column of the wrong type").0
+    }
+
+    fn values_mut<T: UUID>(&mut self) -> &mut Vec<T> {
+        &mut self.values.downcast_mut::<Dense<T>>().expect("column of the wrong type").0
+    }
+
+    /// Removes the entity's index, and moves the last entity into its place, returning the index
+    /// which must be swap-removed from the values.
+    fn unlink(&mut self, entity: Entity) -> Option<usize> {
+        let index = self.dense_index(entity)?;
+        self.sparse[entity.0 as usize] = VACANT;
+        self.entities.swap_remove(index);
+        if let Some(&moved) = self.entities.get(index) {
+            self.sparse[moved.0 as usize] = index as u32;
+        }
+        Some(index)
+    }
+}
+
+/// Components of any `StableAny` type, attached to entities.
+#[derive(Default)]
+pub struct Components {
+    columns: HashMap<StableTypeId, Column>,
+    next: u32,
+}
+
+impl Components {
+    pub fn new() -> Self {
+        Components::default()
+    }
+
+    /// Creates a new entity, with no components.
+    pub fn spawn(&mut self) -> Entity {
+        let entity = Entity(self.next);
+        // entities index the sparse arrays, so must fit in a `usize` on 16-bit targets
+        self.next = self.next.checked_add(1).filter(|&next| next != VACANT && usize::try_from(next).is_ok()).expect("too many entities");
+        entity
+    }
+
+    /// Removes every component of `entity`.
+    pub fn despawn(&mut self, entity: Entity) {
+        for column in self.columns.values_mut() {
+            if let Some(index) = column.unlink(entity) {
+                (column.swap_remove)(&mut column.values, index);
+            }
+        }
+    }
+
+    /// Attaches `value` to `entity`, returning the component of the same type it replaces, if any.
+    pub fn insert<T: UUID>(&mut self, entity: Entity, value: T) -> Option<T> {
+        let column = self.columns.entry(T::UUID).or_insert_with(Column::new::<T>);
+        if let Some(index) = column.dense_index(entity) {
+            return Some(std::mem::replace(&mut column.values_mut::<T>()[index], value));
+        }
+
+        let slot = entity.0 as usize;
+        if column.sparse.len() <= slot {
+            column.sparse.resize(slot + 1, VACANT);
+        }
+        column.sparse[slot] = column.entities.len() as u32;
+        column.entities.push(entity);
+        column.values_mut::<T>().push(value);
+        None
+    }
+
+    /// Detaches and returns the `T` attached to `entity`, if any.
+    pub fn remove<T: UUID>(&mut self, entity: Entity) -> Option<T> {
+        let column = self.columns.get_mut(&T::UUID)?;
+        let index = column.unlink(entity)?;
+        Some(column.values_mut::<T>().swap_remove(index))
+    }
+
+    pub fn contains<T: UUID>(&self, entity: Entity) -> bool {
+        self.columns.get(&T::UUID).is_some_and(|column| column.dense_index(entity).is_some())
+    }
+
+    pub fn get<T: UUID>(&self, entity: Entity) -> Option<&T> {
+        let column = self.columns.get(&T::UUID)?;
+        let index = column.dense_index(entity)?;
+        Some(&column.values::<T>()[index])
+    }
+
+    pub fn get_mut<T: UUID>(&mut self, entity: Entity) -> Option<&mut T> {
+        let column = self.columns.get_mut(&T::UUID)?;
+        let index = column.dense_index(entity)?;
+        Some(&mut column.values_mut::<T>()[index])
+    }
+
+    /// Returns the number of entities with a `T` attached.
+    pub fn count<T: UUID>(&self) -> usize {
+        self.columns.get(&T::UUID).map_or(0, |column| column.entities.len())
+    }
+
+    /// Iterates over every `T`, along with the entity it's attached to, in no particular order.
+    pub fn iter<T: UUID + 'static>(&self) -> impl Iterator<Item = (Entity, &T)> {
+        let column = self.columns.get(&T::UUID);
+        let entities = column.map_or(&[][..], |column| &column.entities[..]);
+        let values = column.map_or(&[][..], |column| &column.values::<T>()[..]);
+        entities.iter().copied().zip(values)
+    }
+
+    /// Iterates mutably over every `T`, along with the entity it's attached to, in no particular order.
+    pub fn iter_mut<T: UUID + 'static>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        let (entities, values) = match self.columns.get_mut(&T::UUID) {
+            Some(column) => {
+                let values = &mut column.values.downcast_mut::<Dense<T>>().expect("column of the wrong type").0[..];
+                (&column.entities[..], values)
+            }
+            None => (&[][..], &mut [][..]),
+        };
+        entities.iter().copied().zip(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(StableAny, Debug, PartialEq)]
+    struct Health(u32);
+
+    #[derive(StableAny, Debug, PartialEq)]
+    struct Name(&'static str);
+
+    #[test]
+    fn insert_and_remove() {
+        let mut world = Components::new();
+        let entities = (0..4).map(|_| world.spawn()).collect::<Vec<_>>();
+        for (i, &entity) in entities.iter().enumerate() {
+            assert_eq!(world.insert(entity, Health(i as u32)), None);
+        }
+        world.insert(entities[1], Name("one"));
+
+        assert_eq!(world.insert(entities[2], Health(20)), Some(Health(2)));
+        assert_eq!(world.remove::<Health>(entities[0]), Some(Health(0)));
+        assert_eq!(world.remove::<Health>(entities[0]), None);
+
+        // the last value was moved into the removed one's place
+        assert_eq!(world.get::<Health>(entities[3]), Some(&Health(3)));
+        assert_eq!(world.get::<Health>(entities[2]), Some(&Health(20)));
+        assert_eq!(world.count::<Health>(), 3);
+        assert!(world.contains::<Name>(entities[1]));
+        assert!(!world.contains::<Name>(entities[2]));
+    }
+
+    #[test]
+    fn despawn_and_iterate() {
+        let mut world = Components::new();
+        let a = world.spawn();
+        let b = world.spawn();
+        let c = world.spawn();
+        world.insert(a, Health(1));
+        world.insert(b, Health(2));
+        world.insert(c, Health(3));
+        world.insert(b, Name("b"));
+
+        world.despawn(b);
+        assert!(world.get::<Name>(b).is_none());
+        assert_eq!(world.count::<Name>(), 0);
+
+        for (_, health) in world.iter_mut::<Health>() {
+            health.0 *= 10;
+        }
+        let mut healths = world.iter::<Health>().map(|(entity, health)| (entity, health.0)).collect::<Vec<_>>();
+        healths.sort();
+        assert_eq!(healths, [(a, 10), (c, 30)]);
+        assert_eq!(world.iter::<u8>().count(), 0);
+    }
+}
@@ -0,0 +1,155 @@
+//! `ThinCow<T>`, a shared thin object which is cloned when first mutated.
+//!
+//! Clones of a [`ThinCow`] share the same thin object, and [`ThinCow::to_mut`] only deep-clones it
+//! (through the [`CloneFn`] of its concrete type) if it's still shared, which suits values read far
+//! more often than they're written, such as the configuration of plugins:
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Config: Send + Sync + 'static {
+//!     fn volume(&self) -> u8;
+//!     fn set_volume(&mut self, volume: u8);
+//! }
+//!
+//! #[derive(Clone)]
+//! struct Audio { volume: u8 }
+//!
+//! impl Config for Audio {
+//!     fn volume(&self) -> u8 { self.volume }
+//!     fn set_volume(&mut self, volume: u8) { self.volume = volume; }
+//! }
+//!
+//! let defaults = ThinCow::<dyn Config>::new(Audio { volume: 5 });
+//! let mut config = defaults.clone();
+//! assert!(config.ptr_eq(&defaults));
+//!
+//! config.to_mut().set_volume(9);
+//! assert_eq!((defaults.volume(), config.volume()), (5, 9));
+//! assert!(!config.ptr_eq(&defaults));
+//! ```
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::prelude::*;
+
+/// A reference-counted thin object, along with the function cloning its value.
+pub struct ThinCow<U: ?Sized + SpecialAssoc + 'static> {
+    shared: Arc<Thin<U>>,
+    clone: CloneFn<U>,
+}
+
+impl<U: ?Sized + SpecialAssoc<Kind = Own> + 'static> ThinCow<U> {
+    /// Creates a shared thin object from the given cloneable value.
+    pub fn new<K>(value: K) -> Self where Thin<U>: ThinExt<U, K> + ThinClone<U, K> {
+        ThinCow { shared: Arc::new(<Thin<U> as ThinExt<U, K>>::new(value)), clone: Thin::<U>::clone_fn::<K>() }
+    }
+
+    /// Shares an existing thin object.
+    ///
+    /// # Safety
+    /// `clone` must have been created for the concrete type of the erased value, as for
+    /// [`Thin::clone_with`].
+    pub unsafe fn from_thin(thin: Thin<U>, clone: CloneFn<U>) -> Self {
+        ThinCow { shared: Arc::new(thin), clone }
+    }
+
+    /// Mutably borrows the thin object, first cloning it if it's shared with other `ThinCow`s.
+    ///
+    /// The borrow can't replace the object, which would leave the `CloneFn` of another type.
+    pub fn to_mut(&mut self) -> Thin<&mut U> {
+        if Arc::get_mut(&mut self.shared).is_none() {
+            self.shared = Arc::new(unsafe { self.shared.clone_with(self.clone) });
+        }
+        Arc::get_mut(&mut self.shared).unwrap().as_mut()
+    }
+
+    /// Takes the thin object, cloning it if it's shared with other `ThinCow`s.
+    pub fn into_thin(self) -> Thin<U> {
+        let clone = self.clone;
+        Arc::try_unwrap(self.shared).unwrap_or_else(|shared| unsafe { shared.clone_with(clone) })
+    }
+
+    /// Whether both share the same thin object.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> Clone for ThinCow<U> {
+    /// Shares the thin object, without cloning its value.
+    fn clone(&self) -> Self {
+        ThinCow { shared: self.shared.clone(), clone: self.clone }
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> Deref for ThinCow<U> {
+    type Target = Thin<U>;
+
+    fn deref(&self) -> &Thin<U> {
+        &self.shared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[thin]
+    trait Counter: 'static {
+        fn get(&self) -> u32;
+        fn incr(&mut self);
+    }
+
+    #[derive(Clone)]
+    struct Counted {
+        count: u32,
+        // counts the live copies of the value
+        #[allow(dead_code)]
+        clones: Rc<()>,
+    }
+
+    impl Counter for Counted {
+        fn get(&self) -> u32 {
+            self.count
+        }
+        fn incr(&mut self) {
+            self.count += 1;
+        }
+    }
+
+    #[test]
+    fn clone_on_write() {
+        let clones = Rc::new(());
+        let original = ThinCow::<dyn Counter>::new(Counted { count: 1, clones: clones.clone() });
+        let mut copy = original.clone();
+        assert!(copy.ptr_eq(&original));
+        assert_eq!(Rc::strong_count(&clones), 2);
+
+        // the first write clones the shared value, and later ones reuse the copy
+        copy.to_mut().incr();
+        copy.to_mut().incr();
+        assert_eq!(Rc::strong_count(&clones), 3);
+        assert_eq!((original.get(), copy.get()), (1, 3));
+        assert!(!copy.ptr_eq(&original));
+
+        // an unshared value isn't cloned
+        let mut unique = original;
+        unique.to_mut().incr();
+        assert_eq!(Rc::strong_count(&clones), 3);
+        assert_eq!(unique.into_thin().get(), 2);
+        assert_eq!(Rc::strong_count(&clones), 2);
+
+        // taking a shared value leaves it to the other `ThinCow`s
+        let shared = copy.clone();
+        let taken = copy.into_thin();
+        assert_eq!(Rc::strong_count(&clones), 3);
+        assert_eq!((taken.get(), shared.get()), (3, 3));
+        drop((taken, shared));
+        assert_eq!(Rc::strong_count(&clones), 1);
+    }
+}
@@ -0,0 +1,184 @@
+//! Uniform downcasting for thin objects whose vtables record the `TypeId` of their concrete type,
+//! in the spirit of the `downcast-rs` crate.
+//!
+//! Traits annotated with `#[thin(any)]` (and `Thin<dyn Any>` itself) implement [`ThinAny`], which
+//! gives their thin objects the methods of [`DowncastThin`].
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin(any, rust_only)]
+//! trait Event: 'static {
+//!     fn name(&self) -> &'static str;
+//! }
+//!
+//! struct Click { x: u32, y: u32 }
+//! impl Event for Click {
+//!     fn name(&self) -> &'static str { "click" }
+//! }
+//!
+//! let event = Thin::<dyn Event>::new(Click { x: 1, y: 2 });
+//! assert!(event.is::<Click>());
+//! let click = event.downcast_ref::<Click>().unwrap();
+//! assert_eq!((click.x, click.y), (1, 2));
+//! ```
+//!
+//! Note that since `TypeId`s aren't stable between compilations, neither is downcasting objects
+//! created on the other side of an FFI boundary.
+
+use std::any::TypeId;
+use std::ptr::NonNull;
+
+use crate::prelude::*;
+use crate::__private::*;
+
+/// An object type whose bundles record the `TypeId` of their concrete type.
+///
+/// # Safety
+/// `erased_type_id` must return the `TypeId` of the value in the bundle at `ptr`, and that value
+/// must directly follow the vtable pointer, as in every bundle created by `#[thin]`.
+pub unsafe trait ThinAny: SpecialAssoc<Kind = Own> {
+    #[doc(hidden)]
+    fn erased_type_id(ptr: NonNull<()>) -> TypeId;
+}
+
+/// Downcasting methods for `Thin<dyn Trait>`s, where `dyn Trait:` [`ThinAny`].
+pub trait DowncastThin: Sized {
+    /// Returns the `TypeId` of the erased value.
+    fn erased_type_id(&self) -> TypeId;
+
+    /// Returns `true` if the erased value is a `T`.
+    fn is<T: 'static>(&self) -> bool {
+        self.erased_type_id() == TypeId::of::<T>()
+    }
+
+    fn downcast_ref<T: 'static>(&self) -> Option<&T>;
+    fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T>;
+
+    /// Takes the erased value by value, or returns the object unchanged if it isn't a `T`.
+    fn downcast<T: 'static>(self) -> Result<T, Self>;
+
+    /// Calls `f` with the erased value if it's a `K`, so that hot paths can check the type once and
+    /// then call `K`'s methods directly (where they can be inlined), rather than through the vtable.
+    fn with_downcast<K: 'static, R>(&self, f: impl FnOnce(&K) -> R) -> Option<R> {
+        self.downcast_ref::<K>().map(f)
+    }
+
+    /// The same as [`with_downcast`](Self::with_downcast), with a mutable borrow.
+    fn with_downcast_mut<K: 'static, R>(&mut self, f: impl FnOnce(&mut K) -> R) -> Option<R> {
+        self.downcast_mut::<K>().map(f)
+    }
+}
+
+/// [`DowncastThin`] for thin objects which may be shared between threads, mirroring
+/// `downcast-rs`'s `DowncastSync`, for use as a bound in generic code.
+pub trait DowncastThinSync: DowncastThin + Send + Sync {}
+
+impl<U: ?Sized + ThinAny + 'static> DowncastThin for Thin<U> {
+    fn erased_type_id(&self) -> TypeId {
+        check_dropped_as::<Self>(self.ptr.as_ptr(), "downcast");
+        U::erased_type_id(self.ptr)
+    }
+
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if !DowncastThin::is::<T>(self) {
+            return None;
+        }
+        let bundle = unsafe { &*(self.ptr.as_ptr() as *const RawBundle<(), T>) };
+        Some(&bundle.value)
+    }
+
+    fn downcast_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        if !DowncastThin::is::<T>(self) {
+            return None;
+        }
+        let bundle = unsafe { &mut *(self.ptr.as_ptr() as *mut RawBundle<(), T>) };
+        Some(&mut bundle.value)
+    }
+
+    fn downcast<T: 'static>(self) -> Result<T, Self> {
+        if !DowncastThin::is::<T>(&self) {
+            return Err(self);
+        }
+        let ptr = self.ptr.as_ptr() as *mut RawBundle<(), T>;
+        std::mem::forget(self);
+        let bundle = unsafe { Box::from_raw(ptr) };
+        Ok(bundle.value)
+    }
+}
+
+impl<U: ?Sized + ThinAny + ThinSend + ThinSync + 'static> DowncastThinSync for Thin<U> {}
+
+#[cfg(test)]
+mod tests {
+    use std::any::Any;
+
+    use crate::prelude::*;
+
+    #[thin(any)]
+    trait Shape: 'static {
+        fn area(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Square(u32);
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+
+    impl Shape for u32 {
+        fn area(&self) -> u32 {
+            0
+        }
+    }
+
+    #[test]
+    fn downcasting() {
+        let mut shape = Thin::<dyn Shape>::new(Square(2));
+        assert_eq!(shape.area(), 4);
+        assert!(shape.is::<Square>());
+        assert!(!shape.is::<u32>());
+
+        shape.downcast_mut::<Square>().unwrap().0 = 3;
+        assert_eq!(shape.area(), 9);
+        assert!(shape.downcast_ref::<u32>().is_none());
+
+        let shape = shape.downcast::<u32>().unwrap_err();
+        assert_eq!(shape.downcast::<Square>().ok(), Some(Square(3)));
+    }
+
+    #[test]
+    fn devirtualized() {
+        let mut shapes = [Thin::<dyn Shape>::new(Square(2)), Thin::<dyn Shape>::new(3u32)];
+        let total = shapes.iter().map(|shape| {
+            shape.with_downcast(|square: &Square| square.area()).unwrap_or_else(|| shape.area())
+        }).sum::<u32>();
+        assert_eq!(total, 4);
+
+        assert_eq!(shapes[0].with_downcast_mut(|square: &mut Square| square.0 += 1), Some(()));
+        assert_eq!(shapes[1].with_downcast_mut(|square: &mut Square| square.0 += 1), None);
+        assert_eq!(shapes[0].area(), 9);
+    }
+
+    #[test]
+    fn same_type() {
+        let a = Thin::<dyn Shape>::new(Square(1));
+        let b = Thin::<dyn Shape>::new(Square(2));
+        let c = Thin::<dyn Shape>::new(1u32);
+
+        assert!(Thin::is_same_type(&a, &b));
+        assert!(!Thin::is_same_type(&a, &c));
+    }
+
+    fn first_u8<T: DowncastThinSync>(objects: &[T]) -> Option<u8> {
+        objects.iter().find_map(|object| object.downcast_ref::<u8>().copied())
+    }
+
+    #[test]
+    fn uniform_api() {
+        let objects = [Thin::<dyn Any + Send + Sync>::new(1u16), Thin::<dyn Any + Send + Sync>::new(2u8)];
+        assert_eq!(first_u8(&objects), Some(2));
+    }
+}
@@ -0,0 +1,133 @@
+//! Controls what happens when a value's `Drop` impl panics inside a thin object's drop shim.
+//!
+//! The policy is global to the binary whose shim is running (i.e. a plugin's objects are
+//! dropped according to the plugin's policy), and defaults to [`DropPanicPolicy::Abort`].
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[repr(u8)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum DropPanicPolicy {
+    /// Abort the process.
+    Abort = 0,
+    /// Catch the panic and log it, leaking anything the panicking `Drop` impl didn't clean up.
+    Swallow = 1,
+    /// Unwind through the shim (which is `extern "C-unwind"`) into the caller.
+    /// Only sound if both sides of the FFI boundary use the same unwinding runtime.
+    Propagate = 2,
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(DropPanicPolicy::Abort as u8);
+
+pub fn set_drop_panic_policy(policy: DropPanicPolicy) {
+    POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn drop_panic_policy() -> DropPanicPolicy {
+    match POLICY.load(Ordering::Relaxed) {
+        1 => DropPanicPolicy::Swallow,
+        2 => DropPanicPolicy::Propagate,
+        _ => DropPanicPolicy::Abort,
+    }
+}
+
+/// Runs `f` (which drops something), applying the current [`DropPanicPolicy`] if it panics.
+#[inline]
+pub(crate) fn with_drop_policy(f: impl FnOnce()) {
+    // the panic handling is shared by every drop shim, rather than instantiated for each type
+    let mut f = Some(f);
+    run_with_drop_policy(&mut || (f.take().unwrap())())
+}
+
+fn run_with_drop_policy(f: &mut dyn FnMut()) {
+    let policy = drop_panic_policy();
+    if policy == DropPanicPolicy::Propagate {
+        return f();
+    }
+
+    let Err(payload) = panic::catch_unwind(AssertUnwindSafe(f)) else { return };
+    let message = panic_message(&*payload);
+    match policy {
+        DropPanicPolicy::Swallow => {
+            #[cfg(feature = "log")]
+            ::log::error!("panic while dropping a thin object: {}", message);
+            #[cfg(not(feature = "log"))]
+            eprintln!("panic while dropping a thin object: {}", message);
+        }
+        _ => {
+            eprintln!("panic while dropping a thin object: {}, aborting", message);
+            std::process::abort();
+        }
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&'static str>() {
+        return message;
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message;
+    }
+    "Box<dyn Any>"
+}
+
+/// Frees the bundle (of type `B`) at `ptr`, according to the current [`DropPanicPolicy`].
+///
+/// # Safety
+/// `ptr` must have been created by `alloc_bundle::<B>` or `Box::<B>::into_raw`, and not be used afterwards.
+#[doc(hidden)]
+pub unsafe fn drop_bundle<B>(ptr: *mut ()) {
+    // outside the policy, as a double drop is a bug in the caller rather than the value's `Drop`
+    crate::poison::check_dropped(ptr, "double drop");
+    with_drop_policy(|| {
+        #[cfg(feature = "debug-poison")]
+        unsafe { crate::poison::poison::<B>(ptr) };
+        #[cfg(all(not(feature = "debug-poison"), feature = "recycle"))]
+        unsafe { crate::recycle::free_bundle::<B>(ptr) };
+        #[cfg(all(not(feature = "debug-poison"), not(feature = "recycle")))]
+        let _ = unsafe { Box::from_raw(ptr as *mut B) };
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use crate::prelude::*;
+    use super::*;
+
+    #[thin]
+    trait Foo: 'static {
+        fn get(&self) -> u8;
+    }
+
+    struct PanicsOnDrop;
+
+    impl Foo for PanicsOnDrop {
+        fn get(&self) -> u8 {
+            0
+        }
+    }
+
+    impl Drop for PanicsOnDrop {
+        fn drop(&mut self) {
+            panic!("dropped");
+        }
+    }
+
+    // policies are global, so they're all tested sequentially
+    #[test]
+    fn policies() {
+        set_drop_panic_policy(DropPanicPolicy::Swallow);
+        drop(Thin::<dyn Foo>::new(PanicsOnDrop));
+        drop(ThinGuard::new(|| panic!("guard")));
+
+        set_drop_panic_policy(DropPanicPolicy::Propagate);
+        let thin = Thin::<dyn Foo>::new(PanicsOnDrop);
+        assert!(panic::catch_unwind(AssertUnwindSafe(move || drop(thin))).is_err());
+
+        set_drop_panic_policy(DropPanicPolicy::Abort);
+    }
+}
@@ -0,0 +1,201 @@
+//! A length-prefixed framing for erased values, for RPC messages and save files holding values of
+//! many types.
+//!
+//! [`encode`] writes a `Thin<dyn StableAny>` as its `StableTypeId`, the length of its payload and
+//! the payload itself, and [`decode`] reads it back by looking up the decoder registered for the id
+//! with [`register_payload!`](crate::register_payload). Types choose their own payload encoding
+//! by implementing [`Payload`].
+//!
+//! ```rust
+//! use std::io;
+//! use thin_trait_objects::envelope::{self, Payload};
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[derive(StableAny, PartialEq, Debug)]
+//! struct Move { x: i32, y: i32 }
+//!
+//! impl Payload for Move {
+//!     fn encode(&self, out: &mut Vec<u8>) {
+//!         out.extend_from_slice(&self.x.to_le_bytes());
+//!         out.extend_from_slice(&self.y.to_le_bytes());
+//!     }
+//!
+//!     fn decode(bytes: &[u8]) -> io::Result<Self> {
+//!         let bytes: [u8; 8] = bytes.try_into().map_err(|_| io::ErrorKind::InvalidData)?;
+//!         let [x, y] = [&bytes[..4], &bytes[4..]].map(|n| i32::from_le_bytes(n.try_into().unwrap()));
+//!         Ok(Move { x, y })
+//!     }
+//! }
+//!
+//! thin_trait_objects::register_payload!(Move);
+//!
+//! let mut wire = Vec::new();
+//! envelope::encode(&Thin::<dyn StableAny>::new(Move { x: 1, y: -2 }), &mut wire).unwrap();
+//! let value = envelope::decode(&mut &wire[..]).unwrap();
+//! assert_eq!(value.downcast::<Move>(), Some(Move { x: 1, y: -2 }));
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::prelude::*;
+
+/// A value which can be sent in an envelope, once registered with
+/// [`register_payload!`](crate::register_payload).
+pub trait Payload: UUID + Sized {
+    /// Appends the payload to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads the value from the whole of a payload written by `encode`.
+    fn decode(bytes: &[u8]) -> io::Result<Self>;
+}
+
+/// The payload functions registered for a type with [`register_payload!`](crate::register_payload).
+pub struct RegisteredPayload {
+    pub id: StableTypeId,
+    pub encode: fn(&Thin<dyn StableAny>, &mut Vec<u8>),
+    pub decode: fn(&[u8]) -> io::Result<Thin<dyn StableAny>>,
+}
+
+inventory::collect!(RegisteredPayload);
+
+fn encode_erased<T: Payload>(value: &Thin<dyn StableAny>, out: &mut Vec<u8>) {
+    value.downcast_ref::<T>().unwrap().encode(out)
+}
+
+fn decode_erased<T: Payload>(bytes: &[u8]) -> io::Result<Thin<dyn StableAny>> {
+    T::decode(bytes).map(Thin::<dyn StableAny>::new)
+}
+
+impl RegisteredPayload {
+    #[doc(hidden)]
+    pub const fn of<T: Payload>() -> Self {
+        Self { id: StableTypeId::of::<T>(), encode: encode_erased::<T>, decode: decode_erased::<T> }
+    }
+}
+
+/// Registers the [`Payload`] impl of a concrete type, for [`encode`] and [`decode`].
+#[macro_export]
+macro_rules! register_payload {
+    ($ty: ty) => {
+        $crate::registry::__inventory::submit! {
+            $crate::envelope::RegisteredPayload::of::<$ty>()
+        }
+    };
+}
+
+/// Returns the payload functions registered for the type with the given id, if any.
+pub fn lookup_payload(id: StableTypeId) -> Option<&'static RegisteredPayload> {
+    inventory::iter::<RegisteredPayload>.into_iter().find(|payload| payload.id == id)
+}
+
+fn unregistered(id: StableTypeId, kind: io::ErrorKind) -> io::Error {
+    let name = id.lookup_name().unwrap_or("an unknown type");
+    io::Error::new(kind, format!("no payload registered for {} (id {})", name, id))
+}
+
+/// Writes the value in the envelope format: its `StableTypeId` as a `u64`, the length of its
+/// payload as a `u32`, then the payload, all little-endian.
+///
+/// Fails with `InvalidInput` if no payload is registered for the value's type, or it's too long.
+pub fn encode(value: &Thin<dyn StableAny>, writer: &mut impl Write) -> io::Result<()> {
+    let id = value.stable_type_id();
+    let payload = lookup_payload(id).ok_or_else(|| unregistered(id, io::ErrorKind::InvalidInput))?;
+    let mut bytes = vec![0; 12];
+    (payload.encode)(value, &mut bytes);
+    let len = u32::try_from(bytes.len() - 12).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, "payload too long")
+    })?;
+    bytes[..8].copy_from_slice(&unsafe { id.to_u64() }.to_le_bytes());
+    bytes[8..12].copy_from_slice(&len.to_le_bytes());
+    writer.write_all(&bytes)
+}
+
+/// Reads a value written by [`encode`].
+///
+/// Fails with `InvalidData` if no payload is registered for its type, after reading the whole
+/// envelope, so that the values following it can still be read.
+pub fn decode(reader: &mut impl Read) -> io::Result<Thin<dyn StableAny>> {
+    let mut header = [0; 12];
+    reader.read_exact(&mut header)?;
+    let id = unsafe { StableTypeId::new(u64::from_le_bytes(header[..8].try_into().unwrap())) };
+    let len = u32::from_le_bytes(header[8..].try_into().unwrap());
+
+    // reads as much as is there rather than allocating for a garbage length up front
+    let mut bytes = Vec::new();
+    reader.take(len.into()).read_to_end(&mut bytes)?;
+    if bytes.len() != len as usize {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let payload = lookup_payload(id).ok_or_else(|| unregistered(id, io::ErrorKind::InvalidData))?;
+    (payload.decode)(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(StableAny, PartialEq, Debug)]
+    struct Name(String);
+
+    impl Payload for Name {
+        fn encode(&self, out: &mut Vec<u8>) {
+            out.extend_from_slice(self.0.as_bytes());
+        }
+
+        fn decode(bytes: &[u8]) -> io::Result<Self> {
+            String::from_utf8(bytes.to_vec()).map(Name).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+        }
+    }
+
+    crate::register_payload!(Name);
+
+    impl Payload for u16 {
+        fn encode(&self, out: &mut Vec<u8>) {
+            out.extend_from_slice(&self.to_le_bytes());
+        }
+
+        fn decode(bytes: &[u8]) -> io::Result<Self> {
+            bytes.try_into().map(u16::from_le_bytes).map_err(|_| io::ErrorKind::InvalidData.into())
+        }
+    }
+
+    crate::register_payload!(u16);
+
+    #[derive(StableAny)]
+    struct Unregistered;
+
+    #[test]
+    fn round_trip() {
+        let mut wire = Vec::new();
+        encode(&Thin::<dyn StableAny>::new(Name("thin".into())), &mut wire).unwrap();
+        encode(&Thin::<dyn StableAny>::new(7u16), &mut wire).unwrap();
+        assert_eq!(wire.len(), 12 + 4 + 12 + 2);
+        assert_eq!(wire[..8], unsafe { StableTypeId::of::<Name>().to_u64() }.to_le_bytes());
+        assert_eq!(wire[8..12], 4u32.to_le_bytes());
+
+        let mut reader = &wire[..];
+        assert_eq!(decode(&mut reader).unwrap().downcast::<Name>(), Some(Name("thin".into())));
+        assert_eq!(decode(&mut reader).unwrap().downcast::<u16>(), Some(7));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let err = encode(&Thin::<dyn StableAny>::new(Unregistered), &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(err.to_string().ends_with(&format!("::Unregistered (id {})", StableTypeId::of::<Unregistered>())));
+
+        // unknown types are skipped over
+        let mut wire = Vec::new();
+        wire.extend_from_slice(&unsafe { StableTypeId::of::<Unregistered>().to_u64() }.to_le_bytes());
+        wire.extend_from_slice(&1u32.to_le_bytes());
+        wire.push(0);
+        encode(&Thin::<dyn StableAny>::new(7u16), &mut wire).unwrap();
+        let mut reader = &wire[..];
+        assert_eq!(decode(&mut reader).err().unwrap().kind(), io::ErrorKind::InvalidData);
+        assert_eq!(decode(&mut reader).unwrap().downcast::<u16>(), Some(7));
+
+        // truncated payloads
+        assert_eq!(decode(&mut &wire[13..wire.len() - 1]).err().unwrap().kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
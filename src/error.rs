@@ -0,0 +1,415 @@
+//! `ThinError`, a one-pointer-wide, context-carrying error type in the spirit of `anyhow::Error`.
+//!
+//! ```rust
+//! use thin_trait_objects::error::{Context, ThinError};
+//!
+//! fn read_config() -> Result<String, ThinError> {
+//!     let config = std::fs::read_to_string("does/not/exist.toml").context("failed to read config")?;
+//!     Ok(config)
+//! }
+//!
+//! let err = read_config().unwrap_err();
+//! assert_eq!(err.to_string(), "failed to read config");
+//! assert_eq!(err.chain().count(), 2);
+//! ```
+//!
+//! With the `stable_any` feature enabled (as it is by default), errors created with `ThinError::new`
+//! can be downcast by their `StableTypeId`, which (unlike `TypeId`) is meaningful on both sides
+//! of an FFI boundary.
+//!
+//! With the `backtrace` feature enabled, a [`Backtrace`] is captured whenever a new error
+//! (but not a new layer of context) is created.
+
+use std::backtrace::Backtrace;
+use std::fmt::{self, Debug, Display, Formatter};
+use crate::prelude::*;
+use crate::__private::*;
+
+/// `Result<T, ThinError>`
+pub type Result<T, E = ThinError> = std::result::Result<T, E>;
+
+/// An erased error with an optional chain of sources, one pointer wide.
+///
+/// `ThinError` deliberately does not implement `std::error::Error`, so that any
+/// `E: Error + Send + Sync + 'static` can be converted into it with `?`.
+#[repr(transparent)]
+pub struct ThinError(Thin<dyn Node>);
+
+/// The values stored in each link of the chain.
+trait Node: Send + Sync + 'static {}
+
+impl SpecialAssoc for dyn Node {
+    type Kind = Own;
+}
+
+impl ThinSend for dyn Node {}
+impl ThinSync for dyn Node {}
+
+#[repr(C)]
+struct VTable {
+    drop: extern "C-unwind" fn(*mut ()),
+    display: extern "C" fn(RefSelf<'_>, &mut Formatter<'_>) -> bool,
+    debug: extern "C" fn(RefSelf<'_>, &mut Formatter<'_>) -> bool,
+    source: extern "C" fn(RefSelf<'_>) -> Option<&'_ ThinError>,
+    backtrace: extern "C" fn(RefSelf<'_>) -> Option<&'_ Backtrace>,
+    // the offset of the link in the bundle, which depends on the alignment of its error
+    link: usize,
+}
+
+extern "C-unwind" fn drop<T>(ptr: *mut ()) {
+    unsafe { drop_bundle::<RawBundle<VTable, Link<T>>>(ptr) }
+}
+
+extern "C" fn display<T: Display>(recv: RefSelf<'_>, f: &mut Formatter<'_>) -> bool {
+    let link = unsafe { RawBundle::<VTable, Link<T>>::value(recv) };
+    Display::fmt(&link.error, f).is_err()
+}
+
+extern "C" fn debug<T: Debug>(recv: RefSelf<'_>, f: &mut Formatter<'_>) -> bool {
+    let link = unsafe { RawBundle::<VTable, Link<T>>::value(recv) };
+    Debug::fmt(&link.error, f).is_err()
+}
+
+// the header fields come before the error, so a `Link<()>` at the link's offset reads them for
+// every `T`
+unsafe fn header(bundle: *mut ()) -> *mut Link<()> {
+    let vtable = unsafe { *(bundle as *const &'static VTable) };
+    unsafe { bundle.byte_add(vtable.link) as *mut Link<()> }
+}
+
+extern "C" fn source(recv: RefSelf<'_>) -> Option<&'_ ThinError> {
+    let link = unsafe { &*header(recv.ptr as *mut ()) };
+    link.source.as_ref()
+}
+
+extern "C" fn backtrace(recv: RefSelf<'_>) -> Option<&'_ Backtrace> {
+    let link = unsafe { &*header(recv.ptr as *mut ()) };
+    link.backtrace.as_ref()
+}
+
+// keeps the layout of bundles the same whether or not `stable_any` is enabled
+#[cfg(feature = "stable_any")]
+type ErrorId = StableTypeId;
+#[cfg(not(feature = "stable_any"))]
+type ErrorId = u64;
+
+// the value of each bundle
+#[repr(C)]
+struct Link<T> {
+    // `None` if the error wasn't created as `StableAny`
+    uuid: Option<ErrorId>,
+    backtrace: Option<Backtrace>,
+    source: Option<ThinError>,
+    error: T,
+}
+
+/// Stands in for a foreign error's source, which can't be handed across the FFI boundary as-is.
+struct Captured {
+    display: String,
+    debug: String,
+}
+
+impl Display for Captured {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.display)
+    }
+}
+
+impl Debug for Captured {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.debug)
+    }
+}
+
+fn capture_backtrace() -> Option<Backtrace> {
+    #[cfg(feature = "backtrace")]
+    return Some(Backtrace::capture());
+    #[cfg(not(feature = "backtrace"))]
+    return None;
+}
+
+/// Converts the sources of a foreign error into a chain of `ThinError`s.
+fn capture_sources(error: &(dyn std::error::Error + 'static)) -> Option<ThinError> {
+    let source = error.source()?;
+    let captured = Captured {
+        display: source.to_string(),
+        debug: format!("{:?}", source),
+    };
+    Some(ThinError::from_parts(captured, None, capture_sources(source), None))
+}
+
+impl ThinError {
+    fn from_parts<T: Display + Debug + Send + Sync + 'static>(
+        value: T,
+        uuid: Option<ErrorId>,
+        next: Option<ThinError>,
+        trace: Option<Backtrace>,
+    ) -> Self {
+        let vtable: &'static VTable = const {
+            &VTable {
+                drop: drop::<T>,
+                display: display::<T>,
+                debug: debug::<T>,
+                source,
+                backtrace,
+                link: std::mem::offset_of!(RawBundle<VTable, Link<T>>, value),
+            }
+        };
+        let bundle = RawBundle { vtable, value: Link { uuid, backtrace: trace, source: next, error: value } };
+        let ptr = Box::into_raw(Box::new(bundle));
+        ThinError(unsafe { Thin::from_raw(ptr as *mut ()) })
+    }
+
+    fn vtable(&self) -> &'static VTable {
+        check_poison(self.0.ptr.as_ptr());
+        unsafe { *(self.0.ptr.as_ptr() as *const &'static VTable) }
+    }
+
+    /// The fields of the link which don't depend on the type of its error.
+    #[cfg(feature = "stable_any")]
+    fn header(&self) -> &Link<()> {
+        check_poison(self.0.ptr.as_ptr());
+        unsafe { &*header(self.0.ptr.as_ptr()) }
+    }
+
+    /// Creates a new `ThinError` which may be downcast back into an `E`.
+    #[cfg(feature = "stable_any")]
+    pub fn new<E: std::error::Error + StableAny + Send + Sync + 'static>(error: E) -> Self {
+        let source = capture_sources(&error);
+        ThinError::from_parts(error, Some(StableTypeId::of::<E>()), source, capture_backtrace())
+    }
+
+    /// Creates a new `ThinError` from a message, such as a `&'static str` or `String`.
+    pub fn msg<M: Display + Debug + Send + Sync + 'static>(message: M) -> Self {
+        ThinError::from_parts(message, None, None, capture_backtrace())
+    }
+
+    /// Wraps this error in a new layer of context, which becomes the outermost link of the chain.
+    pub fn context<C: Display + Debug + Send + Sync + 'static>(self, context: C) -> Self {
+        ThinError::from_parts(context, None, Some(self), None)
+    }
+
+    /// Returns the next link of the chain, if any.
+    pub fn source(&self) -> Option<&ThinError> {
+        (self.vtable().source)(RefSelf::new(&self.0))
+    }
+
+    /// Iterates over this error and its sources, outermost first.
+    pub fn chain(&self) -> Chain<'_> {
+        Chain { next: Some(self) }
+    }
+
+    /// Returns the innermost link of the chain.
+    pub fn root_cause(&self) -> &ThinError {
+        self.chain().last().unwrap()
+    }
+
+    /// Returns the backtrace captured when the underlying error was created, if any.
+    ///
+    /// Backtraces are only captured with the `backtrace` feature enabled.
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.chain().find_map(|link| (link.vtable().backtrace)(RefSelf::new(&link.0)))
+    }
+
+    /// Returns `true` if the outermost link of the chain is an `E`.
+    #[cfg(feature = "stable_any")]
+    pub fn is<E: StableAny>(&self) -> bool {
+        self.header().uuid == Some(StableTypeId::of::<E>())
+    }
+
+    /// Returns a reference to the first `E` in the chain, if any.
+    #[cfg(feature = "stable_any")]
+    pub fn downcast_ref<E: StableAny>(&self) -> Option<&E> {
+        let link = self.chain().find(|link| link.is::<E>())?;
+        let bundle = unsafe { &*(link.0.ptr.as_ptr() as *const RawBundle<VTable, Link<E>>) };
+        Some(&bundle.value.error)
+    }
+
+    /// Returns a mutable reference to the first `E` in the chain, if any.
+    #[cfg(feature = "stable_any")]
+    pub fn downcast_mut<E: StableAny>(&mut self) -> Option<&mut E> {
+        let mut link = self;
+        loop {
+            if link.is::<E>() {
+                let bundle = unsafe { &mut *(link.0.ptr.as_ptr() as *mut RawBundle<VTable, Link<E>>) };
+                return Some(&mut bundle.value.error);
+            }
+            let header = unsafe { &mut *header(link.0.ptr.as_ptr()) };
+            link = header.source.as_mut()?;
+        }
+    }
+
+    /// Attempts to take the outermost link of the chain by value.
+    #[cfg(feature = "stable_any")]
+    pub fn downcast<E: StableAny>(self) -> std::result::Result<E, Self> {
+        if !self.is::<E>() {
+            return Err(self);
+        }
+        let ptr = self.0.ptr.as_ptr() as *mut RawBundle<VTable, Link<E>>;
+        ::std::mem::forget(self);
+        let bundle = unsafe { Box::from_raw(ptr) };
+        Ok(bundle.value.error)
+    }
+}
+
+impl<E: std::error::Error + Send + Sync + 'static> From<E> for ThinError {
+    /// Erases `error`, which can't be downcast since `E` might not be `StableAny`.
+    fn from(error: E) -> Self {
+        let source = capture_sources(&error);
+        ThinError::from_parts(error, None, source, capture_backtrace())
+    }
+}
+
+impl Display for ThinError {
+    /// The alternate flag (`{:#}`) prints the whole chain, separated by `": "`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, link) in self.chain().enumerate() {
+            if i > 0 {
+                if !f.alternate() { break }
+                f.write_str(": ")?;
+            }
+            if (link.vtable().display)(RefSelf::new(&link.0), f) {
+                return Err(fmt::Error);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ThinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if (self.vtable().debug)(RefSelf::new(&self.0), f) {
+            return Err(fmt::Error);
+        }
+        if let Some(source) = self.source() {
+            f.write_str("\n\nCaused by:")?;
+            for (i, link) in source.chain().enumerate() {
+                write!(f, "\n    {}: {}", i, link)?;
+            }
+        }
+        if let Some(backtrace) = self.backtrace() {
+            write!(f, "\n\nStack backtrace:\n{}", backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the links of a [`ThinError`]'s chain, returned by [`ThinError::chain`].
+pub struct Chain<'a> {
+    next: Option<&'a ThinError>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a ThinError;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = current.source();
+        Some(current)
+    }
+}
+
+/// Adds context to the error variants of `Result`s and the `None` variants of `Option`s.
+pub trait Context<T> {
+    fn context<C: Display + Debug + Send + Sync + 'static>(self, context: C) -> Result<T>;
+    fn with_context<C: Display + Debug + Send + Sync + 'static, F: FnOnce() -> C>(self, f: F) -> Result<T>;
+}
+
+impl<T, E: Into<ThinError>> Context<T> for std::result::Result<T, E> {
+    fn context<C: Display + Debug + Send + Sync + 'static>(self, context: C) -> Result<T> {
+        self.map_err(|error| error.into().context(context))
+    }
+
+    fn with_context<C: Display + Debug + Send + Sync + 'static, F: FnOnce() -> C>(self, f: F) -> Result<T> {
+        self.map_err(|error| error.into().context(f()))
+    }
+}
+
+impl<T> Context<T> for Option<T> {
+    fn context<C: Display + Debug + Send + Sync + 'static>(self, context: C) -> Result<T> {
+        self.ok_or_else(|| ThinError::msg(context))
+    }
+
+    fn with_context<C: Display + Debug + Send + Sync + 'static, F: FnOnce() -> C>(self, f: F) -> Result<T> {
+        self.ok_or_else(|| ThinError::msg(f()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::{Display, Formatter};
+
+    use super::*;
+
+    #[derive(Debug)]
+    #[cfg_attr(feature = "stable_any", derive(StableAny))]
+    #[cfg_attr(not(feature = "stable_any"), allow(dead_code))]
+    struct Fatal(u8);
+
+    impl Display for Fatal {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "fatal error {}", self.0)
+        }
+    }
+
+    impl std::error::Error for Fatal {}
+
+    #[test]
+    fn one_pointer_wide() {
+        assert_eq!(size_of::<ThinError>(), size_of::<usize>());
+        assert_eq!(size_of::<Option<ThinError>>(), size_of::<usize>());
+    }
+
+    #[test]
+    #[cfg(feature = "stable_any")]
+    fn context_chain() {
+        let err = ThinError::new(Fatal(1)).context("while loading").context("while starting");
+
+        assert_eq!(err.to_string(), "while starting");
+        assert_eq!(format!("{:#}", err), "while starting: while loading: fatal error 1");
+        assert_eq!(err.root_cause().to_string(), "fatal error 1");
+        assert_eq!(err.chain().count(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "stable_any")]
+    fn downcasting() {
+        let mut err = ThinError::new(Fatal(1)).context("while loading");
+
+        assert!(!err.is::<Fatal>());
+        err.downcast_mut::<Fatal>().unwrap().0 += 1;
+        assert_eq!(err.downcast_ref::<Fatal>().unwrap().0, 2);
+
+        let err = err.downcast::<Fatal>().unwrap_err();
+        let err = err.source().unwrap();
+        assert!(err.is::<Fatal>());
+    }
+
+    #[test]
+    fn overaligned_context() {
+        #[derive(Debug)]
+        #[repr(align(64))]
+        struct Aligned(&'static str);
+
+        impl Display for Aligned {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+
+        let err = ThinError::msg("inner").context(Aligned("outer"));
+        assert_eq!(format!("{:#}", err), "outer: inner");
+        assert_eq!(err.source().unwrap().to_string(), "inner");
+    }
+
+    #[test]
+    fn foreign_errors() {
+        let err: ThinError = "x".parse::<u8>().unwrap_err().into();
+        assert_eq!(err.to_string(), "invalid digit found in string");
+        #[cfg(feature = "stable_any")]
+        assert!(err.downcast_ref::<u8>().is_none());
+
+        let none: Option<u8> = None;
+        assert_eq!(none.context("missing").unwrap_err().to_string(), "missing");
+    }
+}
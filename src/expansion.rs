@@ -0,0 +1,259 @@
+//! Helpers performing the unsafe operations of the code `#[thin]` generates, which calls them from
+//! `unsafe` blocks spanned as the macro's own code, so that its expansion compiles in crates with
+//! `#![forbid(unsafe_code)]` (`#[thin(c_free)]` aside, whose destructor is exported with
+//! `#[unsafe(no_mangle)]`).
+//!
+//! None of this is public API. The helpers are `unsafe`, and only sound when called as the expansion
+//! calls them, on the bundles and vtables it generated: a `dyn Trait`'s bundles are `RawBundle<V, K>`s
+//! of its vtable struct `V`. Likewise, the traits stand in for the `unsafe impl`s of the object-type
+//! traits, which the blanket impls here provide instead, and [`Expanded`] requires an
+//! [`ExpansionToken`], which can only be created unsafely, so that they can't be implemented in safe
+//! code either.
+
+use std::ptr::NonNull;
+
+use crate::prelude::*;
+use crate::__private::*;
+use crate::raw::RawVTableHeader;
+
+/// Vouches that the object type implementing [`Expanded`] was generated by `#[thin]`.
+#[derive(Clone, Copy)]
+pub struct ExpansionToken(());
+
+impl ExpansionToken {
+    /// # Safety
+    /// The token may only be used by the code `#[thin]` generates, for the object type of its own trait.
+    pub const unsafe fn new() -> Self {
+        ExpansionToken(())
+    }
+}
+
+/// The object types `#[thin]` generates, the `dyn Trait` of each trait, whose bundles are
+/// `RawBundle`s of the trait's vtable struct.
+///
+/// The traits below require it, so that their blanket impls don't overlap with the impls of the
+/// object-type traits for other types, such as `dyn Any`, and can only be reached with a token.
+pub trait Expanded: SpecialAssoc<Kind = Own> {
+    const TOKEN: ExpansionToken;
+}
+
+/// Stands in for [`ThinVTable`]: `vtable` returns the trait's vtable for `K`.
+pub trait ExpandedVTable<K>: Expanded {
+    fn vtable() -> *const ();
+}
+
+// SAFETY: `Expanded` can only be implemented with a token, i.e. by `#[thin]`, which returns the
+// trait's vtable for `K`
+unsafe impl<U: ?Sized + ExpandedVTable<K> + 'static, K> ThinVTable<U, K> for Thin<U> {
+    fn vtable() -> *const () {
+        <U as ExpandedVTable<K>>::vtable()
+    }
+}
+
+/// Stands in for [`ThinBatched`]: `batched_vtable` returns the trait's vtable for batched `K`s,
+/// whose drop slot calls [`expanded_batched_drop`].
+pub trait ExpandedBatched<K>: Expanded {
+    fn batched_vtable() -> *const ();
+}
+
+// SAFETY: as for `ThinVTable`
+unsafe impl<U: ?Sized + ExpandedBatched<K> + 'static, K> ThinBatched<U, K> for Thin<U> {
+    fn batched_vtable() -> *const () {
+        <U as ExpandedBatched<K>>::batched_vtable()
+    }
+}
+
+/// Stands in for [`ThinAny`]: `erased_type_id` reads the type id slot of the vtable.
+#[cfg(feature = "any")]
+pub trait ExpandedAny: Expanded {
+    fn erased_type_id(ptr: NonNull<()>) -> std::any::TypeId;
+}
+
+// SAFETY: as for `ThinVTable`
+#[cfg(feature = "any")]
+unsafe impl<U: ?Sized + ExpandedAny> ThinAny for U {
+    fn erased_type_id(ptr: NonNull<()>) -> std::any::TypeId {
+        <U as ExpandedAny>::erased_type_id(ptr)
+    }
+}
+
+/// Stands in for [`ThinExtensionSlot`]: `erased_extension` reads the extension slot of the vtable.
+pub trait ExpandedExtensionSlot: Expanded {
+    fn erased_extension(ptr: NonNull<()>) -> Option<&'static Extension>;
+}
+
+// SAFETY: as for `ThinVTable`
+unsafe impl<U: ?Sized + ExpandedExtensionSlot> ThinExtensionSlot for U {
+    fn erased_extension(ptr: NonNull<()>) -> Option<&'static Extension> {
+        <U as ExpandedExtensionSlot>::erased_extension(ptr)
+    }
+}
+
+/// Stands in for [`ThinUUID`]: `UUID` is hashed from the trait's path, by [`expanded_stable_id`].
+#[cfg(feature = "stable_any")]
+pub trait ExpandedUUID: Expanded {
+    const UUID: StableTypeId;
+}
+
+// SAFETY: as for `ThinVTable`
+#[cfg(feature = "stable_any")]
+unsafe impl<U: ?Sized + ExpandedUUID> ThinUUID for U {
+    const UUID: StableTypeId = <U as ExpandedUUID>::UUID;
+}
+
+/// The value of the `RawBundle<V, K>` the receiver of a shim points to.
+///
+/// # Safety
+/// `recv` must point to a live `RawBundle<V, K>`.
+#[inline(always)]
+pub unsafe fn expanded_value<V: 'static, K>(recv: RefSelf<'_>) -> &K {
+    unsafe { RawBundle::<V, K>::value(recv) }
+}
+
+/// The value of the `RawBundle<V, K>` the receiver of a shim points to.
+///
+/// # Safety
+/// `recv` must point to a live `RawBundle<V, K>`.
+#[inline(always)]
+pub unsafe fn expanded_value_mut<V: 'static, K>(recv: MutSelf<'_>) -> &mut K {
+    unsafe { RawBundle::<V, K>::value_mut(recv) }
+}
+
+/// Moves `value` to the heap in a `RawBundle`, as a `Thin<U>`.
+///
+/// # Safety
+/// `vtable` must be `U`'s vtable for `K`.
+#[inline(always)]
+pub unsafe fn expanded_thin<U: ?Sized + SpecialAssoc + 'static, V: 'static, K>(vtable: &'static V, value: K) -> Thin<U> {
+    unsafe { Thin::from_raw(alloc_bundle(RawBundle { vtable, value })) }
+}
+
+/// The sealed copy of a vtable `#[thin]` generated (see [`RawVTableHeader::seal`]).
+///
+/// # Safety
+/// `vtable` must be a vtable struct generated by `#[thin]`.
+#[inline(always)]
+pub unsafe fn expanded_seal<V: 'static>(vtable: &'static V) -> &'static V {
+    unsafe { RawVTableHeader::seal(vtable) }
+}
+
+/// Frees a `RawBundle<V, K>`, from the drop slot of its vtable.
+///
+/// # Safety
+/// `ptr` must point to a live `RawBundle<V, K>`, which isn't used afterwards.
+#[inline(always)]
+pub unsafe fn expanded_drop<V: 'static, K>(ptr: *mut ()) {
+    unsafe { drop_bundle::<RawBundle<V, K>>(ptr) }
+}
+
+/// Frees a `RawBundle<V, K>` after running its `PinnedDrop`, from the drop slot of a pinned vtable.
+///
+/// # Safety
+/// As for [`expanded_drop`].
+#[inline(always)]
+pub unsafe fn expanded_pinned_drop<V: 'static, K: PinnedDrop>(ptr: *mut ()) {
+    unsafe { drop_pinned_bundle::<RawBundle<V, K>, K>(ptr) }
+}
+
+/// Drops a batched `RawBundle<V, K>`, from the drop slot of a batched vtable.
+///
+/// # Safety
+/// As for [`expanded_drop`], and the bundle must have been allocated by a batch.
+#[inline(always)]
+pub unsafe fn expanded_batched_drop<V: 'static, K>(ptr: *mut ()) {
+    unsafe { drop_batched_bundle::<RawBundle<V, K>>(ptr) }
+}
+
+/// Loads the vtable of the thin object at `ptr`, which is a `V` as far as it goes.
+///
+/// # Safety
+/// `ptr` must point to a live thin object whose vtable has the layout of a `V` as far as it goes.
+#[inline(always)]
+pub unsafe fn expanded_vtable<V>(ptr: NonNull<()>) -> NonNull<V> {
+    check_poison(ptr.as_ptr());
+    // SAFETY:
+    // see https://adventures.michaelfbryan.com/posts/ffi-safe-polymorphism-in-rust/?utm_source=user-forums&utm_medium=social&utm_campaign=thin-trait-objects#pointer-to-vtable--object
+    unsafe { *ptr.cast::<NonNull<V>>().as_ptr() }
+}
+
+/// The extent of the method slots of a vtable loaded by [`expanded_vtable`], after verifying its
+/// checksum.
+///
+/// # Safety
+/// `vtable` must have been loaded by [`expanded_vtable`].
+#[inline(always)]
+pub unsafe fn expanded_vtable_size<V>(vtable: NonNull<V>) -> usize {
+    unsafe { RawVTableHeader::verified_size(vtable.cast().as_ptr()) }
+}
+
+/// Reads the method slot of `V` at `offset` in a vtable loaded by [`expanded_vtable`], whose slots
+/// extend `size` bytes, panicking if they don't extend to it. `field` projects `V` onto the same
+/// field, to give its type (it isn't called, as the vtable may be shorter than a `V`).
+///
+/// # Safety
+/// `vtable` must have been loaded by [`expanded_vtable`], `size` read by [`expanded_vtable_size`],
+/// and `offset` must be that of `field`.
+#[inline(always)]
+#[track_caller]
+pub unsafe fn expanded_slot<V, F: Copy>(
+    vtable: NonNull<V>, size: usize, offset: usize, field: fn(&V) -> &F, trait_name: &str, name: &str,
+) -> F {
+    let _ = field;
+    // the vtable may be shorter than a `V`, so only the fields known to exist are read
+    if size < offset + size_of::<F>() {
+        missing_slot(trait_name, name);
+    }
+    unsafe { vtable.byte_add(offset).cast::<F>().read() }
+}
+
+/// Loads the vtable of the thin object at `ptr` and reads one of the fields of `V` following the
+/// method slots, which end at `slots_size` in a `V`. Panics if the vtable's slots end elsewhere, as
+/// it was then created against another version of the trait, and its fields aren't where `V` has them.
+///
+/// # Safety
+/// As for [`expanded_vtable`], and `offset` must be that of `field`, after `slots_size`.
+#[inline(always)]
+#[track_caller]
+pub unsafe fn expanded_load_slot<V, F: Copy>(
+    ptr: NonNull<()>, slots_size: usize, offset: usize, field: fn(&V) -> &F, trait_name: &str, name: &str,
+) -> F {
+    let _ = field;
+    let vtable = unsafe { expanded_vtable::<V>(ptr) };
+    if unsafe { expanded_vtable_size(vtable) } != slots_size {
+        missing_slot(trait_name, name);
+    }
+    unsafe { vtable.byte_add(offset).cast::<F>().read() }
+}
+
+/// A vtable loaded by [`expanded_vtable`], whose slots extend `size` bytes, as a whole `V`, unless
+/// they end elsewhere than at `slots_size` (i.e. it was created against another version of the trait).
+///
+/// # Safety
+/// As for [`expanded_slot`], and `slots_size` must be where the method slots of a `V` end.
+#[inline(always)]
+pub unsafe fn expanded_whole_vtable<V>(vtable: NonNull<V>, size: usize, slots_size: usize) -> Option<&'static V> {
+    // the reference may only be created once the vtable is known to be a whole `V`
+    match size == slots_size {
+        true => Some(unsafe { vtable.as_ref() }),
+        false => None,
+    }
+}
+
+/// Converts between the generic and the instantiated type of an argument or return value of an
+/// instantiated method.
+///
+/// # Safety
+/// `A` and `B` must be the same type.
+#[inline(always)]
+pub unsafe fn expanded_cast<A, B>(a: A) -> B {
+    unsafe { cast_same(a) }
+}
+
+/// The `StableTypeId` of a `Thin<dyn Trait>` of a `#[thin(stable_any)]` trait, from its hash.
+///
+/// # Safety
+/// `hash` must be hashed as `#[thin]` hashes the trait's path.
+#[cfg(feature = "stable_any")]
+pub const unsafe fn expanded_stable_id(hash: u64) -> StableTypeId {
+    unsafe { StableTypeId::new(hash) }
+}
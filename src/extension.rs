@@ -0,0 +1,119 @@
+//! Per-type static metadata stored in the vtables of `#[thin(extension)]` traits, for frameworks
+//! layering reflection, serialization or scripting data on top of thin objects.
+//!
+//! Every type a thin object of such a trait is created from implements [`ThinExtension`] for the
+//! trait, optionally providing an [`Extension`], which [`Thin::extension`] then returns.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin(extension, rust_only)]
+//! trait Widget: 'static {
+//!     fn draw(&self) -> String;
+//! }
+//!
+//! struct Fields(&'static [&'static str]);
+//!
+//! struct Button { label: String }
+//! impl Widget for Button {
+//!     fn draw(&self) -> String { format!("[{}]", self.label) }
+//! }
+//! impl ThinExtension<dyn Widget> for Button {
+//!     const EXTENSION: Option<&'static Extension> = Some(&Extension::new(&Fields(&["label"])));
+//! }
+//!
+//! impl Widget for u8 {
+//!     fn draw(&self) -> String { self.to_string() }
+//! }
+//! impl ThinExtension<dyn Widget> for u8 {}
+//!
+//! let button = Thin::<dyn Widget>::new(Button { label: "OK".into() });
+//! assert_eq!(button.extension::<Fields>().unwrap().0, ["label"]);
+//! assert!(Thin::<dyn Widget>::new(1u8).extension::<Fields>().is_none());
+//! ```
+//!
+//! The extension follows the method slots in the vtable (before the `TypeId` of `#[thin(any)]`
+//! traits), as a pointer which is null if the type provides none.
+
+use std::any::Any;
+use std::ptr::NonNull;
+
+use crate::prelude::*;
+use crate::__private::*;
+
+/// Type-erased static metadata, as stored in a vtable.
+pub struct Extension(&'static (dyn Any + Send + Sync));
+
+impl Extension {
+    pub const fn new<M: Any + Send + Sync>(metadata: &'static M) -> Self {
+        Self(metadata)
+    }
+
+    /// Returns the metadata, if it's an `M`.
+    pub fn get<M: Any>(&self) -> Option<&'static M> {
+        self.0.downcast_ref()
+    }
+}
+
+/// Provides the [`Extension`] stored in the vtables of `Thin<U>`s created from `Self`.
+pub trait ThinExtension<U: ?Sized> {
+    const EXTENSION: Option<&'static Extension> = None;
+}
+
+/// An object type whose vtables have an extension slot, as those of `#[thin(extension)]` traits do.
+///
+/// # Safety
+/// `erased_extension` must return the extension in the vtable of the bundle at `ptr`.
+pub unsafe trait ThinExtensionSlot: SpecialAssoc<Kind = Own> {
+    #[doc(hidden)]
+    fn erased_extension(ptr: NonNull<()>) -> Option<&'static Extension>;
+}
+
+impl<U: ?Sized + ThinExtensionSlot> Thin<U> {
+    /// Returns the metadata provided for the concrete type, if there is some and it's an `M`.
+    pub fn extension<M: Any>(&self) -> Option<&'static M> {
+        check_poison(self.ptr.as_ptr());
+        U::erased_extension(self.ptr)?.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[thin(extension)]
+    trait Node: 'static {
+        fn children(&self) -> usize;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Schema(&'static str);
+
+    struct Leaf;
+    impl Node for Leaf {
+        fn children(&self) -> usize {
+            0
+        }
+    }
+    impl ThinExtension<dyn Node> for Leaf {
+        const EXTENSION: Option<&'static Extension> = Some(&Extension::new(&Schema("leaf")));
+    }
+
+    impl Node for Vec<u8> {
+        fn children(&self) -> usize {
+            self.len()
+        }
+    }
+    impl ThinExtension<dyn Node> for Vec<u8> {}
+
+    #[test]
+    fn extensions() {
+        let leaf = Thin::<dyn Node>::new(Leaf);
+        assert_eq!(leaf.extension::<Schema>(), Some(&Schema("leaf")));
+        assert_eq!(leaf.extension::<u8>(), None);
+
+        let list = Thin::<dyn Node>::new(vec![1, 2]);
+        assert_eq!(list.children(), 2);
+        assert_eq!(list.extension::<Schema>(), None);
+    }
+}
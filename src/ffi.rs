@@ -0,0 +1,518 @@
+//! FFI-safe companions of `&str`, `String`, `&[T]` and `Vec<T>`, for the signatures of `#[thin]`
+//! methods.
+//!
+//! References to `str` and slices are wide pointers, and `String` and `Vec` have no stable layout,
+//! so none of them may cross the C ABI. [`ThinStr`] and [`ThinSlice`] borrow as they do, as a
+//! `repr(C)` pointer and length, while [`ThinString`] and [`ThinVecFfi`] own their buffer, and carry
+//! the function that frees it: a buffer allocated on one side of the boundary is always freed by
+//! the allocator which allocated it, even if the two sides were built against different ones.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Directory: 'static {
+//!     fn lookup(&self, name: ThinStr<'_>) -> ThinVecFfi<u32>;
+//!     fn describe(&self, ids: ThinSlice<'_, u32>) -> ThinString;
+//! }
+//!
+//! struct Users;
+//!
+//! impl Directory for Users {
+//!     fn lookup(&self, name: ThinStr<'_>) -> ThinVecFfi<u32> {
+//!         name.bytes().map(u32::from).collect()
+//!     }
+//!     fn describe(&self, ids: ThinSlice<'_, u32>) -> ThinString {
+//!         format!("{} ids", ids.len()).into()
+//!     }
+//! }
+//!
+//! let users = Thin::<dyn Directory>::new(Users);
+//! let ids = users.lookup("ab".into());
+//! assert_eq!(ids, [97, 98]);
+//! assert_eq!(users.describe(ThinSlice::new(&ids)), "2 ids");
+//! assert_eq!(ids.into_vec(), vec![97, 98]);
+//! ```
+//!
+//! Foreign code filling these in must uphold the invariants of their std counterparts: strings
+//! are UTF-8, and the pointers are non-null, aligned, and valid for `len` elements (`cap` for the
+//! allocation of an owned buffer, which is passed back to its `free` function as is).
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::{ptr, slice, str};
+
+/// An FFI-safe `&'a str`.
+#[repr(C)]
+pub struct ThinStr<'a> {
+    ptr: NonNull<u8>,
+    len: usize,
+    phantom: PhantomData<&'a str>,
+}
+
+unsafe impl Send for ThinStr<'_> {}
+unsafe impl Sync for ThinStr<'_> {}
+
+impl<'a> ThinStr<'a> {
+    pub const fn new(s: &'a str) -> Self {
+        ThinStr { ptr: NonNull::from_ref(s).cast(), len: s.len(), phantom: PhantomData }
+    }
+
+    pub const fn as_str(self) -> &'a str {
+        unsafe { str::from_utf8_unchecked(slice::from_raw_parts(self.ptr.as_ptr(), self.len)) }
+    }
+}
+
+impl Clone for ThinStr<'_> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for ThinStr<'_> {}
+
+impl Default for ThinStr<'_> {
+    fn default() -> Self {
+        ThinStr::new("")
+    }
+}
+
+impl Deref for ThinStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<'a> From<&'a str> for ThinStr<'a> {
+    fn from(s: &'a str) -> Self {
+        ThinStr::new(s)
+    }
+}
+
+impl<'a> From<ThinStr<'a>> for &'a str {
+    fn from(s: ThinStr<'a>) -> Self {
+        s.as_str()
+    }
+}
+
+impl From<ThinStr<'_>> for String {
+    fn from(s: ThinStr<'_>) -> Self {
+        s.as_str().to_owned()
+    }
+}
+
+/// An FFI-safe `String`, which frees its buffer with the function it was created with.
+#[repr(C)]
+pub struct ThinString {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    free: extern "C-unwind" fn(NonNull<u8>, usize, usize),
+}
+
+unsafe impl Send for ThinString {}
+unsafe impl Sync for ThinString {}
+
+extern "C-unwind" fn free_string(ptr: NonNull<u8>, len: usize, cap: usize) {
+    drop(unsafe { String::from_raw_parts(ptr.as_ptr(), len, cap) })
+}
+
+impl ThinString {
+    pub const fn new() -> Self {
+        ThinString { ptr: NonNull::dangling(), len: 0, cap: 0, free: free_string }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.as_thin_str().as_str()
+    }
+
+    pub fn as_thin_str(&self) -> ThinStr<'_> {
+        ThinStr { ptr: self.ptr, len: self.len, phantom: PhantomData }
+    }
+
+    /// Converts back to a `String`, which reuses the buffer if it was allocated by this side of the
+    /// boundary, and copies it otherwise.
+    pub fn into_string(self) -> String {
+        let this = ManuallyDrop::new(self);
+        if ptr::fn_addr_eq(this.free, free_string as extern "C-unwind" fn(_, _, _)) {
+            return unsafe { String::from_raw_parts(this.ptr.as_ptr(), this.len, this.cap) };
+        }
+        let s = this.as_str().to_owned();
+        (this.free)(this.ptr, this.len, this.cap);
+        s
+    }
+}
+
+impl Drop for ThinString {
+    fn drop(&mut self) {
+        (self.free)(self.ptr, self.len, self.cap)
+    }
+}
+
+impl Clone for ThinString {
+    fn clone(&self) -> Self {
+        ThinString::from(self.as_str())
+    }
+}
+
+impl Default for ThinString {
+    fn default() -> Self {
+        ThinString::new()
+    }
+}
+
+impl Deref for ThinString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<String> for ThinString {
+    fn from(s: String) -> Self {
+        let mut s = ManuallyDrop::new(s);
+        ThinString { ptr: NonNull::new(s.as_mut_ptr()).unwrap(), len: s.len(), cap: s.capacity(), free: free_string }
+    }
+}
+
+impl From<&str> for ThinString {
+    fn from(s: &str) -> Self {
+        ThinString::from(s.to_owned())
+    }
+}
+
+impl From<ThinString> for String {
+    fn from(s: ThinString) -> Self {
+        s.into_string()
+    }
+}
+
+/// An FFI-safe `&'a [T]`.
+#[repr(C)]
+pub struct ThinSlice<'a, T> {
+    ptr: NonNull<T>,
+    len: usize,
+    phantom: PhantomData<&'a [T]>,
+}
+
+unsafe impl<T: Sync> Send for ThinSlice<'_, T> {}
+unsafe impl<T: Sync> Sync for ThinSlice<'_, T> {}
+
+impl<'a, T> ThinSlice<'a, T> {
+    pub const fn new(s: &'a [T]) -> Self {
+        ThinSlice { ptr: NonNull::from_ref(s).cast(), len: s.len(), phantom: PhantomData }
+    }
+
+    pub const fn as_slice(self) -> &'a [T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Clone for ThinSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ThinSlice<'_, T> {}
+
+impl<T> Default for ThinSlice<'_, T> {
+    fn default() -> Self {
+        ThinSlice::new(&[])
+    }
+}
+
+impl<T> Deref for ThinSlice<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<'a, T> From<&'a [T]> for ThinSlice<'a, T> {
+    fn from(s: &'a [T]) -> Self {
+        ThinSlice::new(s)
+    }
+}
+
+impl<'a, T> From<ThinSlice<'a, T>> for &'a [T] {
+    fn from(s: ThinSlice<'a, T>) -> Self {
+        s.as_slice()
+    }
+}
+
+/// An FFI-safe `Vec<T>`, which frees its buffer with the function it was created with.
+///
+/// Not to be confused with [`ThinVec`](crate::vec::ThinVec), a vector of thin objects.
+#[repr(C)]
+pub struct ThinVecFfi<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    cap: usize,
+    free: extern "C-unwind" fn(NonNull<T>, usize, usize),
+}
+
+unsafe impl<T: Send> Send for ThinVecFfi<T> {}
+unsafe impl<T: Sync> Sync for ThinVecFfi<T> {}
+
+extern "C-unwind" fn free_vec<T>(ptr: NonNull<T>, len: usize, cap: usize) {
+    drop(unsafe { Vec::from_raw_parts(ptr.as_ptr(), len, cap) })
+}
+
+impl<T> ThinVecFfi<T> {
+    pub const fn new() -> Self {
+        ThinVecFfi { ptr: NonNull::dangling(), len: 0, cap: 0, free: free_vec::<T> }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        self.as_thin_slice().as_slice()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    pub fn as_thin_slice(&self) -> ThinSlice<'_, T> {
+        ThinSlice { ptr: self.ptr, len: self.len, phantom: PhantomData }
+    }
+
+    /// Converts back to a `Vec`, which reuses the buffer if it was allocated by this side of the
+    /// boundary, and moves the elements out of it otherwise.
+    pub fn into_vec(self) -> Vec<T> {
+        let this = ManuallyDrop::new(self);
+        if ptr::fn_addr_eq(this.free, free_vec::<T> as extern "C-unwind" fn(_, _, _)) {
+            return unsafe { Vec::from_raw_parts(this.ptr.as_ptr(), this.len, this.cap) };
+        }
+        let mut vec = Vec::with_capacity(this.len);
+        unsafe {
+            ptr::copy_nonoverlapping(this.ptr.as_ptr(), vec.as_mut_ptr(), this.len);
+            vec.set_len(this.len);
+        }
+        // the elements were moved out, so only the buffer is left to free
+        (this.free)(this.ptr, 0, this.cap);
+        vec
+    }
+}
+
+impl<T> Drop for ThinVecFfi<T> {
+    fn drop(&mut self) {
+        (self.free)(self.ptr, self.len, self.cap)
+    }
+}
+
+impl<T: Clone> Clone for ThinVecFfi<T> {
+    fn clone(&self) -> Self {
+        ThinVecFfi::from(self.as_slice().to_vec())
+    }
+}
+
+impl<T> Default for ThinVecFfi<T> {
+    fn default() -> Self {
+        ThinVecFfi::new()
+    }
+}
+
+impl<T> Deref for ThinVecFfi<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for ThinVecFfi<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T> From<Vec<T>> for ThinVecFfi<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut vec = ManuallyDrop::new(vec);
+        ThinVecFfi { ptr: NonNull::new(vec.as_mut_ptr()).unwrap(), len: vec.len(), cap: vec.capacity(), free: free_vec::<T> }
+    }
+}
+
+impl<T> From<ThinVecFfi<T>> for Vec<T> {
+    fn from(vec: ThinVecFfi<T>) -> Self {
+        vec.into_vec()
+    }
+}
+
+impl<T> FromIterator<T> for ThinVecFfi<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        ThinVecFfi::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+// the std traits, as the std counterparts implement them
+
+macro_rules! impl_str {
+    ($($ty:ty),*) => {$(
+        impl Debug for $ty {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_str(), f)
+            }
+        }
+
+        impl Display for $ty {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                Display::fmt(self.as_str(), f)
+            }
+        }
+
+        impl PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_str() == other.as_str()
+            }
+        }
+
+        impl Eq for $ty {}
+
+        impl PartialEq<str> for $ty {
+            fn eq(&self, other: &str) -> bool {
+                self.as_str() == other
+            }
+        }
+
+        impl PartialEq<&str> for $ty {
+            fn eq(&self, other: &&str) -> bool {
+                self.as_str() == *other
+            }
+        }
+
+        impl Hash for $ty {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_str().hash(state)
+            }
+        }
+    )*};
+}
+
+impl_str!(ThinStr<'_>, ThinString);
+
+macro_rules! impl_slice {
+    ($($ty:ty),*) => {$(
+        impl<T: Debug> Debug for $ty {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                Debug::fmt(self.as_slice(), f)
+            }
+        }
+
+        impl<T: PartialEq> PartialEq for $ty {
+            fn eq(&self, other: &Self) -> bool {
+                self.as_slice() == other.as_slice()
+            }
+        }
+
+        impl<T: Eq> Eq for $ty {}
+
+        impl<T: PartialEq> PartialEq<[T]> for $ty {
+            fn eq(&self, other: &[T]) -> bool {
+                self.as_slice() == other
+            }
+        }
+
+        impl<T: PartialEq, const N: usize> PartialEq<[T; N]> for $ty {
+            fn eq(&self, other: &[T; N]) -> bool {
+                self.as_slice() == other
+            }
+        }
+
+        impl<T: Hash> Hash for $ty {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.as_slice().hash(state)
+            }
+        }
+    )*};
+}
+
+impl_slice!(ThinSlice<'_, T>, ThinVecFfi<T>);
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[thin]
+    trait Store: 'static {
+        fn put(&mut self, key: ThinStr<'_>, values: ThinSlice<'_, u32>);
+        fn take(&mut self) -> ThinVecFfi<u32>;
+        fn key(&self) -> ThinStr<'_>;
+        fn keys(&self) -> ThinString;
+    }
+
+    #[derive(Default)]
+    struct Memory(String, Vec<u32>);
+
+    impl Store for Memory {
+        fn put(&mut self, key: ThinStr<'_>, values: ThinSlice<'_, u32>) {
+            self.0 = key.into();
+            self.1.extend_from_slice(&values);
+        }
+        fn take(&mut self) -> ThinVecFfi<u32> {
+            std::mem::take(&mut self.1).into()
+        }
+        fn key(&self) -> ThinStr<'_> {
+            self.0.as_str().into()
+        }
+        fn keys(&self) -> ThinString {
+            format!("[{}]", self.0).into()
+        }
+    }
+
+    #[test]
+    fn methods() {
+        let mut store = Thin::<dyn Store>::new(Memory::default());
+        store.put("a".into(), ThinSlice::new(&[1, 2]));
+        store.put(ThinStr::new("b"), (&[3][..]).into());
+        assert_eq!((store.key(), store.keys().as_str()), (ThinStr::new("b"), "[b]"));
+        assert_eq!(format!("{:?} {}", store.key(), store.keys()), "\"b\" [b]");
+        let values = store.take();
+        assert_eq!(values, [1, 2, 3]);
+        assert!(store.take().is_empty());
+        assert_eq!(values.into_vec(), vec![1, 2, 3]);
+    }
+
+    extern "C-unwind" fn free_foreign<T>(ptr: NonNull<T>, len: usize, cap: usize) {
+        // stands in for the allocator of the other side
+        drop(unsafe { Vec::from_raw_parts(ptr.as_ptr(), len, cap) })
+    }
+
+    #[test]
+    fn foreign() {
+        // a buffer freed by another function is copied out of, and released through that function
+        let mut vec = ManuallyDrop::new(vec![Rc::new(1), Rc::new(2)]);
+        let rc = vec[0].clone();
+        let foreign = ThinVecFfi { ptr: NonNull::new(vec.as_mut_ptr()).unwrap(), len: 2, cap: vec.capacity(), free: free_foreign };
+        let moved = foreign.into_vec();
+        assert_eq!((Rc::strong_count(&rc), *moved[1]), (2, 2));
+        drop(moved);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let mut s = ManuallyDrop::new(String::from("foreign"));
+        let foreign = ThinString { ptr: NonNull::new(s.as_mut_ptr()).unwrap(), len: s.len(), cap: s.capacity(), free: free_foreign };
+        assert_eq!(foreign.clone().into_string(), "foreign");
+        assert_eq!(String::from(foreign), "foreign");
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(size_of::<ThinStr<'_>>(), 2 * size_of::<usize>());
+        assert_eq!(size_of::<ThinSlice<'_, u64>>(), 2 * size_of::<usize>());
+        assert_eq!(size_of::<ThinString>(), 4 * size_of::<usize>());
+        assert_eq!(size_of::<ThinVecFfi<u8>>(), 4 * size_of::<usize>());
+        assert_eq!(ThinString::new(), "");
+        assert_eq!(ThinVecFfi::<()>::from(vec![(); 3]).into_vec().len(), 3);
+    }
+}
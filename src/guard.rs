@@ -0,0 +1,111 @@
+use crate::drop_panic::with_drop_policy;
+use crate::prelude::*;
+use crate::__private::*;
+
+/// An erased `FnOnce()` which is run when the guard is dropped, one pointer wide.
+///
+/// Guards can be handed across FFI boundaries, allowing hosts and plugins to hand each
+/// other cleanup obligations which fire even on early returns or panics.
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use thin_trait_objects::prelude::*;
+///
+/// static CLEANED_UP: AtomicBool = AtomicBool::new(false);
+///
+/// let guard = ThinGuard::new(|| CLEANED_UP.store(true, Ordering::SeqCst));
+/// drop(guard);
+/// assert!(CLEANED_UP.load(Ordering::SeqCst));
+/// ```
+///
+/// Panics from within the function are handled according to the [`DropPanicPolicy`](crate::drop_panic::DropPanicPolicy).
+#[repr(transparent)]
+pub struct ThinGuard(Thin<dyn Guard>);
+
+trait Guard: Send + 'static {}
+
+impl SpecialAssoc for dyn Guard {
+    type Kind = Own;
+}
+
+impl ThinSend for dyn Guard {}
+
+#[repr(C)]
+struct VTable {
+    // runs the function, then frees the bundle
+    drop: extern "C-unwind" fn(*mut ()),
+    // frees the bundle without running the function
+    dismiss: extern "C-unwind" fn(*mut ()),
+}
+
+extern "C-unwind" fn drop<F: FnOnce()>(ptr: *mut ()) {
+    with_drop_policy(|| {
+        let bundle = unsafe { Box::from_raw(ptr as *mut RawBundle<VTable, F>) };
+        (bundle.value)()
+    })
+}
+
+extern "C-unwind" fn dismiss<F>(ptr: *mut ()) {
+    unsafe { drop_bundle::<RawBundle<VTable, F>>(ptr) }
+}
+
+impl ThinGuard {
+    /// Creates a guard which runs `f` when dropped.
+    pub fn new<F: FnOnce() + Send + 'static>(f: F) -> Self {
+        let vtable: &'static VTable = const {
+            &VTable {
+                drop: drop::<F>,
+                dismiss: dismiss::<F>,
+            }
+        };
+        let bundle = RawBundle { vtable, value: f };
+        let ptr = Box::into_raw(Box::new(bundle));
+        ThinGuard(unsafe { Thin::from_raw(ptr as *mut ()) })
+    }
+
+    /// Consumes the guard without running its function.
+    pub fn dismiss(self) {
+        let ptr = self.0.ptr.as_ptr();
+        check_poison(ptr);
+        let vtable = unsafe { *(ptr as *const &'static VTable) };
+        let dismiss = vtable.dismiss;
+        ::std::mem::forget(self);
+        dismiss(ptr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn one_pointer_wide() {
+        assert_eq!(size_of::<ThinGuard>(), size_of::<usize>());
+    }
+
+    #[test]
+    fn runs_on_drop() {
+        let count = Arc::new(AtomicU8::new(0));
+        let captured = count.clone();
+
+        let guard = ThinGuard::new(move || { captured.fetch_add(1, Ordering::SeqCst); });
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        drop(guard);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn dismissed() {
+        let count = Arc::new(AtomicU8::new(0));
+        let captured = count.clone();
+
+        let guard = ThinGuard::new(move || { captured.fetch_add(1, Ordering::SeqCst); });
+        guard.dismiss();
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        // the closure (and its captures) should still have been dropped
+        assert_eq!(Arc::strong_count(&count), 1);
+    }
+}
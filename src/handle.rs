@@ -0,0 +1,175 @@
+//! Integer handles to thin objects, for C callers which shouldn't be trusted with pointers.
+//!
+//! A [`HandleRegistry`] owns the thin objects it's given and hands out `u64` handles to them, made
+//! of a slot index and the generation of the slot. Removing an object bumps the generation of its
+//! slot, so stale, forged or double-freed handles are rejected rather than reaching freed memory,
+//! even once the slot is reused. `0` is never a valid handle, so it can stand for none.
+//!
+//! ```rust
+//! use std::sync::Mutex;
+//! use thin_trait_objects::handle::HandleRegistry;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Counter: Send + 'static {
+//!     fn incr(&mut self) -> u32;
+//! }
+//!
+//! impl Counter for u32 {
+//!     fn incr(&mut self) -> u32 { *self += 1; *self }
+//! }
+//!
+//! static COUNTERS: Mutex<HandleRegistry<dyn Counter>> = Mutex::new(HandleRegistry::new());
+//!
+//! extern "C" fn counter_new() -> u64 {
+//!     COUNTERS.lock().unwrap().insert(Thin::new(0u32))
+//! }
+//!
+//! // returns 0 for invalid handles
+//! extern "C" fn counter_incr(handle: u64) -> u32 {
+//!     COUNTERS.lock().unwrap().get_mut(handle).map_or(0, |mut counter| counter.incr())
+//! }
+//!
+//! extern "C" fn counter_free(handle: u64) -> bool {
+//!     COUNTERS.lock().unwrap().remove(handle).is_some()
+//! }
+//!
+//! let handle = counter_new();
+//! assert_eq!((counter_incr(handle), counter_incr(handle)), (1, 2));
+//! assert!(counter_free(handle));
+//! assert!(!counter_free(handle));
+//! assert_eq!(counter_incr(handle), 0);
+//! ```
+
+use crate::prelude::*;
+
+struct Slot<U: ?Sized + SpecialAssoc + 'static> {
+    // odd while the slot is occupied, so that a handle's generation is never 0
+    generation: u32,
+    value: Option<Thin<U>>,
+}
+
+/// Thin objects owned on behalf of C callers, which refer to them by handle.
+pub struct HandleRegistry<U: ?Sized + SpecialAssoc + 'static> {
+    slots: Vec<Slot<U>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> HandleRegistry<U> {
+    pub const fn new() -> Self {
+        HandleRegistry { slots: Vec::new(), free: Vec::new(), len: 0 }
+    }
+
+    /// Takes ownership of the thin object, returning its handle.
+    pub fn insert(&mut self, value: Thin<U>) -> u64 {
+        self.len += 1;
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.slots.len()).expect("too many handles");
+                self.slots.push(Slot { generation: 0, value: None });
+                index
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.generation += 1;
+        slot.value = Some(value);
+        (slot.generation as u64) << 32 | index as u64
+    }
+
+    fn slot(&self, handle: u64) -> Option<&Slot<U>> {
+        let slot = self.slots.get(handle as u32 as usize)?;
+        (slot.generation == (handle >> 32) as u32 && slot.value.is_some()).then_some(slot)
+    }
+
+    fn slot_mut(&mut self, handle: u64) -> Option<&mut Slot<U>> {
+        let slot = self.slots.get_mut(handle as u32 as usize)?;
+        (slot.generation == (handle >> 32) as u32 && slot.value.is_some()).then_some(slot)
+    }
+
+    /// Whether the handle refers to an object in the registry.
+    pub fn contains(&self, handle: u64) -> bool {
+        self.slot(handle).is_some()
+    }
+
+    pub fn get(&self, handle: u64) -> Option<Thin<&U>> {
+        self.slot(handle)?.value.as_ref().map(Thin::as_ref)
+    }
+
+    pub fn get_mut(&mut self, handle: u64) -> Option<Thin<&mut U>> {
+        self.slot_mut(handle)?.value.as_mut().map(Thin::as_mut)
+    }
+
+    /// Takes the object back out of the registry, invalidating its handle.
+    pub fn remove(&mut self, handle: u64) -> Option<Thin<U>> {
+        let index = handle as u32;
+        let slot = self.slot_mut(handle)?;
+        let value = slot.value.take();
+        slot.generation += 1;
+        // a slot whose generations are exhausted is retired, rather than handing out old handles again
+        if slot.generation < u32::MAX - 1 {
+            self.free.push(index);
+        }
+        self.len -= 1;
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> Default for HandleRegistry<U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[thin]
+    trait Value: 'static {
+        fn get(&self) -> u32;
+        fn set(&mut self, value: u32);
+    }
+
+    impl Value for u32 {
+        fn get(&self) -> u32 {
+            *self
+        }
+        fn set(&mut self, value: u32) {
+            *self = value;
+        }
+    }
+
+    #[test]
+    fn handles() {
+        let mut registry = HandleRegistry::<dyn Value>::new();
+        let a = registry.insert(Thin::new(1u32));
+        let b = registry.insert(Thin::new(2u32));
+        assert_ne!(a, b);
+        registry.get_mut(b).unwrap().set(3);
+        assert_eq!((registry.get(a).unwrap().get(), registry.get(b).unwrap().get()), (1, 3));
+
+        // the slot of a removed object is reused, under a new generation
+        assert_eq!(registry.remove(a).unwrap().get(), 1);
+        assert!(registry.remove(a).is_none());
+        let c = registry.insert(Thin::new(4u32));
+        assert_eq!(c as u32, a as u32);
+        assert!(!registry.contains(a) && registry.get(a).is_none());
+        assert_eq!(registry.get(c).unwrap().get(), 4);
+        assert_eq!(registry.len(), 2);
+
+        // handles which were never given out
+        assert!(!registry.contains(0));
+        assert!(!registry.contains(c + (2 << 32)));
+        assert!(!registry.contains(1 << 32 | 7));
+    }
+}
@@ -0,0 +1,169 @@
+//! Thin objects stored inline in a fixed budget of bytes, for erased dispatch without an allocator.
+//!
+//! An [`InlineThin<U, N>`] holds a value of up to `N` bytes (aligned to no more than a pointer) after
+//! its `'static` vtable pointer, laid out like the bundle of a `Thin<U>`, and lends out borrowed thin
+//! views of it as [`ThinStack`] does. Unlike `ThinStack`, the type of the value is erased, so objects
+//! of different types can be stored in the same place. Values which don't fit are a compile error.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Filter: 'static {
+//!     fn apply(&mut self, sample: f32) -> f32;
+//! }
+//!
+//! struct Gain(f32);
+//! impl Filter for Gain {
+//!     fn apply(&mut self, sample: f32) -> f32 { sample * self.0 }
+//! }
+//!
+//! struct Smooth { last: f32, factor: f32 }
+//! impl Filter for Smooth {
+//!     fn apply(&mut self, sample: f32) -> f32 {
+//!         self.last += (sample - self.last) * self.factor;
+//!         self.last
+//!     }
+//! }
+//!
+//! let mut chain: [InlineThin<dyn Filter, 8>; 2] = [
+//!     InlineThin::new(Gain(2.0)),
+//!     InlineThin::new(Smooth { last: 0.0, factor: 0.5 }),
+//! ];
+//! let out = chain.iter_mut().fold(1.0, |sample, filter| filter.as_thin_mut().apply(sample));
+//! assert_eq!(out, 1.0);
+//! ```
+//!
+//! ```rust,compile_fail
+//! # use thin_trait_objects::prelude::*;
+//! # #[thin]
+//! # trait Filter: 'static {
+//! #     fn apply(&mut self, sample: f32) -> f32;
+//! # }
+//! # impl Filter for [f32; 4] {
+//! #     fn apply(&mut self, sample: f32) -> f32 { sample }
+//! # }
+//! // 16 bytes don't fit in 8
+//! let filter = InlineThin::<dyn Filter, 8>::new([0f32; 4]);
+//! ```
+
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+use crate::prelude::*;
+
+/// The bundle of a `Thin<U>`, whose value is stored in place in `N` bytes.
+///
+/// The value is dropped along with the `InlineThin`, never through the vtable.
+#[repr(C)]
+pub struct InlineThin<U: ?Sized + SpecialAssoc + 'static, const N: usize> {
+    vtable: *const (),
+    // follows the vtable pointer, as the value of a bundle does if it's at most pointer-aligned
+    value: MaybeUninit<[u8; N]>,
+    drop: unsafe fn(*mut ()),
+    phantom: PhantomData<fn() -> Thin<U>>,
+}
+
+// the value implements `U`, so it's `Send` and `Sync` if `U` is
+unsafe impl<U: ?Sized + ThinSend + 'static, const N: usize> Send for InlineThin<U, N> {}
+unsafe impl<U: ?Sized + ThinSync + 'static, const N: usize> Sync for InlineThin<U, N> {}
+
+unsafe fn drop_value<K>(ptr: *mut ()) {
+    unsafe { std::ptr::drop_in_place(ptr as *mut K) }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static, const N: usize> InlineThin<U, N> {
+    /// Stores the value inline, failing to compile if it's larger than `N` bytes or more than
+    /// pointer-aligned.
+    pub fn new<K>(value: K) -> Self where Thin<U>: ThinVTable<U, K> {
+        const {
+            assert!(size_of::<K>() <= N, "the value is larger than the inline storage");
+            assert!(align_of::<K>() <= align_of::<*const ()>(), "the value is more aligned than a pointer");
+        };
+        let mut inline = InlineThin {
+            vtable: <Thin<U> as ThinVTable<U, K>>::vtable(),
+            value: MaybeUninit::uninit(),
+            drop: drop_value::<K>,
+            phantom: PhantomData,
+        };
+        unsafe { (inline.value.as_mut_ptr() as *mut K).write(value) };
+        inline
+    }
+
+    /// Borrows the value as a thin object.
+    pub fn as_thin(&self) -> Thin<&U> {
+        unsafe { Thin::from_raw(self as *const Self as *mut ()) }
+    }
+
+    /// Mutably borrows the value as a thin object.
+    pub fn as_thin_mut(&mut self) -> Thin<&mut U> {
+        unsafe { Thin::from_raw(self as *mut Self as *mut ()) }
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static, const N: usize> Drop for InlineThin<U, N> {
+    fn drop(&mut self) {
+        unsafe { (self.drop)(self.value.as_mut_ptr() as *mut ()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[thin]
+    trait Shape: 'static {
+        fn area(&self) -> u32;
+        fn scale(&mut self, factor: u32);
+    }
+
+    struct Square(u32, #[allow(dead_code)] Rc<()>);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+        fn scale(&mut self, factor: u32) {
+            self.0 *= factor;
+        }
+    }
+
+    impl Shape for u8 {
+        fn area(&self) -> u32 {
+            *self as u32
+        }
+        fn scale(&mut self, factor: u32) {
+            *self *= factor as u8;
+        }
+    }
+
+    impl Shape for () {
+        fn area(&self) -> u32 {
+            0
+        }
+        fn scale(&mut self, _: u32) {}
+    }
+
+    #[test]
+    fn inline_values() {
+        let rc = Rc::new(());
+        let mut shapes: Vec<InlineThin<dyn Shape, 16>> = vec![
+            InlineThin::new(Square(2, rc.clone())),
+            InlineThin::new(3u8),
+            InlineThin::new(()),
+        ];
+        shapes.iter_mut().for_each(|shape| shape.as_thin_mut().scale(2));
+        assert_eq!(shapes.iter().map(|shape| shape.as_thin().area()).collect::<Vec<_>>(), [16, 6, 0]);
+        assert_eq!(Rc::strong_count(&rc), 2);
+
+        // moving the storage moves the value with it
+        let square = shapes.remove(0);
+        assert_eq!(square.as_thin().area(), 16);
+        drop(shapes);
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(square);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
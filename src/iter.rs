@@ -0,0 +1,132 @@
+//! `Thin<dyn Iterator<Item = T>>`, to which `#[thin]` traits erase `impl Iterator<Item = T>` return types.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! let mut evens = Thin::<dyn Iterator<Item = u32>>::new((0..).step_by(2));
+//! assert_eq!(evens.next(), Some(0));
+//! assert_eq!(evens.take(3).collect::<Vec<_>>(), [2, 4, 6]);
+//! ```
+
+use crate::prelude::*;
+use crate::__private::*;
+
+macro_rules! impl_thin_dyn_iterator {
+    ($($bounds: path),*) => {
+        const _: () = {
+            #[repr(C)]
+            pub struct VTable<T> {
+                drop: extern "C-unwind" fn(*mut ()),
+                // `Option<T>` isn't FFI-safe, so these use the Rust ABI
+                next: fn(MutSelf<'_>) -> Option<T>,
+                size_hint: fn(RefSelf<'_>) -> (usize, Option<usize>),
+            }
+
+            extern "C-unwind" fn drop<K: Iterator<Item: 'static>>(ptr: *mut ()) {
+                unsafe { drop_bundle::<RawBundle<VTable<K::Item>, K>>(ptr) }
+            }
+
+            fn next<K: Iterator<Item: 'static>>(recv: MutSelf<'_>) -> Option<K::Item> {
+                let value = unsafe { RawBundle::<VTable<K::Item>, K>::value_mut(recv) };
+                value.next()
+            }
+
+            fn size_hint<K: Iterator<Item: 'static>>(recv: RefSelf<'_>) -> (usize, Option<usize>) {
+                let value = unsafe { RawBundle::<VTable<K::Item>, K>::value(recv) };
+                value.size_hint()
+            }
+
+            fn vtable<K: Iterator<Item: 'static>>() -> &'static VTable<K::Item> {
+                const {
+                    &VTable {
+                        drop: drop::<K>,
+                        next: next::<K>,
+                        size_hint: size_hint::<K>,
+                    }
+                }
+            }
+
+            impl<T: 'static> SpecialAssoc for dyn Iterator<Item = T> $(+ $bounds)* {
+                type Kind = Own;
+            }
+
+            impl<T: 'static> SlotLayout for dyn Iterator<Item = T> $(+ $bounds)* {
+                const SLOTS: &'static [&'static str] = &["drop", "next", "size_hint"];
+                type VTable = VTable<T>;
+            }
+
+            unsafe impl<T: 'static, K: Iterator<Item = T> $(+ $bounds)* + 'static> ThinVTable<dyn Iterator<Item = T> $(+ $bounds)*, K> for Thin<dyn Iterator<Item = T> $(+ $bounds)*> {
+                fn vtable() -> *const () {
+                    vtable::<K>() as *const VTable<T> as *const ()
+                }
+            }
+
+            impl<T: 'static, K: Iterator<Item = T> $(+ $bounds)* + 'static> ThinExt<dyn Iterator<Item = T> $(+ $bounds)*, K> for Thin<dyn Iterator<Item = T> $(+ $bounds)*> {
+                fn new(value: K) -> Self {
+                    let bundle = RawBundle { vtable: vtable::<K>(), value };
+                    unsafe { Thin::from_raw(alloc_bundle(bundle)) }
+                }
+            }
+
+            impl<T: 'static> Thin<dyn Iterator<Item = T> $(+ $bounds)*> {
+                fn vtable(&self) -> &'static VTable<T> {
+                    check_poison(self.ptr.as_ptr());
+                    unsafe { *(self.ptr.as_ptr() as *const &'static VTable<T>) }
+                }
+            }
+
+            impl<T: 'static> Iterator for Thin<dyn Iterator<Item = T> $(+ $bounds)*> {
+                type Item = T;
+
+                fn next(&mut self) -> Option<T> {
+                    let next = self.vtable().next;
+                    next(MutSelf::new(self))
+                }
+
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    let size_hint = self.vtable().size_hint;
+                    size_hint(RefSelf::new(self))
+                }
+            }
+        };
+    };
+}
+
+impl_thin_dyn_iterator!();
+impl_thin_dyn_iterator!(Send);
+
+// values are only ever created from `K: Iterator + Send`
+impl<T: 'static> ThinSend for dyn Iterator<Item = T> + Send {}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[thin]
+    trait Tokens: 'static {
+        fn tokens(&self) -> impl Iterator<Item = u8>;
+        fn count(&self) -> usize;
+    }
+
+    struct Text(&'static str);
+
+    impl Tokens for Text {
+        fn tokens(&self) -> impl Iterator<Item = u8> + 'static {
+            self.0.bytes().filter(u8::is_ascii_alphabetic)
+        }
+        fn count(&self) -> usize {
+            self.tokens().count()
+        }
+    }
+
+    #[test]
+    fn erased_returns() {
+        let text = Thin::<dyn Tokens>::new(Text("a b, c"));
+        assert_eq!(text.tokens().collect::<Vec<_>>(), b"abc");
+        assert_eq!(text.count(), 3);
+        assert_eq!(Box::new(Text("d")).tokens().size_hint(), (0, Some(1)));
+
+        let send = Thin::<dyn Iterator<Item = u8> + Send>::new(0..4);
+        assert_eq!(std::thread::spawn(|| send.sum::<u8>()).join().unwrap(), 6);
+    }
+}
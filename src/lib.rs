@@ -33,6 +33,10 @@
 //! }
 //! ```
 //!
+//! The code `#[thin]` generates calls its `unsafe` helpers only from blocks spanned as the macro's own,
+//! which `unsafe_code` doesn't report, so it may be used in crates with `#![forbid(unsafe_code)]`
+//! (`#[thin(c_free)]`'s exported destructor aside).
+//!
 //! #### Built-in support for `Any`
 //! `Thin<dyn Any>` is provided as a replacement for `Box<dyn Any>`.
 //!
@@ -40,6 +44,8 @@
 //! use std::any::Any;
 //! use thin_trait_objects::prelude::*;
 //!
+//! # #[cfg(not(feature = "any"))] fn main() {}
+//! # #[cfg(feature = "any")]
 //! fn main() {
 //!     let mut thin = Thin::<dyn Any>::new(8u8);
 //!     let borrow = thin.downcast_mut::<u8>().unwrap();
@@ -55,45 +61,234 @@
 //! between different compilations. The `StableAny` trait is provided as a work-around, and may be
 //! implemented for your types using `#[derive(StableAny)]` or the `impl_stable_any!` macro.
 //!
+//! #### Features
+//! - FFI-safe strings and buffers to pass through methods: `ThinStr`, `ThinString`, `ThinSlice<T>` and `ThinVecFfi<T>` ([`ffi`]).
+//! - Vtable layouts, through `SlotLayout`, the `<dyn Trait>::SLOT_*` constants and `Thin::call_slot`, and C headers
+//!   rendered from `<dyn Trait>::DESCRIPTOR` ([`codegen`]).
+//! - Vtables written by hand, `thin_vtable!` for traits `#[thin]` can't take, and `Thin::from_parts` ([`raw`]).
+//! - Calls to slots missing from vtables created against an older version of the trait panic (see `Thin::has_slot`),
+//!   and panics in methods abort, naming the trait, the method and the concrete type.
+//! - Thin objects on the stack ([`stack`]), in `static`s ([`statics`]), stored inline ([`inline`]), pinned ([`pin`]),
+//!   allocated in batches ([`batch`]) or shared until written ([`cow`]).
+//! - Ownership passed to and from C with `Thin::into_c_void` and `from_c_void`, or as checked handles ([`handle`]).
+//! - `Display`, `Debug` and `Error` supertraits implemented by `Thin<dyn Trait>` ([`bridge`]), as are `Send` and `Sync` ones.
+//! - Per-type static metadata ([`extension`]), `Thin::from_fn` for single-method traits, `Thin::from_dyn` for wrapping
+//!   a `Box<dyn Trait>`, and `Thin<dyn Iterator<Item = T>>` for methods returning `impl Iterator`.
+//! - Traits with const parameters, each instance of which is its own object type (`Thin<dyn Buffered<64>>`).
+//!
+//! #### `#[thin]` options
+//! - `hot` (on methods): give the method one of the first slots, next to the header.
+//! - `skip` (on methods): leave the method out of the vtable; `Thin<dyn Trait>` uses its default body.
+//! - `instantiate(T = u8, String)` (on generic methods): give each listed type its own slot.
+//! - `any`: record the concrete type's `TypeId`, for the downcasts of `DowncastThin` (see the `downcast` module).
+//! - `extension`, `stable_any`, `fn_impl`, `no_impl`, `c_free`, `dual_abi` and `rust_only`: see the modules and types above.
+//!
+//! #### Optional features
+//! - `any` and `stable_any` (enabled by default): `Thin<dyn Any>`, and `StableAny` with its derive and provided impls,
+//!   along with the `downcast`, `components`, `mailbox`, `plugin` and `protocol` modules.
+//! - `backtrace`: capture a `Backtrace` whenever a new `ThinError` is created.
+//! - `debug-poison`, `debug-checksum`: poison dropped objects, and checksum vtables before every dispatch.
+//! - `profile`, `stats`, `recycle`: per-type call counts, live object counts, and reuse of freed allocations (see their modules).
+//! - `stress`: multi-threaded stress tests (`cargo test --release --features stress --test stress`).
+//! - `type-name`: store the concrete type's name in `Thin<dyn Any>` and `Thin<dyn StableAny>` vtables.
+//! - `hash-fnv`, `hash-xxh3`: generate `StableTypeId`s with FNV-1a or xxh3 rather than SipHash-1-3.
+//! - `log`, `rand`, `rayon`: `Thin<dyn Log>` (see the `logger` module), `Thin<dyn RngCore>`, and parallel iterators over `ThinVec`s.
+//! - `registry`: a link-time catalogue of concrete `StableAny` types (see the `registry` and `envelope` modules).
+//! - `serde`: `Serialize` and `Deserialize` for `StableTypeId`.
+//! - `bytes`, `chrono`, `serde_json`, `time`, `uuid`: `UUID` impls for the common payload types of those crates.
+//!
 //! #### Limitations
 //! - Annotated traits must have a `'static` bound (for now).
-//! - Methods with non-lifetime generics are not supported.
+//! - Borrowed receivers such as `&K` and `&mut K` don't implement the trait (unlike `Box<K>`), because of the `'static` bound.
+//! - The crate needs `std`, so bare-metal targets (e.g. AVR or MSP430 firmware) aren't supported yet, though nothing
+//!   assumes 64-bit pointers: `StableHasher` hashes `usize`s as `u64`s, and 64-bit atomics are only used where the target has them.
+//! - Methods with type generics are only supported over a closed set of types, listed with
+//!   `#[thin(instantiate(T = u8, String))]`, which gives each type its own slot. Calls with any other type panic,
+//!   and such methods can't be called on `dyn Trait` itself (only on `Thin<dyn Trait>` and implementors).
+//! - Other methods which can't be dispatched (e.g. generic ones, or those taking `self` by value) may be annotated
+//!   `#[thin(skip)]`, which leaves them out of the vtable, like `where Self: Sized` does for `dyn Trait`.
+//!   `Thin<dyn Trait>` uses their default body, and panics if they have none.
+//! - `#[thin]`'s output refers to `::thin_trait_objects`, so the crate can't be renamed in `Cargo.toml`.
 
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::ptr::NonNull;
-use crate::prelude::StableAny;
+use std::ptr::{self, NonNull};
 
+// lets `#[thin]`, whose output refers to `::thin_trait_objects`, be used within this crate
+extern crate self as thin_trait_objects;
+
+#[cfg(feature = "any")]
 mod any;
+#[cfg(feature = "any")]
+pub mod downcast;
+#[cfg(feature = "stable_any")]
 mod stable_any;
+mod checksum;
+mod expansion;
+mod guard;
+mod iter;
+mod poison;
+
+pub mod batch;
+pub mod boxed;
+pub mod bridge;
+pub mod codegen;
+pub mod command;
+pub mod cow;
+#[cfg(feature = "stable_any")]
+pub mod components;
+pub mod drop_panic;
+pub mod error;
+pub mod extension;
+pub mod ffi;
+pub mod handle;
+pub mod inline;
+#[cfg(feature = "stable_any")]
+pub mod mailbox;
+pub mod pin;
+#[cfg(feature = "stable_any")]
+pub mod plugin;
+#[cfg(feature = "stable_any")]
+pub mod protocol;
+pub mod raw;
+pub mod spawn;
+pub mod stack;
+pub mod statics;
+pub mod subscribers;
+pub mod vec;
+
+#[cfg(feature = "log")]
+pub mod logger;
+
+#[cfg(feature = "rand")]
+mod rng;
+
+#[cfg(feature = "rayon")]
+mod par;
+
+#[cfg(feature = "profile")]
+pub mod profile;
+#[cfg(feature = "stats")]
+pub mod stats;
+
+#[cfg(feature = "recycle")]
+pub mod recycle;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
+#[cfg(feature = "registry")]
+pub mod envelope;
 
 pub mod prelude {
-    pub use thin_trait_objects_macros::thin;
+    pub use thin_trait_objects_macros::{thin, thin_vtable};
     pub use crate::{
-        Thin, //ThinRef, //ThinMut,
-        ThinExt,
+        Thin, //ThinRef,
+        ThinMut,
+        ThinExt, ThinVTable, ThinClone, CloneFn, ThinFromFn,
+        SlotLayout, SlotArgs,
         RefSelf, MutSelf,
-        Own, Ref, Mut, SpecialAssoc
+        Own, Ref, Mut, SpecialAssoc,
+        ThinSend, ThinSync
     };
 
+    pub use crate::boxed::ThinBox;
+    pub use crate::cow::ThinCow;
+    pub use crate::error::ThinError;
+    pub use crate::guard::ThinGuard;
+    pub use crate::stack::ThinStack;
+    pub use crate::inline::InlineThin;
+    pub use crate::thin_stack;
+    pub use crate::statics::ThinStatic;
+    pub use crate::batch::{ThinBatch, ThinBatched};
+    pub use crate::pin::{PinnedDrop, ThinPin, ThinPinnedDrop};
+    pub use crate::static_thin_ref;
+    pub use crate::vec::ThinVec;
+    pub use crate::ffi::{ThinStr, ThinString, ThinSlice, ThinVecFfi};
+
+    pub use crate::extension::{Extension, ThinExtension, ThinExtensionSlot};
+    pub use crate::bridge::{DisplaySlot, DebugSlot, ErrorSlot};
+
+    #[cfg(feature = "any")]
+    pub use crate::downcast::{ThinAny, DowncastThin, DowncastThinSync};
+
+    #[cfg(feature = "stable_any")]
     pub use thin_trait_objects_macros::{
-        StableAny, impl_stable_any
+        StableAny, impl_stable_any, export_thin_plugin
     };
 
+    #[cfg(feature = "stable_any")]
+    pub use crate::plugin::ThinPluginDescriptor;
+    // `impl_stable_any!` and `#[derive(StableAny)]` expand to its unqualified name
+    #[cfg(feature = "stable_any")]
+    #[doc(hidden)]
+    pub use crate::__register_stable_any;
+
+    #[cfg(feature = "stable_any")]
     pub use crate::stable_any::{
-        UUID, StableAny, StableTypeId
+        UUID, ThinUUID, StableAny, StableTypeId, StableHasher, stable_id,
+        StableAnyRef, StableAnyMut, DowncastError
+    };
+}
+
+/// The items the code generated by the macros refers to, which aren't public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::drop_panic::drop_bundle;
+    pub use crate::batch::drop_batched_bundle;
+    pub use crate::pin::drop_pinned_bundle;
+    pub use crate::poison::{check_poison, check_dropped, check_dropped_as};
+    pub use crate::{alloc_bundle, cast_same, count_call, count_drop, count_new, dispatch, missing_instantiation, missing_slot, skipped_method};
+    pub use crate::checksum::{VTableChecksum, VTABLE_CHECKSUM};
+    pub use crate::raw::{RawBundle, RawVTableHeader};
+    pub use crate::expansion::{
+        ExpansionToken, Expanded, ExpandedVTable, ExpandedBatched, ExpandedExtensionSlot,
+        expanded_value, expanded_value_mut, expanded_thin, expanded_drop, expanded_pinned_drop, expanded_batched_drop,
+        expanded_seal, expanded_vtable, expanded_vtable_size, expanded_slot, expanded_load_slot, expanded_whole_vtable, expanded_cast,
     };
+    #[cfg(feature = "any")]
+    pub use crate::expansion::ExpandedAny;
+    #[cfg(feature = "stable_any")]
+    pub use crate::expansion::{ExpandedUUID, expanded_stable_id};
+    pub use crate::codegen::{TraitDescriptor, MethodDescriptor};
+    #[cfg(feature = "stable_any")]
+    pub use crate::{plugin::__into_raw, protocol::trait_abi_hash};
 }
 
 #[repr(transparent)]
 pub struct Thin<T: ?Sized + SpecialAssoc> {
-    // type-erased `*mut Bundle<K> where `K: F` and `T` is `dyn F`
+    // type-erased `*mut RawBundle<V, K>` where `K: F`, `T` is `dyn F` and `V` is its vtable
     pub ptr: NonNull<()>,
     phantom: PhantomData<T>,
 }
 
-unsafe impl<T: ?Sized + SpecialAssoc + Send> Send for Thin<T> {}
-unsafe impl<T: ?Sized + SpecialAssoc + Sync> Sync for Thin<T> {}
+/// Object types whose thin objects may be sent to other threads.
+///
+/// `#[thin]` implements it for `dyn Trait` if `Send` is a supertrait of the trait. It's safe to
+/// implement, as it requires `Self: Send`: a `dyn Trait` is only `Send` if every value it can be
+/// created from is.
+#[diagnostic::on_unimplemented(
+    message = "thin objects of `{Self}` can't be sent to other threads",
+    label = "`{Self}` isn't `ThinSend`",
+    note = "`#[thin]` traits need `Send` as a supertrait for their thin objects to be `Send`",
+)]
+pub trait ThinSend: SpecialAssoc + Send {}
+
+/// Object types whose thin objects may be shared between threads.
+///
+/// `#[thin]` implements it for `dyn Trait` if `Sync` is a supertrait of the trait. It's safe to
+/// implement, as it requires `Self: Sync`.
+#[diagnostic::on_unimplemented(
+    message = "thin objects of `{Self}` can't be shared between threads",
+    label = "`{Self}` isn't `ThinSync`",
+    note = "`#[thin]` traits need `Sync` as a supertrait for their thin objects to be `Sync`",
+)]
+pub trait ThinSync: SpecialAssoc + Sync {}
+
+// thread safety is opted into per object type rather than following from `T`, so that it's only
+// claimed where the constructors require it: for `dyn Trait`, by `Send` being a supertrait
+unsafe impl<T: ?Sized + ThinSend> Send for Thin<T> {}
+unsafe impl<T: ?Sized + ThinSync> Sync for Thin<T> {}
 
 impl<T: ?Sized + SpecialAssoc + 'static> Thin<T> {
     #[doc(hidden)]
@@ -105,25 +300,362 @@ impl<T: ?Sized + SpecialAssoc + 'static> Thin<T> {
     }
 }
 
+impl<T: ?Sized + SpecialAssoc<Kind = Own> + 'static> Thin<T> {
+    /// Wraps an existing `Box<dyn Trait>` in a thin object, for adopting thin objects at API
+    /// boundaries before the boxes are replaced. Calls go through both vtables, and the box is a
+    /// second allocation.
+    pub fn from_dyn(boxed: Box<T>) -> Thin<T> where Thin<T>: ThinExt<T, Box<T>> {
+        <Thin<T> as ThinExt<T, Box<T>>>::new(boxed)
+    }
+
+    /// Gives up ownership of the thin object, for passing it to C, which frees it with the trait's
+    /// `#[thin(c_free)]` destructor (or the vtable's drop slot).
+    pub fn into_c_void(self) -> *mut std::ffi::c_void {
+        std::mem::ManuallyDrop::new(self).ptr.as_ptr().cast()
+    }
+
+    /// Takes back ownership of a thin object given to C by [`into_c_void`](Self::into_c_void).
+    ///
+    /// # Safety
+    /// `ptr` must have been returned by `into_c_void` on a `Thin<T>`, and not be used afterwards.
+    pub unsafe fn from_c_void(ptr: *mut std::ffi::c_void) -> Thin<T> {
+        unsafe { Thin::from_raw(ptr.cast()) }
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc<Kind = Own> + SlotLayout + 'static> Thin<T> {
+    /// Assembles a thin object from a vtable and an object allocated separately (e.g. by a JIT, or
+    /// by C code implementing the trait's `FooVTable` from the generated header), by writing the
+    /// vtable pointer into the object's first word.
+    ///
+    /// # Safety
+    /// `vtable` must be laid out as `T`'s vtables are (see the `raw` module), and `object` must point to
+    /// writable storage starting with a pointer-sized slot for the vtable, followed by whatever the
+    /// vtable's methods expect, and be freed by its drop slot.
+    pub unsafe fn from_parts(vtable: &'static T::VTable, object: *mut std::ffi::c_void) -> Thin<T> {
+        unsafe { (object as *mut &'static T::VTable).write(vtable) };
+        unsafe { Thin::from_raw(object.cast()) }
+    }
+
+    /// Splits the thin object into its vtable and its object, giving up ownership as
+    /// [`into_c_void`](Self::into_c_void) does. The inverse of [`from_parts`](Self::from_parts).
+    ///
+    /// Panics if the vtable was created against an older version of the trait, so isn't a whole
+    /// `T::VTable`.
+    pub fn into_parts(self) -> (&'static T::VTable, *mut std::ffi::c_void) {
+        poison::check_poison(self.ptr.as_ptr());
+        assert!(self.has_slot(T::SLOTS.len() - 1), "the vtable was created against an older version of the trait");
+        let object = self.into_c_void();
+        (unsafe { *(object as *const &'static T::VTable) }, object)
+    }
+}
+
 pub trait ThinExt<U: ?Sized + SpecialAssoc +'static, T> {
     /// Creates a new `Thin<dyn _>` from the given value.
     fn new(val: T) -> Thin<U>;
 }
 
+/// Provides the vtable of `Thin<U>`s created from a `K`, for building their bundles in place, as
+/// [`ThinStack`](crate::stack::ThinStack) does.
+///
+/// `#[thin]` implements this alongside [`ThinExt`].
+///
+/// # Safety
+/// `vtable` must return a pointer to a `'static` vtable of `U` for `K`, whose bundles are a
+/// `#[repr(C)]` struct of the pointer followed by the value.
+pub unsafe trait ThinVTable<U: ?Sized + SpecialAssoc + 'static, K> {
+    fn vtable() -> *const ();
+}
+
+/// Creates a `Thin<U>` from a closure, which stands in for the only method of the trait.
+///
+/// `#[thin]` implements this for traits with a single method, provided the method has no explicit
+/// lifetimes, doesn't return a borrow, and the trait's only supertraits are `'static`, `Send` and `Sync`.
+/// The closure is `Fn` for `&self` methods and `FnMut` for `&mut self` methods.
+pub trait ThinFromFn<U: ?Sized + SpecialAssoc + 'static, F> {
+    fn from_fn(f: F) -> Thin<U>;
+}
+
+//========================//
+// cloning without a clone slot in every vtable
+
+/// Clones the value behind a `Thin<T>`, which must be of the concrete type the function was created for.
+pub type CloneFn<T> = extern "C" fn(RefSelf<'_>) -> Thin<T>;
+
+/// Provides the [`CloneFn`] for values of type `K` behind a `Thin<U>`.
+///
+/// `#[thin]` implements this for every `K: Clone` implementing the trait.
+pub trait ThinClone<U: ?Sized + SpecialAssoc + 'static, K> {
+    fn clone_fn() -> CloneFn<U>;
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Thin<T> {
+    /// Returns the function used by [`Thin::clone_with`] to clone a `K`.
+    pub fn clone_fn<K>() -> CloneFn<T> where Thin<T>: ThinClone<T, K> {
+        <Thin<T> as ThinClone<T, K>>::clone_fn()
+    }
+
+    /// Clones the erased value using `f`.
+    ///
+    /// # Safety
+    /// `f` must have been created for the concrete type of the erased value, e.g. with [`Thin::clone_fn`].
+    pub unsafe fn clone_with(&self, f: CloneFn<T>) -> Thin<T> {
+        poison::check_poison(self.ptr.as_ptr());
+        f(RefSelf::new(self))
+    }
+}
+
+//========================//
+// raw vtable access, for code generators emitting calls without going through the trait
+
+/// The vtable layout of `Thin<Self>`. `#[thin]` implements this for `dyn Trait`,
+/// along with a `SLOT_<METHOD>` constant on `dyn Trait` for the index of each method.
+pub trait SlotLayout {
+    /// The names of the slots, in order. Slot `0` is always `drop`.
+    const SLOTS: &'static [&'static str];
+
+    /// The slot holding the size of the vtable in bytes, if it records one.
+    ///
+    /// `#[thin]` vtables record their size in slot `1`, so that callers can detect vtables created
+    /// against an older version of the trait, which lack its trailing methods.
+    const SIZE_SLOT: Option<usize> = None;
+
+    /// The slot holding the checksum of the vtable, with the `debug-checksum` feature enabled.
+    const CHECKSUM_SLOT: Option<usize> = None;
+
+    /// The struct the vtables are, which `#[thin]` declares without naming it (the generated C
+    /// header calls it `FooVTable`).
+    type VTable: 'static;
+}
+
+/// Moves `bundle` to the heap for a `ThinExt::new` impl, reusing an allocation kept by the current
+/// thread with the `recycle` feature.
+#[doc(hidden)]
+pub fn alloc_bundle<B>(bundle: B) -> *mut () {
+    #[cfg(feature = "recycle")]
+    return recycle::alloc_bundle(bundle);
+    #[cfg(not(feature = "recycle"))]
+    return Box::into_raw(Box::new(bundle)) as *mut ();
+}
+
+/// Counts a call through a vtable slot, with the `profile` feature.
+#[doc(hidden)]
+#[inline(always)]
+pub fn count_call<T: 'static>(trait_name: &'static str, method: &'static str) {
+    #[cfg(feature = "profile")]
+    profile::count_call::<T>(trait_name, method);
+    #[cfg(not(feature = "profile"))]
+    let _ = (trait_name, method);
+}
+
+/// Counts a thin object allocated for a trait, with the `stats` feature.
+#[doc(hidden)]
+#[inline(always)]
+pub fn count_new<T: 'static>(trait_name: &'static str) {
+    #[cfg(feature = "stats")]
+    stats::count_new::<T>(trait_name);
+    #[cfg(not(feature = "stats"))]
+    let _ = trait_name;
+}
+
+/// Counts a thin object of a trait dropped, with the `stats` feature.
+#[doc(hidden)]
+#[inline(always)]
+pub fn count_drop<T: 'static>(trait_name: &'static str) {
+    #[cfg(feature = "stats")]
+    stats::count_drop::<T>(trait_name);
+    #[cfg(not(feature = "stats"))]
+    let _ = trait_name;
+}
+
+/// Calls a method from its `extern "C"` shim, where a panic aborts with an erased call stack, so
+/// that the panic is raised again naming the trait, the method and the concrete type `T`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn dispatch<T: ?Sized, R>(trait_name: &'static str, method: &'static str, f: impl FnOnce() -> R) -> R {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(ret) => ret,
+        Err(payload) => dispatch_panicked(trait_name, method, std::any::type_name::<T>(), &*payload),
+    }
+}
+
+#[cold]
+fn dispatch_panicked(trait_name: &str, method: &str, ty: &str, payload: &(dyn std::any::Any + Send)) -> ! {
+    let message = drop_panic::panic_message(payload);
+    panic!("{} (in `{}::{}` on a `{}`)", message, trait_name, method, ty)
+}
+
+/// Panics on a call to a `#[thin(instantiate(...))]` method with a type it wasn't instantiated for.
+#[doc(hidden)]
+#[cold]
+#[track_caller]
+pub fn missing_instantiation(trait_name: &str, method: &str, ty: &str) -> ! {
+    panic!("`{}::{}` isn't instantiated for `{}`", trait_name, method, ty)
+}
+
+/// Panics on a call to a `#[thin(skip)]` method without a default body, through a type which can't
+/// dispatch it.
+#[doc(hidden)]
+#[cold]
+#[track_caller]
+pub fn skipped_method(trait_name: &str, method: &str) -> ! {
+    panic!("`{}::{}` is `#[thin(skip)]`, so can't be called on a thin object", trait_name, method)
+}
+
+/// Converts between two types for calls to instantiations of generic methods.
+///
+/// # Safety
+/// `A` and `B` must be the same type.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn cast_same<A, B>(a: A) -> B {
+    let a = std::mem::ManuallyDrop::new(a);
+    unsafe { std::mem::transmute_copy(&*a) }
+}
+
+/// Panics on a call to a method missing from a vtable created against an older version of its trait.
+#[doc(hidden)]
+#[cold]
+#[track_caller]
+pub fn missing_slot(trait_name: &str, method: &str) -> ! {
+    panic!(
+        "the vtable of this `Thin<dyn {}>` has no slot for `{}`, and was likely created against an older version of the trait",
+        trait_name, method,
+    )
+}
+
+/// A tuple of arguments passed to a slot by [`Thin::call_slot`], after the receiver.
+pub trait SlotArgs {
+    /// Calls `slot` as an `extern "C" fn(recv, args...) -> Ret`.
+    ///
+    /// # Safety
+    /// `slot` must be a function with that signature.
+    unsafe fn call<Ret>(self, slot: *const (), recv: *mut ()) -> Ret;
+}
+
+macro_rules! impl_slot_args {
+    ($($arg: ident),*) => {
+        impl<$($arg),*> SlotArgs for ($($arg,)*) {
+            #[allow(non_snake_case)]
+            unsafe fn call<Ret>(self, slot: *const (), recv: *mut ()) -> Ret {
+                let f = unsafe { std::mem::transmute::<*const (), extern "C" fn(*mut (), $($arg),*) -> Ret>(slot) };
+                let ($($arg,)*) = self;
+                f(recv, $($arg),*)
+            }
+        }
+    };
+}
+
+impl_slot_args!();
+impl_slot_args!(A);
+impl_slot_args!(A, B);
+impl_slot_args!(A, B, C);
+impl_slot_args!(A, B, C, D);
+impl_slot_args!(A, B, C, D, E);
+impl_slot_args!(A, B, C, D, E, F);
+
+impl<T: ?Sized + SpecialAssoc + SlotLayout + 'static> Thin<T> {
+    /// Calls the method in vtable slot `index` with a `&self` receiver.
+    ///
+    /// Panics if `index` is out of bounds, the size slot, or missing from the vtable (see [`Thin::has_slot`]).
+    ///
+    /// # Safety
+    /// The slot must take a `&self` receiver followed by `args`, and return `Ret`, after the erasure
+    /// `#[thin]` performs on its method (e.g. slices are split into a pointer and a length).
+    /// `index` must not be `0`, the drop slot.
+    pub unsafe fn call_slot<Args: SlotArgs, Ret>(&self, index: usize, args: Args) -> Ret {
+        let slot = self.slot(index);
+        unsafe { args.call(slot, self.ptr.as_ptr()) }
+    }
+
+    /// Calls the method in vtable slot `index` with a `&mut self` receiver.
+    ///
+    /// Panics under the same conditions as [`Thin::call_slot`].
+    ///
+    /// # Safety
+    /// As for [`Thin::call_slot`].
+    pub unsafe fn call_slot_mut<Args: SlotArgs, Ret>(&mut self, index: usize, args: Args) -> Ret {
+        let slot = self.slot(index);
+        unsafe { args.call(slot, self.ptr.as_ptr()) }
+    }
+
+    /// Returns the size in bytes of the vtable's header and method slots, as recorded by whoever
+    /// created it, if it records one.
+    pub fn vtable_size(&self) -> Option<usize> {
+        let slot = T::SIZE_SLOT?;
+        let vtable = self.vtable_ptr() as *const usize;
+        Some(unsafe { *vtable.add(slot) })
+    }
+
+    /// Returns `true` if the vtable has slot `index`, which may not be the case for vtables created
+    /// against an older version of the trait.
+    pub fn has_slot(&self, index: usize) -> bool {
+        match self.vtable_size() {
+            Some(size) => (index + 1) * size_of::<*const ()>() <= size,
+            None => index < T::SLOTS.len(),
+        }
+    }
+
+    fn slot(&self, index: usize) -> *const () {
+        assert!(index < T::SLOTS.len(), "slot {} out of bounds for a vtable of {} slots", index, T::SLOTS.len());
+        assert!(Some(index) != T::SIZE_SLOT, "slot {} holds the size of the vtable, not a method", index);
+        assert!(Some(index) != T::CHECKSUM_SLOT, "slot {} holds the checksum of the vtable, not a method", index);
+        if let (Some(size), Some(checksum)) = (self.vtable_size(), T::CHECKSUM_SLOT) {
+            let vtable = self.vtable_ptr();
+            let checksum = unsafe { *((vtable as *const usize).add(checksum) as *const checksum::VTableChecksum) };
+            unsafe { checksum.verify(vtable, size) };
+        }
+        assert!(self.has_slot(index), "slot {} is missing from a vtable created against an older version of the trait", index);
+        let vtable = self.vtable_ptr() as *const *const ();
+        unsafe { *vtable.add(index) }
+    }
+}
+
+//========================//
+// concrete type comparisons
+
+impl<T: ?Sized + SpecialAssoc + 'static> Thin<T> {
+    /// Returns `true` if `a` and `b` share a vtable, which implies their values are of the same concrete type.
+    ///
+    /// The converse doesn't hold: vtables aren't shared across FFI boundaries (and may be duplicated
+    /// between codegen units), so values of the same type can have different vtables.
+    pub fn same_vtable(a: &Self, b: &Self) -> bool {
+        ptr::eq(a.vtable_ptr(), b.vtable_ptr())
+    }
+
+    /// Returns `true` if the values of `a` and `b` are of the same concrete type.
+    ///
+    /// Falls back to comparing stored type ids (e.g. for `Thin<dyn Any>`) if the vtables differ.
+    pub fn is_same_type(a: &Self, b: &Self) -> bool {
+        Thin::same_vtable(a, b) || T::same_type(a.ptr, b.ptr)
+    }
+
+    fn vtable_ptr(&self) -> *const () {
+        poison::check_poison(self.ptr.as_ptr());
+        // SAFETY: every bundle starts with a pointer to its vtable
+        unsafe { *(self.ptr.as_ptr() as *const *const ()) }
+    }
+}
+
 //========================//
 // impls to avoid double-indirection
 // `&Thin<_>` or `&mut Thin<_>`
 
 
 impl<T: ?Sized + SpecialAssoc + 'static> Thin<T> {
-    fn as_ref(&self) -> Thin<&T> {
+    /// Borrows the thin object as a one-pointer `Thin<&T>`, which derefs to `Thin<T>`, so that the
+    /// trait's methods may be called on it directly.
+    pub fn as_ref(&self) -> Thin<&T> {
         Thin {
             ptr: self.ptr,
             phantom: PhantomData
         }
     }
 
-    fn as_mut(&mut self) -> Thin<&mut T> {
+    /// Mutably borrows the thin object as a one-pointer `Thin<&mut T>`, which derefs to a [`ThinMut`].
+    ///
+    /// See [`Thin::as_ref`].
+    pub fn as_mut(&mut self) -> Thin<&mut T> {
         Thin {
             ptr: self.ptr,
             phantom: PhantomData
@@ -131,8 +663,8 @@ impl<T: ?Sized + SpecialAssoc + 'static> Thin<T> {
     }
 }
 
-impl<T: ?Sized + SpecialAssoc + 'static> Thin<&T> {
-    fn copy(&self) -> Thin<&T> {
+impl<T: ?Sized + SpecialAssoc + 'static> Clone for Thin<&T> {
+    fn clone(&self) -> Self {
         Thin {
             ptr: self.ptr,
             phantom: PhantomData
@@ -147,16 +679,47 @@ impl<T: ?Sized + SpecialAssoc + 'static> Deref for Thin<&T> {
     }
 }
 
-impl<T: ?Sized + SpecialAssoc + 'static> Deref for Thin<&mut T> {
+/// A mutably borrowed thin object, which `Thin<&mut T>` derefs to.
+///
+/// `#[thin]` implements the trait for `ThinMut<dyn Trait>`, and it derefs to `Thin<T>` for
+/// everything else taking `&self`. Unlike `&mut Thin<T>`, a `&mut ThinMut<T>` can't be used to
+/// replace the borrowed object, as `ThinMut` is unsized.
+#[repr(transparent)]
+pub struct ThinMut<T: ?Sized + SpecialAssoc> {
+    // always a single element: the `Thin<&mut T>` the view was borrowed from
+    thin: [Thin<T>],
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> ThinMut<T> {
+    /// Mutably borrows the object as a `Thin<T>`.
+    ///
+    /// # Safety
+    ///
+    /// The `Thin<T>` mustn't be replaced (e.g. with `std::mem::swap`), as it doesn't own the object.
+    pub unsafe fn as_thin_mut(&mut self) -> &mut Thin<T> {
+        &mut self.thin[0]
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Deref for ThinMut<T> {
     type Target = Thin<T>;
     fn deref(&self) -> &Self::Target {
-        unsafe { &*(self as *const Thin<&mut T> as *const Thin<T>) }
+        &self.thin[0]
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Deref for Thin<&mut T> {
+    type Target = ThinMut<T>;
+    fn deref(&self) -> &Self::Target {
+        let thin = self as *const Thin<&mut T> as *const Thin<T>;
+        unsafe { &*(std::ptr::slice_from_raw_parts(thin, 1) as *const ThinMut<T>) }
     }
 }
 
 impl<T: ?Sized + SpecialAssoc + 'static> DerefMut for Thin<&mut T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut *(self as *mut Thin<&mut T> as *mut Thin<T>) }
+        let thin = self as *mut Thin<&mut T> as *mut Thin<T>;
+        unsafe { &mut *(std::ptr::slice_from_raw_parts_mut(thin, 1) as *mut ThinMut<T>) }
     }
 }
 
@@ -170,6 +733,12 @@ pub struct Mut;
 
 pub trait SpecialAssoc: SpecialParam<Self::Kind> {
     type Kind;
+
+    /// Compares the type ids stored in two bundles, for object types which store them.
+    #[doc(hidden)]
+    fn same_type(_a: NonNull<()>, _b: NonNull<()>) -> bool {
+        false
+    }
 }
 
 pub trait SpecialParam<K> {
@@ -187,9 +756,11 @@ impl<T: ?Sized + 'static> SpecialAssoc for T {
 
 impl<T: ?Sized + SpecialAssoc + 'static> SpecialParam<Own> for T {
     fn drop(ptr: NonNull<()>) {
-        // SAFETY: `Bundle` and `VTable` are `#[repr(C)]`,
-        // so the `drop` field of `VTable` will be positioned first in the memory layout of `Bundle`.
-        let dropper: extern "C" fn(*mut ()) = unsafe { *ptr.as_ptr().cast() };
+        poison::check_dropped(ptr.as_ptr(), "double drop");
+        // SAFETY: `Bundle` and `VTable` are `#[repr(C)]`, so `Bundle` starts with a pointer
+        // to its `VTable`, and the `drop` field will be positioned first in the memory layout of `VTable`.
+        let vtable: *const extern "C-unwind" fn(*mut ()) = unsafe { *ptr.as_ptr().cast() };
+        let dropper = unsafe { *vtable };
         dropper(ptr.as_ptr());
     }
 }
@@ -198,23 +769,33 @@ impl<T: ?Sized + SpecialAssoc + 'static> SpecialParam<Own> for T {
 
 impl<T: ?Sized + SpecialAssoc + 'static> SpecialAssoc for &T {
     type Kind = Ref;
+    fn same_type(a: NonNull<()>, b: NonNull<()>) -> bool {
+        T::same_type(a, b)
+    }
 }
 impl<T: ?Sized + SpecialAssoc + 'static> SpecialParam<Ref> for &T {
     fn drop(ptr: NonNull<()>) {
         // we dont own the pointed-to value
     }
 }
+impl<T: ?Sized + ThinSync + 'static> ThinSend for &T {}
+impl<T: ?Sized + ThinSync + 'static> ThinSync for &T {}
 
 // &mut T
 
 impl<T: ?Sized + SpecialAssoc + 'static> SpecialAssoc for &mut T {
     type Kind = Mut;
+    fn same_type(a: NonNull<()>, b: NonNull<()>) -> bool {
+        T::same_type(a, b)
+    }
 }
 impl<T: ?Sized + SpecialAssoc + 'static> SpecialParam<Mut> for &mut T {
     fn drop(ptr: NonNull<()>) {
         // we dont own the pointed-to value
     }
 }
+impl<T: ?Sized + ThinSend + 'static> ThinSend for &mut T {}
+impl<T: ?Sized + ThinSync + 'static> ThinSync for &mut T {}
 
 // Drop
 
@@ -262,6 +843,7 @@ impl<'a> MutSelf<'a> {
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use crate::__private::*;
 
     #[thin]
     trait Foo: 'static {
@@ -303,6 +885,659 @@ mod tests {
         fn self_lifetime<'a>(&'a self) -> &'a u8;
         fn elided_self_lifetime<'a>(&self, other: &'a u8) -> &'a u8;
         fn more_lifetimes<'a, 'b>(&'a self, other: &'b u8) -> &'b u8;
+        fn elided_lifetimes(&self, a: &u8, b: Option<&u8>) -> &u8;
+    }
+
+    fn add_one<F: Foo>(foo: &mut F) -> u8 {
+        foo.add(1);
+        *foo.get()
+    }
+
+    #[test]
+    fn clone_with() {
+        let thin = Thin::<dyn Foo>::new(8u8);
+        let mut clone = unsafe { thin.clone_with(Thin::<dyn Foo>::clone_fn::<u8>()) };
+        clone.add(1);
+
+        assert_eq!(*thin.get(), 8u8);
+        assert_eq!(*clone.get(), 9u8);
+    }
+
+    #[test]
+    fn delegation() {
+        let mut boxed: Box<dyn Foo> = Box::new(8u8);
+        assert_eq!(add_one(&mut boxed), 9u8);
+
+        let mut boxed_thin = Box::new(Thin::<dyn Foo>::new(8u8));
+        assert_eq!(add_one(&mut boxed_thin), 9u8);
+
+        fn read<F: ReadOnly>(foo: &F) -> u8 {
+            foo.read()
+        }
+
+        assert_eq!(read(&Box::new(Thin::<dyn ReadOnly>::new(8u8))), 8u8);
+
+        // shared handles, without dereferencing to the thin object first
+        #[allow(clippy::arc_with_non_send_sync)]
+        let shared = std::sync::Arc::new(Thin::<dyn ReadOnly>::new(8u8));
+        assert_eq!(read(&shared), 8u8);
+        assert_eq!(read(&std::rc::Rc::new(Thin::<dyn ReadOnly>::new(8u8))), 8u8);
+
+        // `Arc<K>` only implements traits which are `Send` but not `Sync` if `K` is `Sync`
+        fn peek<F: Peek>(peek: &F) -> u32 {
+            peek.peek()
+        }
+        assert_eq!(peek(&Thin::<dyn Peek>::new(4u32)), 4);
+        assert_eq!(peek(&std::sync::Arc::new(4u32)), 4);
+    }
+
+    #[thin]
+    trait Peek: Send + 'static {
+        fn peek(&self) -> u32;
+    }
+
+    impl Peek for u32 {
+        fn peek(&self) -> u32 {
+            *self
+        }
+    }
+
+    #[test]
+    fn call_slot() {
+        // with the `debug-checksum` feature, the checksum slot follows the size
+        let header = if VTABLE_CHECKSUM { 3 } else { 2 };
+        assert_eq!(<dyn Foo as SlotLayout>::SLOTS[..2], ["drop", "size"]);
+        assert_eq!(<dyn Foo as SlotLayout>::SLOTS[header..], ["add", "get"]);
+        assert_eq!(<dyn Foo>::SLOT_ADD, header);
+
+        let mut thin = Thin::<dyn Foo>::new(8u8);
+        unsafe { thin.call_slot_mut::<_, ()>(<dyn Foo>::SLOT_ADD, (1u8,)) };
+        let val: &u8 = unsafe { thin.call_slot(<dyn Foo>::SLOT_GET, ()) };
+        assert_eq!(*val, 9u8);
+    }
+
+    #[thin]
+    trait Edges: 'static {
+        fn reset(&mut self) -> ();
+        fn fail(&self) -> !;
+        fn get(&self) -> u8;
+    }
+
+    impl Edges for u8 {
+        #[allow(clippy::unused_unit)]
+        fn reset(&mut self) -> () {
+            *self = 0;
+        }
+        fn fail(&self) -> ! {
+            panic!("failed with {}", self)
+        }
+        fn get(&self) -> u8 {
+            *self
+        }
+    }
+
+    #[test]
+    fn unit_and_never_returns() {
+        let mut thin = Thin::<dyn Edges>::new(8u8);
+        thin.reset();
+        let _: fn(&Thin<dyn Edges>) -> ! = Edges::fail;
+        assert_eq!(thin.get(), 0);
+
+        // panicking out of a shim aborts, so the diverging method is only called through a `Box`
+        let boxed = Box::new(3u8);
+        let result = std::panic::catch_unwind(|| boxed.fail());
+        assert!(result.is_err());
+    }
+
+    // spelled out on purpose, in every form
+    #[allow(clippy::needless_arbitrary_self_type, clippy::needless_lifetimes)]
+    mod typed {
+        use crate::prelude::*;
+
+        #[thin]
+        pub trait Typed: 'static {
+            fn len(self: &Self) -> usize;
+            fn push(self: &mut Self, value: u32);
+            fn first<'a>(self: &'a Self) -> &'a u32;
+            fn last<'a>(self: &'a mut Self) -> &'a mut u32;
+        }
+
+        impl Typed for Vec<u32> {
+            fn len(self: &Self) -> usize {
+                Vec::len(self)
+            }
+            fn push(self: &mut Self, value: u32) {
+                Vec::push(self, value)
+            }
+            fn first<'a>(self: &'a Self) -> &'a u32 {
+                &self[0]
+            }
+            fn last<'a>(self: &'a mut Self) -> &'a mut u32 {
+                self.last_mut().unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn typed_receivers() {
+        use typed::Typed;
+
+        let mut thin = Thin::<dyn Typed>::new(vec![1u32]);
+        Typed::push(&mut thin, 2);
+        *Typed::last(&mut thin) += 10;
+        assert_eq!(Typed::len(&thin), 2);
+        assert_eq!(*Typed::first(&thin), 1);
+        assert_eq!(*Typed::last(&mut thin), 12);
+    }
+
+    #[thin]
+    trait Handler: Send + 'static {
+        fn handle(&mut self, request: &u32) -> u32;
+    }
+
+    struct Doubler;
+
+    impl Handler for Doubler {
+        fn handle(&mut self, request: &u32) -> u32 {
+            request * 2
+        }
+    }
+
+    #[thin]
+    trait Factory: 'static {
+        fn make(&self) -> u64;
+    }
+
+    #[test]
+    fn from_fn() {
+        let mut total = 0;
+        let mut handler = Thin::<dyn Handler>::from_fn(move |request: &u32| {
+            total += *request;
+            total
+        });
+        assert_eq!(handler.handle(&2), 2);
+        assert_eq!(handler.handle(&3), 5);
+
+        let factory = Thin::<dyn Factory>::from_fn(|| 7);
+        assert_eq!(factory.make(), 7);
+    }
+
+    #[test]
+    fn from_dyn() {
+        let boxed: Box<dyn Handler> = Box::new(Doubler);
+        let mut handler = Thin::<dyn Handler>::from_dyn(boxed);
+        assert_eq!(handler.handle(&4), 8);
+        assert_eq!(size_of_val(&handler), size_of::<*const ()>());
+    }
+
+    #[thin(fn_impl)]
+    trait Scale: Send + Sync + 'static {
+        fn scale(&self, x: u32) -> u32;
+    }
+
+    fn triple(x: u32) -> u32 {
+        x * 3
+    }
+
+    #[test]
+    fn fn_impl() {
+        let factor = 2;
+        let double = Thin::<dyn Scale>::new(move |x| x * factor);
+        assert_eq!(double.scale(4), 8);
+
+        let triple = Thin::<dyn Scale>::new(triple as fn(u32) -> u32);
+        assert_eq!(triple.scale(4), 12);
+
+        // `Box<F>` is covered by the impl for closures
+        let boxed: Box<dyn Fn(u32) -> u32 + Send + Sync> = Box::new(|x| x + 1);
+        assert_eq!(boxed.scale(4), 5);
+    }
+
+    #[thin]
+    trait Voice: 'static {
+        fn note_on(&mut self, pitch: u8);
+        fn note_off(&mut self);
+        #[thin(hot)]
+        fn sample(&mut self) -> f32;
+    }
+
+    impl Voice for f32 {
+        fn note_on(&mut self, pitch: u8) {
+            *self = pitch as f32;
+        }
+        fn note_off(&mut self) {
+            *self = 0.0;
+        }
+        fn sample(&mut self) -> f32 {
+            *self
+        }
+    }
+
+    #[test]
+    fn hot_slots() {
+        let slots = <dyn Voice as SlotLayout>::SLOTS;
+        assert_eq!(slots[slots.len() - 3..], ["sample", "note_on", "note_off"]);
+        assert_eq!(<dyn Voice>::SLOT_SAMPLE, slots.len() - 3);
+
+        let mut voice = Thin::<dyn Voice>::new(0.0f32);
+        voice.note_on(60);
+        assert_eq!(voice.sample(), 60.0);
+        let sample: f32 = unsafe { voice.call_slot_mut(<dyn Voice>::SLOT_SAMPLE, ()) };
+        assert_eq!(sample, 60.0);
+        voice.note_off();
+        assert_eq!(voice.sample(), 0.0);
+    }
+
+    #[thin]
+    trait Store: 'static {
+        #[thin(instantiate(T = u8, String))]
+        fn put<T>(&mut self, key: u32, value: &T) -> usize;
+        fn len(&self) -> usize;
+    }
+
+    #[derive(Default)]
+    struct Entries(Vec<String>);
+
+    impl Store for Entries {
+        fn put<T>(&mut self, key: u32, value: &T) -> usize {
+            self.0.push(format!("{}: {}", key, std::any::type_name::<T>()));
+            size_of_val(value)
+        }
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    #[test]
+    fn instantiations() {
+        let slots = <dyn Store as SlotLayout>::SLOTS;
+        assert_eq!(slots[slots.len() - 3..], ["put__u8", "put__String", "len"]);
+
+        let mut store = Thin::<dyn Store>::new(Entries::default());
+        assert_eq!(store.put(1, &8u8), 1);
+        assert_eq!(store.put(2, &String::from("value")), size_of::<String>());
+        let put: usize = unsafe { store.call_slot_mut(<dyn Store>::SLOT_PUT__U8, (3u32, &9u8)) };
+        assert_eq!(put, 1);
+        assert_eq!(store.len(), 3);
+
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| store.put(4, &1u16))).unwrap_err();
+        assert_eq!(err.downcast_ref::<String>().unwrap(), "`Store::put` isn't instantiated for `u16`");
+    }
+
+    #[thin]
+    trait Gain: 'static {
+        fn apply(&self, value: u32) -> u64;
+        #[thin(skip)]
+        fn apply_all<I: IntoIterator<Item = u32>>(&self, values: I) -> Vec<u64> {
+            values.into_iter().map(|value| self.apply(value)).collect()
+        }
+        #[thin(skip)]
+        fn name(&self) -> String;
+    }
+
+    struct Twice;
+
+    impl Gain for Twice {
+        fn apply(&self, value: u32) -> u64 {
+            value as u64 * 2
+        }
+        fn name(&self) -> String {
+            "twice".into()
+        }
+    }
+
+    #[test]
+    fn skipped_methods() {
+        assert_eq!(<dyn Gain as SlotLayout>::SLOTS.last(), Some(&"apply"));
+
+        let scale = Thin::<dyn Gain>::new(Twice);
+        assert_eq!(scale.apply_all([1, 2]), [2, 4]);
+        assert_eq!(Box::new(Twice).name(), "twice");
+
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| scale.name())).unwrap_err();
+        assert_eq!(err.downcast_ref::<String>().unwrap(), "`Gain::name` is `#[thin(skip)]`, so can't be called on a thin object");
+    }
+
+    #[test]
+    fn dispatch_context() {
+        assert_eq!(dispatch::<Twice, _>("Gain", "apply", || 4), 4);
+
+        let err = std::panic::catch_unwind(|| dispatch::<Twice, u32>("Gain", "apply", || panic!("overflow"))).unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert_eq!(message, &format!("overflow (in `Gain::apply` on a `{}`)", std::any::type_name::<Twice>()));
+    }
+
+    // two versions of a plugin interface, the second of which adds a method
+    mod v1 {
+        use crate::prelude::*;
+
+        #[thin]
+        pub trait Plugin: 'static {
+            fn id(&self) -> u32;
+        }
+
+        // with fields after the method slots
+        #[cfg(feature = "any")]
+        #[thin(any)]
+        pub trait Described: std::fmt::Display + 'static {
+            fn id(&self) -> u32;
+        }
+    }
+
+    mod v2 {
+        use crate::prelude::*;
+
+        #[thin]
+        pub trait Plugin: 'static {
+            fn id(&self) -> u32;
+            fn version(&self) -> u32;
+        }
+
+        #[cfg(feature = "any")]
+        #[thin(any)]
+        pub trait Described: std::fmt::Display + 'static {
+            fn id(&self) -> u32;
+            fn version(&self) -> u32;
+        }
+    }
+
+    impl v1::Plugin for u32 {
+        fn id(&self) -> u32 {
+            *self
+        }
+    }
+
+    #[test]
+    fn older_vtables() {
+        let old = Thin::<dyn v1::Plugin>::new(7u32);
+        assert_eq!(old.vtable_size(), Some((<dyn v1::Plugin>::SLOT_ID + 1) * size_of::<usize>()));
+
+        // as if a plugin built against v1 were loaded by a host built against v2
+        let ptr = old.ptr.as_ptr();
+        std::mem::forget(old);
+        let new = unsafe { Thin::<dyn v2::Plugin>::from_raw(ptr) };
+
+        assert_eq!(v2::Plugin::id(&new), 7);
+        assert!(new.has_slot(<dyn v2::Plugin>::SLOT_ID));
+        assert!(!new.has_slot(<dyn v2::Plugin>::SLOT_VERSION));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| v2::Plugin::version(&new)));
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "any")]
+    impl v1::Described for u32 {
+        fn id(&self) -> u32 {
+            *self
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "any")]
+    fn older_vtables_with_trailing_fields() {
+        let old = Thin::<dyn v1::Described>::new(7u32);
+        assert_eq!(old.to_string(), "7");
+
+        let ptr = old.ptr.as_ptr();
+        std::mem::forget(old);
+        let new = unsafe { Thin::<dyn v2::Described>::from_raw(ptr) };
+
+        // the bridge and the type id follow the slots, where the new version's slot for `version` is
+        assert_eq!(v2::Described::id(&new), 7);
+        assert!(!new.has_slot(<dyn v2::Described>::SLOT_VERSION));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| v2::Described::version(&new)));
+        assert!(result.is_err());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| new.to_string()));
+        assert!(result.is_err());
+        let result = std::panic::catch_unwind(|| <dyn v2::Described as ThinAny>::erased_type_id(new.ptr));
+        assert!(result.is_err());
+    }
+
+    // the same interface, as seen by foreign code and by Rust code built along with the plugin
+    mod foreign {
+        use crate::prelude::*;
+
+        #[thin]
+        pub trait Sum: 'static {
+            fn sum(&self, x: u32) -> u32;
+        }
+    }
+
+    mod native {
+        use crate::prelude::*;
+
+        #[thin(dual_abi)]
+        pub trait Sum: 'static {
+            fn sum(&self, x: u32) -> u32;
+        }
+    }
+
+    impl native::Sum for u32 {
+        fn sum(&self, x: u32) -> u32 {
+            *self + x
+        }
+    }
+
+    extern "C" fn wrong_sum(_: RefSelf<'_>, _: u32) -> u32 {
+        0
+    }
+
+    #[test]
+    fn dual_abi() {
+        let thin = Thin::<dyn native::Sum>::new(1u32);
+        assert_eq!(native::Sum::sum(&thin, 2), 3);
+        let val: u32 = unsafe { thin.call_slot(<dyn native::Sum>::SLOT_SUM, (2u32,)) };
+        assert_eq!(val, 3);
+
+        // a copy of the vtable whose `extern "C"` entry is wrong, which is only called once the
+        // Rust-ABI entries are missing, or attributed to another compilation
+        let vtable = unsafe { *(thin.ptr.as_ptr() as *const *const usize) };
+        let size = thin.vtable_size().unwrap();
+        let rust = size / size_of::<usize>();
+        let mut copy = unsafe { std::slice::from_raw_parts(vtable, rust + 1) }.to_vec();
+        copy[<dyn native::Sum>::SLOT_SUM] = wrong_sum as extern "C" fn(RefSelf<'_>, u32) -> u32 as usize;
+        // which is sealed again, as the checksum covers the slots
+        #[cfg(feature = "debug-checksum")]
+        {
+            let checksum = unsafe { crate::checksum::VTableChecksum::of(copy.as_ptr() as *const (), size) };
+            unsafe { *(copy.as_mut_ptr().add(2) as *mut crate::checksum::VTableChecksum) = checksum };
+        }
+        let bundle = thin.ptr.as_ptr() as *mut *const usize;
+        unsafe { *bundle = copy.as_ptr() };
+        assert_eq!(native::Sum::sum(&thin, 2), 3);
+        let mut entries = unsafe { std::slice::from_raw_parts(copy[rust] as *const usize, 2) }.to_vec();
+        entries[0] = 1;
+        copy[rust] = entries.as_ptr() as usize;
+        unsafe { *bundle = copy.as_ptr() };
+        assert_eq!(native::Sum::sum(&thin, 2), 0);
+
+        // vtables created by foreign code leave the pointer to the Rust-ABI entries null
+        copy[rust] = 0;
+        unsafe { *bundle = copy.as_ptr() };
+        assert_eq!(native::Sum::sum(&thin, 2), 0);
+        unsafe { *bundle = vtable };
+
+        assert_eq!(<dyn native::Sum as SlotLayout>::SLOTS, <dyn foreign::Sum as SlotLayout>::SLOTS);
+    }
+
+    #[thin]
+    trait Gauge: 'static {
+        fn level(&self) -> u32;
+    }
+
+    // a vtable and object built by hand, as foreign code would from the generated header
+    #[repr(C)]
+    struct GaugeVTable {
+        header: crate::raw::RawVTableHeader,
+        level: extern "C" fn(RefSelf<'_>) -> u32,
+    }
+
+    #[repr(C)]
+    struct Tank {
+        vtable: *const (),
+        litres: u32,
+    }
+
+    extern "C-unwind" fn drop_tank(ptr: *mut ()) {
+        drop(unsafe { Box::from_raw(ptr as *mut Tank) });
+    }
+
+    extern "C" fn tank_level(recv: RefSelf<'_>) -> u32 {
+        unsafe { (*(recv.ptr as *const Tank)).litres }
+    }
+
+    static TANK_VTABLE: GaugeVTable = GaugeVTable {
+        header: crate::raw::RawVTableHeader::new(drop_tank, size_of::<GaugeVTable>()),
+        level: tank_level,
+    };
+
+    #[test]
+    fn from_parts() {
+        // the generated vtable struct can't be named, so the hand-written one is cast to it
+        let sealed = unsafe { crate::raw::RawVTableHeader::seal(&TANK_VTABLE) };
+        let vtable = unsafe { &*(sealed as *const GaugeVTable as *const <dyn Gauge as SlotLayout>::VTable) };
+        let tank = Box::into_raw(Box::new(Tank { vtable: std::ptr::null(), litres: 40 }));
+        let thin = unsafe { Thin::<dyn Gauge>::from_parts(vtable, tank.cast()) };
+        assert_eq!(thin.level(), 40);
+
+        let (parts_vtable, object) = thin.into_parts();
+        assert!(std::ptr::eq(parts_vtable, vtable) && object == tank.cast());
+        let thin = unsafe { Thin::<dyn Gauge>::from_parts(parts_vtable, object) };
+        assert_eq!(thin.level(), 40);
+    }
+
+    #[thin(dual_abi)]
+    trait Buffered<const N: usize>: 'static {
+        fn fill(&mut self, byte: u8);
+        fn sum(&self) -> u32;
+        fn capacity(&self) -> usize {
+            N
+        }
+    }
+
+    struct Ring<const N: usize>([u8; N]);
+
+    impl<const N: usize> Buffered<N> for Ring<N> {
+        fn fill(&mut self, byte: u8) {
+            self.0 = [byte; N];
+        }
+        fn sum(&self) -> u32 {
+            self.0.iter().map(|&byte| byte as u32).sum()
+        }
+    }
+
+    #[test]
+    fn const_generics() {
+        let mut small = Thin::<dyn Buffered<4>>::new(Ring([0; 4]));
+        let mut large = Thin::<dyn Buffered<64>>::new(Ring([0; 64]));
+        small.fill(2);
+        large.fill(2);
+        assert_eq!((small.sum(), small.capacity()), (8, 4));
+        assert_eq!((large.sum(), large.capacity()), (128, 64));
+        assert_eq!(<dyn Buffered<4>>::SLOT_SUM, <dyn Buffered<64>>::SLOT_SUM);
+
+        let boxed: Box<Ring<4>> = Box::new(Ring([1; 4]));
+        assert_eq!(Buffered::<4>::sum(&boxed), 4);
+    }
+
+    #[thin(rust_only)]
+    trait Labelled: 'static {
+        fn label(&self) -> &str;
+        fn rename(&mut self, label: String) -> (String, usize);
+    }
+
+    impl Labelled for String {
+        fn label(&self) -> &str {
+            self
+        }
+        fn rename(&mut self, label: String) -> (String, usize) {
+            let old = std::mem::replace(self, label);
+            let len = old.len();
+            (old, len)
+        }
+    }
+
+    #[test]
+    fn rust_only() {
+        let mut thin = Thin::<dyn Labelled>::new(String::from("old"));
+        assert_eq!(thin.rename("new".into()), ("old".into(), 3));
+        assert_eq!(thin.label(), "new");
+    }
+
+    #[thin(no_impl)]
+    trait Exported: 'static {
+        fn double(&self, x: u32) -> u32;
+    }
+
+    impl Exported for u32 {
+        fn double(&self, x: u32) -> u32 {
+            *self * x * 2
+        }
+    }
+
+    #[test]
+    fn no_impl() {
+        let thin = Thin::<dyn Exported>::new(3u32);
+        let val: u32 = unsafe { thin.call_slot(<dyn Exported>::SLOT_DOUBLE, (5u32,)) };
+        assert_eq!(val, 30);
+    }
+
+    // exercises the macro on a large trait, which should stay quick to expand and compile
+    macro_rules! large_trait {
+        ($($method: ident),*) => {
+            #[thin]
+            trait Large: 'static {
+                $(fn $method(&self, x: u32) -> u32;)*
+            }
+
+            impl Large for u32 {
+                $(fn $method(&self, x: u32) -> u32 { *self + x })*
+            }
+        };
+    }
+
+    large_trait!(
+        m00, m01, m02, m03, m04, m05, m06, m07, m08, m09,
+        m10, m11, m12, m13, m14, m15, m16, m17, m18, m19,
+        m20, m21, m22, m23, m24, m25, m26, m27, m28, m29,
+        m30, m31, m32, m33, m34, m35, m36, m37, m38, m39,
+        m40, m41, m42, m43, m44, m45, m46, m47, m48, m49
+    );
+
+    #[test]
+    fn large_trait() {
+        let thin = Thin::<dyn Large>::new(1u32);
+        assert_eq!(thin.m00(1), 2);
+        assert_eq!(thin.m49(2), 3);
+        assert_eq!(<dyn Large as SlotLayout>::SLOTS.len(), <dyn Large>::SLOT_M49 + 1);
+    }
+
+    #[thin]
+    trait ReadOnly: 'static {
+        fn read(&self) -> u8;
+    }
+
+    impl ReadOnly for u8 {
+        fn read(&self) -> u8 {
+            *self
+        }
+    }
+
+    impl ReadOnly for u16 {
+        fn read(&self) -> u8 {
+            *self as u8
+        }
+    }
+
+    #[test]
+    fn same_type() {
+        let a = Thin::<dyn ReadOnly>::new(8u8);
+        let b = Thin::<dyn ReadOnly>::new(9u8);
+        let c = Thin::<dyn ReadOnly>::new(8u16);
+
+        assert!(Thin::same_vtable(&a, &b));
+        assert!(Thin::is_same_type(&a, &b));
+        assert!(!Thin::same_vtable(&a, &c));
+        assert!(!Thin::is_same_type(&a, &c));
     }
 
     #[test]
@@ -311,10 +1546,11 @@ mod tests {
 
         let mut borrow_mut = owned.as_mut();
         borrow_mut.add(1);
+        assert_eq!(*borrow_mut.get(), 9u8);
         drop(borrow_mut);
 
         let borrow = owned.as_ref();
-        let clone = borrow.copy();
+        let clone = borrow.clone();
 
         let a = clone.get();
         assert_eq!(*a, 9u8);
@@ -322,11 +1558,49 @@ mod tests {
         let b = borrow.get();
         assert_eq!(*b, 9u8);
     }
+
+    // only the attribute is in scope, and the names below are all used by `#[thin]` internally
+    mod scoped {
+        use crate::prelude::thin;
+
+        #[repr(C)]
+        pub struct Bundle(pub u32);
+
+        #[repr(transparent)]
+        pub struct K(pub u32);
+
+        pub fn sum() -> u32 {
+            #[thin]
+            trait Local: 'static {
+                fn add(&self, shim: u32, bundle: Bundle) -> u32;
+                fn get(&self, recv: K, size: u32) -> u32;
+            }
+
+            impl Local for u32 {
+                fn add(&self, shim: u32, bundle: Bundle) -> u32 {
+                    self + shim + bundle.0
+                }
+
+                fn get(&self, recv: K, size: u32) -> u32 {
+                    self + recv.0 + size
+                }
+            }
+
+            let thin = <crate::Thin<dyn Local> as crate::ThinExt<dyn Local, u32>>::new(1);
+            thin.add(2, Bundle(3)) + thin.get(K(4), 5)
+        }
+    }
+
+    #[test]
+    fn in_functions() {
+        assert_eq!(scoped::sum(), 16);
+    }
 }
 
 /// Example output of the `#[thin]` attribute
 mod example_macro_expansion {
     use crate::prelude::*;
+    use crate::__private::*;
 
     // #[thin]
     trait Foo: 'static {
@@ -337,56 +1611,101 @@ mod example_macro_expansion {
     // expansion:
     const _: () = {
         #[repr(C)]
-        struct VTable {
-            drop: extern "C" fn(*mut ()),
+        pub struct VTable {
+            drop: extern "C-unwind" fn(*mut ()),
+            size: usize,
+            checksum: VTableChecksum,
             add: extern "C" fn(MutSelf<'_>, u8),
             get: extern "C" fn(RefSelf<'_>) -> &'_ u8,
         }
-        extern "C" fn drop<T: Foo>(ptr: *mut ()) {
-            let bundle = ptr as *mut Bundle<T>;
-            let _ = unsafe { Box::from_raw(bundle) };
+        extern "C-unwind" fn drop<T: Foo>(ptr: *mut ()) {
+            unsafe { expanded_drop::<VTable, T>(ptr) }
         }
+        #[warn(improper_ctypes_definitions)]
         extern "C" fn add<T: Foo>(recv: MutSelf<'_>, other: u8) {
-            let bundle = unsafe { &mut *(recv.ptr as *mut Bundle<T>) };
-            let recv = &mut bundle.value;
+            let recv = unsafe { expanded_value_mut::<VTable, T>(recv) };
             T::add(recv, other)
         }
+        #[warn(improper_ctypes_definitions)]
         extern "C" fn get<T: Foo>(recv: RefSelf<'_>) -> &'_ u8 {
-            let bundle = unsafe { &*(recv.ptr as *const Bundle<T>) };
-            let recv = &bundle.value;
+            let recv = unsafe { expanded_value::<VTable, T>(recv) };
             T::get(recv)
         }
-        #[repr(C)]
-        struct Bundle<T> {
-            vtable: VTable,
-            value: T,
-        }
         impl SpecialAssoc for dyn Foo { type Kind = Own; }
+        // the bundles are `RawBundle`s of `VTable`, which the `expanded_*` helpers rely on
+        impl Expanded for dyn Foo {
+            const TOKEN: ExpansionToken = unsafe { ExpansionToken::new() };
+        }
         impl<K: Foo> ThinExt<dyn Foo, K> for Thin<dyn Foo> {
             fn new(value: K) -> Self {
-                let vtable = VTable { drop: drop::<K>, add: add::<K>, get: get::<K> };
-                let bundle = Bundle { vtable, value };
-                let ptr = Box::into_raw(Box::new(bundle));
-                unsafe { Thin::from_raw(ptr as *mut ()) }
+                let vtable: &'static VTable = const { &VTable { drop: drop::<K>, size: size_of::<VTable>(), checksum: VTableChecksum::UNSEALED, add: add::<K>, get: get::<K> } };
+                unsafe { expanded_thin(expanded_seal(vtable), value) }
             }
         }
+        extern "C" fn clone_bundle<T: Foo + Clone>(recv: RefSelf<'_>) -> Thin<dyn Foo> {
+            let value = unsafe { expanded_value::<VTable, T>(recv) };
+            Thin::<dyn Foo>::new(value.clone())
+        }
+        impl<K: Foo + Clone> ThinClone<dyn Foo, K> for Thin<dyn Foo> {
+            fn clone_fn() -> CloneFn<dyn Foo> { clone_bundle::<K> }
+        }
         impl Foo for Thin<dyn Foo> {
             fn add(&mut self, other: u8) {
+                let vtable = unsafe { expanded_vtable::<VTable>(self.ptr) };
                 let shim = {
-                    let vtable = unsafe { &*(self.ptr.as_ptr() as *const VTable) };
-                    vtable.add
+                    let size = unsafe { expanded_vtable_size(vtable) };
+                    let offset = ::core::mem::offset_of!(VTable, add);
+                    unsafe { expanded_slot(vtable, size, offset, |vtable| &vtable.add, "Foo", "add") }
                 };
                 let recv = MutSelf::new(self);
                 shim(recv, other)
             }
             fn get(&self) -> &'_ u8 {
+                let vtable = unsafe { expanded_vtable::<VTable>(self.ptr) };
                 let shim = {
-                    let vtable = unsafe { &*(self.ptr.as_ptr() as *const VTable) };
-                    vtable.get
+                    let size = unsafe { expanded_vtable_size(vtable) };
+                    let offset = ::core::mem::offset_of!(VTable, get);
+                    unsafe { expanded_slot(vtable, size, offset, |vtable| &vtable.get, "Foo", "get") }
                 };
                 let recv = RefSelf::new(self);
                 shim(recv)
             }
         }
+        const _: () = {
+            use ::core::mem::size_of;
+            assert!(size_of::<Thin<dyn Foo>>() == size_of::<*mut ()>(), "`Thin<dyn _>` must be pointer-sized");
+            assert!(size_of::<Option<Thin<dyn Foo>>>() == size_of::<*mut ()>(), "`Option<Thin<dyn _>>` must be pointer-sized");
+            assert!(
+                size_of::<VTable>() == (2 + 2) * size_of::<extern "C" fn()>() + size_of::<VTableChecksum>(),
+                "every vtable slot must be a single pointer-sized word",
+            );
+        };
+        impl SlotLayout for dyn Foo {
+            const SLOTS: &'static [&'static str] = match VTABLE_CHECKSUM {
+                true => &["drop", "size", "checksum", "add", "get"],
+                false => &["drop", "size", "add", "get"],
+            };
+            const SIZE_SLOT: Option<usize> = Some(1);
+            const CHECKSUM_SLOT: Option<usize> = match VTABLE_CHECKSUM {
+                true => Some(2),
+                false => None,
+            };
+            type VTable = VTable;
+        }
+        #[allow(dead_code)]
+        impl dyn Foo {
+            const SLOT_ADD: usize = ::core::mem::offset_of!(VTable, add) / size_of::<*const ()>();
+            const SLOT_GET: usize = ::core::mem::offset_of!(VTable, get) / size_of::<*const ()>();
+        }
     };
+    impl<K: Foo + ?Sized> Foo for Box<K> {
+        fn add(&mut self, other: u8) {
+            K::add(&mut **self, other)
+        }
+        fn get(&self) -> &'_ u8 {
+            K::get(&**self, )
+        }
+    }
+    // `impl<K: Foo + ?Sized> Foo for Arc<K> where Arc<K>: 'static` (and likewise for `Rc<K>`) is only
+    // generated if every method takes `&self`
 }
\ No newline at end of file
@@ -0,0 +1,312 @@
+//! `Thin<dyn Log>` and a small protocol for handing a host's logger to dynamically loaded plugins.
+//!
+//! The host wraps its global logger with [`host_logger`] and passes it to the plugin's
+//! [`install_logger`], after which the plugin's `log::info!` (etc.) calls are routed back to the host.
+//!
+//! Calls cross the boundary as `repr(C)` records of the metadata and the message, which the plugin
+//! formats, as the host and plugin may be built against different versions of `log`.
+//!
+//! ```rust
+//! use thin_trait_objects::logger::{forward_to, InstallLogger};
+//!
+//! // in the plugin
+//! #[unsafe(no_mangle)]
+//! pub extern "C" fn plugin_install_logger() -> InstallLogger {
+//!     thin_trait_objects::logger::install_logger
+//! }
+//!
+//! // in the host, after loading the plugin and looking up `plugin_install_logger`
+//! let install = plugin_install_logger();
+//! forward_to(install);
+//! ```
+
+use std::borrow::Cow;
+
+use ::log::{Level, LevelFilter, Log, Metadata, Record};
+use crate::prelude::*;
+use crate::__private::*;
+use crate::raw::RawVTableHeader;
+
+/// Signature of [`install_logger`], as exported by a plugin.
+///
+/// `max_level` is a [`LevelFilter`] cast to `usize`.
+pub type InstallLogger = extern "C" fn(logger: Thin<dyn Log>, max_level: usize) -> bool;
+
+/// A `Metadata`, marshalled for the C ABI, as the types of `log` have no stable layout (and the host
+/// and plugin may not even use the same version of it).
+#[repr(C)]
+struct RawMetadata<'a> {
+    // a `Level` as `u32`, from 1 (`Error`) to 5 (`Trace`)
+    level: u32,
+    target: ThinStr<'a>,
+}
+
+/// A `Record`, marshalled likewise. Its message is formatted by the caller, absent strings are
+/// empty, and an absent line is 0.
+#[repr(C)]
+struct RawRecord<'a> {
+    metadata: RawMetadata<'a>,
+    message: ThinStr<'a>,
+    module_path: ThinStr<'a>,
+    file: ThinStr<'a>,
+    line: u32,
+}
+
+impl<'a> RawMetadata<'a> {
+    fn new(metadata: &'a Metadata<'a>) -> Self {
+        RawMetadata { level: metadata.level() as u32, target: metadata.target().into() }
+    }
+
+    fn level(&self) -> Level {
+        Level::iter().nth((self.level as usize).saturating_sub(1)).unwrap_or(Level::Trace)
+    }
+}
+
+fn non_empty(s: ThinStr<'_>) -> Option<&str> {
+    (!s.is_empty()).then_some(s.as_str())
+}
+
+const _: () = {
+    #[repr(C)]
+    pub struct VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: VTableChecksum,
+        enabled: extern "C" fn(RefSelf<'_>, &RawMetadata<'_>) -> bool,
+        log: extern "C" fn(RefSelf<'_>, &RawRecord<'_>),
+        flush: extern "C" fn(RefSelf<'_>),
+    }
+
+    extern "C-unwind" fn drop<T>(ptr: *mut ()) {
+        unsafe { drop_bundle::<RawBundle<VTable, T>>(ptr) }
+    }
+
+    extern "C" fn enabled<T: Log>(recv: RefSelf<'_>, metadata: &RawMetadata<'_>) -> bool {
+        let value = unsafe { RawBundle::<VTable, T>::value(recv) };
+        T::enabled(value, &Metadata::builder().level(metadata.level()).target(&metadata.target).build())
+    }
+
+    extern "C" fn log<T: Log>(recv: RefSelf<'_>, record: &RawRecord<'_>) {
+        let value = unsafe { RawBundle::<VTable, T>::value(recv) };
+        T::log(value, &Record::builder()
+            .level(record.metadata.level())
+            .target(&record.metadata.target)
+            .args(format_args!("{}", record.message))
+            .module_path(non_empty(record.module_path))
+            .file(non_empty(record.file))
+            .line((record.line != 0).then_some(record.line))
+            .build())
+    }
+
+    extern "C" fn flush<T: Log>(recv: RefSelf<'_>) {
+        let value = unsafe { RawBundle::<VTable, T>::value(recv) };
+        T::flush(value)
+    }
+
+    impl SpecialAssoc for dyn Log {
+        type Kind = Own;
+    }
+
+    impl ThinSend for dyn Log {}
+    impl ThinSync for dyn Log {}
+
+    impl SlotLayout for dyn Log {
+        const SLOTS: &'static [&'static str] = match VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "enabled", "log", "flush"],
+            false => &["drop", "size", "enabled", "log", "flush"],
+        };
+        const SIZE_SLOT: Option<usize> = Some(1);
+        const CHECKSUM_SLOT: Option<usize> = match VTABLE_CHECKSUM {
+            true => Some(2),
+            false => None,
+        };
+        type VTable = VTable;
+    }
+
+    impl<K: Log + 'static> ThinExt<dyn Log, K> for Thin<dyn Log> {
+        fn new(value: K) -> Self {
+            let vtable: &'static VTable = const {
+                &VTable {
+                    drop: drop::<K>,
+                    size: size_of::<VTable>(),
+                    checksum: VTableChecksum::UNSEALED,
+                    enabled: enabled::<K>,
+                    log: log::<K>,
+                    flush: flush::<K>,
+                }
+            };
+            // SAFETY: `VTable` starts with its header
+            let vtable = unsafe { RawVTableHeader::seal(vtable) };
+            let bundle = RawBundle { vtable, value };
+            let ptr = Box::into_raw(Box::new(bundle));
+            unsafe { Thin::from_raw(ptr as *mut ()) }
+        }
+    }
+
+    impl Thin<dyn Log> {
+        fn vtable(&self) -> &'static VTable {
+            check_poison(self.ptr.as_ptr());
+            let vtable = unsafe { *(self.ptr.as_ptr() as *const &'static VTable) };
+            // every vtable has all three slots, so the size is only read to verify the checksum
+            unsafe { RawVTableHeader::verified_size(vtable as *const VTable as *const RawVTableHeader) };
+            vtable
+        }
+    }
+
+    impl Log for Thin<dyn Log> {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            let shim = self.vtable().enabled;
+            shim(RefSelf::new(self), &RawMetadata::new(metadata))
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            let shim = self.vtable().log;
+            // formatted on this side, as `Arguments` can't cross the boundary
+            let message = match record.args().as_str() {
+                Some(message) => Cow::Borrowed(message),
+                None => Cow::Owned(record.args().to_string()),
+            };
+            let raw = RawRecord {
+                metadata: RawMetadata::new(record.metadata()),
+                message: ThinStr::new(&message),
+                module_path: record.module_path().unwrap_or_default().into(),
+                file: record.file().unwrap_or_default().into(),
+                line: record.line().unwrap_or(0),
+            };
+            shim(RefSelf::new(self), &raw)
+        }
+
+        fn flush(&self) {
+            let shim = self.vtable().flush;
+            shim(RefSelf::new(self))
+        }
+    }
+};
+
+/// Forwards to whichever logger is installed in the current binary at the time of each call.
+struct Forward;
+
+impl Log for Forward {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        ::log::logger().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        ::log::logger().log(record)
+    }
+
+    fn flush(&self) {
+        ::log::logger().flush()
+    }
+}
+
+/// Returns a `Thin<dyn Log>` forwarding to this binary's global logger.
+pub fn host_logger() -> Thin<dyn Log> {
+    Thin::<dyn Log>::new(Forward)
+}
+
+/// Hands this binary's global logger and max level to a plugin's [`install_logger`].
+///
+/// Returns `false` if the plugin already had a logger installed.
+pub fn forward_to(install: InstallLogger) -> bool {
+    install(host_logger(), ::log::max_level() as usize)
+}
+
+/// Installs `logger` as this binary's global logger.
+///
+/// Returns `false` (dropping `logger`) if a logger was already installed.
+pub extern "C" fn install_logger(logger: Thin<dyn Log>, max_level: usize) -> bool {
+    let logger: &'static Thin<dyn Log> = Box::leak(Box::new(logger));
+    if ::log::set_logger(logger).is_err() {
+        // SAFETY: `set_logger` failed, so no other reference to `logger` exists
+        let _ = unsafe { Box::from_raw(logger as *const Thin<dyn Log> as *mut Thin<dyn Log>) };
+        return false;
+    }
+
+    let max_level = LevelFilter::iter().nth(max_level).unwrap_or(LevelFilter::Trace);
+    ::log::set_max_level(max_level);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use ::log::{Level, Log, Metadata, Record};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() <= Level::Info
+        }
+        fn log(&self, record: &Record<'_>) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(format!("{}", record.args()));
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn dispatch() {
+        let capture = Capture::default();
+        let records = capture.0.clone();
+
+        let thin = Thin::<dyn Log>::new(capture);
+        thin.log(&Record::builder().level(Level::Info).args(format_args!("kept")).build());
+        thin.log(&Record::builder().level(Level::Debug).args(format_args!("filtered")).build());
+        thin.flush();
+
+        assert_eq!(*records.lock().unwrap(), vec!["kept".to_string()]);
+    }
+
+    #[test]
+    fn marshalling() {
+        struct Fields(Arc<Mutex<Vec<String>>>);
+
+        impl Log for Fields {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                metadata.target() != "quiet"
+            }
+            fn log(&self, record: &Record<'_>) {
+                let fields = format!(
+                    "{} {} {:?} {:?} {:?} {}",
+                    record.level(), record.target(), record.module_path(), record.file(), record.line(), record.args(),
+                );
+                self.0.lock().unwrap().push(fields);
+            }
+            fn flush(&self) {}
+        }
+
+        let records = Arc::default();
+        let thin = Thin::<dyn Log>::new(Fields(Arc::clone(&records)));
+        assert!(!thin.enabled(&Metadata::builder().target("quiet").build()));
+        thin.log(&Record::builder()
+            .level(Level::Warn)
+            .target("plugin")
+            .args(format_args!("{} + {}", 1, 2))
+            .module_path(Some("plugin::io"))
+            .line(Some(7))
+            .build());
+        assert_eq!(*records.lock().unwrap(), vec!["WARN plugin Some(\"plugin::io\") None Some(7) 1 + 2".to_string()]);
+        // the vtable records its size
+        let slots = <dyn Log as SlotLayout>::SLOTS.len();
+        assert!(thin.has_slot(slots - 1) && !thin.has_slot(slots));
+    }
+
+    #[test]
+    fn install() {
+        let capture = Capture::default();
+        let records = capture.0.clone();
+
+        assert!(install_logger(Thin::<dyn Log>::new(capture), LevelFilter::Info as usize));
+        assert!(!install_logger(Thin::<dyn Log>::new(Capture::default()), LevelFilter::Info as usize));
+        assert_eq!(::log::max_level(), LevelFilter::Info);
+
+        ::log::info!("routed");
+        assert_eq!(*records.lock().unwrap(), vec!["routed".to_string()]);
+    }
+}
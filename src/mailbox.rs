@@ -0,0 +1,158 @@
+//! `ThinMailbox`, a multi-producer single-consumer queue of erased messages, dispatched to handlers
+//! by the `StableTypeId` of each message.
+//!
+//! Messages are `Thin<dyn StableAny + Send>` and handlers are `Thin<dyn MessageHandler>`, both
+//! FFI-safe, so plugins can send each other messages through a mailbox owned by the host.
+//!
+//! ```rust
+//! use std::sync::{Arc, Mutex};
+//! use thin_trait_objects::mailbox::ThinMailbox;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[derive(StableAny)]
+//! struct Ping(u32);
+//!
+//! let pings = Arc::new(Mutex::new(Vec::new()));
+//! let mut mailbox = ThinMailbox::new();
+//! let received = pings.clone();
+//! mailbox.register_fn(move |ping: Ping| received.lock().unwrap().push(ping.0));
+//!
+//! let sender = mailbox.sender();
+//! std::thread::spawn(move || sender.send(Ping(1)).ok()).join().unwrap();
+//! mailbox.sender().send(Ping(2)).ok();
+//!
+//! assert!(mailbox.dispatch_pending().is_empty());
+//! assert_eq!(*pings.lock().unwrap(), [1, 2]);
+//! ```
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::prelude::*;
+
+/// An erased message.
+pub type Message = Thin<dyn StableAny + Send>;
+
+/// Handles the messages of the type it was registered for.
+#[thin]
+pub trait MessageHandler: Send + 'static {
+    fn handle(&mut self, message: Message);
+}
+
+/// Sends messages to a [`ThinMailbox`]. Cheap to clone, and may be sent to other threads.
+#[derive(Clone)]
+pub struct MailboxSender(Sender<Message>);
+
+impl MailboxSender {
+    /// Sends `message`, returning it if the mailbox has been dropped.
+    pub fn send<T: UUID + Send>(&self, message: T) -> Result<(), T> {
+        self.send_thin(Thin::<dyn StableAny + Send>::new(message))
+            .map_err(|message| message.downcast::<T>().unwrap())
+    }
+
+    /// Sends an already erased message, returning it if the mailbox has been dropped.
+    pub fn send_thin(&self, message: Message) -> Result<(), Message> {
+        self.0.send(message).map_err(|err| err.0)
+    }
+}
+
+/// A queue of erased messages, and the handlers they're dispatched to.
+pub struct ThinMailbox {
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    handlers: HashMap<StableTypeId, Thin<dyn MessageHandler>>,
+}
+
+impl Default for ThinMailbox {
+    fn default() -> Self {
+        ThinMailbox::new()
+    }
+}
+
+impl ThinMailbox {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        ThinMailbox { sender, receiver, handlers: HashMap::new() }
+    }
+
+    /// Returns a new sender of messages to this mailbox.
+    pub fn sender(&self) -> MailboxSender {
+        MailboxSender(self.sender.clone())
+    }
+
+    /// Registers `handler` for messages of type `id`, returning the handler it replaces, if any.
+    pub fn register(&mut self, id: StableTypeId, handler: Thin<dyn MessageHandler>) -> Option<Thin<dyn MessageHandler>> {
+        self.handlers.insert(id, handler)
+    }
+
+    /// Registers a closure taking messages of type `T`.
+    pub fn register_fn<T: UUID + Send, F: FnMut(T) + Send + 'static>(&mut self, mut f: F) -> Option<Thin<dyn MessageHandler>> {
+        let handler = Thin::<dyn MessageHandler>::from_fn(move |message: Message| {
+            f(message.downcast::<T>().expect("message dispatched to the wrong handler"))
+        });
+        self.register(T::UUID, handler)
+    }
+
+    /// Removes the handler for messages of type `id`, returning it.
+    pub fn unregister(&mut self, id: StableTypeId) -> Option<Thin<dyn MessageHandler>> {
+        self.handlers.remove(&id)
+    }
+
+    /// Dispatches every message received so far, returning those without a handler.
+    pub fn dispatch_pending(&mut self) -> Vec<Message> {
+        let mut unhandled = Vec::new();
+        while let Ok(message) = self.receiver.try_recv() {
+            match self.handlers.get_mut(&StableAny::stable_type_id(&message)) {
+                Some(handler) => handler.handle(message),
+                None => unhandled.push(message),
+            }
+        }
+        unhandled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[derive(StableAny)]
+    struct Add(u32);
+
+    #[derive(StableAny)]
+    struct Unknown;
+
+    struct Total(Arc<AtomicU32>);
+
+    impl MessageHandler for Total {
+        fn handle(&mut self, message: Message) {
+            let Add(n) = message.downcast::<Add>().unwrap();
+            self.0.fetch_add(n, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn dispatching() {
+        let total = Arc::new(AtomicU32::new(0));
+        let mut mailbox = ThinMailbox::new();
+        mailbox.register(StableTypeId::of::<Add>(), Thin::<dyn MessageHandler>::new(Total(total.clone())));
+
+        let threads = (1..=4).map(|n| {
+            let sender = mailbox.sender();
+            std::thread::spawn(move || sender.send(Add(n)).ok().unwrap())
+        }).collect::<Vec<_>>();
+        threads.into_iter().for_each(|thread| thread.join().unwrap());
+        mailbox.sender().send(Unknown).ok().unwrap();
+
+        let unhandled = mailbox.dispatch_pending();
+        assert_eq!(total.load(Ordering::Relaxed), 10);
+        assert_eq!(unhandled.len(), 1);
+        assert!(unhandled[0].stable_is::<Unknown>());
+
+        assert!(mailbox.unregister(StableTypeId::of::<Add>()).is_some());
+        mailbox.sender().send(Add(1)).ok().unwrap();
+        assert_eq!(mailbox.dispatch_pending().len(), 1);
+    }
+}
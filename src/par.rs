@@ -0,0 +1,101 @@
+//! Parallel iteration over [`ThinVec`]s with `rayon`, yielding the same borrowed thin views as
+//! [`ThinVec::iter`] and [`ThinVec::iter_mut`].
+//!
+//! Slices of `Thin<T>` (e.g. from [`ThinVec::as_slice`]) get rayon's own slice impls, yielding `&Thin<T>`.
+//!
+//! ```rust
+//! use rayon::prelude::*;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Body: Send + Sync + 'static {
+//!     fn step(&mut self);
+//!     fn position(&self) -> u32;
+//! }
+//!
+//! struct Particle(u32);
+//! impl Body for Particle {
+//!     fn step(&mut self) { self.0 += 1 }
+//!     fn position(&self) -> u32 { self.0 }
+//! }
+//!
+//! let mut bodies: ThinVec<dyn Body> = (0..100).map(|n| Thin::<dyn Body>::new(Particle(n))).collect();
+//! bodies.par_iter_mut().for_each(|mut body| body.step());
+//! assert_eq!(bodies.par_iter().map(|body| body.position()).sum::<u32>(), (1..=100).sum::<u32>());
+//! ```
+
+use rayon::iter::{IntoParallelIterator, Map};
+use rayon::prelude::*;
+use rayon::{slice, vec};
+
+use crate::prelude::*;
+
+impl<T: ?Sized + ThinSend + 'static> IntoParallelIterator for ThinVec<T> {
+    type Item = Thin<T>;
+    type Iter = vec::IntoIter<Thin<T>>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.into_vec().into_par_iter()
+    }
+}
+
+impl<'a, T: ?Sized + ThinSync + 'static> IntoParallelIterator for &'a ThinVec<T> {
+    type Item = Thin<&'a T>;
+    type Iter = Map<slice::Iter<'a, Thin<T>>, fn(&'a Thin<T>) -> Thin<&'a T>>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_slice().par_iter().map(Thin::as_ref)
+    }
+}
+
+impl<'a, T: ?Sized + ThinSend + 'static> IntoParallelIterator for &'a mut ThinVec<T> {
+    type Item = Thin<&'a mut T>;
+    type Iter = Map<slice::IterMut<'a, Thin<T>>, fn(&'a mut Thin<T>) -> Thin<&'a mut T>>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.as_mut_slice().par_iter_mut().map(Thin::as_mut)
+    }
+}
+
+impl<T: ?Sized + ThinSend + 'static> FromParallelIterator<Thin<T>> for ThinVec<T> {
+    fn from_par_iter<I: IntoParallelIterator<Item = Thin<T>>>(iter: I) -> Self {
+        ThinVec::from(Vec::from_par_iter(iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use rayon::prelude::*;
+
+    use crate::prelude::*;
+
+    #[thin]
+    trait Counter: Send + Sync + 'static {
+        fn add(&mut self, n: u32);
+        fn get(&self) -> u32;
+    }
+
+    impl Counter for u32 {
+        fn add(&mut self, n: u32) {
+            *self += n;
+        }
+        fn get(&self) -> u32 {
+            *self
+        }
+    }
+
+    #[test]
+    fn parallel() {
+        let mut vec: ThinVec<dyn Counter> = (0..1000u32).into_par_iter().map(Thin::<dyn Counter>::new).collect();
+        vec.par_iter_mut().for_each(|mut counter| counter.add(1));
+
+        let sum = AtomicU32::new(0);
+        vec.par_iter().for_each(|counter| { sum.fetch_add(counter.get(), Ordering::Relaxed); });
+        assert_eq!(sum.into_inner(), (1..=1000).sum::<u32>());
+
+        let values = vec.into_par_iter().map(|counter| counter.get()).collect::<Vec<_>>();
+        assert_eq!(values, (1..=1000).collect::<Vec<_>>());
+    }
+}
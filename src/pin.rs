@@ -0,0 +1,168 @@
+//! Pinned thin objects, for self-referential implementors.
+//!
+//! The value of a thin object stays where it was allocated until it's dropped in place, unless it's
+//! moved out of the thin object (e.g. by downcasting), which [`ThinPin`] prevents. (`Pin<Thin<U>>`
+//! isn't possible, as `Thin` doesn't deref to its value.) Values which need to see themselves pinned
+//! when dropped implement [`PinnedDrop`], and are created with [`ThinPinnedDrop::pin_with_drop`],
+//! whose vtable's drop slot runs it before dropping the value.
+//!
+//! ```rust
+//! use std::pin::Pin;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Cursor: 'static {
+//!     fn start(&mut self);
+//!     fn peek(&self) -> u8;
+//! }
+//!
+//! // `next` points into `data`, once started
+//! struct Bytes { data: [u8; 4], next: *const u8 }
+//!
+//! impl Cursor for Bytes {
+//!     fn start(&mut self) { self.next = &self.data[1]; }
+//!     fn peek(&self) -> u8 { unsafe { *self.next } }
+//! }
+//!
+//! impl PinnedDrop for Bytes {
+//!     fn drop(self: Pin<&mut Self>) {
+//!         assert_eq!(self.next, &self.data[1] as *const u8);
+//!     }
+//! }
+//!
+//! let mut cursor = Thin::<dyn Cursor>::pin_with_drop(Bytes { data: [1, 2, 3, 4], next: std::ptr::null() });
+//! // SAFETY: `start` doesn't move the value
+//! unsafe { cursor.as_thin_mut() }.start();
+//! assert_eq!(cursor.as_thin().peek(), 2);
+//! ```
+
+use std::pin::Pin;
+
+use crate::drop_panic::{drop_bundle, with_drop_policy};
+use crate::prelude::*;
+use crate::__private::*;
+
+/// A destructor which sees the value pinned, run before the value is dropped in place (and so
+/// before its `Drop` impl, if it has one).
+pub trait PinnedDrop {
+    fn drop(self: Pin<&mut Self>);
+}
+
+/// Creates a pinned `Thin<U>` from a [`PinnedDrop`] value.
+///
+/// `#[thin]` implements this for every implementor of the trait which implements `PinnedDrop`.
+pub trait ThinPinnedDrop<U: ?Sized + SpecialAssoc + 'static, K: PinnedDrop> {
+    fn pin_with_drop(value: K) -> ThinPin<U>;
+}
+
+/// A thin object whose value won't be moved until it's dropped.
+#[repr(transparent)]
+pub struct ThinPin<U: ?Sized + SpecialAssoc + 'static>(Thin<U>);
+
+impl<U: ?Sized + SpecialAssoc<Kind = Own> + 'static> Thin<U> {
+    /// Pins the thin object, whose value is never moved by the thin object itself.
+    pub fn into_pin(self) -> ThinPin<U> {
+        ThinPin(self)
+    }
+
+    /// Creates a pinned thin object from the given value.
+    pub fn pin<K>(value: K) -> ThinPin<U> where Thin<U>: ThinExt<U, K> {
+        <Thin<U> as ThinExt<U, K>>::new(value).into_pin()
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static> ThinPin<U> {
+    /// Borrows the thin object, through which the value can't be moved.
+    pub fn as_thin(&self) -> &Thin<U> {
+        &self.0
+    }
+
+    /// Mutably borrows the thin object.
+    ///
+    /// # Safety
+    /// The value mustn't be moved through the thin object, e.g. by `downcast`, or by `mem::swap`
+    /// on the value borrowed from `downcast_mut`.
+    pub unsafe fn as_thin_mut(&mut self) -> &mut Thin<U> {
+        &mut self.0
+    }
+}
+
+/// Runs the `PinnedDrop` of the value in the bundle (of type `B`) at `ptr`, then frees the bundle.
+///
+/// # Safety
+/// As for `drop_bundle`, and the bundle's value must be a `K`.
+#[doc(hidden)]
+pub unsafe fn drop_pinned_bundle<B, K: PinnedDrop>(ptr: *mut ()) {
+    crate::poison::check_dropped(ptr, "double drop");
+    with_drop_policy(|| {
+        let value = unsafe { &mut (*(ptr as *mut RawBundle<(), K>)).value };
+        K::drop(unsafe { Pin::new_unchecked(value) });
+    });
+    unsafe { drop_bundle::<B>(ptr) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::marker::PhantomPinned;
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[thin]
+    trait Buffer: 'static {
+        fn attach(&mut self);
+        fn tail(&self) -> u32;
+    }
+
+    // `tail` points to the last element of `items`, once attached
+    struct Ring {
+        items: [u32; 8],
+        tail: *const u32,
+        drops: Rc<Cell<u32>>,
+        _pinned: PhantomPinned,
+    }
+
+    impl Ring {
+        fn new(drops: &Rc<Cell<u32>>) -> Self {
+            Ring { items: [0, 1, 2, 3, 4, 5, 6, 7], tail: std::ptr::null(), drops: drops.clone(), _pinned: PhantomPinned }
+        }
+    }
+
+    impl Buffer for Ring {
+        fn attach(&mut self) {
+            self.tail = &self.items[7];
+        }
+        fn tail(&self) -> u32 {
+            unsafe { *self.tail }
+        }
+    }
+
+    impl PinnedDrop for Ring {
+        fn drop(self: Pin<&mut Self>) {
+            // the value hasn't moved since it was attached
+            assert_eq!(self.tail, &self.items[7] as *const u32);
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn self_referential() {
+        let drops = Rc::new(Cell::new(0));
+        let mut ring = Thin::<dyn Buffer>::pin_with_drop(Ring::new(&drops));
+        unsafe { ring.as_thin_mut() }.attach();
+        assert_eq!(ring.as_thin().tail(), 7);
+
+        // moving the pinned handle doesn't move the value
+        let rings = vec![ring];
+        assert_eq!(rings[0].as_thin().tail(), 7);
+        drop(rings);
+        assert_eq!(drops.get(), 1);
+
+        // without `pin_with_drop`, only the value's `Drop` impl runs
+        let mut ring = Thin::<dyn Buffer>::pin(Ring::new(&drops));
+        unsafe { ring.as_thin_mut() }.attach();
+        drop(ring);
+        assert_eq!(drops.get(), 1);
+    }
+}
@@ -0,0 +1,190 @@
+//! Descriptors of the thin objects a cdylib provides, exported under predictable symbol names so
+//! that a host can discover them by inspecting the library's symbols, before calling anything.
+//!
+//! `export_thin_plugin!(Trait for Type)` exports a [`ThinPluginDescriptor`] named
+//! `THIN_PLUGIN__{Trait}__{Type}` (see [`symbol_name`]), whose constructor creates a
+//! `Thin<dyn Trait>` from `Type::default()`. A constructor expression may be given instead, as in
+//! `export_thin_plugin!(Trait for Type = Type::new(42))`.
+//!
+//! ```rust
+//! use thin_trait_objects::plugin::ThinPluginDescriptor;
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! pub trait Greeter: 'static {
+//!     fn greet(&self) -> u32;
+//! }
+//!
+//! #[derive(Default)]
+//! pub struct English;
+//!
+//! impl Greeter for English {
+//!     fn greet(&self) -> u32 {
+//!         42
+//!     }
+//! }
+//!
+//! export_thin_plugin!(Greeter for English);
+//!
+//! // a host would look the symbol up by name, with `dlsym` or similar
+//! let descriptor: &ThinPluginDescriptor = &THIN_PLUGIN__Greeter__English;
+//! assert_eq!(descriptor.trait_name(), "Greeter");
+//! let greeter = unsafe { descriptor.construct::<dyn Greeter>() }.unwrap();
+//! assert_eq!(greeter.greet(), 42);
+//! ```
+
+use std::ffi::{c_char, CStr};
+use std::mem::ManuallyDrop;
+
+use crate::prelude::*;
+use crate::protocol::trait_abi_hash;
+
+/// The prefix of the names of the symbols exported by `export_thin_plugin!`.
+pub const SYMBOL_PREFIX: &str = "THIN_PLUGIN__";
+
+/// Returns the name of the symbol `export_thin_plugin!` exports for the given trait and type,
+/// named without their module paths.
+pub fn symbol_name(trait_name: &str, type_name: &str) -> String {
+    format!("{SYMBOL_PREFIX}{trait_name}__{type_name}")
+}
+
+/// Describes a thin object provided by a library, exported by `export_thin_plugin!`.
+///
+/// The strings are nul-terminated UTF-8.
+#[repr(C)]
+pub struct ThinPluginDescriptor {
+    pub trait_name: *const c_char,
+    pub type_name: *const c_char,
+    /// The [`trait_abi_hash`] of the trait.
+    pub abi_hash: u64,
+    /// The version of the crate exporting the descriptor.
+    pub version: *const c_char,
+    /// Creates a thin object, returning the pointer it wraps. It unwinds if the type's constructor
+    /// panics, as the drop slot does, whereas a panic in a method slot (which is `extern "C"`) aborts.
+    pub constructor: extern "C-unwind" fn() -> *mut (),
+}
+
+// the pointers are to static strings
+unsafe impl Send for ThinPluginDescriptor {}
+unsafe impl Sync for ThinPluginDescriptor {}
+
+unsafe fn str_from_ptr<'a>(ptr: *const c_char) -> &'a str {
+    unsafe { CStr::from_ptr(ptr) }.to_str().expect("plugin descriptor string isn't UTF-8")
+}
+
+impl ThinPluginDescriptor {
+    pub fn trait_name(&self) -> &str {
+        unsafe { str_from_ptr(self.trait_name) }
+    }
+
+    pub fn type_name(&self) -> &str {
+        unsafe { str_from_ptr(self.type_name) }
+    }
+
+    pub fn version(&self) -> &str {
+        unsafe { str_from_ptr(self.version) }
+    }
+
+    /// Creates a thin object, returning `None` if its trait's ABI hash doesn't match `T`'s.
+    ///
+    /// # Safety
+    /// The descriptor must be for a `Thin<T>`: the ABI hash only covers the names of the vtable slots,
+    /// not the signatures of the methods, nor the name of the trait.
+    pub unsafe fn construct<T: ?Sized + SpecialAssoc + SlotLayout + 'static>(&self) -> Option<Thin<T>> {
+        if self.abi_hash != trait_abi_hash::<T>() {
+            return None;
+        }
+        Some(unsafe { Thin::from_raw((self.constructor)()) })
+    }
+}
+
+/// Returns the pointer wrapped by `thin`, for `export_thin_plugin!` constructors.
+#[doc(hidden)]
+pub fn __into_raw<T: ?Sized + SpecialAssoc + 'static>(thin: Thin<T>) -> *mut () {
+    ManuallyDrop::new(thin).ptr.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[thin]
+    pub trait Counter: 'static {
+        fn count(&mut self) -> u32;
+    }
+
+    #[thin]
+    pub trait Other: 'static {
+        fn other(&self);
+    }
+
+    #[derive(Default)]
+    pub struct FromZero(u32);
+
+    impl Counter for FromZero {
+        fn count(&mut self) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    pub struct Counting(u32);
+
+    impl Counter for Counting {
+        fn count(&mut self) -> u32 {
+            self.0 += 10;
+            self.0
+        }
+    }
+
+    pub struct Failing;
+
+    impl Failing {
+        fn new() -> Self {
+            panic!("no counter")
+        }
+    }
+
+    impl Counter for Failing {
+        fn count(&mut self) -> u32 {
+            0
+        }
+    }
+
+    export_thin_plugin!(Counter for FromZero);
+    export_thin_plugin!(self::Counter for self::Counting = Counting(100));
+    export_thin_plugin!(Counter for Failing = Failing::new());
+
+    // as a host would see them
+    mod host {
+        unsafe extern "C" {
+            pub static THIN_PLUGIN__Counter__FromZero: super::ThinPluginDescriptor;
+            pub static THIN_PLUGIN__Counter__Counting: super::ThinPluginDescriptor;
+            pub static THIN_PLUGIN__Counter__Failing: super::ThinPluginDescriptor;
+        }
+    }
+
+    #[test]
+    fn descriptors() {
+        assert_eq!(symbol_name("Counter", "FromZero"), "THIN_PLUGIN__Counter__FromZero");
+        let descriptor = unsafe { &host::THIN_PLUGIN__Counter__FromZero };
+        assert_eq!(descriptor.trait_name(), "Counter");
+        assert_eq!(descriptor.type_name(), "FromZero");
+        assert_eq!(descriptor.version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(descriptor.abi_hash, trait_abi_hash::<dyn Counter>());
+
+        assert!(unsafe { descriptor.construct::<dyn Other>() }.is_none());
+        let mut counter = unsafe { descriptor.construct::<dyn Counter>() }.unwrap();
+        assert_eq!(counter.count(), 1);
+
+        let descriptor = unsafe { &host::THIN_PLUGIN__Counter__Counting };
+        assert_eq!(descriptor.type_name(), "Counting");
+        let mut counter = unsafe { descriptor.construct::<dyn Counter>() }.unwrap();
+        assert_eq!(counter.count(), 110);
+
+        // a panicking constructor unwinds into the host, rather than aborting it
+        let descriptor = unsafe { &host::THIN_PLUGIN__Counter__Failing };
+        let result = std::panic::catch_unwind(|| unsafe { descriptor.construct::<dyn Counter>() });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Use-after-free detection, enabled by the `debug-poison` feature.
+//!
+//! Rather than being freed, dropped bundles have their header overwritten with a pointer to a
+//! tombstone naming their type, and are leaked, so that any later use of the object reads the
+//! tombstone instead of freed memory. Method calls abort with a diagnostic, while double drops and
+//! downcasts (which are more likely the result of misusing `Thin::from_raw`) panic.
+
+#[cfg(feature = "debug-poison")]
+const POISON: usize = usize::from_ne_bytes([0xDB; size_of::<usize>()]);
+
+// takes the place of the vtable, whose first word (the drop shim) can never be `POISON`
+#[cfg(feature = "debug-poison")]
+#[repr(C)]
+struct Tombstone {
+    poison: usize,
+    bundle_name: fn() -> &'static str,
+}
+
+/// Returns the name of the bundle type if the bundle at `ptr` has already been dropped.
+#[cfg(feature = "debug-poison")]
+#[inline(always)]
+fn dropped_bundle(ptr: *const ()) -> Option<&'static str> {
+    // SAFETY: poisoned bundles are never freed, so these reads are valid even after the object is
+    // dropped, and every vtable starts with a pointer-sized drop shim
+    let header = unsafe { *(ptr as *const *const Tombstone) };
+    match unsafe { (*header).poison } == POISON {
+        true => Some((unsafe { (*header).bundle_name })()),
+        false => None,
+    }
+}
+
+/// Aborts if the bundle at `ptr` has already been dropped. Does nothing without the `debug-poison` feature.
+#[doc(hidden)]
+#[inline(always)]
+pub fn check_poison(ptr: *const ()) {
+    #[cfg(feature = "debug-poison")]
+    if let Some(bundle_name) = dropped_bundle(ptr) {
+        eprintln!("use of a thin object at {:p} (a `{}`) after it was dropped, aborting", ptr, bundle_name);
+        std::process::abort();
+    }
+    #[cfg(not(feature = "debug-poison"))]
+    let _ = ptr;
+}
+
+/// Panics if the bundle at `ptr` has already been dropped, naming its type. Does nothing without
+/// the `debug-poison` feature.
+#[doc(hidden)]
+#[inline(always)]
+#[track_caller]
+pub fn check_dropped(ptr: *const (), action: &str) {
+    #[cfg(feature = "debug-poison")]
+    if let Some(bundle_name) = dropped_bundle(ptr) {
+        panic!("{} of a thin object at {:p} (a `{}`) after it was dropped", action, ptr, bundle_name);
+    }
+    #[cfg(not(feature = "debug-poison"))]
+    let _ = (ptr, action);
+}
+
+/// The same as [`check_dropped`], also naming the type of the thin object, `T`, through which the
+/// bundle was used.
+#[doc(hidden)]
+#[inline(always)]
+#[track_caller]
+pub fn check_dropped_as<T: ?Sized>(ptr: *const (), action: &str) {
+    #[cfg(feature = "debug-poison")]
+    if let Some(bundle_name) = dropped_bundle(ptr) {
+        panic!(
+            "{} of a `{}` at {:p} (a `{}`) after it was dropped",
+            action, std::any::type_name::<T>(), ptr, bundle_name,
+        );
+    }
+    #[cfg(not(feature = "debug-poison"))]
+    let _ = (ptr, action);
+}
+
+/// Drops the value in the bundle at `ptr` without freeing it, leaving the header poisoned.
+///
+/// # Safety
+/// `ptr` must have been created by `Box::<B>::into_raw`, and not be used afterwards.
+#[cfg(feature = "debug-poison")]
+pub(crate) unsafe fn poison<B>(ptr: *mut ()) {
+    // poisons the header even if dropping the value panics
+    struct Poison(*mut (), &'static Tombstone);
+    impl Drop for Poison {
+        fn drop(&mut self) {
+            // overwrites the pointer to the vtable, which is no longer in use
+            unsafe { *(self.0 as *mut &'static Tombstone) = self.1 };
+        }
+    }
+
+    let tombstone = const { &Tombstone { poison: POISON, bundle_name: std::any::type_name::<B> } };
+    let _poison = Poison(ptr, tombstone);
+    unsafe { std::ptr::drop_in_place(ptr as *mut B) };
+}
+
+#[cfg(all(test, feature = "debug-poison"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[thin]
+    trait Foo: 'static {
+        fn get(&self) -> u8;
+    }
+
+    impl Foo for u8 {
+        fn get(&self) -> u8 {
+            *self
+        }
+    }
+
+    #[test]
+    fn poisoned_on_drop() {
+        let thin = Thin::<dyn Foo>::new(8u8);
+        let ptr = thin.ptr.as_ptr();
+        assert_eq!(thin.get(), 8u8);
+
+        drop(thin);
+        assert_eq!(unsafe { **(ptr as *const *const usize) }, super::POISON);
+        assert!(super::dropped_bundle(ptr).unwrap().ends_with("__VTable, u8>"));
+    }
+
+    #[test]
+    fn double_drop() {
+        let thin = Thin::<dyn Foo>::new(8u8);
+        let copy = unsafe { Thin::<dyn Foo>::from_raw(thin.ptr.as_ptr()) };
+        drop(thin);
+
+        let message = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || drop(copy))).unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("double drop of a thin object"), "{}", message);
+        assert!(message.contains("RawBundle<") && message.contains("__VTable, u8>"), "{}", message);
+    }
+
+    #[cfg(feature = "any")]
+    #[test]
+    fn downcast_after_drop() {
+        let thin = Thin::<dyn std::any::Any>::new(8u8);
+        let copy = unsafe { Thin::<dyn std::any::Any>::from_raw(thin.ptr.as_ptr()) };
+        drop(thin);
+
+        let message = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| copy.is::<u8>())).unwrap_err();
+        let message = message.downcast_ref::<String>().unwrap();
+        assert!(message.starts_with("downcast of a `thin_trait_objects::Thin<dyn core::any::Any>`"), "{}", message);
+        std::mem::forget(copy);
+    }
+}
@@ -0,0 +1,163 @@
+//! Per-method call counters, enabled by the `profile` feature.
+//!
+//! Every call through a `#[thin]` vtable slot (from Rust or foreign code) is counted per trait,
+//! concrete type and method, so that hosts can find which erased calls dominate, and are worth
+//! devirtualizing or marking `#[thin(hot)]`.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::profile;
+//!
+//! #[thin]
+//! trait Shader: 'static {
+//!     fn shade(&self, x: f32) -> f32;
+//! }
+//!
+//! impl Shader for f32 {
+//!     fn shade(&self, x: f32) -> f32 { self * x }
+//! }
+//!
+//! let shader = Thin::<dyn Shader>::new(0.5f32);
+//! for i in 0..10 {
+//!     shader.shade(i as f32);
+//! }
+//!
+//! let counts = profile::snapshot();
+//! let shade = counts.iter().find(|count| count.trait_name == "Shader").unwrap();
+//! assert_eq!((shade.type_name, shade.method, shade.calls), ("f32", "shade", 10));
+//! ```
+
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicU64;
+use std::sync::RwLock;
+
+/// The number of calls to a method of a trait, on thin objects of a concrete type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallCount {
+    pub trait_name: &'static str,
+    pub type_name: &'static str,
+    pub method: &'static str,
+    pub calls: u64,
+}
+
+struct Counter {
+    trait_name: &'static str,
+    type_name: &'static str,
+    method: &'static str,
+    calls: AtomicU64,
+}
+
+// targets without 64-bit atomics count under a lock instead
+#[cfg(not(target_has_atomic = "64"))]
+struct AtomicU64(std::sync::Mutex<u64>);
+
+#[cfg(not(target_has_atomic = "64"))]
+impl AtomicU64 {
+    const fn new(calls: u64) -> Self {
+        AtomicU64(std::sync::Mutex::new(calls))
+    }
+
+    fn fetch_add(&self, calls: u64, _: Ordering) {
+        *self.0.lock().unwrap() += calls;
+    }
+
+    fn load(&self, _: Ordering) -> u64 {
+        *self.0.lock().unwrap()
+    }
+
+    fn store(&self, calls: u64, _: Ordering) {
+        *self.0.lock().unwrap() = calls;
+    }
+}
+
+type Key = (TypeId, &'static str, &'static str);
+
+// counters are leaked, so that they can be incremented without holding the lock
+static COUNTERS: RwLock<Option<HashMap<Key, &'static Counter>>> = RwLock::new(None);
+
+pub(crate) fn count_call<T: 'static>(trait_name: &'static str, method: &'static str) {
+    let key = (TypeId::of::<T>(), trait_name, method);
+    let counter = COUNTERS.read().unwrap().as_ref().and_then(|counters| counters.get(&key).copied());
+    let counter = counter.unwrap_or_else(|| {
+        let mut counters = COUNTERS.write().unwrap();
+        *counters.get_or_insert_with(HashMap::new).entry(key).or_insert_with(|| {
+            Box::leak(Box::new(Counter { trait_name, type_name: type_name::<T>(), method, calls: AtomicU64::new(0) }))
+        })
+    });
+    counter.calls.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns the number of calls to each method so far, most called first.
+///
+/// Methods which haven't been called (since the last [`reset`]) are omitted.
+pub fn snapshot() -> Vec<CallCount> {
+    let counters = COUNTERS.read().unwrap();
+    let mut counts = counters.iter().flat_map(|counters| counters.values()).map(|counter| CallCount {
+        trait_name: counter.trait_name,
+        type_name: counter.type_name,
+        method: counter.method,
+        calls: counter.calls.load(Ordering::Relaxed),
+    }).filter(|count| count.calls > 0).collect::<Vec<_>>();
+    counts.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| (a.trait_name, a.type_name, a.method).cmp(&(b.trait_name, b.type_name, b.method))));
+    counts
+}
+
+/// Sets every counter back to zero.
+pub fn reset() {
+    for counter in COUNTERS.read().unwrap().iter().flat_map(|counters| counters.values()) {
+        counter.calls.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[thin(dual_abi)]
+    trait Profiled: 'static {
+        fn read(&self) -> u32;
+        fn write(&mut self, value: u32);
+    }
+
+    impl Profiled for u32 {
+        fn read(&self) -> u32 {
+            *self
+        }
+        fn write(&mut self, value: u32) {
+            *self = value;
+        }
+    }
+
+    impl Profiled for u64 {
+        fn read(&self) -> u32 {
+            *self as u32
+        }
+        fn write(&mut self, value: u32) {
+            *self = value as u64;
+        }
+    }
+
+    // the counters are global, so only this test uses `Profiled`
+    #[test]
+    fn counts() {
+        let mut a = Thin::<dyn Profiled>::new(0u32);
+        let b = Thin::<dyn Profiled>::new(0u64);
+        for i in 0..3 {
+            a.write(i);
+            a.read();
+        }
+        a.read();
+        b.read();
+
+        let counts = snapshot().into_iter().filter(|count| count.trait_name == "Profiled").collect::<Vec<_>>();
+        let summary = counts.iter().map(|count| (count.type_name, count.method, count.calls)).collect::<Vec<_>>();
+        assert_eq!(summary, [("u32", "read", 4), ("u32", "write", 3), ("u64", "read", 1)]);
+
+        reset();
+        assert!(snapshot().iter().all(|count| count.trait_name != "Profiled"));
+    }
+}
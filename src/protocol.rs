@@ -0,0 +1,253 @@
+//! A handshake between processes, agreeing on the traits and types both sides support before any
+//! erased payloads are exchanged.
+//!
+//! Each side sends its [`Capabilities`]: the ABI hashes of the thin traits it implements (see
+//! [`trait_abi_hash`]) and the `StableTypeId`s it can decode. [`handshake`] then returns the ones
+//! both sides have in common.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::protocol::Capabilities;
+//!
+//! #[thin]
+//! trait Codec: 'static {
+//!     fn encode(&self, value: u32) -> u64;
+//! }
+//!
+//! #[derive(StableAny)]
+//! struct Frame;
+//!
+//! let local = Capabilities::new().with_trait::<dyn Codec>().with_type::<Frame>().with_type::<u8>();
+//! let mut wire = Vec::new();
+//! local.write_to(&mut wire).unwrap();
+//!
+//! let remote = Capabilities::read_from(&mut &wire[..]).unwrap();
+//! let common = local.intersect(&Capabilities::new().with_type::<Frame>()).intersect(&remote);
+//! assert!(common.supports_type::<Frame>());
+//! assert!(!common.supports_type::<u8>());
+//! assert!(!common.supports_trait::<dyn Codec>());
+//! ```
+
+use std::io::{self, Read, Write};
+
+use crate::prelude::*;
+
+const MAGIC: [u8; 4] = *b"THIN";
+
+/// The version of the wire format, bumped whenever it changes.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// guards against allocating for garbage lengths
+const MAX_ENTRIES: u32 = 1 << 16;
+
+/// Hashes the vtable layout of `T`: the names of its slots, in order.
+///
+/// The hash changes when methods are added, removed, renamed or reordered, but not when only their
+/// signatures change, so it should be paired with a version for traits whose signatures evolve.
+/// It's computed with [`StableHasher`], so both sides must use the same hash algorithm.
+pub const fn trait_abi_hash<T: ?Sized + SlotLayout>() -> u64 {
+    hash_slots(T::SLOTS)
+}
+
+pub(crate) const fn hash_slots(slots: &[&str]) -> u64 {
+    let mut hasher = StableHasher::new();
+    let mut i = 0;
+    while i < slots.len() {
+        hasher.write(slots[i].as_bytes());
+        // separates the names, so that `["ab", "c"]` and `["a", "bc"]` differ
+        hasher.write(&[0xFF]);
+        i += 1;
+    }
+    hasher.finish()
+}
+
+/// The traits and types supported by one side of a connection.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Capabilities {
+    /// ABI hashes of the supported traits, from [`trait_abi_hash`].
+    pub traits: Vec<u64>,
+    pub types: Vec<StableTypeId>,
+}
+
+impl Capabilities {
+    pub fn new() -> Self {
+        Capabilities::default()
+    }
+
+    pub fn with_trait<T: ?Sized + SlotLayout>(mut self) -> Self {
+        self.traits.push(trait_abi_hash::<T>());
+        self
+    }
+
+    pub fn with_type<T: StableAny>(mut self) -> Self {
+        self.types.push(StableTypeId::of::<T>());
+        self
+    }
+
+    pub fn supports_trait<T: ?Sized + SlotLayout>(&self) -> bool {
+        self.traits.contains(&trait_abi_hash::<T>())
+    }
+
+    pub fn supports_type<T: StableAny>(&self) -> bool {
+        self.types.contains(&StableTypeId::of::<T>())
+    }
+
+    /// Returns the traits and types supported by both `self` and `other`, in the order of `self`.
+    pub fn intersect(&self, other: &Capabilities) -> Capabilities {
+        Capabilities {
+            traits: self.traits.iter().copied().filter(|hash| other.traits.contains(hash)).collect(),
+            types: self.types.iter().copied().filter(|id| other.types.contains(id)).collect(),
+        }
+    }
+
+    /// Writes the capabilities in the wire format: the magic bytes `THIN`, then the protocol version,
+    /// the number of traits, their hashes, the number of types and their ids, all little-endian.
+    /// Counts are `u32`s, and hashes and ids `u64`s.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(16 + 8 * (self.traits.len() + self.types.len()));
+        bytes.extend_from_slice(&MAGIC);
+        bytes.extend_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        let ids = self.types.iter().map(|&id| unsafe { id.to_u64() });
+        for list in [self.traits.clone(), ids.collect()] {
+            let len = u32::try_from(list.len()).ok().filter(|&len| len <= MAX_ENTRIES).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "too many capabilities")
+            })?;
+            bytes.extend_from_slice(&len.to_le_bytes());
+            list.iter().for_each(|n| bytes.extend_from_slice(&n.to_le_bytes()));
+        }
+        writer.write_all(&bytes)
+    }
+
+    /// Reads capabilities written by [`Capabilities::write_to`].
+    ///
+    /// Fails with `InvalidData` if the magic bytes or protocol version don't match.
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a thin_trait_objects handshake"));
+        }
+        let version = read_u32(reader)?;
+        if version != PROTOCOL_VERSION {
+            let msg = format!("unsupported handshake protocol version {version}");
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+
+        let traits = read_list(reader)?;
+        let types = read_list(reader)?.into_iter().map(|n| unsafe { StableTypeId::new(n) }).collect();
+        Ok(Capabilities { traits, types })
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_list(reader: &mut impl Read) -> io::Result<Vec<u64>> {
+    let len = read_u32(reader)?;
+    if len > MAX_ENTRIES {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "too many capabilities"));
+    }
+    (0..len).map(|_| {
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }).collect()
+}
+
+/// Sends `local` over `transport`, receives the other side's capabilities, and returns those both
+/// sides support.
+///
+/// Both sides send before receiving, so the transport must buffer at least one message in each
+/// direction (as sockets and pipes do).
+pub fn handshake<S: Read + Write>(transport: &mut S, local: &Capabilities) -> io::Result<Capabilities> {
+    local.write_to(transport)?;
+    transport.flush()?;
+    let remote = Capabilities::read_from(transport)?;
+    Ok(local.intersect(&remote))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[thin]
+    trait Old: 'static {
+        fn a(&self);
+    }
+
+    #[thin]
+    trait New: 'static {
+        fn a(&self);
+        fn b(&self);
+    }
+
+    #[derive(StableAny)]
+    struct Shared;
+
+    #[derive(StableAny)]
+    struct Private;
+
+    struct Duplex {
+        incoming: Cursor<Vec<u8>>,
+        outgoing: Vec<u8>,
+    }
+
+    impl Read for Duplex {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.incoming.read(buf)
+        }
+    }
+
+    impl Write for Duplex {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.outgoing.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn abi_hashes() {
+        assert_ne!(trait_abi_hash::<dyn Old>(), trait_abi_hash::<dyn New>());
+        const HASH: u64 = trait_abi_hash::<dyn Old>();
+        assert_eq!(HASH, trait_abi_hash::<dyn Old>());
+    }
+
+    #[test]
+    fn handshaking() {
+        let remote = Capabilities::new().with_trait::<dyn New>().with_type::<Shared>();
+        let mut incoming = Vec::new();
+        remote.write_to(&mut incoming).unwrap();
+
+        let local = Capabilities::new()
+            .with_trait::<dyn Old>()
+            .with_trait::<dyn New>()
+            .with_type::<Private>()
+            .with_type::<Shared>();
+        let mut transport = Duplex { incoming: Cursor::new(incoming), outgoing: Vec::new() };
+        let common = handshake(&mut transport, &local).unwrap();
+        assert_eq!(common, Capabilities::new().with_trait::<dyn New>().with_type::<Shared>());
+        assert_eq!(Capabilities::read_from(&mut &transport.outgoing[..]).unwrap(), local);
+    }
+
+    #[test]
+    fn rejecting_garbage() {
+        let mut bytes = Vec::new();
+        Capabilities::new().write_to(&mut bytes).unwrap();
+        bytes[4] = 0xFF;
+        let err = Capabilities::read_from(&mut &bytes[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = Capabilities::read_from(&mut &b"HTTP/1.1"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = Capabilities::read_from(&mut &b"THIN"[..]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,349 @@
+//! The leading slots shared by every `#[thin]` vtable, for implementing traits by hand, e.g. in C.
+//!
+//! A thin object points to an object whose first word is a pointer to its vtable ([`RawThinHeader`]).
+//! The vtable starts with a [`RawVTableHeader`], followed by the trait's methods in declaration
+//! order (`#[thin(hot)]` methods first), each taking the object as its first argument, then by the
+//! extension pointer (`#[thin(extension)]`), the `TypeId` (`#[thin(any)]`) and the pointer to the
+//! Rust-ABI entries (`#[thin(dual_abi)]`, null in foreign vtables), if any. The header's size is the
+//! extent of the methods, which those fields follow. The header
+//! generated by the `codegen` module spells out the whole vtable as `FooVTable`.
+//!
+//! A C backend implementing `trait Gauge: 'static { fn level(&self) -> u32; }` allocates objects
+//! starting with the vtable pointer, and frees them from the drop slot:
+//!
+//! ```c
+//! #include "gauge.h"
+//!
+//! typedef struct Tank {
+//!     const GaugeVTable *vtable;
+//!     uint32_t litres;
+//! } Tank;
+//!
+//! static void tank_drop(Gauge *self) { free(self); }
+//! static uint32_t tank_level(const Gauge *self) { return ((const Tank *)self)->litres; }
+//!
+//! // the checksum slot only exists with the `debug-checksum` feature, whose checks C vtables fail
+//! static const GaugeVTable TANK_VTABLE = { tank_drop, sizeof(GaugeVTable), tank_level };
+//!
+//! Gauge *tank_new(uint32_t litres) {
+//!     Tank *tank = malloc(sizeof *tank);
+//!     tank->vtable = &TANK_VTABLE;
+//!     tank->litres = litres;
+//!     return (Gauge *)tank;
+//! }
+//! ```
+//!
+//! The Rust host then takes ownership with `Thin::<dyn Gauge>::from_c_void(tank_new(40).cast())`.
+//! Vtables can be built in Rust the same way:
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::raw::{RawThinHeader, RawVTableHeader};
+//!
+//! #[thin]
+//! trait Gauge: 'static {
+//!     fn level(&self) -> u32;
+//! }
+//!
+//! #[repr(C)]
+//! struct GaugeVTable {
+//!     header: RawVTableHeader,
+//!     level: extern "C" fn(RefSelf<'_>) -> u32,
+//! }
+//!
+//! #[repr(C)]
+//! struct Tank {
+//!     header: RawThinHeader,
+//!     litres: u32,
+//! }
+//!
+//! extern "C-unwind" fn tank_drop(ptr: *mut ()) {
+//!     drop(unsafe { Box::from_raw(ptr as *mut Tank) });
+//! }
+//!
+//! extern "C" fn tank_level(recv: RefSelf<'_>) -> u32 {
+//!     unsafe { (*(recv.ptr as *const Tank)).litres }
+//! }
+//!
+//! static TANK_VTABLE: GaugeVTable = GaugeVTable {
+//!     header: RawVTableHeader::new(tank_drop, size_of::<GaugeVTable>()),
+//!     level: tank_level,
+//! };
+//!
+//! // SAFETY: `GaugeVTable` starts with its header
+//! let vtable = unsafe { RawVTableHeader::seal(&TANK_VTABLE) };
+//! let tank = Box::new(Tank { header: RawThinHeader::new(&vtable.header), litres: 40 });
+//! let gauge = unsafe { Thin::<dyn Gauge>::from_c_void(Box::into_raw(tank).cast()) };
+//! assert_eq!(gauge.level(), 40);
+//! ```
+//!
+//! Traits which can't be annotated with `#[thin]` at all, such as those with type parameters, get
+//! their object type from [`thin_vtable!`](crate::prelude::thin_vtable), which declares the vtable
+//! struct (with the header as its `header` field) and implements `SpecialAssoc` and `SlotLayout`
+//! for the object type. The shims are written by hand, reaching the value through [`RawBundle`],
+//! and the struct's `header::<K>()`, `new_thin` and `of` take care of allocating, dropping and
+//! loading the vtable the way `#[thin]` does. Thin objects aren't `Send` or `Sync` unless
+//! `ThinSend` and `ThinSync` are implemented for the object type, and a panic unwinding out of an
+//! `extern "C"` shim aborts.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::raw::RawBundle;
+//!
+//! // `#[thin]` doesn't take type parameters
+//! trait Handler<E: 'static>: 'static {
+//!     fn handle(&mut self, event: &E) -> bool;
+//! }
+//!
+//! thin_vtable! {
+//!     struct HandlerVTable<E: 'static> for dyn Handler<E> {
+//!         handle: extern "C" fn(MutSelf<'_>, &E) -> bool,
+//!     }
+//! }
+//!
+//! extern "C" fn handle<E: 'static, K: Handler<E>>(recv: MutSelf<'_>, event: &E) -> bool {
+//!     let value = unsafe { RawBundle::<HandlerVTable<E>, K>::value_mut(recv) };
+//!     value.handle(event)
+//! }
+//!
+//! impl<E: 'static, K: Handler<E>> ThinExt<dyn Handler<E>, K> for Thin<dyn Handler<E>> {
+//!     fn new(value: K) -> Self {
+//!         let vtable: &'static HandlerVTable<E> = const {
+//!             &HandlerVTable { header: HandlerVTable::<E>::header::<K>(), handle: handle::<E, K> }
+//!         };
+//!         // SAFETY: the vtable and its shims are for `K`s
+//!         unsafe { vtable.new_thin(value) }
+//!     }
+//! }
+//!
+//! impl<E: 'static> Handler<E> for Thin<dyn Handler<E>> {
+//!     fn handle(&mut self, event: &E) -> bool {
+//!         let handle = HandlerVTable::of(self).handle;
+//!         handle(MutSelf::new(self), event)
+//!     }
+//! }
+//!
+//! impl<F: FnMut(&u32) -> bool + 'static> Handler<u32> for F {
+//!     fn handle(&mut self, event: &u32) -> bool { self(event) }
+//! }
+//!
+//! let mut even = Thin::<dyn Handler<u32>>::new(|event: &u32| event % 2 == 0);
+//! assert!(even.handle(&4) && !even.handle(&5));
+//! ```
+
+use crate::checksum::VTableChecksum;
+use crate::drop_panic::drop_bundle;
+use crate::poison::check_poison;
+use crate::{MutSelf, RefSelf};
+
+/// The slots at the start of every `#[thin]` vtable.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawVTableHeader {
+    /// Drops the object, and frees its allocation.
+    pub drop: extern "C-unwind" fn(*mut ()),
+    /// The size in bytes of the header and the method slots, which lets callers detect vtables
+    /// created against an older version of the trait. The fields after the methods are only read
+    /// from vtables whose methods end where the caller's do.
+    pub size: usize,
+    // a `size_t` with the `debug-checksum` feature, and zero-sized otherwise
+    checksum: VTableChecksum,
+}
+
+impl RawVTableHeader {
+    /// The header of a vtable whose method slots extend `size` bytes. With the `debug-checksum`
+    /// feature, the vtable must be passed through [`seal`](Self::seal) before it's used.
+    pub const fn new(drop: extern "C-unwind" fn(*mut ()), size: usize) -> Self {
+        RawVTableHeader { drop, size, checksum: VTableChecksum::UNSEALED }
+    }
+
+    /// Returns a copy of `vtable` with its checksum filled in, made the first time it's sealed. A
+    /// const context can't read the addresses of the slots, so vtables are created unsealed.
+    /// Returns `vtable` itself without the `debug-checksum` feature.
+    ///
+    /// # Safety
+    /// `V` must start with a `RawVTableHeader`, whose size is at most that of a `V`.
+    #[inline(always)]
+    pub unsafe fn seal<V: 'static>(vtable: &'static V) -> &'static V {
+        #[cfg(feature = "debug-checksum")]
+        {
+            use std::collections::BTreeMap;
+            use std::sync::{Mutex, PoisonError};
+
+            // from the address of each vtable to that of its sealed copy, which is never freed
+            static SEALED: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+            let mut sealed = SEALED.lock().unwrap_or_else(PoisonError::into_inner);
+            let copy = *sealed.entry(vtable as *const V as usize).or_insert_with(|| {
+                let copy = Box::into_raw(Box::new(unsafe { std::ptr::read(vtable) }));
+                let header = copy as *mut RawVTableHeader;
+                unsafe { (*header).checksum = VTableChecksum::of(header as *const (), (*header).size) };
+                copy as usize
+            });
+            unsafe { &*(copy as *const V) }
+        }
+        #[cfg(not(feature = "debug-checksum"))]
+        vtable
+    }
+
+    /// The header of a `V` whose objects are `RawBundle<V, K>`s, for `thin_vtable!`'s `header`.
+    #[doc(hidden)]
+    pub const fn for_bundle<V: 'static, K>() -> Self {
+        RawVTableHeader::new(RawBundle::<V, K>::drop, size_of::<V>())
+    }
+
+    /// Loads the vtable of the object at `ptr` as a `V`, for `thin_vtable!`'s `of`, panicking if
+    /// it's shorter than a `V` (e.g. if it was created against an older version of the trait).
+    ///
+    /// # Safety
+    /// `ptr` must point to a live object whose vtable has the layout of a `V`, as far as it goes.
+    #[doc(hidden)]
+    pub unsafe fn load<V>(ptr: *const (), vtable_name: &str) -> &'static V {
+        check_poison(ptr);
+        let vtable = unsafe { *(ptr as *const *const RawVTableHeader) };
+        let size = unsafe { RawVTableHeader::verified_size(vtable) };
+        if size < size_of::<V>() {
+            panic!(
+                "this thin object's vtable is shorter than a `{}`, and was likely created against an older version of it",
+                vtable_name,
+            );
+        }
+        unsafe { &*(vtable as *const V) }
+    }
+
+    /// The size of the vtable at `vtable`, after verifying its checksum.
+    ///
+    /// # Safety
+    /// `vtable` must point to a vtable starting with a `RawVTableHeader`.
+    pub(crate) unsafe fn verified_size(vtable: *const RawVTableHeader) -> usize {
+        let size = unsafe { (*vtable).size };
+        unsafe { (*vtable).checksum.verify(vtable as *const (), size) };
+        size
+    }
+}
+
+/// The start of every object a thin object points to.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RawThinHeader {
+    pub vtable: *const RawVTableHeader,
+}
+
+impl RawThinHeader {
+    pub const fn new(vtable: &'static RawVTableHeader) -> Self {
+        RawThinHeader { vtable }
+    }
+}
+
+/// The objects of thin objects created by a `thin_vtable!` vtable's `new_thin`: a pointer to the
+/// vtable `V`, followed by the value. Every bundle the crate allocates has this layout, and code
+/// which doesn't know the type of the vtable reads them as `RawBundle<(), K>`.
+#[repr(C)]
+pub struct RawBundle<V: 'static, K> {
+    pub vtable: &'static V,
+    pub value: K,
+}
+
+impl<V: 'static, K> RawBundle<V, K> {
+    // drops the value and frees the bundle, as `#[thin]`'s drop slots do
+    extern "C-unwind" fn drop(ptr: *mut ()) {
+        unsafe { drop_bundle::<Self>(ptr) }
+    }
+
+    /// The value of the bundle the receiver of a shim points to.
+    ///
+    /// # Safety
+    /// `recv` must point to a `RawBundle<V, K>`.
+    pub unsafe fn value(recv: RefSelf<'_>) -> &K {
+        unsafe { &(*(recv.ptr as *const Self)).value }
+    }
+
+    /// The value of the bundle the receiver of a shim points to.
+    ///
+    /// # Safety
+    /// `recv` must point to a `RawBundle<V, K>`.
+    pub unsafe fn value_mut(recv: MutSelf<'_>) -> &mut K {
+        unsafe { &mut (*(recv.ptr as *mut Self)).value }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::offset_of;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[thin]
+    trait Meter: 'static {
+        fn read(&self) -> u32;
+    }
+
+    impl Meter for u32 {
+        fn read(&self) -> u32 {
+            *self
+        }
+    }
+
+    #[test]
+    fn matches_generated_vtables() {
+        let word = size_of::<*const ()>();
+        assert_eq!(<dyn Meter as SlotLayout>::SIZE_SLOT, Some(offset_of!(RawVTableHeader, size) / word));
+        assert_eq!(<dyn Meter as SlotLayout>::CHECKSUM_SLOT.is_some(), size_of::<VTableChecksum>() > 0);
+        assert_eq!(<dyn Meter>::SLOT_READ, size_of::<RawVTableHeader>() / word);
+
+        let meter = Thin::<dyn Meter>::new(7u32);
+        let header = unsafe { &*(*(meter.ptr.as_ptr() as *const RawThinHeader)).vtable };
+        assert_eq!(Some(header.size), meter.vtable_size());
+        #[cfg(feature = "debug-checksum")]
+        assert_eq!(header.checksum, unsafe { VTableChecksum::of(header as *const RawVTableHeader as *const (), header.size) });
+    }
+
+    trait Sink<T: 'static>: 'static {
+        fn put(&mut self, item: T);
+    }
+
+    impl<T: 'static> Sink<T> for Vec<T> {
+        fn put(&mut self, item: T) {
+            self.push(item)
+        }
+    }
+
+    thin_vtable! {
+        struct SinkVTable<T: 'static> for dyn Sink<T> {
+            put: extern "C" fn(MutSelf<'_>, T),
+        }
+    }
+
+    extern "C" fn put<T: 'static, K: Sink<T>>(recv: MutSelf<'_>, item: T) {
+        unsafe { RawBundle::<SinkVTable<T>, K>::value_mut(recv) }.put(item)
+    }
+
+    fn sink<T: 'static, K: Sink<T>>(value: K) -> Thin<dyn Sink<T>> {
+        let vtable: &'static SinkVTable<T> = const { &SinkVTable { header: SinkVTable::<T>::header::<K>(), put: put::<T, K> } };
+        unsafe { vtable.new_thin(value) }
+    }
+
+    #[test]
+    fn manual_vtables() {
+        let items = std::rc::Rc::new(());
+        let mut thin = sink(Vec::new());
+        let vtable = SinkVTable::of(&thin);
+        for _ in 0..3 {
+            (vtable.put)(MutSelf::new(&mut thin), items.clone());
+        }
+        assert_eq!(std::rc::Rc::strong_count(&items), 4);
+        assert_eq!(<dyn Sink<u8> as SlotLayout>::SLOTS.last(), Some(&"put"));
+        drop(thin);
+        assert_eq!(std::rc::Rc::strong_count(&items), 1);
+
+        // a vtable missing the `put` slot
+        static SHORT: RawVTableHeader = RawVTableHeader::new(RawBundle::<SinkVTable<u8>, ()>::drop, size_of::<RawVTableHeader>());
+        let short = unsafe { RawVTableHeader::seal(&SHORT) };
+        let object = crate::alloc_bundle(RawBundle { vtable: short, value: () });
+        let thin = unsafe { Thin::<dyn Sink<u8>>::from_raw(object) };
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| SinkVTable::of(&thin).put)).unwrap_err();
+        assert!(panic.downcast_ref::<String>().unwrap().contains("shorter than a `SinkVTable`"));
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(thin.into_parts()))).unwrap_err();
+        assert!(panic.downcast_ref::<&str>().unwrap().contains("older version of the trait"));
+    }
+}
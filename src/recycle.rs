@@ -0,0 +1,235 @@
+//! A thread-local free-list of thin object allocations, for workloads which create and drop many
+//! similarly-sized thin objects (e.g. every frame).
+//!
+//! With the `recycle` feature, dropping a `#[thin]` object (or a `Thin<dyn Any>`) keeps its
+//! allocation on a free-list for its exact layout, rather than freeing it, and creating the next
+//! object of the same layout on the same thread reuses it. At most [`capacity`] allocations are
+//! kept per layout, and each thread's are freed when it exits.
+//!
+//! Allocations are made with the global allocator and the bundle's own layout, so objects consumed
+//! by other means (e.g. `Thin::downcast`) are freed as usual. With `debug-poison`, dropped objects
+//! are leaked rather than recycled.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::recycle;
+//!
+//! #[thin]
+//! trait Particle: 'static {
+//!     fn step(&mut self);
+//! }
+//!
+//! impl Particle for [f32; 4] {
+//!     fn step(&mut self) {
+//!         self[0] += self[2];
+//!         self[1] += self[3];
+//!     }
+//! }
+//!
+//! for _frame in 0..3 {
+//!     let particles = (0..100).map(|_| Thin::<dyn Particle>::new([0.0; 4])).collect::<Vec<_>>();
+//!     drop(particles);
+//!     assert!(recycle::cached() <= recycle::capacity());
+//! }
+//! recycle::trim();
+//! assert_eq!(recycle::cached(), 0);
+//! ```
+
+use std::alloc::{self, Layout};
+use std::cell::{Cell, RefCell};
+use std::ptr::{self, NonNull};
+
+/// The default [`capacity`].
+pub const DEFAULT_CAPACITY: usize = 64;
+
+struct FreeList {
+    layout: Layout,
+    blocks: Vec<NonNull<u8>>,
+}
+
+#[derive(Default)]
+struct FreeLists(Vec<FreeList>);
+
+impl FreeLists {
+    fn take(&mut self, layout: Layout) -> Option<NonNull<u8>> {
+        self.0.iter_mut().find(|list| list.layout == layout)?.blocks.pop()
+    }
+
+    // returns whether the block was kept
+    fn put(&mut self, layout: Layout, block: NonNull<u8>) -> bool {
+        let capacity = CAPACITY.get();
+        let index = match self.0.iter().position(|list| list.layout == layout) {
+            Some(index) => index,
+            None if capacity == 0 => return false,
+            None => {
+                self.0.push(FreeList { layout, blocks: Vec::new() });
+                self.0.len() - 1
+            }
+        };
+        let list = &mut self.0[index];
+        if list.blocks.len() >= capacity {
+            return false;
+        }
+        list.blocks.push(block);
+        true
+    }
+
+    fn truncate(&mut self, capacity: usize) {
+        for list in &mut self.0 {
+            while list.blocks.len() > capacity {
+                let block = list.blocks.pop().unwrap();
+                unsafe { alloc::dealloc(block.as_ptr(), list.layout) };
+            }
+        }
+    }
+}
+
+impl Drop for FreeLists {
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+
+thread_local! {
+    static FREE_LISTS: RefCell<FreeLists> = RefCell::default();
+    static CAPACITY: Cell<usize> = const { Cell::new(DEFAULT_CAPACITY) };
+}
+
+/// Returns how many allocations of each layout the current thread keeps.
+pub fn capacity() -> usize {
+    CAPACITY.get()
+}
+
+/// Sets how many allocations of each layout the current thread keeps, freeing any beyond that.
+///
+/// A capacity of `0` disables recycling on the current thread.
+pub fn set_capacity(capacity: usize) {
+    CAPACITY.set(capacity);
+    FREE_LISTS.with_borrow_mut(|lists| lists.truncate(capacity));
+}
+
+/// Frees every allocation kept by the current thread.
+pub fn trim() {
+    FREE_LISTS.with_borrow_mut(|lists| lists.truncate(0));
+}
+
+/// Returns the number of allocations kept by the current thread.
+pub fn cached() -> usize {
+    FREE_LISTS.with_borrow(|lists| lists.0.iter().map(|list| list.blocks.len()).sum())
+}
+
+pub(crate) fn alloc_bundle<B>(bundle: B) -> *mut () {
+    let layout = Layout::new::<B>();
+    // the free-lists are unavailable while the thread is exiting
+    let block = FREE_LISTS.try_with(|lists| lists.try_borrow_mut().ok()?.take(layout)).ok().flatten();
+    match block {
+        Some(block) => {
+            let ptr = block.as_ptr() as *mut B;
+            unsafe { ptr.write(bundle) };
+            ptr as *mut ()
+        }
+        None => Box::into_raw(Box::new(bundle)) as *mut (),
+    }
+}
+
+/// Drops the bundle (of type `B`) at `ptr`, keeping its allocation if there's room.
+///
+/// # Safety
+/// `ptr` must have been created by [`alloc_bundle`] or `Box::<B>::into_raw`, and not be used afterwards.
+pub(crate) unsafe fn free_bundle<B>(ptr: *mut ()) {
+    // a panic here leaks the allocation, as with `Box`es under `DropPanicPolicy::Swallow`
+    unsafe { ptr::drop_in_place(ptr as *mut B) };
+    let layout = Layout::new::<B>();
+    let block = unsafe { NonNull::new_unchecked(ptr as *mut u8) };
+    let kept = FREE_LISTS.try_with(|lists| match lists.try_borrow_mut() {
+        Ok(mut lists) => lists.put(layout, block),
+        Err(_) => false,
+    });
+    if kept != Ok(true) {
+        unsafe { alloc::dealloc(block.as_ptr(), layout) };
+    }
+}
+
+#[cfg(all(test, not(feature = "debug-poison")))]
+mod tests {
+    #[cfg(feature = "any")]
+    use std::any::Any;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::prelude::*;
+
+    #[thin]
+    trait Value: 'static {
+        fn get(&self) -> u64;
+    }
+
+    impl Value for u64 {
+        fn get(&self) -> u64 {
+            *self
+        }
+    }
+
+    impl Value for (u64, u64) {
+        fn get(&self) -> u64 {
+            self.0 + self.1
+        }
+    }
+
+    // tests run on their own threads, so each starts with empty free-lists
+    #[test]
+    fn reuse() {
+        let a = Thin::<dyn Value>::new(1u64);
+        let address = a.ptr;
+        drop(a);
+        assert_eq!(cached(), 1);
+
+        let b = Thin::<dyn Value>::new(2u64);
+        assert_eq!(b.ptr, address);
+        assert_eq!(b.get(), 2);
+        assert_eq!(cached(), 0);
+
+        // a different layout doesn't reuse it
+        drop(b);
+        let c = Thin::<dyn Value>::new((3u64, 4u64));
+        assert_ne!(c.ptr, address);
+        assert_eq!(cached(), 1);
+        drop(c);
+        assert_eq!(cached(), 2);
+    }
+
+    #[cfg(feature = "any")]
+    #[test]
+    fn consumed() {
+        // any object of the same layout reuses an allocation
+        drop(Thin::<dyn Value>::new(1u64));
+        let any = Thin::<dyn Any>::new(2u64);
+        assert_eq!(cached(), 0);
+
+        // consuming an object frees its allocation as usual
+        assert_eq!(any.downcast::<u64>(), Some(2));
+        assert_eq!(cached(), 0);
+    }
+
+    #[test]
+    fn capacities() {
+        let values = Rc::new(0u64);
+        let things = (0..10).map(|_| Thin::<dyn Value>::new(values.clone())).collect::<Vec<_>>();
+        set_capacity(4);
+        drop(things);
+        assert_eq!(cached(), 4);
+        assert_eq!(Rc::strong_count(&values), 1);
+
+        set_capacity(1);
+        assert_eq!(cached(), 1);
+        set_capacity(0);
+        assert_eq!(cached(), 0);
+        drop(Thin::<dyn Value>::new(values.clone()));
+        assert_eq!(cached(), 0);
+
+        set_capacity(DEFAULT_CAPACITY);
+        drop(Thin::<dyn Value>::new(values));
+        trim();
+        assert_eq!(cached(), 0);
+    }
+}
@@ -0,0 +1,430 @@
+//! A catalogue of the concrete `StableAny` types linked into the binary, collected at link time.
+//!
+//! Every non-generic type given to `#[derive(StableAny)]` or `impl_stable_any!` is registered
+//! automatically, from any crate, without explicit registration calls.
+//!
+//! In debug builds, the registry is checked for id collisions (which would make downcasts unsound)
+//! the first time it's used; see [`verify_no_collisions`].
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::registry;
+//!
+//! #[derive(StableAny)]
+//! struct Message;
+//!
+//! let ty = registry::lookup(StableTypeId::of::<Message>()).unwrap();
+//! assert!(ty.name.ends_with("::Message"));
+//! ```
+//!
+//! Types may also register a clone function with [`register_clone!`](crate::register_clone), which
+//! `Thin::<dyn StableAny>::try_clone` uses to duplicate erased values without a clone slot in
+//! every vtable.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[derive(StableAny, Clone)]
+//! struct Message(String);
+//!
+//! thin_trait_objects::register_clone!(Message);
+//!
+//! let thin = Thin::<dyn StableAny>::new(Message("hello".into()));
+//! let copy = thin.try_clone().unwrap();
+//! assert_eq!(copy.downcast_ref::<Message>().unwrap().0, "hello");
+//! assert!(Thin::<dyn StableAny>::new(()).try_clone().is_none());
+//! ```
+//!
+//! Hosts can accept values from plugins built against older definitions of a type by keeping the
+//! old definition (with its old id) and registering a migration from it with
+//! [`register_migration!`](crate::register_migration), which `Thin::<dyn StableAny>::downcast_migrating`
+//! follows when the stored type isn't the one asked for. Migrations can be chained.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! // the definition plugins were built against, keeping its id
+//! #[derive(StableAny)]
+//! #[stable_any(unsafe(rename_from = "plugin::Settings"))]
+//! struct SettingsV1 { volume: u8 }
+//!
+//! #[derive(StableAny, PartialEq, Debug)]
+//! struct Settings { volume: u8, muted: bool }
+//!
+//! impl From<SettingsV1> for Settings {
+//!     fn from(old: SettingsV1) -> Self {
+//!         Settings { volume: old.volume, muted: false }
+//!     }
+//! }
+//!
+//! thin_trait_objects::register_migration!(SettingsV1 => Settings);
+//!
+//! let thin = Thin::<dyn StableAny>::new(SettingsV1 { volume: 7 });
+//! assert_eq!(thin.downcast_migrating::<Settings>().ok(), Some(Settings { volume: 7, muted: false }));
+//! ```
+
+use crate::prelude::*;
+use crate::__private::*;
+use crate::protocol::trait_abi_hash;
+
+#[doc(hidden)]
+pub use inventory as __inventory;
+
+/// A registered type.
+#[derive(Debug)]
+pub struct RegisteredType {
+    pub id: StableTypeId,
+    /// The path of the type, as named by the module that registered it.
+    pub name: &'static str,
+}
+
+inventory::collect!(RegisteredType);
+
+/// A clone function registered with [`register_clone!`](crate::register_clone).
+pub struct RegisteredClone {
+    pub id: StableTypeId,
+    /// Clones the thin object at the given pointer into a new allocation with the same vtable.
+    pub clone: extern "C" fn(*const ()) -> *mut (),
+}
+
+inventory::collect!(RegisteredClone);
+
+extern "C" fn clone_bundle<T: Clone>(ptr: *const ()) -> *mut () {
+    check_poison(ptr);
+    let bundle = unsafe { &*(ptr as *const RawBundle<(), T>) };
+    alloc_bundle(RawBundle { vtable: bundle.vtable, value: bundle.value.clone() })
+}
+
+impl RegisteredClone {
+    #[doc(hidden)]
+    pub const fn of<T: StableAny + Clone>() -> Self {
+        Self { id: StableTypeId::of::<T>(), clone: clone_bundle::<T> }
+    }
+}
+
+/// A thin trait implemented by a registered type, from [`register_impl!`](crate::register_impl).
+pub struct RegisteredImpl {
+    pub id: StableTypeId,
+    /// The ABI hash of the trait, from [`trait_abi_hash`].
+    pub trait_hash: u64,
+}
+
+inventory::collect!(RegisteredImpl);
+
+impl RegisteredImpl {
+    #[doc(hidden)]
+    pub const fn of<T: StableAny, U: ?Sized + SpecialAssoc + SlotLayout + 'static>() -> Self where Thin<U>: ThinExt<U, T> {
+        Self { id: StableTypeId::of::<T>(), trait_hash: trait_abi_hash::<U>() }
+    }
+}
+
+/// A migration registered with [`register_migration!`](crate::register_migration).
+pub struct RegisteredMigration {
+    pub from: StableTypeId,
+    pub to: StableTypeId,
+    /// Converts a thin object holding a value of type `from` into one holding a value of type `to`.
+    pub migrate: fn(Thin<dyn StableAny>) -> Thin<dyn StableAny>,
+}
+
+inventory::collect!(RegisteredMigration);
+
+fn migrate<Old: UUID, New: UUID + From<Old>>(thin: Thin<dyn StableAny>) -> Thin<dyn StableAny> {
+    let Some(old) = thin.downcast::<Old>() else { unreachable!("migrations are only applied to their own type") };
+    Thin::<dyn StableAny>::new(New::from(old))
+}
+
+impl RegisteredMigration {
+    #[doc(hidden)]
+    pub const fn of<Old: UUID, New: UUID + From<Old>>() -> Self {
+        Self { from: StableTypeId::of::<Old>(), to: StableTypeId::of::<New>(), migrate: migrate::<Old, New> }
+    }
+}
+
+/// Registers `Clone` for a concrete `StableAny` type, for `Thin::<dyn StableAny>::try_clone`.
+#[macro_export]
+macro_rules! register_clone {
+    ($ty: ty) => {
+        $crate::registry::__inventory::submit! {
+            $crate::registry::RegisteredClone::of::<$ty>()
+        }
+    };
+}
+
+/// Registers a concrete type as implementing thin traits, for [`implements`] and [`traits_of`].
+///
+/// `#[derive(StableAny)]` does this for the traits listed in `#[stable_any(implements(...))]`.
+///
+/// ```rust
+/// use thin_trait_objects::prelude::*;
+/// use thin_trait_objects::registry;
+///
+/// #[thin(rust_only)]
+/// trait Renderer: 'static {
+///     fn render(&self) -> String;
+/// }
+///
+/// #[derive(StableAny)]
+/// #[stable_any(implements(Renderer))]
+/// struct Label(&'static str);
+///
+/// impl Renderer for Label {
+///     fn render(&self) -> String { self.0.to_string() }
+/// }
+///
+/// #[derive(StableAny)]
+/// struct Hidden;
+///
+/// let thin = Thin::<dyn StableAny>::new(Label("hi"));
+/// assert!(registry::implements::<dyn Renderer>(thin.stable_type_id()));
+/// assert!(!registry::implements::<dyn Renderer>(StableTypeId::of::<Hidden>()));
+/// ```
+#[macro_export]
+macro_rules! register_impl {
+    ($ty: ty: $($trait: ty),+ $(,)?) => {
+        $(
+            $crate::registry::__inventory::submit! {
+                $crate::registry::RegisteredImpl::of::<$ty, $trait>()
+            }
+        )+
+    };
+}
+
+/// Registers a migration from an older definition of a type to its current one, through its
+/// `From` impl, for `Thin::<dyn StableAny>::downcast_migrating`.
+#[macro_export]
+macro_rules! register_migration {
+    ($old: ty => $new: ty) => {
+        $crate::registry::__inventory::submit! {
+            $crate::registry::RegisteredMigration::of::<$old, $new>()
+        }
+    };
+}
+
+/// Returns every registered type, in no particular order.
+pub fn types() -> impl Iterator<Item = &'static RegisteredType> {
+    #[cfg(debug_assertions)]
+    {
+        static CHECKED: std::sync::Once = std::sync::Once::new();
+        CHECKED.call_once(verify_no_collisions);
+    }
+    inventory::iter::<RegisteredType>.into_iter()
+}
+
+/// Returns every pair of distinct registered types which share an id.
+pub fn collisions() -> Vec<[&'static RegisteredType; 2]> {
+    find_collisions(inventory::iter::<RegisteredType>.into_iter().collect())
+}
+
+/// Panics, listing the colliding types, if any two registered types share an id.
+pub fn verify_no_collisions() {
+    let collisions = collisions();
+    if collisions.is_empty() {
+        return;
+    }
+
+    let mut message = String::from("StableTypeId collisions between registered types:");
+    for [a, b] in collisions {
+        message += &format!("\n  `{}` and `{}` (id {:?})", a.name, b.name, a.id);
+    }
+    panic!("{}", message);
+}
+
+fn find_collisions(mut types: Vec<&RegisteredType>) -> Vec<[&RegisteredType; 2]> {
+    types.sort_by_key(|ty| unsafe { ty.id.to_u64() });
+    types.windows(2)
+        .filter(|pair| pair[0].id == pair[1].id)
+        .map(|pair| [pair[0], pair[1]])
+        .collect()
+}
+
+/// Returns the registered type with the given id, if any.
+pub fn lookup(id: StableTypeId) -> Option<&'static RegisteredType> {
+    types().find(|ty| ty.id == id)
+}
+
+/// Returns the clone function registered for the type with the given id, if any.
+pub fn lookup_clone(id: StableTypeId) -> Option<extern "C" fn(*const ()) -> *mut ()> {
+    inventory::iter::<RegisteredClone>.into_iter().find(|clone| clone.id == id).map(|clone| clone.clone)
+}
+
+/// Whether the type with the given id was registered as implementing the thin trait `U`.
+pub fn implements<U: ?Sized + SlotLayout>(id: StableTypeId) -> bool {
+    implements_abi(id, trait_abi_hash::<U>())
+}
+
+/// Whether the type with the given id was registered as implementing a thin trait with the given ABI
+/// hash, e.g. one received in another process's [`Capabilities`](crate::protocol::Capabilities).
+pub fn implements_abi(id: StableTypeId, trait_hash: u64) -> bool {
+    traits_of(id).any(|hash| hash == trait_hash)
+}
+
+/// Returns the ABI hashes of the thin traits the type with the given id was registered as implementing.
+pub fn traits_of(id: StableTypeId) -> impl Iterator<Item = u64> {
+    inventory::iter::<RegisteredImpl>.into_iter().filter(move |registered| registered.id == id).map(|registered| registered.trait_hash)
+}
+
+/// Returns the ids of the types registered as implementing the thin trait with the given ABI hash.
+pub fn implementors(trait_hash: u64) -> impl Iterator<Item = StableTypeId> {
+    inventory::iter::<RegisteredImpl>.into_iter().filter(move |registered| registered.trait_hash == trait_hash).map(|registered| registered.id)
+}
+
+/// Returns the shortest chain of registered migrations from the type with id `from` to the one with
+/// id `to`, if there is one.
+pub fn migration_path(from: StableTypeId, to: StableTypeId) -> Option<Vec<&'static RegisteredMigration>> {
+    // breadth-first, visiting each type once so that cycles of migrations end
+    let mut visited = vec![from];
+    let mut paths = vec![(from, Vec::new())];
+    while !paths.is_empty() {
+        let mut next = Vec::new();
+        for (id, path) in paths {
+            if id == to {
+                return Some(path);
+            }
+            for migration in inventory::iter::<RegisteredMigration>.into_iter().filter(|migration| migration.from == id) {
+                if !visited.contains(&migration.to) {
+                    visited.push(migration.to);
+                    next.push((migration.to, path.iter().copied().chain([migration]).collect()));
+                }
+            }
+        }
+        paths = next;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(StableAny)]
+    struct Registered;
+
+    #[derive(StableAny)]
+    struct Generic<T>(T);
+
+    #[test]
+    fn registered() {
+        let ty = lookup(StableTypeId::of::<Registered>()).unwrap();
+        assert_eq!(ty.name, "thin_trait_objects::registry::tests::Registered");
+
+        // provided impls are registered too
+        assert!(lookup(StableTypeId::of::<u8>()).is_some());
+        assert!(lookup(StableTypeId::of::<Generic<u8>>()).is_none());
+        assert_eq!(StableTypeId::of::<Registered>().lookup_name(), Some(ty.name));
+    }
+
+    #[derive(StableAny, Clone, PartialEq, Debug)]
+    struct Cloned(Vec<u8>);
+
+    crate::register_clone!(Cloned);
+
+    #[test]
+    fn clones() {
+        let thin = Thin::<dyn StableAny + Send + Sync>::new(Cloned(vec![1, 2]));
+        let copy = thin.try_clone().unwrap();
+        assert_ne!(copy.ptr, thin.ptr);
+        assert_eq!(copy.downcast::<Cloned>(), Some(Cloned(vec![1, 2])));
+
+        assert!(lookup_clone(StableTypeId::of::<Registered>()).is_none());
+        assert!(Thin::<dyn StableAny>::new(Registered).try_clone().is_none());
+    }
+
+    #[thin]
+    trait Shape: 'static {
+        fn sides(&self) -> u32;
+    }
+
+    #[thin]
+    trait Named: 'static {
+        fn initial(&self) -> u8;
+    }
+
+    #[derive(StableAny)]
+    #[stable_any(unsafe(rename_from = "shapes::Square"), implements(Shape))]
+    struct Square;
+
+    impl Shape for Square {
+        fn sides(&self) -> u32 {
+            4
+        }
+    }
+
+    impl Named for Square {
+        fn initial(&self) -> u8 {
+            b's'
+        }
+    }
+
+    crate::register_impl!(Square: dyn Named);
+
+    #[test]
+    fn impls() {
+        let id = StableTypeId::of::<Square>();
+        assert!(implements::<dyn Shape>(id));
+        assert!(implements::<dyn Named>(id));
+        assert!(implements_abi(id, trait_abi_hash::<dyn Shape>()));
+        assert!(!implements::<dyn Shape>(StableTypeId::of::<Registered>()));
+
+        let mut traits = traits_of(id).collect::<Vec<_>>();
+        traits.sort();
+        let mut expected = vec![trait_abi_hash::<dyn Shape>(), trait_abi_hash::<dyn Named>()];
+        expected.sort();
+        assert_eq!(traits, expected);
+        assert_eq!(implementors(trait_abi_hash::<dyn Shape>()).collect::<Vec<_>>(), [id]);
+    }
+
+    #[derive(StableAny)]
+    #[stable_any(unsafe(id = 1))]
+    struct PointV1(i32);
+
+    #[derive(StableAny)]
+    #[stable_any(unsafe(id = 2))]
+    struct PointV2(i32, i32);
+
+    #[derive(StableAny, PartialEq, Debug)]
+    struct Point { x: i32, y: i32, z: i32 }
+
+    impl From<PointV1> for PointV2 {
+        fn from(old: PointV1) -> Self {
+            PointV2(old.0, 0)
+        }
+    }
+
+    impl From<PointV2> for Point {
+        fn from(old: PointV2) -> Self {
+            Point { x: old.0, y: old.1, z: 0 }
+        }
+    }
+
+    crate::register_migration!(PointV1 => PointV2);
+    crate::register_migration!(PointV2 => Point);
+
+    #[test]
+    fn migrations() {
+        let thin = Thin::<dyn StableAny + Send + Sync>::new(PointV1(3));
+        assert_eq!(thin.downcast_migrating::<Point>().ok(), Some(Point { x: 3, y: 0, z: 0 }));
+        let thin = Thin::<dyn StableAny>::new(PointV2(3, 4));
+        assert_eq!(thin.downcast_migrating::<Point>().ok(), Some(Point { x: 3, y: 4, z: 0 }));
+        let thin = Thin::<dyn StableAny>::new(Point { x: 1, y: 2, z: 3 });
+        assert_eq!(thin.downcast_migrating::<Point>().ok(), Some(Point { x: 1, y: 2, z: 3 }));
+
+        // migrations only go forwards
+        let thin = Thin::<dyn StableAny>::new(Point { x: 1, y: 2, z: 3 });
+        assert!(thin.downcast_migrating::<PointV1>().err().unwrap().stable_is::<Point>());
+        assert_eq!(migration_path(StableTypeId::of::<PointV1>(), StableTypeId::of::<Point>()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn no_collisions() {
+        verify_no_collisions();
+
+        let types = [
+            RegisteredType { id: StableTypeId::of::<u8>(), name: "a" },
+            RegisteredType { id: StableTypeId::of::<u16>(), name: "b" },
+            RegisteredType { id: StableTypeId::of::<u8>(), name: "c" },
+        ];
+        let collisions = find_collisions(types.iter().collect());
+        assert_eq!(collisions.len(), 1);
+        assert_eq!([collisions[0][0].name, collisions[0][1].name], ["a", "c"]);
+    }
+}
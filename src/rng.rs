@@ -0,0 +1,141 @@
+//! `Thin<dyn RngCore>`, for injecting (possibly seeded) random number generators into plugins.
+//!
+//! ```rust
+//! use rand_core::RngCore;
+//! use thin_trait_objects::prelude::*;
+//!
+//! struct Counter(u64);
+//!
+//! impl RngCore for Counter {
+//!     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+//!     fn next_u64(&mut self) -> u64 { self.0 += 1; self.0 }
+//!     fn fill_bytes(&mut self, dst: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dst) }
+//! }
+//!
+//! let mut rng = Thin::<dyn RngCore>::new(Counter(0));
+//! assert_eq!(rng.next_u64(), 1);
+//! ```
+
+use rand_core::RngCore;
+use crate::prelude::*;
+use crate::__private::*;
+
+const _: () = {
+    #[repr(C)]
+    pub struct VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        next_u32: extern "C" fn(MutSelf<'_>) -> u32,
+        next_u64: extern "C" fn(MutSelf<'_>) -> u64,
+        // slices aren't FFI-safe, so `dst` is split into a pointer and length
+        fill_bytes: extern "C" fn(MutSelf<'_>, *mut u8, usize),
+    }
+
+    extern "C-unwind" fn drop<T>(ptr: *mut ()) {
+        unsafe { drop_bundle::<RawBundle<VTable, T>>(ptr) }
+    }
+
+    extern "C" fn next_u32<T: RngCore>(recv: MutSelf<'_>) -> u32 {
+        let value = unsafe { RawBundle::<VTable, T>::value_mut(recv) };
+        T::next_u32(value)
+    }
+
+    extern "C" fn next_u64<T: RngCore>(recv: MutSelf<'_>) -> u64 {
+        let value = unsafe { RawBundle::<VTable, T>::value_mut(recv) };
+        T::next_u64(value)
+    }
+
+    extern "C" fn fill_bytes<T: RngCore>(recv: MutSelf<'_>, ptr: *mut u8, len: usize) {
+        let value = unsafe { RawBundle::<VTable, T>::value_mut(recv) };
+        let dst = unsafe { std::slice::from_raw_parts_mut(ptr, len) };
+        T::fill_bytes(value, dst)
+    }
+
+    impl SpecialAssoc for dyn RngCore {
+        type Kind = Own;
+    }
+
+    impl SlotLayout for dyn RngCore {
+        const SLOTS: &'static [&'static str] = &["drop", "next_u32", "next_u64", "fill_bytes"];
+        type VTable = VTable;
+    }
+
+    impl<K: RngCore + 'static> ThinExt<dyn RngCore, K> for Thin<dyn RngCore> {
+        fn new(value: K) -> Self {
+            let vtable: &'static VTable = const {
+                &VTable {
+                    drop: drop::<K>,
+                    next_u32: next_u32::<K>,
+                    next_u64: next_u64::<K>,
+                    fill_bytes: fill_bytes::<K>,
+                }
+            };
+            let bundle = RawBundle { vtable, value };
+            let ptr = Box::into_raw(Box::new(bundle));
+            unsafe { Thin::from_raw(ptr as *mut ()) }
+        }
+    }
+
+    impl Thin<dyn RngCore> {
+        fn vtable(&self) -> &'static VTable {
+            check_poison(self.ptr.as_ptr());
+            unsafe { *(self.ptr.as_ptr() as *const &'static VTable) }
+        }
+    }
+
+    impl RngCore for Thin<dyn RngCore> {
+        fn next_u32(&mut self) -> u32 {
+            let shim = self.vtable().next_u32;
+            shim(MutSelf::new(self))
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let shim = self.vtable().next_u64;
+            shim(MutSelf::new(self))
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            let shim = self.vtable().fill_bytes;
+            shim(MutSelf::new(self), dst.as_mut_ptr(), dst.len())
+        }
+    }
+};
+
+#[cfg(test)]
+mod tests {
+    use rand_core::{impls, RngCore};
+
+    use crate::prelude::*;
+
+    /// xorshift64, so runs are reproducible
+    struct XorShift(u64);
+
+    impl RngCore for XorShift {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            impls::fill_bytes_via_next(self, dst)
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let mut direct = XorShift(42);
+        let mut thin = Thin::<dyn RngCore>::new(XorShift(42));
+
+        assert_eq!(thin.next_u32(), direct.next_u32());
+        assert_eq!(thin.next_u64(), direct.next_u64());
+
+        let mut a = [0u8; 13];
+        let mut b = [0u8; 13];
+        thin.fill_bytes(&mut a);
+        direct.fill_bytes(&mut b);
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,284 @@
+//! `Thin<dyn Spawn>`, an erased handle to the host's executor, so that plugins can schedule work on
+//! it without linking an executor of their own.
+//!
+//! Jobs are [`ThinFnOnce`]s and tasks are [`ThinFuture`]s, both one pointer wide.
+//!
+//! ```rust
+//! use std::future::Future;
+//! use std::pin::Pin;
+//! use std::sync::{mpsc, Arc};
+//! use std::task::{Context, Wake, Waker};
+//! use std::thread::{self, Thread};
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::spawn::{Spawn, ThinFnOnce, ThinFuture};
+//!
+//! // the host's executor, which here runs every job and task on a new thread
+//! struct Threads;
+//!
+//! // wakes a task by unparking the thread polling it
+//! struct Unpark(Thread);
+//!
+//! impl Wake for Unpark {
+//!     fn wake(self: Arc<Self>) {
+//!         self.0.unpark();
+//!     }
+//! }
+//!
+//! impl Spawn for Threads {
+//!     fn spawn_job(&self, job: ThinFnOnce) {
+//!         thread::spawn(move || job.call());
+//!     }
+//!
+//!     fn spawn_task(&self, mut task: ThinFuture) {
+//!         thread::spawn(move || {
+//!             let waker = Waker::from(Arc::new(Unpark(thread::current())));
+//!             let mut cx = Context::from_waker(&waker);
+//!             while Pin::new(&mut task).poll(&mut cx).is_pending() {
+//!                 thread::park();
+//!             }
+//!         });
+//!     }
+//! }
+//!
+//! let spawner = Thin::<dyn Spawn>::new(Threads);
+//! let (sender, receiver) = mpsc::channel();
+//! let job_sender = sender.clone();
+//! spawner.spawn(move || job_sender.send(42).unwrap());
+//! spawner.spawn_future(async move { sender.send(43).unwrap() });
+//! let mut received = [receiver.recv().unwrap(), receiver.recv().unwrap()];
+//! received.sort();
+//! assert_eq!(received, [42, 43]);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::prelude::*;
+use crate::__private::*;
+
+/// Schedules jobs and tasks on an executor.
+#[thin]
+pub trait Spawn: Send + Sync + 'static {
+    /// Runs `job` at some point, possibly on another thread.
+    fn spawn_job(&self, job: ThinFnOnce);
+
+    /// Polls `task` to completion.
+    fn spawn_task(&self, task: ThinFuture);
+}
+
+impl Thin<dyn Spawn> {
+    /// Runs `f` at some point, possibly on another thread.
+    pub fn spawn<F: FnOnce() + Send + 'static>(&self, f: F) {
+        self.spawn_job(ThinFnOnce::new(f))
+    }
+
+    /// Polls `future` to completion.
+    pub fn spawn_future<F: Future<Output = ()> + Send + 'static>(&self, future: F) {
+        self.spawn_task(ThinFuture::new(future))
+    }
+}
+
+//================//
+
+/// An erased `FnOnce() + Send`, one pointer wide.
+///
+/// Dropping it without calling it drops the function.
+#[repr(transparent)]
+pub struct ThinFnOnce(Thin<dyn FnOnceErased>);
+
+trait FnOnceErased: Send + 'static {}
+
+impl SpecialAssoc for dyn FnOnceErased {
+    type Kind = Own;
+}
+
+impl ThinSend for dyn FnOnceErased {}
+
+#[repr(C)]
+struct FnOnceVTable {
+    drop: extern "C-unwind" fn(*mut ()),
+    // calls the function, then frees the bundle
+    call: extern "C-unwind" fn(*mut ()),
+}
+
+extern "C-unwind" fn drop_erased<T>(ptr: *mut ()) {
+    unsafe { drop_bundle::<RawBundle<FnOnceVTable, T>>(ptr) }
+}
+
+extern "C-unwind" fn call<F: FnOnce()>(ptr: *mut ()) {
+    let bundle = unsafe { Box::from_raw(ptr as *mut RawBundle<FnOnceVTable, F>) };
+    (bundle.value)()
+}
+
+impl ThinFnOnce {
+    pub fn new<F: FnOnce() + Send + 'static>(f: F) -> Self {
+        let vtable: &'static FnOnceVTable = const {
+            &FnOnceVTable {
+                drop: drop_erased::<F>,
+                call: call::<F>,
+            }
+        };
+        let ptr = Box::into_raw(Box::new(RawBundle { vtable, value: f }));
+        ThinFnOnce(unsafe { Thin::from_raw(ptr as *mut ()) })
+    }
+
+    /// Calls the function.
+    pub fn call(self) {
+        let ptr = self.0.ptr.as_ptr();
+        check_poison(ptr);
+        let vtable = unsafe { *(ptr as *const &'static FnOnceVTable) };
+        let call = vtable.call;
+        std::mem::forget(self);
+        call(ptr)
+    }
+}
+
+//================//
+
+/// An erased `Future<Output = ()> + Send`, one pointer wide.
+///
+/// Unlike [`ThinFnOnce`], it's only ABI-stable between binaries built with the same version of
+/// Rust, since the `Context` it's polled with is passed through as is.
+#[repr(transparent)]
+pub struct ThinFuture(Thin<dyn FutureErased>);
+
+trait FutureErased: Send + 'static {}
+
+impl SpecialAssoc for dyn FutureErased {
+    type Kind = Own;
+}
+
+impl ThinSend for dyn FutureErased {}
+
+#[repr(C)]
+struct FutureVTable {
+    drop: extern "C-unwind" fn(*mut ()),
+    // polls the future with a `&mut Context<'_>`, returning whether it's ready
+    poll: extern "C-unwind" fn(*mut (), *mut ()) -> bool,
+}
+
+extern "C-unwind" fn poll<F: Future<Output = ()>>(ptr: *mut (), cx: *mut ()) -> bool {
+    let bundle = unsafe { &mut *(ptr as *mut RawBundle<FutureVTable, F>) };
+    // the bundle is never moved out of its allocation
+    let future = unsafe { Pin::new_unchecked(&mut bundle.value) };
+    let cx = unsafe { &mut *(cx as *mut Context<'_>) };
+    future.poll(cx).is_ready()
+}
+
+impl ThinFuture {
+    pub fn new<F: Future<Output = ()> + Send + 'static>(future: F) -> Self {
+        let vtable: &'static FutureVTable = const {
+            &FutureVTable {
+                drop: drop_erased::<F>,
+                poll: poll::<F>,
+            }
+        };
+        let ptr = Box::into_raw(Box::new(RawBundle { vtable, value: future }));
+        ThinFuture(unsafe { Thin::from_raw(ptr as *mut ()) })
+    }
+}
+
+// the future itself is pinned in its allocation
+impl Unpin for ThinFuture {}
+
+impl Future for ThinFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let ptr = self.0.ptr.as_ptr();
+        check_poison(ptr);
+        let vtable = unsafe { *(ptr as *const &'static FutureVTable) };
+        match (vtable.poll)(ptr, cx as *mut Context<'_> as *mut ()) {
+            true => Poll::Ready(()),
+            false => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{mpsc, Arc};
+    use std::task::{Wake, Waker};
+    use std::thread::{self, Thread};
+
+    use super::*;
+
+    struct Unpark(Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on(mut future: impl Future<Output = ()> + Unpin) {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        while Pin::new(&mut future).poll(&mut cx).is_pending() {
+            thread::park();
+        }
+    }
+
+    struct Threads;
+
+    impl Spawn for Threads {
+        fn spawn_job(&self, job: ThinFnOnce) {
+            thread::spawn(move || job.call());
+        }
+
+        fn spawn_task(&self, task: ThinFuture) {
+            thread::spawn(move || block_on(task));
+        }
+    }
+
+    // ready on the second poll, after waking itself from another thread
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake());
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn spawning() {
+        let spawner = Thin::<dyn Spawn>::new(Threads);
+        let (sender, receiver) = mpsc::channel();
+
+        let job_sender = sender.clone();
+        spawner.spawn(move || job_sender.send("job").unwrap());
+        spawner.spawn_future(async move {
+            YieldOnce(false).await;
+            sender.send("task").unwrap();
+        });
+
+        let mut received = receiver.iter().take(2).collect::<Vec<_>>();
+        received.sort();
+        assert_eq!(received, ["job", "task"]);
+    }
+
+    #[test]
+    fn dropped_unrun() {
+        let count = Arc::new(AtomicU32::new(0));
+        let captured = count.clone();
+        let job = ThinFnOnce::new(move || { captured.fetch_add(1, Ordering::SeqCst); });
+        assert_eq!(size_of::<ThinFnOnce>(), size_of::<usize>());
+        drop(job);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        assert_eq!(Arc::strong_count(&count), 1);
+
+        let captured = count.clone();
+        drop(ThinFuture::new(async move { captured.fetch_add(1, Ordering::SeqCst); }));
+        assert_eq!(Arc::strong_count(&count), 1);
+    }
+}
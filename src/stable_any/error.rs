@@ -0,0 +1,87 @@
+use std::fmt::{self, Debug, Display, Formatter};
+
+use super::StableTypeId;
+
+/// The error returned by the `try_downcast` methods of `Thin<dyn StableAny>`, identifying both
+/// the type which was asked for and the type which was found.
+///
+/// Type names are recorded with the `type-name` feature, and otherwise looked up in the
+/// [`registry`](crate::registry) if that's enabled. For owned downcasts, `V` is the thin object,
+/// which can be recovered with [`into_inner`](Self::into_inner).
+pub struct DowncastError<V = ()> {
+    expected: StableTypeId,
+    actual: StableTypeId,
+    expected_name: Option<&'static str>,
+    actual_name: Option<&'static str>,
+    value: V,
+}
+
+impl DowncastError {
+    pub(crate) fn new(expected: StableTypeId, actual: StableTypeId, expected_name: Option<&'static str>, actual_name: Option<&'static str>) -> Self {
+        #[cfg(feature = "registry")]
+        let (expected_name, actual_name) = (expected_name.or(expected.lookup_name()), actual_name.or(actual.lookup_name()));
+        Self { expected, actual, expected_name, actual_name, value: () }
+    }
+}
+
+impl<V> DowncastError<V> {
+    pub(crate) fn with_value<W>(self, value: W) -> DowncastError<W> {
+        let Self { expected, actual, expected_name, actual_name, value: _ } = self;
+        DowncastError { expected, actual, expected_name, actual_name, value }
+    }
+
+    /// The id of the type which was asked for.
+    pub fn expected(&self) -> StableTypeId {
+        self.expected
+    }
+
+    /// The id of the type which was found.
+    pub fn actual(&self) -> StableTypeId {
+        self.actual
+    }
+
+    /// The name of the type which was asked for, if known.
+    pub fn expected_name(&self) -> Option<&'static str> {
+        self.expected_name
+    }
+
+    /// The name of the type which was found, if known.
+    pub fn actual_name(&self) -> Option<&'static str> {
+        self.actual_name
+    }
+
+    /// Returns the value which couldn't be downcast.
+    pub fn into_inner(self) -> V {
+        self.value
+    }
+}
+
+fn describe(f: &mut Formatter<'_>, id: StableTypeId, name: Option<&str>) -> fmt::Result {
+    match name {
+        Some(name) => write!(f, "`{name}` ({id})"),
+        None => write!(f, "{id}"),
+    }
+}
+
+/// Formats as e.g. ``expected `u16` (a3f0...), found `u8` (5c21...)``.
+impl<V> Display for DowncastError<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to downcast: expected ")?;
+        describe(f, self.expected, self.expected_name)?;
+        f.write_str(", found ")?;
+        describe(f, self.actual, self.actual_name)
+    }
+}
+
+impl<V> Debug for DowncastError<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DowncastError")
+            .field("expected", &self.expected)
+            .field("actual", &self.actual)
+            .field("expected_name", &self.expected_name)
+            .field("actual_name", &self.actual_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V> std::error::Error for DowncastError<V> {}
@@ -0,0 +1,194 @@
+use super::StableTypeId;
+
+/// The hasher `StableTypeId`s are generated with, exposed so that other tools (e.g. C hosts or
+/// code generators) can recompute them.
+///
+/// The id of a type is the hash of, in order:
+/// - the version of the crate declaring it (`CARGO_PKG_VERSION`),
+/// - its module path (`module_path!()`) and name,
+/// - the id of each type parameter, as a little-endian `u64`,
+/// - the value of each const parameter, cast to a little-endian `u128`.
+///
+/// The algorithm is SipHash-1-3 (with zeroed keys) by default, or FNV-1a with the `hash-fnv` feature,
+/// or xxh3 with the `hash-xxh3` feature (which takes precedence). For xxh3, each write is hashed
+/// separately, seeded with the hash of everything before it.
+/// Binaries exchanging ids must all use the same algorithm.
+#[derive(Copy, Clone)]
+pub struct StableHasher {
+    #[cfg(not(any(feature = "hash-fnv", feature = "hash-xxh3")))]
+    sip: const_siphasher::sip::SipHasher13,
+    #[cfg(any(feature = "hash-fnv", feature = "hash-xxh3"))]
+    state: u64,
+}
+
+#[cfg(all(feature = "hash-fnv", not(feature = "hash-xxh3")))]
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+#[cfg(all(feature = "hash-fnv", not(feature = "hash-xxh3")))]
+const FNV_PRIME: u64 = 0x100000001b3;
+
+impl StableHasher {
+    pub const fn new() -> Self {
+        #[cfg(not(any(feature = "hash-fnv", feature = "hash-xxh3")))]
+        return StableHasher { sip: const_siphasher::sip::SipHasher13::new() };
+        #[cfg(all(feature = "hash-fnv", not(feature = "hash-xxh3")))]
+        return StableHasher { state: FNV_OFFSET_BASIS };
+        #[cfg(feature = "hash-xxh3")]
+        return StableHasher { state: 0 };
+    }
+
+    pub const fn write(&mut self, bytes: &[u8]) {
+        #[cfg(not(any(feature = "hash-fnv", feature = "hash-xxh3")))]
+        self.sip.write(bytes);
+
+        #[cfg(all(feature = "hash-fnv", not(feature = "hash-xxh3")))]
+        {
+            let mut i = 0;
+            while i < bytes.len() {
+                self.state ^= bytes[i] as u64;
+                self.state = self.state.wrapping_mul(FNV_PRIME);
+                i += 1;
+            }
+        }
+
+        #[cfg(feature = "hash-xxh3")]
+        {
+            self.state = xxhash_rust::const_xxh3::xxh3_64_with_seed(bytes, self.state);
+        }
+    }
+
+    pub const fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes())
+    }
+
+    pub const fn finish(&self) -> u64 {
+        #[cfg(not(any(feature = "hash-fnv", feature = "hash-xxh3")))]
+        return self.sip.finish();
+        #[cfg(any(feature = "hash-fnv", feature = "hash-xxh3"))]
+        return self.state;
+    }
+}
+
+// the longest module path `stable_id` can hash
+const MAX_MODULE_PATH: usize = 512;
+
+/// Computes the `StableTypeId` `#[derive(StableAny)]` gives a type, from:
+/// - the name and version of the crate declaring it,
+/// - the path of its module within that crate (`""` for the crate root, otherwise e.g. `"a::b"`),
+/// - its name, without generics,
+/// - the ids of its type parameters, in order.
+///
+/// For hosts and code generators which need to precompute ids. Types with const parameters, or
+/// derived with `#[stable_any(unsafe(rename_from = ...))]` or `#[stable_any(unsafe(id = ...))]`,
+/// aren't covered.
+///
+/// ```rust
+/// use thin_trait_objects::prelude::*;
+///
+/// mod shapes {
+///     use thin_trait_objects::prelude::*;
+///
+///     #[derive(StableAny)]
+///     pub struct Square;
+///
+///     pub const PATH: &str = module_path!();
+/// }
+///
+/// // e.g. `("my_crate", "shapes")`
+/// let (crate_name, module_path) = shapes::PATH.split_once("::").unwrap();
+/// let id = stable_id(crate_name, env!("CARGO_PKG_VERSION"), module_path, "Square", &[]);
+/// assert_eq!(id, StableTypeId::of::<shapes::Square>());
+/// ```
+///
+/// # Panics
+/// If the module path, with the crate's name, is longer than 512 bytes.
+pub const fn stable_id(crate_name: &str, version: &str, module_path: &str, type_name: &str, params: &[u64]) -> StableTypeId {
+    // `module_path!()` is hashed in one write, which some algorithms distinguish from several
+    let mut path = [0u8; MAX_MODULE_PATH];
+    let mut len = 0;
+    let parts: [&[u8]; 3] = match module_path.is_empty() {
+        true => [crate_name.as_bytes(), b"", b""],
+        false => [crate_name.as_bytes(), b"::", module_path.as_bytes()],
+    };
+    let mut i = 0;
+    while i < parts.len() {
+        let mut j = 0;
+        while j < parts[i].len() {
+            assert!(len < MAX_MODULE_PATH, "the module path is too long");
+            path[len] = parts[i][j];
+            len += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+
+    let mut hasher = StableHasher::new();
+    hasher.write(version.as_bytes());
+    hasher.write(path.split_at(len).0);
+    hasher.write(type_name.as_bytes());
+    let mut i = 0;
+    while i < params.len() {
+        hasher.write_u64(params[i]);
+        i += 1;
+    }
+    unsafe { StableTypeId::new(hasher.finish()) }
+}
+
+/// Lets values be hashed consistently across binaries, as long as their `Hash` impls agree.
+///
+/// Integers are written little-endian, and `usize`s and `isize`s (including the lengths of slices
+/// and strings) as 64-bit integers, so that hashes don't depend on the target's endianness or pointer width.
+impl std::hash::Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        StableHasher::write(self, bytes)
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        StableHasher::write_u64(self, n)
+    }
+
+    fn write_u128(&mut self, n: u128) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        StableHasher::write_u64(self, n as u64)
+    }
+
+    fn write_i16(&mut self, n: i16) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_i32(&mut self, n: i32) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_i64(&mut self, n: i64) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_i128(&mut self, n: i128) {
+        self.write(&n.to_le_bytes())
+    }
+
+    fn write_isize(&mut self, n: isize) {
+        self.write(&(n as i64).to_le_bytes())
+    }
+
+    fn finish(&self) -> u64 {
+        StableHasher::finish(self)
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher::new()
+    }
+}
@@ -1,30 +1,100 @@
-use std::fmt::{Debug, Formatter};
+use std::fmt::{Debug, Display, Formatter, LowerHex};
+use std::hash::{Hash, Hasher};
+use std::num::ParseIntError;
 use std::marker::PhantomData;
+use std::ptr::NonNull;
+use std::str::FromStr;
 use crate::prelude::*;
+use crate::__private::*;
 use crate::{Own, SpecialAssoc};
 
 /// Module providing implementations of `UUID` for various foreign types.
 mod provided;
+mod hash;
+mod view;
+mod error;
+#[cfg(feature = "serde")]
+mod serde;
+
+pub use hash::{StableHasher, stable_id};
+pub use view::{StableAnyRef, StableAnyMut};
+pub use error::DowncastError;
 
 pub unsafe trait UUID {
     const UUID: StableTypeId;
 }
 
+/// Registers a concrete type in the [`registry`](crate::registry), along with the thin traits it
+/// implements, if the `registry` feature is enabled.
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "registry")]
+macro_rules! __register_stable_any {
+    (@named $name: expr, $ty: ty) => {
+        $crate::registry::__inventory::submit! {
+            $crate::registry::RegisteredType {
+                id: <$ty as $crate::prelude::UUID>::UUID,
+                name: $name,
+            }
+        }
+    };
+    ($ty: ty $(, $trait: ty)*) => {
+        $crate::__register_stable_any!(@named concat!(module_path!(), "::", stringify!($ty)), $ty);
+        $($crate::register_impl!($ty: $trait);)*
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "registry"))]
+macro_rules! __register_stable_any {
+    (@named $name: expr, $ty: ty) => {};
+    ($ty: ty $(, $trait: ty)*) => {};
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Hash)]
 pub struct StableTypeId(u64);
 
 impl StableTypeId {
-    const unsafe fn new(val: u64) -> Self {
+    #[doc(hidden)]
+    pub const unsafe fn new(val: u64) -> Self {
         Self(val)
     }
 
-    const unsafe fn to_u64(self) -> u64 {
+    #[doc(hidden)]
+    pub const unsafe fn to_u64(self) -> u64 {
         self.0
     }
 
     pub const fn of<T: StableAny>() -> StableTypeId {
         T::Inner::UUID
     }
+
+    /// Returns the id of the (possibly erased) type of `val`.
+    pub fn of_val<T: StableAny + ?Sized>(val: &T) -> StableTypeId {
+        val.stable_type_id()
+    }
+
+    /// Returns the name of the type with this id, if it's in the [`registry`](crate::registry).
+    #[cfg(feature = "registry")]
+    pub fn lookup_name(self) -> Option<&'static str> {
+        crate::registry::lookup(self).map(|ty| ty.name)
+    }
+
+    /// Panics if the id of `T` isn't `expected`.
+    ///
+    /// For tests guarding a type's wire identity, which changes if the type is renamed or moved
+    /// (unless it's derived with `#[stable_any(unsafe(rename_from = "..."))]` or
+    /// `#[stable_any(unsafe(id = ...))]`).
+    #[track_caller]
+    pub fn assert_stable<T: StableAny>(expected: u64) {
+        let id = StableTypeId::of::<T>();
+        assert!(
+            id.0 == expected,
+            "the StableTypeId of `{}` changed from {} to {}",
+            std::any::type_name::<T>(), expected, id.0,
+        );
+    }
 }
 
 impl Debug for StableTypeId {
@@ -33,6 +103,29 @@ impl Debug for StableTypeId {
     }
 }
 
+/// Formats the id as 16 hex digits, which can be parsed back with `str::parse`.
+impl Display for StableTypeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{:016x}", self.0))
+    }
+}
+
+impl LowerHex for StableTypeId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.0, f)
+    }
+}
+
+/// Parses an id formatted with `Display` or `LowerHex`, optionally prefixed with `0x`.
+impl FromStr for StableTypeId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        u64::from_str_radix(digits, 16).map(StableTypeId)
+    }
+}
+
 mod private {
     pub trait Sealed {}
 }
@@ -53,104 +146,380 @@ impl<T: UUID> StableAny for T {
 
 //================//
 
+// the implementation shared by every set of bounds, which `impl_thin_dyn_stable_any!` delegates to
+
+// `eq` and `hash` are only set for objects created with `new_keyed`, and `debug` for those created
+// with `new_debug`
+#[repr(C)]
+struct VTable {
+    drop: extern "C-unwind" fn(*mut ()),
+    uuid: StableTypeId,
+    eq: Option<extern "C" fn(*const (), *const ()) -> bool>,
+    hash: Option<extern "C" fn(*const ()) -> u64>,
+    // returns whether formatting failed
+    debug: Option<extern "C-unwind" fn(*const (), &mut Formatter<'_>) -> bool>,
+    #[cfg(feature = "type-name")]
+    type_name: extern "C" fn(&mut usize) -> *const u8,
+}
+
+extern "C-unwind" fn drop<T>(ptr: *mut ()) {
+    unsafe { drop_bundle::<RawBundle<VTable, T>>(ptr) }
+}
+
+extern "C" fn eq<T: PartialEq>(a: *const (), b: *const ()) -> bool {
+    let a = unsafe { &*(a as *const RawBundle<VTable, T>) };
+    let b = unsafe { &*(b as *const RawBundle<VTable, T>) };
+    a.value == b.value
+}
+
+extern "C" fn hash<T: Hash>(ptr: *const ()) -> u64 {
+    let bundle = unsafe { &*(ptr as *const RawBundle<VTable, T>) };
+    let mut hasher = StableHasher::new();
+    bundle.value.hash(&mut hasher);
+    hasher.finish()
+}
+
+extern "C-unwind" fn debug<T: Debug>(ptr: *const (), f: &mut Formatter<'_>) -> bool {
+    let bundle = unsafe { &*(ptr as *const RawBundle<VTable, T>) };
+    bundle.value.fmt(f).is_err()
+}
+
+#[cfg(feature = "type-name")]
+extern "C" fn type_name<T>(len: &mut usize) -> *const u8 {
+    let name = ::std::any::type_name::<T>();
+    *len = name.len();
+    name.as_ptr()
+}
+
+fn erase<U: ?Sized + SpecialAssoc + 'static, K: StableAny>(value: K) -> Thin<U> {
+    let vtable: &'static VTable = const {
+        &VTable {
+            drop: drop::<K>,
+            uuid: StableTypeId::of::<K>(),
+            eq: None,
+            hash: None,
+            debug: None,
+            #[cfg(feature = "type-name")]
+            type_name: type_name::<K>,
+        }
+    };
+    let bundle = RawBundle { vtable, value };
+    unsafe { Thin::from_raw(alloc_bundle(bundle)) }
+}
+
+fn erase_keyed<U: ?Sized + SpecialAssoc + 'static, K: StableAny + Eq + Hash>(value: K) -> Thin<U> {
+    let vtable: &'static VTable = const {
+        &VTable {
+            drop: drop::<K>,
+            uuid: StableTypeId::of::<K>(),
+            eq: Some(eq::<K>),
+            hash: Some(hash::<K>),
+            debug: None,
+            #[cfg(feature = "type-name")]
+            type_name: type_name::<K>,
+        }
+    };
+    let bundle = RawBundle { vtable, value };
+    unsafe { Thin::from_raw(alloc_bundle(bundle)) }
+}
+
+fn erase_debug<U: ?Sized + SpecialAssoc + 'static, K: StableAny + Debug>(value: K) -> Thin<U> {
+    let vtable: &'static VTable = const {
+        &VTable {
+            drop: drop::<K>,
+            uuid: StableTypeId::of::<K>(),
+            eq: None,
+            hash: None,
+            debug: Some(debug::<K>),
+            #[cfg(feature = "type-name")]
+            type_name: type_name::<K>,
+        }
+    };
+    let bundle = RawBundle { vtable, value };
+    unsafe { Thin::from_raw(alloc_bundle(bundle)) }
+}
+
+fn same_type(a: NonNull<()>, b: NonNull<()>) -> bool {
+    let a = unsafe { *(a.as_ptr() as *const &'static VTable) };
+    let b = unsafe { *(b.as_ptr() as *const &'static VTable) };
+    a.uuid == b.uuid
+}
+
+fn vtable_of<U: ?Sized + SpecialAssoc>(thin: &Thin<U>) -> &'static VTable {
+    check_poison(thin.ptr.as_ptr());
+    unsafe { *(thin.ptr.as_ptr() as *const &'static VTable) }
+}
+
+fn objects_eq<U: ?Sized + SpecialAssoc>(a: &Thin<U>, b: &Thin<U>) -> bool {
+    if a.ptr == b.ptr {
+        return true;
+    }
+    let (a_vtable, b_vtable) = (vtable_of(a), vtable_of(b));
+    match (a_vtable.eq, b_vtable.eq) {
+        (Some(eq), Some(_)) if a_vtable.uuid == b_vtable.uuid => eq(a.ptr.as_ptr(), b.ptr.as_ptr()),
+        _ => false,
+    }
+}
+
+fn hash_object<U: ?Sized + SpecialAssoc, H: Hasher>(thin: &Thin<U>, state: &mut H) {
+    let vtable = vtable_of(thin);
+    vtable.uuid.hash(state);
+    match vtable.hash {
+        Some(hash) => hash(thin.ptr.as_ptr()).hash(state),
+        None => thin.ptr.hash(state),
+    }
+}
+
+fn debug_object<U: ?Sized + SpecialAssoc>(thin: &Thin<U>, f: &mut Formatter<'_>) -> std::fmt::Result {
+    struct Value(extern "C-unwind" fn(*const (), &mut Formatter<'_>) -> bool, *const ());
+    impl Debug for Value {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            match (self.0)(self.1, f) {
+                false => Ok(()),
+                true => Err(std::fmt::Error),
+            }
+        }
+    }
+
+    let vtable = vtable_of(thin);
+    let mut out = f.debug_struct("Thin");
+    out.field("id", &vtable.uuid);
+    #[cfg(feature = "registry")]
+    if let Some(name) = vtable.uuid.lookup_name() {
+        out.field("name", &name);
+    }
+    match vtable.debug {
+        Some(debug) => out.field("value", &Value(debug, thin.ptr.as_ptr())).finish(),
+        None => out.finish_non_exhaustive(),
+    }
+}
+
+/// Clones the value with the function registered for its type, if there is one.
+#[cfg(feature = "registry")]
+fn try_clone<U: ?Sized + SpecialAssoc + 'static>(thin: &Thin<U>) -> Option<Thin<U>> {
+    let clone = crate::registry::lookup_clone(vtable_of(thin).uuid)?;
+    Some(unsafe { Thin::from_raw(clone(thin.ptr.as_ptr())) })
+}
+
+/// Downcasts the value, first migrating it along the registered migrations from its type, if any.
+#[cfg(feature = "registry")]
+fn downcast_migrating<U: ?Sized + SpecialAssoc + 'static, T: UUID>(thin: Thin<U>) -> Result<T, Thin<U>> {
+    if is_type::<U, T>(&thin) {
+        return Ok(unsafe { downcast_unchecked(thin) });
+    }
+    let Some(path) = crate::registry::migration_path(vtable_of(&thin).uuid, T::UUID) else {
+        return Err(thin);
+    };
+    // the bundles of every `Thin<dyn StableAny + ...>` are the same, and the intermediate values
+    // never leave this function
+    let ptr = thin.ptr.as_ptr();
+    ::std::mem::forget(thin);
+    let thin = path.iter().fold(unsafe { Thin::<dyn StableAny>::from_raw(ptr) }, |thin, migration| (migration.migrate)(thin));
+    Ok(unsafe { downcast_unchecked(thin) })
+}
+
+fn is_type<U: ?Sized + SpecialAssoc, T: UUID>(thin: &Thin<U>) -> bool {
+    check_dropped_as::<Thin<U>>(thin.ptr.as_ptr(), "downcast");
+    T::UUID == vtable_of(thin).uuid
+}
+
+unsafe fn downcast_unchecked<U: ?Sized + SpecialAssoc, T>(thin: Thin<U>) -> T {
+    let ptr = thin.ptr.as_ptr() as *mut RawBundle<VTable, T>;
+    ::std::mem::forget(thin);
+    let bundle = unsafe { Box::from_raw(ptr) };
+    bundle.value
+}
+
+unsafe fn downcast_ref_unchecked<U: ?Sized + SpecialAssoc, T>(thin: &Thin<U>) -> &T {
+    let bundle = unsafe { &*(thin.ptr.as_ptr() as *const RawBundle<VTable, T>) };
+    &bundle.value
+}
+
+unsafe fn downcast_mut_unchecked<U: ?Sized + SpecialAssoc, T>(thin: &mut Thin<U>) -> &mut T {
+    let bundle = unsafe { &mut *(thin.ptr.as_ptr() as *mut RawBundle<VTable, T>) };
+    &mut bundle.value
+}
+
+fn downcast_thin<U: ?Sized + SpecialAssoc, T: UUID>(thin: Thin<U>) -> Result<ThinBox<T>, Thin<U>> {
+    if !is_type::<U, T>(&thin) {
+        return Err(thin);
+    }
+    let ptr = thin.ptr.as_ptr();
+    ::std::mem::forget(thin);
+    Ok(unsafe { ThinBox::from_raw(ptr) })
+}
+
+fn downcast_error<U: ?Sized + SpecialAssoc, T: UUID>(thin: &Thin<U>) -> DowncastError {
+    #[cfg(feature = "type-name")]
+    let names = {
+        let mut len = 0;
+        let ptr = (vtable_of(thin).type_name)(&mut len);
+        let actual = unsafe { ::std::str::from_utf8_unchecked(::std::slice::from_raw_parts(ptr, len)) };
+        (Some(::std::any::type_name::<T>()), Some(actual))
+    };
+    #[cfg(not(feature = "type-name"))]
+    let names = (None, None);
+    DowncastError::new(T::UUID, vtable_of(thin).uuid, names.0, names.1)
+}
+
+// as with `dyn Any`, a generic impl for every set of bounds would overlap with those of other traits,
+// so each gets its own, which only delegates
 macro_rules! impl_thin_dyn_stable_any {
     ($($bounds: path),*) => {
-        const _: () = {
-            #[repr(C)]
-            struct VTable {
-                drop: extern "C" fn(*mut ()),
-                uuid: StableTypeId,
+        impl<K: StableAny $(+ $bounds)*> ThinExt<dyn StableAny $(+ $bounds)*, K> for Thin<dyn StableAny $(+ $bounds)*> {
+            fn new(value: K) -> Self {
+                erase(value)
             }
+        }
+
+        impl SpecialAssoc for dyn StableAny $(+ $bounds)* {
+            type Kind = Own;
 
-            extern "C" fn drop<T>(ptr: *mut ()) {
-                let bundle = ptr as *mut Bundle<T>;
-                let _ = unsafe { Box::from_raw(bundle) };
+            fn same_type(a: NonNull<()>, b: NonNull<()>) -> bool {
+                same_type(a, b)
             }
+        }
 
-            #[repr(C)]
-            struct Bundle<T> {
-                vtable: VTable,
-                value: T,
+        impl private::Sealed for Thin<dyn StableAny $(+ $bounds)*> {}
+        impl StableAny for Thin<dyn StableAny $(+ $bounds)*> {
+            type Inner = dyn StableAny $(+ $bounds)*;
+            fn stable_type_id(&self) -> StableTypeId {
+                vtable_of(self).uuid
             }
+        }
 
-            impl<K: StableAny $(+ $bounds)*> ThinExt<dyn StableAny $(+ $bounds)*, K> for Thin<dyn StableAny $(+ $bounds)*> {
-                fn new(value: K) -> Self {
-                    let vtable = VTable { drop: drop::<K>, uuid: StableTypeId::of::<K>() };
-                    let bundle = Bundle { vtable, value };
-                    let ptr = Box::into_raw(Box::new(bundle));
-                    unsafe { Thin::from_raw(ptr as *mut ()) }
-                }
+        /// Objects created with `new_keyed` are compared by type, then value. Any others are only
+        /// equal to themselves.
+        impl PartialEq for Thin<dyn StableAny $(+ $bounds)*> {
+            fn eq(&self, other: &Self) -> bool {
+                objects_eq(self, other)
             }
+        }
 
-            impl SpecialAssoc for dyn StableAny $(+ $bounds)* {
-                type Kind = Own;
+        impl Eq for Thin<dyn StableAny $(+ $bounds)*> {}
+
+        /// Objects created with `new_keyed` are hashed by type and value (with [`StableHasher`]),
+        /// and any others by type and address.
+        impl Hash for Thin<dyn StableAny $(+ $bounds)*> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                hash_object(self, state)
             }
+        }
 
-            impl private::Sealed for Thin<dyn StableAny $(+ $bounds)*> {}
-            impl StableAny for Thin<dyn StableAny $(+ $bounds)*> {
-                type Inner = dyn StableAny $(+ $bounds)*;
-                fn stable_type_id(&self) -> StableTypeId {
-                    let vtable = unsafe { &*(self.ptr.as_ptr() as *const VTable) };
-                    vtable.uuid
-                }
+        /// Formats the id of the value's type, its name if it's in the
+        /// [`registry`](crate::registry), and the value itself if it was created with `new_debug`.
+        impl Debug for Thin<dyn StableAny $(+ $bounds)*> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                debug_object(self, f)
             }
+        }
 
-            impl Thin<dyn StableAny $(+ $bounds)*> {
-                unsafe fn downcast_unchecked<T>(self) -> T {
-                    let ptr = self.ptr.as_ptr() as *mut Bundle<T>;
-                    ::std::mem::forget(self);
-                    let bundle = unsafe { Box::from_raw(ptr) };
-                    bundle.value
-                }
+        impl Thin<dyn StableAny $(+ $bounds)*> {
+            /// Creates a thin object whose `Debug` output includes the value's.
+            pub fn new_debug<K: StableAny + Debug $(+ $bounds)*>(value: K) -> Self {
+                erase_debug(value)
+            }
+
+            /// Creates a thin object which is compared and hashed by value (see the `PartialEq` and
+            /// `Hash` impls), for use as a key in maps and sets of erased values.
+            pub fn new_keyed<K: StableAny + Eq + Hash $(+ $bounds)*>(value: K) -> Self {
+                erase_keyed(value)
+            }
+
+            /// Clones the value with the function registered for its type by
+            /// [`register_clone!`](crate::register_clone), if there is one.
+            #[cfg(feature = "registry")]
+            pub fn try_clone(&self) -> Option<Self> {
+                try_clone(self)
+            }
+
+            /// Downcasts the value, migrating it to a `T` first if migrations from its type to `T`
+            /// were registered with [`register_migration!`](crate::register_migration).
+            #[cfg(feature = "registry")]
+            pub fn downcast_migrating<T: UUID>(self) -> Result<T, Self> {
+                downcast_migrating(self)
+            }
+
+            pub fn stable_is<T: UUID>(&self) -> bool {
+                is_type::<_, T>(self)
+            }
 
-                unsafe fn downcast_ref_unchecked<T>(&self) -> &T {
-                    let ptr = self.ptr.as_ptr() as *const Bundle<T>;
-                    let bundle = unsafe { &*ptr };
-                    &bundle.value
+            pub fn downcast<T: UUID>(self) -> Option<T> {
+                if self.stable_is::<T>() {
+                    return Some(unsafe { downcast_unchecked(self) });
                 }
+                None
+            }
+
+            /// Downcasts to a `ThinBox<T>`, which keeps the value in its current allocation.
+            pub fn downcast_thin<T: UUID>(self) -> Result<ThinBox<T>, Self> {
+                downcast_thin(self)
+            }
 
-                unsafe fn downcast_mut_unchecked<T>(&mut self) -> &mut T {
-                    let ptr = self.ptr.as_ptr() as *mut Bundle<T>;
-                    let bundle = unsafe { &mut *ptr };
-                    &mut bundle.value
+            pub fn downcast_ref<T: UUID>(&self) -> Option<&T> {
+                if self.stable_is::<T>() {
+                    return Some(unsafe { downcast_ref_unchecked(self) });
                 }
+                None
+            }
 
-                pub fn stable_is<T: UUID>(&self) -> bool {
-                    T::UUID == StableAny::stable_type_id(self)
+            pub fn downcast_mut<T: UUID>(&mut self) -> Option<&mut T> {
+                if self.stable_is::<T>() {
+                    return Some(unsafe { downcast_mut_unchecked(self) });
                 }
+                None
+            }
 
-                pub fn downcast<T: UUID>(self) -> Option<T> {
-                    if self.stable_is::<T>() {
-                        let val: T = unsafe { self.downcast_unchecked::<T>() };
-                        return Some(val);
-                    }
-                    None
+            /// Like `downcast`, but on failure returns an error describing both types, from
+            /// which the thin object can be recovered.
+            pub fn try_downcast<T: UUID>(self) -> Result<T, DowncastError<Self>> {
+                if self.stable_is::<T>() {
+                    return Ok(unsafe { downcast_unchecked(self) });
                 }
+                Err(downcast_error::<_, T>(&self).with_value(self))
+            }
 
-                pub fn downcast_ref<T: UUID>(&self) -> Option<&T> {
-                    if self.stable_is::<T>() {
-                        let val: &T = unsafe { self.downcast_ref_unchecked::<T>() };
-                        return Some(val);
-                    }
-                    None
+            /// Like `downcast_ref`, but on failure returns an error describing both types.
+            pub fn try_downcast_ref<T: UUID>(&self) -> Result<&T, DowncastError> {
+                if self.stable_is::<T>() {
+                    return Ok(unsafe { downcast_ref_unchecked(self) });
                 }
+                Err(downcast_error::<_, T>(self))
+            }
 
-                pub fn downcast_mut<T: UUID>(&mut self) -> Option<&mut T> {
-                    if self.stable_is::<T>() {
-                        let val: &mut T = unsafe { self.downcast_mut_unchecked::<T>() };
-                        return Some(val);
-                    }
-                    None
+            /// Like `downcast_mut`, but on failure returns an error describing both types.
+            pub fn try_downcast_mut<T: UUID>(&mut self) -> Result<&mut T, DowncastError> {
+                if self.stable_is::<T>() {
+                    return Ok(unsafe { downcast_mut_unchecked(self) });
                 }
+                Err(downcast_error::<_, T>(self))
             }
-        };
+        }
+
+        impl ThinMut<dyn StableAny $(+ $bounds)*> {
+            // only the value is borrowed mutably
+            pub fn downcast_mut<T: UUID>(&mut self) -> Option<&mut T> {
+                unsafe { self.as_thin_mut() }.downcast_mut()
+            }
+
+            pub fn try_downcast_mut<T: UUID>(&mut self) -> Result<&mut T, DowncastError> {
+                unsafe { self.as_thin_mut() }.try_downcast_mut()
+            }
+        }
     };
 }
 
 impl_thin_dyn_stable_any!();
 impl_thin_dyn_stable_any!(Send);
+impl_thin_dyn_stable_any!(Sync);
 impl_thin_dyn_stable_any!(Send, Sync);
 
+// values are only ever created from `K: StableAny + Send` and the like
+impl ThinSend for dyn StableAny + Send {}
+impl ThinSend for dyn StableAny + Send + Sync {}
+impl ThinSync for dyn StableAny + Sync {}
+impl ThinSync for dyn StableAny + Send + Sync {}
+
 // the following UUIDs where randomly generated using
 // https://numbergenerator.org/random-16-digit-hex-codes-generator
 
@@ -162,10 +531,28 @@ unsafe impl UUID for dyn StableAny + Send {
     const UUID: StableTypeId = StableTypeId(0x5B16BCA86ABA3C25u64);
 }
 
+unsafe impl UUID for dyn StableAny + Sync {
+    const UUID: StableTypeId = StableTypeId(0x81A0C0170CD8017Fu64);
+}
+
 unsafe impl UUID for dyn StableAny + Send + Sync {
     const UUID: StableTypeId = StableTypeId(0xFDB2A76E12E2D8D8u64);
 }
 
+/// Gives `Thin<Self>` a `StableTypeId`, so that thin objects can be nested in `Thin<dyn StableAny>`s.
+///
+/// `#[thin(stable_any)]` implements this for `dyn Trait`, from the trait's module path and name.
+///
+/// # Safety
+/// As for [`UUID`].
+pub unsafe trait ThinUUID {
+    const UUID: StableTypeId;
+}
+
+unsafe impl<U: ?Sized + SpecialAssoc + ThinUUID> UUID for Thin<U> {
+    const UUID: StableTypeId = U::UUID;
+}
+
 impl SpecialAssoc for Thin<dyn StableAny> {
     type Kind = Own;
 }
@@ -175,6 +562,7 @@ impl SpecialAssoc for Thin<dyn StableAny> {
 #[cfg(test)]
 #[allow(dead_code)]
 mod tests {
+    use std::collections::HashSet;
     use std::fmt::Display;
     use std::marker::PhantomData;
     use std::mem::ManuallyDrop;
@@ -189,6 +577,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(any(feature = "hash-fnv", feature = "hash-xxh3")))]
     fn compilation_independence() {
         // this will fail every time the crates version changes
         // or the path of this module changes
@@ -199,6 +588,28 @@ mod tests {
         );
     }
 
+    #[derive(StableAny)]
+    #[stable_any(unsafe(rename_from = "thin_trait_objects::stable_any::tests::TestStruct"))]
+    struct Moved<'a, T> {
+        f: T,
+        phantom: PhantomData<&'a ()>,
+    }
+
+    #[derive(StableAny)]
+    #[stable_any(unsafe(id = 42))]
+    struct Pinned;
+
+    #[derive(StableAny)]
+    #[stable_any(unsafe(id = 42))]
+    struct PinnedGeneric<T>(T);
+
+    #[test]
+    fn pinning() {
+        assert_eq!(StableTypeId::of::<Moved<u8>>(), StableTypeId::of::<TestStruct<u8>>());
+        StableTypeId::assert_stable::<Pinned>(42);
+        assert_ne!(StableTypeId::of::<PinnedGeneric<u8>>(), StableTypeId::of::<PinnedGeneric<u16>>());
+    }
+
     #[test]
     fn thin_uuids() {
         assert_eq!(
@@ -211,12 +622,79 @@ mod tests {
             unsafe { StableTypeId::new(6563640938470194213) }
         );
 
+        assert_eq!(
+            StableTypeId::of::<Thin<dyn StableAny + Sync>>(),
+            unsafe { StableTypeId::new(9340676832398672255) }
+        );
+
         assert_eq!(
             StableTypeId::of::<Thin<dyn StableAny + Send + Sync>>(),
             unsafe { StableTypeId::new(18280857928655362264) }
         );
     }
 
+    #[derive(StableAny)]
+    struct Buffer<const N: usize>([u8; N]);
+
+    #[derive(StableAny)]
+    struct Flagged<T, const ON: bool, const TAG: char>(T);
+
+    #[test]
+    fn const_generics() {
+        assert_ne!(StableTypeId::of::<Buffer<1>>(), StableTypeId::of::<Buffer<2>>());
+        assert_ne!(StableTypeId::of::<Flagged<u8, true, 'a'>>(), StableTypeId::of::<Flagged<u8, false, 'a'>>());
+        assert_ne!(StableTypeId::of::<Flagged<u8, true, 'a'>>(), StableTypeId::of::<Flagged<u8, true, 'b'>>());
+    }
+
+    #[test]
+    fn recomputable() {
+        let mut hasher = StableHasher::new();
+        hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.write(module_path!().as_bytes());
+        hasher.write(b"TestStruct");
+        hasher.write_u64(unsafe { u8::UUID.to_u64() });
+        assert_eq!(unsafe { StableTypeId::new(hasher.finish()) }, StableTypeId::of::<TestStruct<u8>>());
+    }
+
+    #[test]
+    fn pointer_width_independent() {
+        use std::hash::{Hash, Hasher};
+
+        // a slice's length is hashed as a `u64`, whatever the width of `usize`
+        let mut slice = StableHasher::new();
+        [1u16, 2].as_slice().hash(&mut slice);
+        let mut fixed = StableHasher::new();
+        fixed.write_u64(2);
+        fixed.write(&[1, 0, 2, 0]);
+        assert_eq!(Hasher::finish(&slice), Hasher::finish(&fixed));
+    }
+
+    #[test]
+    fn precomputed() {
+        let id = stable_id("thin_trait_objects", env!("CARGO_PKG_VERSION"), "stable_any::tests", "TestStruct", &[unsafe { u8::UUID.to_u64() }]);
+        assert_eq!(id, StableTypeId::of::<TestStruct<u8>>());
+
+        const ID: StableTypeId = stable_id("thin_trait_objects", env!("CARGO_PKG_VERSION"), "stable_any::tests", "Pinned", &[]);
+        assert_ne!(ID, StableTypeId::of::<TestStruct<u8>>());
+    }
+
+    #[test]
+    fn of_val() {
+        let thin = Thin::<dyn StableAny>::new(8u8);
+        assert_eq!(StableTypeId::of_val(&thin), StableTypeId::of::<u8>());
+        assert_eq!(StableTypeId::of_val(&8u8), StableTypeId::of::<u8>());
+    }
+
+    #[test]
+    fn formatting() {
+        let id = StableTypeId::of::<TestStruct<u8>>();
+        let formatted = id.to_string();
+        assert_eq!(formatted.len(), 16);
+        assert_eq!(formatted.parse::<StableTypeId>(), Ok(id));
+        assert_eq!(format!("{:#x}", id).parse::<StableTypeId>(), Ok(id));
+        assert!("not hex".parse::<StableTypeId>().is_err());
+    }
+
     #[test]
     fn type_generics() {
         assert_ne!(StableTypeId::of::<TestStruct<u8>>(), StableTypeId::of::<TestStruct<u16>>());
@@ -234,6 +712,12 @@ mod tests {
         bar: ManuallyDrop<B>,
     }
 
+    #[test]
+    fn sync_only() {
+        let thin = Thin::<dyn StableAny + Sync>::new(8u8);
+        assert_eq!(thin.downcast_ref::<u8>(), Some(&8u8));
+    }
+
     #[test]
     fn downcasting() {
         let mut thin = Thin::<dyn StableAny>::new(8u8);
@@ -247,4 +731,122 @@ mod tests {
         let val = thin.downcast::<u8>().unwrap();
         assert_eq!(val, 9u8);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn downcast_errors() {
+        let mut thin = Thin::<dyn StableAny>::new(8u8);
+        assert_eq!(thin.try_downcast_mut::<u8>().ok(), Some(&mut 8));
+
+        let err = thin.try_downcast_ref::<u16>().unwrap_err();
+        assert_eq!(err.expected(), StableTypeId::of::<u16>());
+        assert_eq!(err.actual(), StableTypeId::of::<u8>());
+        #[cfg(feature = "type-name")]
+        assert_eq!(
+            err.to_string(),
+            format!("failed to downcast: expected `u16` ({}), found `u8` ({})", err.expected(), err.actual()),
+        );
+
+        let thin = thin.try_downcast::<u16>().unwrap_err().into_inner();
+        assert_eq!(thin.try_downcast::<u8>().ok(), Some(8));
+    }
+
+    #[test]
+    fn downcast_thin() {
+        let thin = Thin::<dyn StableAny>::new([7u32; 64]);
+        let Err(thin) = thin.downcast_thin::<u8>() else { panic!("downcast to the wrong type") };
+
+        let mut boxed = thin.downcast_thin::<[u32; 64]>().ok().unwrap();
+        boxed[0] = 8;
+        assert_eq!(boxed[..2], [8, 7]);
+    }
+
+    #[test]
+    fn keys() {
+        let mut set = HashSet::new();
+        assert!(set.insert(Thin::<dyn StableAny>::new_keyed(8u8)));
+        assert!(set.insert(Thin::<dyn StableAny>::new_keyed(8u16)));
+        assert!(!set.insert(Thin::<dyn StableAny>::new_keyed(8u8)));
+        assert!(set.contains(&Thin::<dyn StableAny>::new_keyed(8u16)));
+        assert!(!set.contains(&Thin::<dyn StableAny>::new_keyed(9u16)));
+
+        // objects which aren't keyed are only equal to themselves
+        let unkeyed = Thin::<dyn StableAny>::new(8u8);
+        assert!(unkeyed == unkeyed);
+        assert!(!set.contains(&unkeyed));
+        assert!(set.insert(unkeyed));
+        assert_eq!(set.len(), 3);
+    }
+
+    struct Pair<A, B>(A, B);
+
+    impl_stable_any! {
+        impl<T: Clone> Pair<T, u8> where T: Send;
+        impl Pair<u8, u16>;
+        impl<T, const N: usize> Pair<[T; N], ()>;
+    }
+
+    #[test]
+    fn impl_grammar() {
+        assert_ne!(StableTypeId::of::<Pair<u32, u8>>(), StableTypeId::of::<Pair<u64, u8>>());
+        assert_ne!(StableTypeId::of::<Pair<u8, u8>>(), StableTypeId::of::<Pair<u8, u16>>());
+        assert_ne!(StableTypeId::of::<Pair<[u8; 1], ()>>(), StableTypeId::of::<Pair<[u8; 2], ()>>());
+        assert!(Thin::<dyn StableAny>::new(Pair(1u8, 2u16)).downcast_ref::<Pair<u8, u16>>().is_some());
+    }
+
+    #[thin(stable_any)]
+    trait Greet: 'static {
+        fn greet(&self) -> u8;
+    }
+
+    #[thin(stable_any)]
+    trait Versioned<const V: u8>: 'static {}
+
+    impl Greet for u8 {
+        fn greet(&self) -> u8 {
+            *self
+        }
+    }
+
+    #[test]
+    fn nested_thin() {
+        let any = Thin::<dyn StableAny>::new(Thin::<dyn Greet>::new(7u8));
+        assert_eq!(any.stable_type_id(), StableTypeId::of::<Thin<dyn Greet>>());
+        assert_eq!(any.downcast_ref::<Thin<dyn Greet>>().unwrap().greet(), 7);
+        assert!(any.downcast_ref::<u8>().is_none());
+
+        assert_ne!(StableTypeId::of::<Thin<dyn Greet>>(), StableTypeId::of::<Thin<dyn StableAny>>());
+        assert_ne!(StableTypeId::of::<Thin<dyn Versioned<1>>>(), StableTypeId::of::<Thin<dyn Versioned<2>>>());
+        #[cfg(feature = "registry")]
+        assert!(StableTypeId::of::<Thin<dyn Greet>>().lookup_name().unwrap().ends_with("::tests::Thin<dyn Greet>"));
+    }
+
+    #[derive(StableAny, Debug)]
+    struct Point {
+        x: i32,
+    }
+
+    #[test]
+    fn debug() {
+        let id = StableTypeId::of::<Point>();
+        let named = match cfg!(feature = "registry") {
+            true => format!("name: \"{}::Point\", ", module_path!()),
+            false => String::new(),
+        };
+        let debug = Thin::<dyn StableAny + Send>::new_debug(Point { x: 1 });
+        assert_eq!(format!("{:?}", debug), format!("Thin {{ id: {:?}, {}value: Point {{ x: 1 }} }}", id, named));
+        assert_eq!(debug.downcast_ref::<Point>().unwrap().x, 1);
+
+        let opaque = Thin::<dyn StableAny>::new(Point { x: 1 });
+        assert_eq!(format!("{:?}", opaque), format!("Thin {{ id: {:?}, {}.. }}", id, named));
+    }
+
+    #[test]
+    fn same_type() {
+        let a = Thin::<dyn StableAny>::new(8u8);
+        let b = Thin::<dyn StableAny>::new(9u8);
+        let c = Thin::<dyn StableAny>::new(8u16);
+
+        assert!(Thin::is_same_type(&a, &b));
+        assert!(!Thin::is_same_type(&a, &c));
+    }
+}
@@ -0,0 +1,67 @@
+//! Payload types from popular crates, each behind a feature of the same name.
+
+#[cfg(feature = "bytes")]
+mod bytes {
+    use ::bytes::{Bytes, BytesMut};
+    use crate::prelude::*;
+
+    impl_stable_any! {
+        Bytes; BytesMut
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono {
+    use ::chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+    use crate::prelude::*;
+
+    impl_stable_any! {
+        DateTime<Tz: TimeZone>;
+        Utc; FixedOffset;
+        NaiveDate; NaiveDateTime; NaiveTime;
+        TimeDelta
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ::chrono::{DateTime, FixedOffset, Utc};
+        use crate::prelude::*;
+
+        #[test]
+        fn time_zones() {
+            assert_ne!(<DateTime<Utc>>::UUID, <DateTime<FixedOffset>>::UUID);
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+mod serde_json {
+    use ::serde_json::{Map, Number, Value};
+    use crate::prelude::*;
+
+    impl_stable_any! {
+        Value; Number;
+        Map<K, V>
+    }
+}
+
+#[cfg(feature = "time")]
+mod time {
+    use ::time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcDateTime, UtcOffset};
+    use crate::prelude::*;
+
+    impl_stable_any! {
+        Date; Time; UtcOffset; Duration;
+        OffsetDateTime; PrimitiveDateTime; UtcDateTime
+    }
+}
+
+#[cfg(feature = "uuid")]
+mod uuid {
+    use ::uuid::Uuid;
+    use crate::prelude::*;
+
+    impl_stable_any! {
+        Uuid
+    }
+}
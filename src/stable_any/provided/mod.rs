@@ -1,2 +1,4 @@
 mod std;
-mod primitive;
\ No newline at end of file
+mod primitive;
+mod num;
+mod ecosystem;
\ No newline at end of file
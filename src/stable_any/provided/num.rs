@@ -0,0 +1,64 @@
+use std::num::{
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+    Saturating, Wrapping,
+};
+#[cfg(target_has_atomic = "8")]
+use std::sync::atomic::{AtomicBool, AtomicU8};
+#[cfg(target_has_atomic = "16")]
+use std::sync::atomic::AtomicU16;
+#[cfg(target_has_atomic = "32")]
+use std::sync::atomic::AtomicU32;
+#[cfg(target_has_atomic = "64")]
+use std::sync::atomic::AtomicU64;
+#[cfg(target_has_atomic = "ptr")]
+use std::sync::atomic::AtomicUsize;
+use crate::prelude::*;
+
+impl_stable_any! {
+    NonZeroU8; NonZeroU16; NonZeroU32; NonZeroU64; NonZeroU128; NonZeroUsize;
+    NonZeroI8; NonZeroI16; NonZeroI32; NonZeroI64; NonZeroI128; NonZeroIsize;
+
+    Wrapping<T>;
+    Saturating<T>;
+}
+
+// atomics are only provided for the widths the target supports
+#[cfg(target_has_atomic = "8")]
+impl_stable_any! {
+    AtomicBool; AtomicU8;
+}
+
+#[cfg(target_has_atomic = "16")]
+impl_stable_any! {
+    AtomicU16
+}
+
+#[cfg(target_has_atomic = "32")]
+impl_stable_any! {
+    AtomicU32
+}
+
+#[cfg(target_has_atomic = "64")]
+impl_stable_any! {
+    AtomicU64
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl_stable_any! {
+    AtomicUsize
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::{NonZeroU8, NonZeroU16, Wrapping};
+
+    use crate::prelude::*;
+
+    #[test]
+    fn distinct() {
+        assert_ne!(NonZeroU8::UUID, NonZeroU16::UUID);
+        assert_ne!(<Wrapping<u8>>::UUID, <Wrapping<u16>>::UUID);
+        assert_ne!(<Wrapping<u8>>::UUID, u8::UUID);
+    }
+}
@@ -9,6 +9,12 @@ type Array<T, const N: usize> = [T; N];
 
 type Slice<T> = [T];
 
+// `&T` only covers sized `T`, so references to unsized types are listed separately
+type RefStr<'a> = &'a str;
+type RefSlice<'a, T> = &'a [T];
+type MutStr<'a> = &'a mut str;
+type MutSlice<'a, T> = &'a mut [T];
+
 type Tuple1<T1>                                             = (T1,);
 type Tuple2<T1, T2>                                         = (T1, T2);
 type Tuple3<T1, T2, T3>                                     = (T1, T2, T3);
@@ -34,10 +40,17 @@ impl_stable_any! {
 
     char;
 
+    str;
+
     Ref<'a, T>;
     Mut<'a, T>;
 
     Slice<T>;
+    RefSlice<'a, T>;
+    MutSlice<'a, T>;
+
+    RefStr<'a>;
+    MutStr<'a>;
 
     Array<T, const N: usize>;
 
@@ -63,4 +76,12 @@ mod tests {
     fn array() {
         assert_ne!(<[u8; 1]>::UUID, <[u8; 2]>::UUID);
     }
+
+    #[test]
+    fn unsized_refs() {
+        assert_eq!("foo".stable_type_id(), <&str>::UUID);
+        assert_eq!(b"foo".as_slice().stable_type_id(), <&[u8]>::UUID);
+        assert_ne!(<&[u8]>::UUID, <&[u16]>::UUID);
+        assert_ne!(<&str>::UUID, <&[u8]>::UUID);
+    }
 }
\ No newline at end of file
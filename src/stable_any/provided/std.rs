@@ -1,10 +1,107 @@
-use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::ops::{Range, RangeInclusive};
+use std::path::{Path, PathBuf};
+use std::rc::{self, Rc};
+use std::sync::{self, Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
 use crate::prelude::*;
 
-// TODO: more standard types
+// `&T` only covers sized `T`, so references to unsized types are listed separately
+type RefCStr<'a> = &'a CStr;
+type RefOsStr<'a> = &'a OsStr;
+type RefPath<'a> = &'a Path;
+
+// likewise `Box<T>`, `Rc<T>` and `Arc<T>`
+type BoxStr = Box<str>;
+type BoxSlice<T> = Box<[T]>;
+type RcStr = Rc<str>;
+type ArcStr = Arc<str>;
+
+// both are named `Weak`, which would give them the same ids
+type RcWeak<T> = rc::Weak<T>;
+type ArcWeak<T> = sync::Weak<T>;
 
 impl_stable_any! {
     Option<T>;
     Result<T, E>;
-    Rc<T>
-}
\ No newline at end of file
+    Box<T>;
+    BoxStr;
+    BoxSlice<T>;
+    Rc<T>;
+    RcWeak<T>;
+    RcStr;
+
+    Arc<T>;
+    ArcWeak<T>;
+    ArcStr;
+    Mutex<T>;
+    RwLock<T>;
+
+    Cell<T>;
+    RefCell<T>;
+
+    String;
+    Vec<T>;
+    VecDeque<T>;
+    LinkedList<T>;
+    BinaryHeap<T>;
+    BTreeMap<K, V>;
+    BTreeSet<T>;
+    HashMap<K, V>;
+    HashSet<T>;
+
+    CStr; OsStr; Path;
+    RefCStr<'a>; RefOsStr<'a>; RefPath<'a>;
+    CString; OsString; PathBuf;
+
+    Duration; Instant; SystemTime;
+
+    Ordering;
+    Reverse<T>;
+    Range<T>;
+    RangeInclusive<T>;
+    PhantomData<T>;
+    ManuallyDrop<T>;
+
+    IpAddr; Ipv4Addr; Ipv6Addr;
+    SocketAddr; SocketAddrV4; SocketAddrV6
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+    use std::ffi::OsStr;
+    use std::path::Path;
+
+    use crate::prelude::*;
+
+    #[test]
+    fn weak() {
+        assert_ne!(<std::rc::Weak<u8>>::UUID, <std::sync::Weak<u8>>::UUID);
+    }
+
+    #[test]
+    fn containers() {
+        let ids = [<Vec<u8>>::UUID, <VecDeque<u8>>::UUID, <Box<[u8]>>::UUID, <Vec<u16>>::UUID, <HashSet<u8>>::UUID, <BTreeSet<u8>>::UUID];
+        assert!(ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id)));
+        assert_ne!(<HashMap<u8, u16>>::UUID, <HashMap<u16, u8>>::UUID);
+        assert_ne!(<Box<str>>::UUID, String::UUID);
+
+        let thin = Thin::<dyn StableAny>::new(vec![String::from("a")]);
+        assert_eq!(thin.downcast_ref::<Vec<String>>().unwrap()[0], "a");
+        assert!(thin.downcast_ref::<VecDeque<String>>().is_none());
+    }
+
+    #[test]
+    fn unsized_refs() {
+        let path = Path::new("foo");
+        assert_eq!(path.stable_type_id(), <&Path>::UUID);
+        assert_ne!(<&Path>::UUID, <&OsStr>::UUID);
+    }
+}
@@ -0,0 +1,57 @@
+//! `StableTypeId`s are serialized as their `Display` form in human-readable formats, and as `u64`s otherwise.
+
+use std::fmt::Formatter;
+
+use ::serde::de::{self, Visitor};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::StableTypeId;
+
+impl Serialize for StableTypeId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match serializer.is_human_readable() {
+            true => serializer.collect_str(self),
+            false => serializer.serialize_u64(self.0),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StableTypeId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_str(IdVisitor),
+            false => deserializer.deserialize_u64(IdVisitor),
+        }
+    }
+}
+
+struct IdVisitor;
+
+impl Visitor<'_> for IdVisitor {
+    type Value = StableTypeId;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a StableTypeId, as a hex string or u64")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(StableTypeId(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn round_trip() {
+        let id = StableTypeId::of::<u8>();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", id));
+        assert_eq!(serde_json::from_str::<StableTypeId>(&json).unwrap(), id);
+    }
+}
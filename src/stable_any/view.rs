@@ -0,0 +1,120 @@
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+/// An erased `&T`, for passing a borrowed value where any `StableAny` type is accepted
+/// without first moving it into a `Thin<dyn StableAny>`.
+///
+/// ```rust
+/// use thin_trait_objects::prelude::*;
+///
+/// fn describe(val: StableAnyRef<'_>) -> String {
+///     match val.downcast_ref::<u32>() {
+///         Some(n) => format!("u32: {}", n),
+///         None => String::from("something else"),
+///     }
+/// }
+///
+/// assert_eq!(describe(StableAnyRef::new(&8u32)), "u32: 8");
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct StableAnyRef<'a> {
+    id: StableTypeId,
+    ptr: *const (),
+    phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> StableAnyRef<'a> {
+    pub fn new<T: StableAny>(val: &'a T) -> Self {
+        StableAnyRef {
+            id: StableTypeId::of::<T>(),
+            ptr: val as *const T as *const (),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn stable_type_id(&self) -> StableTypeId {
+        self.id
+    }
+
+    pub fn is<T: StableAny>(&self) -> bool {
+        self.id == StableTypeId::of::<T>()
+    }
+
+    pub fn downcast_ref<T: StableAny>(&self) -> Option<&'a T> {
+        match self.is::<T>() {
+            true => Some(unsafe { &*(self.ptr as *const T) }),
+            false => None,
+        }
+    }
+}
+
+/// An erased `&mut T`; see [`StableAnyRef`].
+#[repr(C)]
+pub struct StableAnyMut<'a> {
+    id: StableTypeId,
+    ptr: *mut (),
+    phantom: PhantomData<&'a mut ()>,
+}
+
+impl<'a> StableAnyMut<'a> {
+    pub fn new<T: StableAny>(val: &'a mut T) -> Self {
+        StableAnyMut {
+            id: StableTypeId::of::<T>(),
+            ptr: val as *mut T as *mut (),
+            phantom: PhantomData,
+        }
+    }
+
+    pub fn stable_type_id(&self) -> StableTypeId {
+        self.id
+    }
+
+    pub fn is<T: StableAny>(&self) -> bool {
+        self.id == StableTypeId::of::<T>()
+    }
+
+    pub fn downcast_ref<T: StableAny>(&self) -> Option<&T> {
+        match self.is::<T>() {
+            true => Some(unsafe { &*(self.ptr as *const T) }),
+            false => None,
+        }
+    }
+
+    pub fn downcast_mut<T: StableAny>(&mut self) -> Option<&mut T> {
+        match self.is::<T>() {
+            true => Some(unsafe { &mut *(self.ptr as *mut T) }),
+            false => None,
+        }
+    }
+
+    /// Reborrows as a [`StableAnyRef`].
+    pub fn as_ref(&self) -> StableAnyRef<'_> {
+        StableAnyRef {
+            id: self.id,
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn views() {
+        let mut val = 8u8;
+
+        let mut view = StableAnyMut::new(&mut val);
+        assert!(view.downcast_mut::<u16>().is_none());
+        *view.downcast_mut::<u8>().unwrap() += 1;
+        assert_eq!(view.as_ref().downcast_ref::<u8>(), Some(&9u8));
+
+        let view = StableAnyRef::new(&val);
+        assert!(view.is::<u8>());
+        assert_eq!(view.stable_type_id(), StableTypeId::of::<u8>());
+        assert_eq!(view.downcast_ref::<u8>(), Some(&9u8));
+    }
+}
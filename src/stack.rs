@@ -0,0 +1,160 @@
+//! Thin objects whose bundles live on the stack, for passing locals to APIs expecting thin handles
+//! without allocating.
+//!
+//! A [`ThinStack`] holds a value along with its vtable pointer, laid out like the bundle of a
+//! `Thin<dyn Trait>`, and lends out borrowed thin views of it. [`thin_stack!`](crate::thin_stack)
+//! builds one in a temporary:
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Counter: 'static {
+//!     fn count(&mut self) -> u32;
+//! }
+//!
+//! impl Counter for u32 {
+//!     fn count(&mut self) -> u32 { *self += 1; *self }
+//! }
+//!
+//! fn count_twice(counter: &mut ThinMut<dyn Counter>) -> u32 {
+//!     counter.count();
+//!     counter.count()
+//! }
+//!
+//! // valid until the end of the statement
+//! assert_eq!(count_twice(&mut thin_stack!(1u32 as dyn Counter)), 3);
+//!
+//! // valid until the end of the scope
+//! thin_stack!(let counter = 5u32 as dyn Counter);
+//! assert_eq!(counter.count(), 6);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+/// The bundle of a `Thin<U>` created from a `K`, stored in place rather than on the heap.
+///
+/// The value is dropped along with the `ThinStack`, never through the vtable.
+#[repr(C)]
+pub struct ThinStack<U: ?Sized + SpecialAssoc + 'static, K> {
+    vtable: *const (),
+    value: K,
+    phantom: PhantomData<fn() -> Thin<U>>,
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static, K> ThinStack<U, K> where Thin<U>: ThinVTable<U, K> {
+    pub fn new(value: K) -> Self {
+        ThinStack { vtable: <Thin<U> as ThinVTable<U, K>>::vtable(), value, phantom: PhantomData }
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static, K> ThinStack<U, K> {
+    /// Borrows the value as a thin object.
+    pub fn as_thin(&self) -> Thin<&U> {
+        unsafe { Thin::from_raw(self as *const Self as *mut ()) }
+    }
+
+    /// Mutably borrows the value as a thin object.
+    pub fn as_thin_mut(&mut self) -> Thin<&mut U> {
+        unsafe { Thin::from_raw(self as *mut Self as *mut ()) }
+    }
+
+    pub fn get(&self) -> &K {
+        &self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut K {
+        &mut self.value
+    }
+
+    pub fn into_inner(self) -> K {
+        self.value
+    }
+}
+
+/// Builds a [`ThinStack`] from a value and mutably borrows it as a `Thin<&mut dyn Trait>`.
+///
+/// `thin_stack!(value as dyn Trait)` is valid until the end of the enclosing statement, and
+/// `thin_stack!(let name = value as dyn Trait)` binds `name` until the end of the enclosing scope.
+/// The value is everything before the first `as`, so casts within it must be parenthesized.
+#[macro_export]
+macro_rules! thin_stack {
+    (@let $name: ident [$($value: tt)+] as $ty: ty) => {
+        let mut $name = $crate::stack::ThinStack::<$ty, _>::new($($value)+);
+        #[allow(unused_mut)]
+        let mut $name = $name.as_thin_mut();
+    };
+    (@let $name: ident [$($value: tt)*] $next: tt $($rest: tt)*) => {
+        $crate::thin_stack!(@let $name [$($value)* $next] $($rest)*)
+    };
+    (@expr [$($value: tt)+] as $ty: ty) => {
+        $crate::stack::ThinStack::<$ty, _>::new($($value)+).as_thin_mut()
+    };
+    (@expr [$($value: tt)*] $next: tt $($rest: tt)*) => {
+        $crate::thin_stack!(@expr [$($value)* $next] $($rest)*)
+    };
+    (let $name: ident = $($rest: tt)+) => {
+        $crate::thin_stack!(@let $name [] $($rest)+)
+    };
+    ($($rest: tt)+) => {
+        $crate::thin_stack!(@expr [] $($rest)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[thin]
+    trait Shape: 'static {
+        fn area(&self) -> u32;
+        fn scale(&mut self, factor: u32);
+    }
+
+    struct Square(u32, #[allow(dead_code)] Rc<()>);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+        fn scale(&mut self, factor: u32) {
+            self.0 *= factor;
+        }
+    }
+
+    fn area(shape: &Thin<dyn Shape>) -> u32 {
+        shape.area()
+    }
+
+    #[test]
+    fn stack_views() {
+        let rc = Rc::new(());
+        let mut square = ThinStack::<dyn Shape, _>::new(Square(2, rc.clone()));
+        square.as_thin_mut().scale(3);
+        assert_eq!(area(&square.as_thin()), 36);
+        assert_eq!(square.get().0, 6);
+        drop(square);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        assert_eq!(area(&thin_stack!(Square(4, rc.clone()) as dyn Shape)), 16);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        thin_stack!(let square = Square(1, rc.clone()) as dyn Shape);
+        square.scale(5);
+        assert_eq!(square.area(), 25);
+        assert_eq!(Rc::strong_count(&rc), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "any")]
+    fn stack_any() {
+        let mut value = ThinStack::<dyn std::any::Any, _>::new(5u8);
+        *value.as_thin_mut().downcast_mut::<u8>().unwrap() += 1;
+        assert_eq!(value.as_thin().downcast_ref::<u8>(), Some(&6));
+        assert_eq!(value.into_inner(), 6);
+    }
+}
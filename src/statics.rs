@@ -0,0 +1,116 @@
+//! Thin objects whose bundles are `static`, for handing out default or global implementations
+//! without allocating or counting references.
+//!
+//! A [`ThinStatic`] is built in a `const` context, and lends out `Thin<&'static dyn Trait>`s, which
+//! deref to `Thin<dyn Trait>` and do nothing when dropped. [`static_thin_ref!`](crate::static_thin_ref)
+//! declares one in place:
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//!
+//! #[thin]
+//! trait Config: 'static {
+//!     fn retries(&self) -> u32;
+//! }
+//!
+//! struct Defaults { retries: u32 }
+//! impl Config for Defaults {
+//!     fn retries(&self) -> u32 { self.retries }
+//! }
+//!
+//! fn defaults() -> Thin<&'static dyn Config> {
+//!     static_thin_ref!(Defaults { retries: 3 }: Defaults as dyn Config)
+//! }
+//!
+//! assert_eq!(defaults().retries(), 3);
+//! assert_eq!(defaults().ptr, defaults().ptr);
+//! ```
+
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::prelude::*;
+
+/// The bundle of a `Thin<U>` created from a `K`, which can be stored in a `static`.
+#[repr(C)]
+pub struct ThinStatic<U: ?Sized + SpecialAssoc + 'static, K> {
+    // vtables can't be looked up in const contexts, so this is null until the first `get`
+    vtable: AtomicPtr<()>,
+    value: K,
+    phantom: PhantomData<fn() -> Thin<U>>,
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static, K> ThinStatic<U, K> {
+    pub const fn new(value: K) -> Self {
+        ThinStatic { vtable: AtomicPtr::new(null_mut()), value, phantom: PhantomData }
+    }
+}
+
+impl<U: ?Sized + SpecialAssoc + 'static, K> ThinStatic<U, K> where Thin<U>: ThinVTable<U, K> {
+    /// Borrows the value as a thin object.
+    pub fn get(&self) -> Thin<&U> {
+        if self.vtable.load(Ordering::Acquire).is_null() {
+            // the vtable is only ever written once, so that it's never written while being read
+            // (non-atomically) through a thin object
+            let vtable = <Thin<U> as ThinVTable<U, K>>::vtable() as *mut ();
+            let _ = self.vtable.compare_exchange(null_mut(), vtable, Ordering::AcqRel, Ordering::Acquire);
+        }
+        unsafe { Thin::from_raw(self as *const Self as *mut ()) }
+    }
+}
+
+/// Declares a [`ThinStatic`] holding a constant value, and borrows it as a `Thin<&'static dyn Trait>`.
+///
+/// `static_thin_ref!(VALUE: Type as dyn Trait)` evaluates to the same thin object every time, as the
+/// value is stored in a `static` of the given type (which can't be inferred for items). The value is
+/// everything before the first `:`, and must be `Sync`.
+#[macro_export]
+macro_rules! static_thin_ref {
+    (@munch [$($value: tt)+] : $k: ty as $u: ty) => {{
+        static BUNDLE: $crate::statics::ThinStatic<$u, $k> = $crate::statics::ThinStatic::new($($value)+);
+        BUNDLE.get()
+    }};
+    (@munch [$($value: tt)*] $next: tt $($rest: tt)*) => {
+        $crate::static_thin_ref!(@munch [$($value)* $next] $($rest)*)
+    };
+    ($($rest: tt)+) => {
+        $crate::static_thin_ref!(@munch [] $($rest)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[thin]
+    trait Greeter: 'static {
+        fn language(&self) -> u32;
+    }
+
+    struct English;
+    impl Greeter for English {
+        fn language(&self) -> u32 {
+            1
+        }
+    }
+
+    const ENGLISH: English = English;
+
+    fn default_greeter() -> Thin<&'static dyn Greeter> {
+        static_thin_ref!(ENGLISH: English as dyn Greeter)
+    }
+
+    #[test]
+    fn static_bundles() {
+        let greeters = (0..4).map(|_| std::thread::spawn(|| default_greeter().ptr.as_ptr() as usize)).collect::<Vec<_>>();
+        for greeter in greeters {
+            assert_eq!(greeter.join().unwrap(), default_greeter().ptr.as_ptr() as usize);
+        }
+
+        let greeter = default_greeter();
+        assert_eq!(greeter.language(), 1);
+        drop(greeter.clone());
+        assert_eq!(default_greeter().language(), 1);
+    }
+}
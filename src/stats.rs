@@ -0,0 +1,175 @@
+//! Live object counters, enabled by the `stats` feature.
+//!
+//! Every thin object of a `#[thin]` trait allocated by `Thin::new` (or by cloning it, `from_fn` or
+//! `pin_with_drop`) is counted until it's dropped, per trait and per concrete type, along with the
+//! highest count reached, so that long-running hosts can watch for plugins leaking objects
+//! without a heap profiler. Objects in a `ThinBatch` or stored inline aren't counted.
+//!
+//! ```rust
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::stats;
+//!
+//! #[thin]
+//! trait Voice: 'static {
+//!     fn pitch(&self) -> f32;
+//! }
+//!
+//! impl Voice for f32 {
+//!     fn pitch(&self) -> f32 { *self }
+//! }
+//!
+//! let voices = (0..4).map(|i| Thin::<dyn Voice>::new(i as f32)).collect::<Vec<_>>();
+//! drop(voices);
+//! let _held = Thin::<dyn Voice>::new(1.0f32);
+//!
+//! let report = stats::report();
+//! let voice = report.iter().find(|stats| stats.trait_name == "Voice").unwrap();
+//! assert_eq!((voice.live, voice.peak), (1, 4));
+//! assert_eq!((voice.types[0].type_name, voice.types[0].live), ("f32", 1));
+//! ```
+
+use std::any::{type_name, TypeId};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+/// The live objects of a trait, in total and per concrete type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraitStats {
+    pub trait_name: &'static str,
+    pub live: usize,
+    pub peak: usize,
+    /// Most live first.
+    pub types: Vec<TypeStats>,
+}
+
+/// The live objects of a trait created from one concrete type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeStats {
+    pub type_name: &'static str,
+    pub live: usize,
+    pub peak: usize,
+}
+
+struct Counter {
+    trait_name: &'static str,
+    // `None` for the total of the trait
+    type_name: Option<&'static str>,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl Counter {
+    fn created(&self) {
+        let live = self.live.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak.fetch_max(live, Ordering::Relaxed);
+    }
+
+    // objects assembled with `Thin::from_parts` are dropped without having been counted
+    fn dropped(&self) {
+        let _ = self.live.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |live| live.checked_sub(1));
+    }
+}
+
+type Key = (&'static str, Option<TypeId>);
+
+// counters are leaked, so that they can be updated without holding the lock
+static COUNTERS: RwLock<Option<HashMap<Key, &'static Counter>>> = RwLock::new(None);
+
+fn counter(trait_name: &'static str, ty: Option<(TypeId, &'static str)>) -> &'static Counter {
+    let key = (trait_name, ty.map(|(id, _)| id));
+    let counter = COUNTERS.read().unwrap().as_ref().and_then(|counters| counters.get(&key).copied());
+    counter.unwrap_or_else(|| {
+        let mut counters = COUNTERS.write().unwrap();
+        *counters.get_or_insert_with(HashMap::new).entry(key).or_insert_with(|| {
+            let type_name = ty.map(|(_, name)| name);
+            Box::leak(Box::new(Counter { trait_name, type_name, live: AtomicUsize::new(0), peak: AtomicUsize::new(0) }))
+        })
+    })
+}
+
+pub(crate) fn count_new<T: 'static>(trait_name: &'static str) {
+    counter(trait_name, None).created();
+    counter(trait_name, Some((TypeId::of::<T>(), type_name::<T>()))).created();
+}
+
+pub(crate) fn count_drop<T: 'static>(trait_name: &'static str) {
+    counter(trait_name, None).dropped();
+    counter(trait_name, Some((TypeId::of::<T>(), type_name::<T>()))).dropped();
+}
+
+/// Returns the live and peak counts of every trait which has had objects, most live first.
+pub fn report() -> Vec<TraitStats> {
+    let counters = COUNTERS.read().unwrap();
+    let counters = counters.iter().flat_map(|counters| counters.values()).collect::<Vec<_>>();
+    let mut report = counters.iter().filter(|counter| counter.type_name.is_none()).map(|total| {
+        let mut types = counters.iter().filter(|counter| counter.trait_name == total.trait_name).filter_map(|counter| {
+            Some(TypeStats {
+                type_name: counter.type_name?,
+                live: counter.live.load(Ordering::Relaxed),
+                peak: counter.peak.load(Ordering::Relaxed),
+            })
+        }).collect::<Vec<_>>();
+        types.sort_by(|a, b| b.live.cmp(&a.live).then_with(|| a.type_name.cmp(b.type_name)));
+        TraitStats {
+            trait_name: total.trait_name,
+            live: total.live.load(Ordering::Relaxed),
+            peak: total.peak.load(Ordering::Relaxed),
+            types,
+        }
+    }).collect::<Vec<_>>();
+    report.sort_by(|a, b| b.live.cmp(&a.live).then_with(|| a.trait_name.cmp(b.trait_name)));
+    report
+}
+
+/// Sets every peak back to the current live count.
+pub fn reset_peaks() {
+    for counter in COUNTERS.read().unwrap().iter().flat_map(|counters| counters.values()) {
+        counter.peak.store(counter.live.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[thin]
+    trait Tracked: 'static {
+        fn get(&self) -> u32;
+    }
+
+    impl Tracked for u32 {
+        fn get(&self) -> u32 {
+            *self
+        }
+    }
+
+    impl Tracked for u8 {
+        fn get(&self) -> u32 {
+            *self as u32
+        }
+    }
+
+    fn tracked() -> TraitStats {
+        report().into_iter().find(|stats| stats.trait_name == "Tracked").unwrap()
+    }
+
+    // the counters are global, so only this test uses `Tracked`
+    #[test]
+    fn live_counts() {
+        let mut objects = (0..3).map(|i| Thin::<dyn Tracked>::new(i as u32)).collect::<Vec<_>>();
+        objects.push(Thin::new(1u8));
+        objects.push(Thin::new(2u8));
+        objects.truncate(2);
+
+        let stats = tracked();
+        assert_eq!((stats.live, stats.peak), (2, 5));
+        let types = stats.types.iter().map(|ty| (ty.type_name, ty.live, ty.peak)).collect::<Vec<_>>();
+        assert_eq!(types, [("u32", 2, 3), ("u8", 0, 2)]);
+
+        reset_peaks();
+        drop(objects);
+        assert_eq!((tracked().live, tracked().peak), (0, 2));
+    }
+}
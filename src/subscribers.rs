@@ -0,0 +1,230 @@
+//! `Subscribers<T>`, a registry of thin listener objects, with RAII handles to their subscriptions.
+//!
+//! ```rust
+//! use std::sync::atomic::{AtomicU32, Ordering};
+//! use std::sync::Arc;
+//! use thin_trait_objects::prelude::*;
+//! use thin_trait_objects::subscribers::Subscribers;
+//!
+//! #[thin]
+//! trait Listener: 'static {
+//!     fn on_event(&mut self, event: u32);
+//! }
+//!
+//! let subscribers = Subscribers::<dyn Listener>::new();
+//! let total = Arc::new(AtomicU32::new(0));
+//! let counter = total.clone();
+//! let handle = subscribers.subscribe(Thin::<dyn Listener>::from_fn(move |event| {
+//!     counter.fetch_add(event, Ordering::Relaxed);
+//! }));
+//!
+//! subscribers.for_each(|listener| listener.on_event(2));
+//! drop(handle);
+//! subscribers.for_each(|listener| listener.on_event(3));
+//! assert_eq!(total.load(Ordering::Relaxed), 2);
+//! ```
+
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::prelude::*;
+
+struct Inner<T: ?Sized + SpecialAssoc + 'static> {
+    next: u64,
+    entries: Vec<(u64, Thin<T>)>,
+    // while dispatching, the entries are taken out of the registry, and removals are deferred
+    dispatching: bool,
+    removed: Vec<u64>,
+}
+
+/// A registry of listeners, which may subscribe and unsubscribe while being notified.
+pub struct Subscribers<T: ?Sized + SpecialAssoc + 'static> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+/// Unsubscribes a listener when dropped, unless detached.
+///
+/// Each subscription is identified by a `u64` token, which can be handed across an FFI boundary
+/// in place of the handle (see [`SubscriptionHandle::detach`]).
+#[must_use = "dropping the handle unsubscribes the listener"]
+pub struct SubscriptionHandle {
+    token: u64,
+    registry: Weak<dyn Unsubscribe>,
+}
+
+trait Unsubscribe {
+    fn unsubscribe(&self, token: u64);
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Unsubscribe for Mutex<Inner<T>> {
+    fn unsubscribe(&self, token: u64) {
+        // the listener is dropped outside of the lock, in case it holds a handle of its own
+        let _listener = remove(&mut self.lock().unwrap(), token);
+    }
+}
+
+fn remove<T: ?Sized + SpecialAssoc + 'static>(inner: &mut Inner<T>, token: u64) -> Option<Thin<T>> {
+    if let Some(index) = inner.entries.iter().position(|(entry, _)| *entry == token) {
+        return Some(inner.entries.remove(index).1);
+    }
+    if inner.dispatching && token < inner.next {
+        inner.removed.push(token);
+    }
+    None
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Default for Subscribers<T> {
+    fn default() -> Self {
+        Subscribers::new()
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Subscribers<T> {
+    pub fn new() -> Self {
+        let inner = Inner { next: 0, entries: Vec::new(), dispatching: false, removed: Vec::new() };
+        Subscribers { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Subscribes `listener`, until the returned handle is dropped.
+    pub fn subscribe(&self, listener: Thin<T>) -> SubscriptionHandle {
+        let mut inner = self.inner.lock().unwrap();
+        let token = inner.next;
+        inner.next += 1;
+        inner.entries.push((token, listener));
+
+        let registry: Arc<dyn Unsubscribe> = self.inner.clone();
+        SubscriptionHandle { token, registry: Arc::downgrade(&registry) }
+    }
+
+    /// Unsubscribes the listener with the given token, returning it.
+    ///
+    /// Returns `None` if there was no such listener, or if it's currently being notified by
+    /// [`Subscribers::for_each`], in which case it's dropped once notification finishes.
+    pub fn unsubscribe(&self, token: u64) -> Option<Thin<T>> {
+        remove(&mut self.inner.lock().unwrap(), token)
+    }
+
+    /// Returns the number of subscribed listeners, not counting those being notified.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Calls `f` on every listener, in the order they subscribed.
+    ///
+    /// Listeners subscribed during the call aren't notified until the next one, and calls nested
+    /// within `f` see no listeners.
+    pub fn for_each(&self, mut f: impl FnMut(&mut Thin<T>)) {
+        let mut entries = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.dispatching = true;
+            std::mem::take(&mut inner.entries)
+        };
+
+        for (_, listener) in &mut entries {
+            f(listener);
+        }
+
+        let removed = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.dispatching = false;
+            let removed = std::mem::take(&mut inner.removed);
+            let (kept, removed) = entries.into_iter().partition::<Vec<_>, _>(|(token, _)| !removed.contains(token));
+            let subscribed = std::mem::replace(&mut inner.entries, kept);
+            inner.entries.extend(subscribed);
+            removed
+        };
+        drop(removed);
+    }
+}
+
+impl SubscriptionHandle {
+    /// The token identifying this subscription.
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Keeps the listener subscribed, returning the token with which it can be unsubscribed.
+    pub fn detach(self) -> u64 {
+        let token = self.token;
+        std::mem::forget(self);
+        token
+    }
+}
+
+impl Drop for SubscriptionHandle {
+    fn drop(&mut self) {
+        if let Some(registry) = self.registry.upgrade() {
+            registry.unsubscribe(self.token);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[thin]
+    trait Listener: 'static {
+        fn on_event(&mut self, event: u32);
+    }
+
+    struct Log(Rc<RefCell<Vec<(u32, u32)>>>, u32);
+
+    impl Listener for Log {
+        fn on_event(&mut self, event: u32) {
+            self.0.borrow_mut().push((self.1, event));
+        }
+    }
+
+    #[test]
+    fn handles() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let subscribers = Subscribers::<dyn Listener>::new();
+        let first = subscribers.subscribe(Thin::<dyn Listener>::new(Log(log.clone(), 1)));
+        let second = subscribers.subscribe(Thin::<dyn Listener>::new(Log(log.clone(), 2)));
+        let token = second.detach();
+        assert_eq!(subscribers.len(), 2);
+
+        subscribers.for_each(|listener| listener.on_event(10));
+        drop(first);
+        subscribers.for_each(|listener| listener.on_event(20));
+        assert_eq!(*log.borrow(), [(1, 10), (2, 10), (2, 20)]);
+
+        assert!(subscribers.unsubscribe(token).is_some());
+        assert!(subscribers.is_empty());
+    }
+
+    #[test]
+    fn changes_during_notification() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let subscribers = Subscribers::<dyn Listener>::new();
+        let mut first = Some(subscribers.subscribe(Thin::<dyn Listener>::new(Log(log.clone(), 1))));
+        let mut added = None;
+
+        subscribers.for_each(|listener| {
+            listener.on_event(10);
+            first.take();
+            added.get_or_insert_with(|| subscribers.subscribe(Thin::<dyn Listener>::new(Log(log.clone(), 2))));
+        });
+        assert_eq!(subscribers.len(), 1);
+
+        subscribers.for_each(|listener| listener.on_event(20));
+        assert_eq!(*log.borrow(), [(1, 10), (2, 20)]);
+        drop(added);
+        assert!(subscribers.is_empty());
+    }
+
+    #[test]
+    fn outliving_the_registry() {
+        let subscribers = Subscribers::<dyn Listener>::new();
+        let handle = subscribers.subscribe(Thin::<dyn Listener>::from_fn(|_| {}));
+        drop(subscribers);
+        drop(handle);
+    }
+}
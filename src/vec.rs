@@ -0,0 +1,242 @@
+//! A vector of thin objects, for use in place of `Vec<Box<dyn Trait>>`.
+
+use std::ops::RangeBounds;
+use std::{slice, vec};
+
+use crate::prelude::*;
+
+/// A `Vec<Thin<T>>`, whose iterators yield borrowed thin views (`Thin<&T>` and `Thin<&mut T>`)
+/// rather than references to `Thin`s.
+///
+/// ```rust
+/// use thin_trait_objects::prelude::*;
+///
+/// #[thin]
+/// trait Shape: 'static {
+///     fn area(&self) -> u32;
+/// }
+///
+/// struct Square(u32);
+/// impl Shape for Square {
+///     fn area(&self) -> u32 { self.0 * self.0 }
+/// }
+///
+/// let mut shapes: ThinVec<dyn Shape> = (1..=4).map(|n| Thin::<dyn Shape>::new(Square(n))).collect();
+/// shapes.retain(|shape| shape.area() % 2 == 0);
+/// assert_eq!(shapes.iter().map(|shape| shape.area()).sum::<u32>(), 20);
+/// ```
+pub struct ThinVec<T: ?Sized + SpecialAssoc + 'static>(Vec<Thin<T>>);
+
+impl<T: ?Sized + SpecialAssoc + 'static> ThinVec<T> {
+    pub const fn new() -> Self {
+        ThinVec(Vec::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        ThinVec(Vec::with_capacity(capacity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn push(&mut self, value: Thin<T>) {
+        self.0.push(value)
+    }
+
+    pub fn pop(&mut self) -> Option<Thin<T>> {
+        self.0.pop()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Thin<&T>> {
+        self.0.get(index).map(Thin::as_ref)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<Thin<&mut T>> {
+        self.0.get_mut(index).map(Thin::as_mut)
+    }
+
+    pub fn as_slice(&self) -> &[Thin<T>] {
+        &self.0
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Thin<T>] {
+        &mut self.0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter(self.0.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut(self.0.iter_mut())
+    }
+
+    /// Keeps only the objects for which `f` returns `true`, dropping the rest in place.
+    pub fn retain<F: FnMut(&Thin<T>) -> bool>(&mut self, f: F) {
+        self.0.retain(f)
+    }
+
+    /// Removes the objects in `range`, returning them by value.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> vec::Drain<'_, Thin<T>> {
+        self.0.drain(range)
+    }
+
+    pub fn into_vec(self) -> Vec<Thin<T>> {
+        self.0
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Default for ThinVec<T> {
+    fn default() -> Self {
+        ThinVec::new()
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> From<Vec<Thin<T>>> for ThinVec<T> {
+    fn from(vec: Vec<Thin<T>>) -> Self {
+        ThinVec(vec)
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> FromIterator<Thin<T>> for ThinVec<T> {
+    fn from_iter<I: IntoIterator<Item = Thin<T>>>(iter: I) -> Self {
+        ThinVec(iter.into_iter().collect())
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> Extend<Thin<T>> for ThinVec<T> {
+    fn extend<I: IntoIterator<Item = Thin<T>>>(&mut self, iter: I) {
+        self.0.extend(iter)
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> IntoIterator for ThinVec<T> {
+    type Item = Thin<T>;
+    type IntoIter = vec::IntoIter<Thin<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T: ?Sized + SpecialAssoc + 'static> IntoIterator for &'a ThinVec<T> {
+    type Item = Thin<&'a T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: ?Sized + SpecialAssoc + 'static> IntoIterator for &'a mut ThinVec<T> {
+    type Item = Thin<&'a mut T>;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+//================//
+
+/// Iterator over `Thin<&T>`s, created by [`ThinVec::iter`].
+pub struct Iter<'a, T: ?Sized + SpecialAssoc + 'static>(slice::Iter<'a, Thin<T>>);
+
+impl<'a, T: ?Sized + SpecialAssoc + 'static> Iterator for Iter<'a, T> {
+    type Item = Thin<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Thin::as_ref)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(Thin::as_ref)
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> ExactSizeIterator for Iter<'_, T> {}
+
+/// Iterator over `Thin<&mut T>`s, created by [`ThinVec::iter_mut`].
+pub struct IterMut<'a, T: ?Sized + SpecialAssoc + 'static>(slice::IterMut<'a, Thin<T>>);
+
+impl<'a, T: ?Sized + SpecialAssoc + 'static> Iterator for IterMut<'a, T> {
+    type Item = Thin<&'a mut T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(Thin::as_mut)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> DoubleEndedIterator for IterMut<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(Thin::as_mut)
+    }
+}
+
+impl<T: ?Sized + SpecialAssoc + 'static> ExactSizeIterator for IterMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[thin]
+    trait Counter: 'static {
+        fn add(&mut self, n: u32);
+        fn get(&self) -> u32;
+    }
+
+    impl Counter for u32 {
+        fn add(&mut self, n: u32) {
+            *self += n;
+        }
+        fn get(&self) -> u32 {
+            *self
+        }
+    }
+
+    fn counters(range: std::ops::Range<u32>) -> impl Iterator<Item = Thin<dyn Counter>> {
+        range.map(Thin::<dyn Counter>::new)
+    }
+
+    #[test]
+    fn iteration() {
+        let mut vec: ThinVec<dyn Counter> = counters(0..3).collect();
+        vec.extend(counters(3..5));
+        assert_eq!(vec.len(), 5);
+
+        for mut counter in &mut vec {
+            counter.add(10);
+        }
+        let values = vec.iter().map(|counter| counter.get()).collect::<Vec<_>>();
+        assert_eq!(values, [10, 11, 12, 13, 14]);
+
+        let values = vec.into_iter().rev().map(|counter| counter.get()).collect::<Vec<_>>();
+        assert_eq!(values, [14, 13, 12, 11, 10]);
+    }
+
+    #[test]
+    fn retain_and_drain() {
+        let mut vec: ThinVec<dyn Counter> = counters(0..6).collect();
+        vec.retain(|counter| counter.get() % 2 == 0);
+
+        let drained = vec.drain(1..).map(|counter| counter.get()).collect::<Vec<_>>();
+        assert_eq!(drained, [2, 4]);
+        assert_eq!(vec.len(), 1);
+        assert_eq!(vec.get(0).unwrap().get(), 0);
+    }
+}
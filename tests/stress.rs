@@ -0,0 +1,98 @@
+//! Multi-threaded stress tests for thin objects shared and dropped across threads.
+//!
+//! Run with `cargo test --release --features stress --test stress`.
+#![cfg(feature = "stress")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Barrier};
+use std::thread;
+
+use thin_trait_objects::prelude::*;
+
+const THREADS: usize = 8;
+const ROUNDS: usize = 200;
+
+#[thin]
+trait Job: Send + Sync + 'static {
+    fn run(&self) -> usize;
+}
+
+struct Tracked(usize, Arc<AtomicUsize>);
+
+impl Job for Tracked {
+    fn run(&self) -> usize {
+        self.0
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn batched_objects_dropped_across_threads() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    for _ in 0..ROUNDS {
+        // small chunks, so that the objects of a thread share chunks with the others'
+        let mut batch = ThinBatch::<dyn Job>::with_chunk_size(256);
+        let mut queues = (0..THREADS).map(|_| Vec::new()).collect::<Vec<_>>();
+        for i in 0..THREADS * 16 {
+            queues[i % THREADS].push(batch.push(Tracked(i, drops.clone())));
+        }
+
+        let barrier = Arc::new(Barrier::new(THREADS + 1));
+        let threads = queues
+            .into_iter()
+            .map(|jobs| {
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    jobs.iter().map(|job| job.run()).sum::<usize>()
+                })
+            })
+            .collect::<Vec<_>>();
+        barrier.wait();
+        drop(batch);
+        let sum = threads.into_iter().map(|thread| thread.join().unwrap()).sum::<usize>();
+        assert_eq!(sum, (0..THREADS * 16).sum::<usize>());
+    }
+    assert_eq!(drops.load(Ordering::Relaxed), ROUNDS * THREADS * 16);
+}
+
+#[test]
+fn objects_sent_and_shared_between_threads() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel::<Thin<dyn Job>>();
+    let consumer = thread::spawn(move || receiver.into_iter().map(|job| job.run()).sum::<usize>());
+
+    // each object is borrowed by every thread before being sent on to be dropped
+    let producers = (0..THREADS)
+        .map(|t| {
+            let (drops, sender) = (drops.clone(), sender.clone());
+            thread::spawn(move || {
+                for round in 0..ROUNDS {
+                    let job = Arc::new(Thin::<dyn Job>::new(Tracked(t * ROUNDS + round, drops.clone())));
+                    let borrowers = (0..2)
+                        .map(|_| {
+                            let job = job.clone();
+                            thread::spawn(move || job.run())
+                        })
+                        .collect::<Vec<_>>();
+                    for borrower in borrowers {
+                        assert_eq!(borrower.join().unwrap(), t * ROUNDS + round);
+                    }
+                    let Ok(job) = Arc::try_unwrap(job) else { unreachable!() };
+                    sender.send(job).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    assert_eq!(consumer.join().unwrap(), (0..THREADS * ROUNDS).sum::<usize>());
+    assert_eq!(drops.load(Ordering::Relaxed), THREADS * ROUNDS);
+}
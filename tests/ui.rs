@@ -0,0 +1,15 @@
+//! Compile-fail tests for the diagnostics of the proc macros.
+//!
+//! Run with `TRYBUILD=overwrite` to accept changed diagnostics.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+    // the derive and `export_thin_plugin!` are only exported with `stable_any`
+    #[cfg(feature = "stable_any")]
+    cases.compile_fail("tests/ui/stable_any/*.rs");
+    // the diagnostics list the thread-safe object types, some of which depend on the features
+    #[cfg(all(feature = "any", feature = "stable_any", not(feature = "log")))]
+    cases.compile_fail("tests/ui/thread_safety/*.rs");
+}
@@ -0,0 +1,29 @@
+use thin_trait_objects::prelude::*;
+
+// the object of a `ThinCow` can't be replaced by one of another type, which its `CloneFn` would
+// then be called on
+#[thin]
+trait Config: 'static {
+    fn volume(&self) -> u8;
+}
+
+#[derive(Clone)]
+struct Audio(u8);
+
+impl Config for Audio {
+    fn volume(&self) -> u8 {
+        self.0
+    }
+}
+
+impl Config for String {
+    fn volume(&self) -> u8 {
+        0
+    }
+}
+
+fn main() {
+    let mut config = ThinCow::<dyn Config>::new(Audio(5));
+    *config.to_mut() = Thin::<dyn Config>::new(String::from("loud"));
+    let _ = config.clone().into_thin();
+}
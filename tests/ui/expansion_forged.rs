@@ -0,0 +1,30 @@
+use thin_trait_objects::prelude::*;
+use thin_trait_objects::__private::*;
+
+// the helpers behind `#[thin]`'s expansion are `unsafe`, and its object-type traits can't be
+// implemented without an unsafely created token
+trait Forged: 'static {}
+
+impl SpecialAssoc for dyn Forged {
+    type Kind = Own;
+}
+
+impl Expanded for dyn Forged {}
+
+const TOKEN: ExpansionToken = ExpansionToken::new();
+
+#[thin]
+trait Foo: 'static {
+    fn get(&self) -> u8;
+}
+
+impl Foo for u8 {
+    fn get(&self) -> u8 {
+        *self
+    }
+}
+
+fn main() {
+    let thin = Thin::<dyn Foo>::new(8u8);
+    let _: &String = expanded_value::<u64, String>(RefSelf::new(&thin));
+}
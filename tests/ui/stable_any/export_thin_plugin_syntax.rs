@@ -0,0 +1,17 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+pub trait Foo: 'static {
+    fn foo(&self);
+}
+
+#[derive(Default)]
+pub struct Bar;
+
+impl Foo for Bar {
+    fn foo(&self) {}
+}
+
+export_thin_plugin!(Bar: Foo);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use thin_trait_objects::prelude::*;
+
+#[derive(StableAny)]
+#[stable_any(unsafe(rename_from = "OldName"))]
+struct Foo;
+
+fn main() {}
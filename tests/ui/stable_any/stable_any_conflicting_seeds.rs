@@ -0,0 +1,7 @@
+use thin_trait_objects::prelude::*;
+
+#[derive(StableAny)]
+#[stable_any(unsafe(id = 1, rename_from = "old::Foo"))]
+struct Foo;
+
+fn main() {}
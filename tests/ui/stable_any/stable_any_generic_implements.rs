@@ -0,0 +1,12 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Shape: 'static {
+    fn sides(&self) -> u32;
+}
+
+#[derive(StableAny)]
+#[stable_any(implements(Shape))]
+struct Polygon<T>(T);
+
+fn main() {}
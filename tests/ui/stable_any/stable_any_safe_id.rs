@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+// a pinned id may be another type's, so must be vouched for
+#[derive(StableAny)]
+#[stable_any(id = 1)]
+struct Foo;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use thin_trait_objects::prelude::*;
+
+#[derive(StableAny)]
+#[stable_any(name = "Foo")]
+struct Foo;
+
+fn main() {}
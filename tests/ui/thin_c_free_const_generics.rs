@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin(c_free)]
+trait Foo<const N: usize>: 'static {
+    fn take(&self, value: u32);
+}
+
+fn main() {}
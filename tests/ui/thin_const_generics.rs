@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    fn take<const N: usize>(&self, value: u32);
+}
+
+fn main() {}
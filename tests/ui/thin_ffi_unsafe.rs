@@ -0,0 +1,13 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    fn name(&self) -> String;
+}
+
+#[thin]
+trait Bar: 'static {
+    fn set(&mut self, values: &[u8]);
+}
+
+fn main() {}
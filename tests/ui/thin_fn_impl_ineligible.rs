@@ -0,0 +1,9 @@
+use thin_trait_objects::prelude::*;
+
+#[thin(fn_impl)]
+trait Foo: 'static {
+    fn foo(&self);
+    fn bar(&self);
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+use thin_trait_objects::prelude::*;
+
+// a borrowed thin object can't be swapped for another, which the borrow would then free
+#[thin]
+trait Foo: 'static {
+    fn add(&mut self, n: u8);
+}
+
+impl Foo for u8 {
+    fn add(&mut self, n: u8) {
+        *self += n;
+    }
+}
+
+fn main() {
+    let mut owned = Thin::<dyn Foo>::new(1u8);
+    let mut borrow = owned.as_mut();
+    let _ = std::mem::replace(&mut *borrow, Thin::<dyn Foo>::new(2u8));
+}
@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    fn new() -> u32;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    const ID: u32;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    fn sum(&self, (a, b): (u32, u32)) -> u32;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo<T>: 'static {
+    fn take(&self, value: u32);
+}
+
+fn main() {}
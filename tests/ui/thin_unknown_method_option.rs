@@ -0,0 +1,9 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    #[thin(cold)]
+    fn foo(&self);
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin(fast)]
+trait Foo: 'static {
+    fn foo(&self);
+}
+
+fn main() {}
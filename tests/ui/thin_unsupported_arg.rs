@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    fn sum(&self, values: [u32; 4]) -> u32;
+}
+
+fn main() {}
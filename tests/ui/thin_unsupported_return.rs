@@ -0,0 +1,8 @@
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Foo: 'static {
+    fn bytes(&self) -> [u8; 4];
+}
+
+fn main() {}
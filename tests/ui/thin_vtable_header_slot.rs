@@ -0,0 +1,14 @@
+use thin_trait_objects::prelude::*;
+
+trait Foo<T: 'static>: 'static {
+    fn foo(&self) -> T;
+}
+
+thin_vtable! {
+    struct FooVTable<T: 'static> for dyn Foo<T> {
+        header: usize,
+        foo: extern "C" fn(RefSelf<'_>) -> T,
+    }
+}
+
+fn main() {}
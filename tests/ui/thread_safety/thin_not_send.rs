@@ -0,0 +1,16 @@
+use std::rc::Rc;
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Local: 'static {
+    fn get(&self) -> u32;
+}
+
+impl Local for Rc<u32> {
+    fn get(&self) -> u32 { **self }
+}
+
+fn main() {
+    let local = Thin::<dyn Local>::new(Rc::new(1u32));
+    std::thread::spawn(move || local.get());
+}
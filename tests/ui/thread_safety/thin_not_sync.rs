@@ -0,0 +1,19 @@
+use std::cell::Cell;
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Counter: Send + 'static {
+    fn incr(&self) -> u32;
+}
+
+impl Counter for Cell<u32> {
+    fn incr(&self) -> u32 { self.replace(self.get() + 1) }
+}
+
+fn main() {
+    let counter = Thin::<dyn Counter>::new(Cell::new(0u32));
+    std::thread::scope(|s| {
+        s.spawn(|| counter.incr());
+        s.spawn(|| counter.incr());
+    });
+}
@@ -0,0 +1,16 @@
+use std::rc::Rc;
+use thin_trait_objects::prelude::*;
+
+#[thin]
+trait Shared: Send + 'static {
+    fn get(&self) -> u32;
+}
+
+impl Shared for Rc<u32> {
+    fn get(&self) -> u32 { **self }
+}
+
+fn main() {
+    let shared = Thin::<dyn Shared>::new(Rc::new(1u32));
+    std::thread::spawn(move || shared.get());
+}
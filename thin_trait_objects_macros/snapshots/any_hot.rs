@@ -0,0 +1,304 @@
+trait Voice: 'static {
+    fn note_on(&mut self, note: u8);
+    fn sample(&mut self) -> f32;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        sample: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>) -> f32,
+        note_on: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>, u8),
+        type_id: ::core::any::TypeId,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::offset_of!(__VTable, type_id)
+    }
+    extern "C-unwind" fn __drop<__T: Voice>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Voice");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Voice + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Voice");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn sample<__T: Voice>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+    ) -> f32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Voice", "sample");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Voice", "sample", move || -> f32 { __T::sample(recv) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn note_on<__T: Voice>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+        note: u8,
+    ) {
+        ::thin_trait_objects::__private::count_call::<__T>("Voice", "note_on");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Voice", "note_on", move || { __T::note_on(recv, note) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Voice {
+        type Kind = ::thin_trait_objects::prelude::Own;
+        fn same_type(a: ::core::ptr::NonNull<()>, b: ::core::ptr::NonNull<()>) -> bool {
+            <dyn Voice as ::thin_trait_objects::prelude::ThinAny>::erased_type_id(a)
+                == <dyn Voice as ::thin_trait_objects::prelude::ThinAny>::erased_type_id(
+                    b,
+                )
+        }
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Voice {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    impl ::thin_trait_objects::__private::ExpandedAny for dyn Voice {
+        fn erased_type_id(ptr: ::core::ptr::NonNull<()>) -> ::core::any::TypeId {
+            let offset = ::core::mem::offset_of!(__VTable, type_id);
+            unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable,
+                    _,
+                >(
+                    ptr,
+                    __slots_size(),
+                    offset,
+                    |vtable| &vtable.type_id,
+                    "Voice",
+                    "type_id",
+                )
+            }
+        }
+    }
+    fn __vtable<__K: Voice>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                sample: sample::<__K>,
+                note_on: note_on::<__K>,
+                type_id: ::core::any::TypeId::of::<__K>(),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Voice + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                sample: sample::<__K>,
+                note_on: note_on::<__K>,
+                type_id: ::core::any::TypeId::of::<__K>(),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Voice> ::thin_trait_objects::__private::ExpandedVTable<__K> for dyn Voice {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Voice> ::thin_trait_objects::prelude::ThinExt<dyn Voice, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Voice> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Voice");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Voice + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Voice, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Voice> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Voice> {
+            ::thin_trait_objects::__private::count_new::<__K>("Voice");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Voice,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Voice + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Voice> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Voice,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Voice, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Voice + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Voice, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Voice> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Voice> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Voice for ::thin_trait_objects::prelude::Thin<dyn Voice> {
+        #[inline]
+        fn sample(&mut self) -> f32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, sample);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.sample,
+                        "Voice",
+                        "sample",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv)
+        }
+        #[inline]
+        fn note_on(&mut self, note: u8) {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, note_on);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.note_on,
+                        "Voice",
+                        "note_on",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv, note)
+        }
+    }
+    impl Voice for ::thin_trait_objects::prelude::ThinMut<dyn Voice>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Voice>: 'static,
+    {
+        #[inline]
+        fn sample(&mut self) -> f32 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Voice,
+            > as Voice>::sample(unsafe {
+                ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self)
+            })
+        }
+        #[inline]
+        fn note_on(&mut self, note: u8) {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Voice,
+            > as Voice>::note_on(
+                unsafe { ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self) },
+                note,
+            )
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Voice >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Voice >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 2usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Voice {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "sample", "note_on"],
+            false => &["drop", "size", "sample", "note_on"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Voice {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Voice",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "sample",
+                    mut_receiver: true,
+                    args: &[],
+                    ret: "f32",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "note_on",
+                    mut_receiver: true,
+                    args: &[("note", "u8")],
+                    ret: "()",
+                },
+            ],
+            any: true,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_SAMPLE: usize = ::core::mem::offset_of!(__VTable, sample)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_NOTE_ON: usize = ::core::mem::offset_of!(__VTable, note_on)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Voice + ?Sized> Voice for ::std::boxed::Box<__K> {
+    fn sample(&mut self) -> f32 {
+        __K::sample(&mut **self)
+    }
+    fn note_on(&mut self, note: u8) {
+        __K::note_on(&mut **self, note)
+    }
+}
@@ -0,0 +1,302 @@
+pub trait Counter: 'static {
+    fn get(&self) -> u32;
+    fn add(&mut self, n: u32, wrap: bool);
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        get: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>) -> u32,
+        add: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>, u32, bool),
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Counter>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Counter");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Counter + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Counter");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn get<__T: Counter>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Counter", "get");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Counter", "get", move || -> u32 { __T::get(recv) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn add<__T: Counter>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+        n: u32,
+        wrap: bool,
+    ) {
+        ::thin_trait_objects::__private::count_call::<__T>("Counter", "add");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Counter", "add", move || { __T::add(recv, n, wrap) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Counter {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Counter {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Counter>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                get: get::<__K>,
+                add: add::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Counter + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                get: get::<__K>,
+                add: add::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Counter> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Counter {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Counter> ::thin_trait_objects::prelude::ThinExt<dyn Counter, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Counter> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Counter");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Counter + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Counter, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Counter> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Counter> {
+            ::thin_trait_objects::__private::count_new::<__K>("Counter");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Counter,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Counter>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Counter>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                get: get::<__K>,
+                add: add::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Counter> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Counter {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Counter + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Counter> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Counter,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Counter,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        __K: Counter + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Counter, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Counter> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Counter> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Counter for ::thin_trait_objects::prelude::Thin<dyn Counter> {
+        #[inline]
+        fn get(&self) -> u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, get);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.get,
+                        "Counter",
+                        "get",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+        #[inline]
+        fn add(&mut self, n: u32, wrap: bool) {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, add);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.add,
+                        "Counter",
+                        "add",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv, n, wrap)
+        }
+    }
+    impl Counter for ::thin_trait_objects::prelude::ThinMut<dyn Counter>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Counter>: 'static,
+    {
+        #[inline]
+        fn get(&self) -> u32 {
+            <::thin_trait_objects::prelude::Thin<dyn Counter> as Counter>::get(&**self)
+        }
+        #[inline]
+        fn add(&mut self, n: u32, wrap: bool) {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Counter,
+            > as Counter>::add(
+                unsafe { ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self) },
+                n,
+                wrap,
+            )
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Counter >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Counter >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 2usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Counter {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "get", "add"],
+            false => &["drop", "size", "get", "add"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Counter {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        pub const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Counter",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "get",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "u32",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "add",
+                    mut_receiver: true,
+                    args: &[("n", "u32"), ("wrap", "bool")],
+                    ret: "()",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        pub const SLOT_GET: usize = ::core::mem::offset_of!(__VTable, get)
+            / ::core::mem::size_of::<*const ()>();
+        pub const SLOT_ADD: usize = ::core::mem::offset_of!(__VTable, add)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Counter + ?Sized> Counter for ::std::boxed::Box<__K> {
+    fn get(&self) -> u32 {
+        __K::get(&**self)
+    }
+    fn add(&mut self, n: u32, wrap: bool) {
+        __K::add(&mut **self, n, wrap)
+    }
+}
@@ -0,0 +1,377 @@
+trait Failure: ::std::error::Error + Send + 'static {
+    fn code(&self) -> u32;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        code: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>) -> u32,
+        fmt_display: extern "C-unwind" fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            &mut ::core::fmt::Formatter<'_>,
+        ) -> bool,
+        fmt_debug: extern "C-unwind" fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            &mut ::core::fmt::Formatter<'_>,
+        ) -> bool,
+        error_source: for<'a> extern "C-unwind" fn(
+            ::thin_trait_objects::prelude::RefSelf<'a>,
+        ) -> ::core::option::Option<&'a (dyn ::core::error::Error + 'static)>,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::offset_of!(__VTable, fmt_display)
+    }
+    extern "C-unwind" fn __fmt_display<__T: Failure>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> bool {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::core::fmt::Display::fmt(value, f).is_err()
+    }
+    impl ::thin_trait_objects::prelude::DisplaySlot for dyn Failure {
+        fn fmt_display(
+            thin: &::thin_trait_objects::prelude::Thin<Self>,
+            f: &mut ::core::fmt::Formatter<'_>,
+        ) -> ::core::fmt::Result {
+            let offset = ::core::mem::offset_of!(__VTable, fmt_display);
+            let fmt_display = unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable,
+                    _,
+                >(
+                    thin.ptr,
+                    __slots_size(),
+                    offset,
+                    |vtable| &vtable.fmt_display,
+                    "Failure",
+                    "fmt",
+                )
+            };
+            match fmt_display(::thin_trait_objects::prelude::RefSelf::new(thin), f) {
+                false => ::core::result::Result::Ok(()),
+                true => ::core::result::Result::Err(::core::fmt::Error),
+            }
+        }
+    }
+    extern "C-unwind" fn __fmt_debug<__T: Failure>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        f: &mut ::core::fmt::Formatter<'_>,
+    ) -> bool {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::core::fmt::Debug::fmt(value, f).is_err()
+    }
+    impl ::thin_trait_objects::prelude::DebugSlot for dyn Failure {
+        fn fmt_debug(
+            thin: &::thin_trait_objects::prelude::Thin<Self>,
+            f: &mut ::core::fmt::Formatter<'_>,
+        ) -> ::core::fmt::Result {
+            let offset = ::core::mem::offset_of!(__VTable, fmt_debug);
+            let fmt_debug = unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable,
+                    _,
+                >(
+                    thin.ptr,
+                    __slots_size(),
+                    offset,
+                    |vtable| &vtable.fmt_debug,
+                    "Failure",
+                    "fmt",
+                )
+            };
+            match fmt_debug(::thin_trait_objects::prelude::RefSelf::new(thin), f) {
+                false => ::core::result::Result::Ok(()),
+                true => ::core::result::Result::Err(::core::fmt::Error),
+            }
+        }
+    }
+    #[allow(improper_ctypes_definitions)]
+    extern "C-unwind" fn __error_source<'a, __T: Failure>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'a>,
+    ) -> ::core::option::Option<&'a (dyn ::core::error::Error + 'static)> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::core::error::Error::source(value)
+    }
+    impl ::thin_trait_objects::prelude::ErrorSlot for dyn Failure {
+        fn error_source(
+            thin: &::thin_trait_objects::prelude::Thin<Self>,
+        ) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+            let offset = ::core::mem::offset_of!(__VTable, error_source);
+            let error_source = unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable,
+                    _,
+                >(
+                    thin.ptr,
+                    __slots_size(),
+                    offset,
+                    |vtable| &vtable.error_source,
+                    "Failure",
+                    "source",
+                )
+            };
+            error_source(::thin_trait_objects::prelude::RefSelf::new(thin))
+        }
+    }
+    extern "C-unwind" fn __drop<__T: Failure>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Failure");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Failure + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Failure");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn code<__T: Failure>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Failure", "code");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Failure", "code", move || -> u32 { __T::code(recv) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Failure {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Failure {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Failure>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                code: code::<__K>,
+                fmt_display: __fmt_display::<__K>,
+                fmt_debug: __fmt_debug::<__K>,
+                error_source: __error_source::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Failure + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                code: code::<__K>,
+                fmt_display: __fmt_display::<__K>,
+                fmt_debug: __fmt_debug::<__K>,
+                error_source: __error_source::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Failure> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Failure {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Failure> ::thin_trait_objects::prelude::ThinExt<dyn Failure, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Failure> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Failure");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Failure + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Failure, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Failure> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Failure> {
+            ::thin_trait_objects::__private::count_new::<__K>("Failure");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Failure,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Failure>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Failure>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                code: code::<__K>,
+                fmt_display: __fmt_display::<__K>,
+                fmt_debug: __fmt_debug::<__K>,
+                error_source: __error_source::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Failure> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Failure {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Failure + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Failure> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Failure,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Failure,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        __K: Failure + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Failure, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Failure> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Failure> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Failure for ::thin_trait_objects::prelude::Thin<dyn Failure> {
+        #[inline]
+        fn code(&self) -> u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, code);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.code,
+                        "Failure",
+                        "code",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+    }
+    impl Failure for ::thin_trait_objects::prelude::ThinMut<dyn Failure>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<
+            dyn Failure,
+        >: ::std::error::Error + Send + 'static,
+    {
+        #[inline]
+        fn code(&self) -> u32 {
+            <::thin_trait_objects::prelude::Thin<dyn Failure> as Failure>::code(&**self)
+        }
+    }
+    impl ::thin_trait_objects::prelude::ThinSend for dyn Failure {}
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Failure >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Failure >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Failure {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "code"],
+            false => &["drop", "size", "code"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Failure {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Failure",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "code",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "u32",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_CODE: usize = ::core::mem::offset_of!(__VTable, code)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Failure + ?Sized> Failure for ::std::boxed::Box<__K>
+where
+    ::std::boxed::Box<__K>: ::std::error::Error + Send + 'static,
+{
+    fn code(&self) -> u32 {
+        __K::code(&**self)
+    }
+}
+impl<__K: Failure + ?Sized> Failure for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: ::std::error::Error + Send + 'static,
+{
+    fn code(&self) -> u32 {
+        __K::code(&**self)
+    }
+}
+impl<__K: Failure + ?Sized> Failure for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: ::std::error::Error + Send + 'static,
+{
+    fn code(&self) -> u32 {
+        __K::code(&**self)
+    }
+}
@@ -0,0 +1,270 @@
+trait AudioSink: 'static {
+    fn write(&mut self, sample: f32);
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        write: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>, f32),
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: AudioSink>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("AudioSink");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: AudioSink + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("AudioSink");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn write<__T: AudioSink>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+        sample: f32,
+    ) {
+        ::thin_trait_objects::__private::count_call::<__T>("AudioSink", "write");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("AudioSink", "write", move || { __T::write(recv, sample) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn AudioSink {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn AudioSink {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: AudioSink>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                write: write::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: AudioSink + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                write: write::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: AudioSink> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn AudioSink {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: AudioSink> ::thin_trait_objects::prelude::ThinExt<dyn AudioSink, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn AudioSink> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("AudioSink");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: AudioSink + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn AudioSink, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn AudioSink> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn AudioSink> {
+            ::thin_trait_objects::__private::count_new::<__K>("AudioSink");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn AudioSink,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: AudioSink>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: AudioSink>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                write: write::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: AudioSink> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn AudioSink {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: AudioSink + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn AudioSink> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn AudioSink,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn AudioSink,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        __K: AudioSink + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn AudioSink, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn AudioSink> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn AudioSink> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl AudioSink for ::thin_trait_objects::prelude::Thin<dyn AudioSink> {
+        #[inline]
+        fn write(&mut self, sample: f32) {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, write);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.write,
+                        "AudioSink",
+                        "write",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv, sample)
+        }
+    }
+    impl AudioSink for ::thin_trait_objects::prelude::ThinMut<dyn AudioSink>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn AudioSink>: 'static,
+    {
+        #[inline]
+        fn write(&mut self, sample: f32) {
+            <::thin_trait_objects::prelude::Thin<
+                dyn AudioSink,
+            > as AudioSink>::write(
+                unsafe { ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self) },
+                sample,
+            )
+        }
+    }
+    struct __FnAdapter<__F>(__F);
+    impl<__F: ::core::ops::FnMut(f32) + 'static> AudioSink for __FnAdapter<__F> {
+        fn write(&mut self, sample: f32) {
+            (self.0)(sample)
+        }
+    }
+    impl<
+        __F: ::core::ops::FnMut(f32) + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn AudioSink, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn AudioSink> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn AudioSink,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn AudioSink,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    #[unsafe(no_mangle)]
+    unsafe extern "C" fn audio_sink_object_free(ptr: *mut ::core::ffi::c_void) {
+        if !ptr.is_null() {
+            ::core::mem::drop(unsafe {
+                ::thin_trait_objects::prelude::Thin::<dyn AudioSink>::from_c_void(ptr)
+            });
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn AudioSink >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn AudioSink >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn AudioSink {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "write"],
+            false => &["drop", "size", "write"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn AudioSink {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "AudioSink",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "write",
+                    mut_receiver: true,
+                    args: &[("sample", "f32")],
+                    ret: "()",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::Some("audio_sink_object_free"),
+        };
+        const SLOT_WRITE: usize = ::core::mem::offset_of!(__VTable, write)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: AudioSink + ?Sized> AudioSink for ::std::boxed::Box<__K> {
+    fn write(&mut self, sample: f32) {
+        __K::write(&mut **self, sample)
+    }
+}
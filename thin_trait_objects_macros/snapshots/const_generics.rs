@@ -0,0 +1,299 @@
+trait Buffered<const N: usize>: 'static {
+    fn fill(&mut self, byte: u8);
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable<const N: usize> {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        fill: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>, u8),
+        type_id: ::core::any::TypeId,
+    }
+    const fn __slots_size<const N: usize>() -> usize {
+        ::core::mem::offset_of!(__VTable < N >, type_id)
+    }
+    extern "C-unwind" fn __drop<const N: usize, __T: Buffered<N>>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Buffered");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_drop::<__VTable<N>, __T>(ptr)
+        }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        const N: usize,
+        __T: Buffered<N> + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Buffered");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<
+                __VTable<N>,
+                __T,
+            >(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn fill<const N: usize, __T: Buffered<N>>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+        byte: u8,
+    ) {
+        ::thin_trait_objects::__private::count_call::<__T>("Buffered", "fill");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable<N>, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Buffered", "fill", move || { __T::fill(recv, byte) })
+    }
+    impl<const N: usize> ::thin_trait_objects::prelude::SpecialAssoc
+    for dyn Buffered<N> {
+        type Kind = ::thin_trait_objects::prelude::Own;
+        fn same_type(a: ::core::ptr::NonNull<()>, b: ::core::ptr::NonNull<()>) -> bool {
+            <dyn Buffered<
+                N,
+            > as ::thin_trait_objects::prelude::ThinAny>::erased_type_id(a)
+                == <dyn Buffered<
+                    N,
+                > as ::thin_trait_objects::prelude::ThinAny>::erased_type_id(b)
+        }
+    }
+    impl<const N: usize> ::thin_trait_objects::__private::Expanded for dyn Buffered<N> {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    impl<const N: usize> ::thin_trait_objects::__private::ExpandedAny
+    for dyn Buffered<N> {
+        fn erased_type_id(ptr: ::core::ptr::NonNull<()>) -> ::core::any::TypeId {
+            let offset = ::core::mem::offset_of!(__VTable < N >, type_id);
+            unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable<N>,
+                    _,
+                >(
+                    ptr,
+                    __slots_size::<N>(),
+                    offset,
+                    |vtable| &vtable.type_id,
+                    "Buffered",
+                    "type_id",
+                )
+            }
+        }
+    }
+    fn __vtable<const N: usize, __K: Buffered<N>>() -> &'static __VTable<N> {
+        const {
+            use ::core::mem::size_of;
+            ::core::assert!(
+                size_of:: < ::thin_trait_objects::prelude::Thin < dyn Buffered < N > >>
+                () == size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+            );
+            ::core::assert!(
+                size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin
+                < dyn Buffered < N > >> > () == size_of:: < * mut () > (),
+                "`Option<Thin<dyn _>>` must be pointer-sized",
+            );
+            ::core::assert!(
+                __slots_size:: < N > () == (2 + 1usize) * size_of:: < extern "C" fn () >
+                () + size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+                "every vtable slot must be a single pointer-sized word",
+            );
+        };
+        let vtable = const {
+            &__VTable::<N> {
+                drop: __drop::<N, __K>,
+                size: __slots_size::<N>(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                fill: fill::<N, __K>,
+                type_id: ::core::any::TypeId::of::<__K>(),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<
+        const N: usize,
+        __K: Buffered<N> + ::thin_trait_objects::prelude::PinnedDrop,
+    >() -> &'static __VTable<N> {
+        let vtable = const {
+            &__VTable::<N> {
+                drop: __pinned_drop::<N, __K>,
+                size: __slots_size::<N>(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                fill: fill::<N, __K>,
+                type_id: ::core::any::TypeId::of::<__K>(),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<
+        const N: usize,
+        __K: Buffered<N>,
+    > ::thin_trait_objects::__private::ExpandedVTable<__K> for dyn Buffered<N> {
+        fn vtable() -> *const () {
+            __vtable::<N, __K>() as *const __VTable<N> as *const ()
+        }
+    }
+    impl<
+        const N: usize,
+        __K: Buffered<N>,
+    > ::thin_trait_objects::prelude::ThinExt<dyn Buffered<N>, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Buffered<N>> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Buffered");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(
+                    __vtable::<N, __K>(),
+                    value,
+                )
+            }
+        }
+    }
+    impl<
+        const N: usize,
+        __K: Buffered<N> + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Buffered<N>, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Buffered<N>> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Buffered<N>> {
+            ::thin_trait_objects::__private::count_new::<__K>("Buffered");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Buffered<N>,
+                    _,
+                    _,
+                >(__pinned_vtable::<N, __K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C" fn __clone_bundle<
+        const N: usize,
+        __T: Buffered<N> + ::core::clone::Clone,
+    >(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Buffered<N>> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable<N>, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Buffered<N>,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Buffered<N>,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        const N: usize,
+        __K: Buffered<N> + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Buffered<N>, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Buffered<N>> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Buffered<N>> {
+            __clone_bundle::<N, __K>
+        }
+    }
+    impl<const N: usize> Buffered<N>
+    for ::thin_trait_objects::prelude::Thin<dyn Buffered<N>> {
+        #[inline]
+        fn fill(&mut self, byte: u8) {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable<N>>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable < N >, fill);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.fill,
+                        "Buffered",
+                        "fill",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv, byte)
+        }
+    }
+    impl<const N: usize> Buffered<N>
+    for ::thin_trait_objects::prelude::ThinMut<dyn Buffered<N>>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Buffered<N>>: 'static,
+    {
+        #[inline]
+        fn fill(&mut self, byte: u8) {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Buffered<N>,
+            > as Buffered<
+                N,
+            >>::fill(
+                unsafe { ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self) },
+                byte,
+            )
+        }
+    }
+    struct __FnAdapter<__F>(__F);
+    impl<const N: usize, __F: ::core::ops::FnMut(u8) + 'static> Buffered<N>
+    for __FnAdapter<__F> {
+        fn fill(&mut self, byte: u8) {
+            (self.0)(byte)
+        }
+    }
+    impl<
+        const N: usize,
+        __F: ::core::ops::FnMut(u8) + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn Buffered<N>, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn Buffered<N>> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Buffered<N>,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn Buffered<N>,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    impl<const N: usize> ::thin_trait_objects::prelude::SlotLayout for dyn Buffered<N> {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "fill"],
+            false => &["drop", "size", "fill"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable<N>;
+    }
+    #[allow(dead_code)]
+    impl<const N: usize> dyn Buffered<N> {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Buffered",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "fill",
+                    mut_receiver: true,
+                    args: &[("byte", "u8")],
+                    ret: "()",
+                },
+            ],
+            any: true,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_FILL: usize = ::core::mem::offset_of!(__VTable < N >, fill)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<const N: usize, __K: Buffered<N> + ?Sized> Buffered<N> for ::std::boxed::Box<__K> {
+    fn fill(&mut self, byte: u8) {
+        __K::fill(&mut **self, byte)
+    }
+}
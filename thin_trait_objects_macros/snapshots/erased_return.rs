@@ -0,0 +1,321 @@
+trait Chunks: 'static {
+    fn chunks(&self, size: usize) -> impl Iterator<Item = u8> + 'static
+    where
+        Self: ::core::marker::Sized;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        chunks: extern "C" fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            usize,
+        ) -> ::thin_trait_objects::prelude::Thin<dyn Iterator<Item = u8>>,
+        rust: ::core::option::Option<&'static __RustEntries>,
+    }
+    #[repr(C)]
+    struct __RustEntries {
+        origin: *const u8,
+        chunks: fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            usize,
+        ) -> ::thin_trait_objects::prelude::Thin<dyn Iterator<Item = u8>>,
+    }
+    static __ORIGIN: u8 = 0;
+    fn __rust_abi_chunks<__T: Chunks>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        size: usize,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Iterator<Item = u8>> {
+        ::thin_trait_objects::__private::count_call::<__T>("Chunks", "chunks");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Iterator<Item = u8>,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Iterator<Item = u8>,
+            _,
+        >>::new(__T::chunks(recv, size))
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::offset_of!(__VTable, rust)
+    }
+    extern "C-unwind" fn __drop<__T: Chunks>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Chunks");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Chunks + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Chunks");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn chunks<__T: Chunks>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        size: usize,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Iterator<Item = u8>> {
+        ::thin_trait_objects::__private::count_call::<__T>("Chunks", "chunks");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >(
+            "Chunks",
+            "chunks",
+            move || -> ::thin_trait_objects::prelude::Thin<dyn Iterator<Item = u8>> {
+                <::thin_trait_objects::prelude::Thin<
+                    dyn Iterator<Item = u8>,
+                > as ::thin_trait_objects::prelude::ThinExt<
+                    dyn Iterator<Item = u8>,
+                    _,
+                >>::new(__T::chunks(recv, size))
+            },
+        )
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Chunks {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Chunks {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Chunks>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                chunks: chunks::<__K>,
+                rust: ::core::option::Option::Some(
+                    &__RustEntries {
+                        origin: &raw const __ORIGIN,
+                        chunks: __rust_abi_chunks::<__K>,
+                    },
+                ),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Chunks + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                chunks: chunks::<__K>,
+                rust: ::core::option::Option::Some(
+                    &__RustEntries {
+                        origin: &raw const __ORIGIN,
+                        chunks: __rust_abi_chunks::<__K>,
+                    },
+                ),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Chunks> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Chunks {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Chunks> ::thin_trait_objects::prelude::ThinExt<dyn Chunks, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Chunks> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Chunks");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Chunks + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Chunks, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Chunks> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Chunks> {
+            ::thin_trait_objects::__private::count_new::<__K>("Chunks");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Chunks,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Chunks>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Chunks>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                chunks: chunks::<__K>,
+                rust: ::core::option::Option::Some(
+                    &__RustEntries {
+                        origin: &raw const __ORIGIN,
+                        chunks: __rust_abi_chunks::<__K>,
+                    },
+                ),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Chunks> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Chunks {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Chunks + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Chunks> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Chunks,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Chunks, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Chunks + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Chunks, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Chunks> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Chunks> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Chunks for ::thin_trait_objects::prelude::Thin<dyn Chunks> {
+        #[inline]
+        fn chunks(&self, size: usize) -> impl Iterator<Item = u8> + 'static {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                if let ::core::option::Option::Some(whole) = unsafe {
+                    ::thin_trait_objects::__private::expanded_whole_vtable(
+                        vtable,
+                        size,
+                        __slots_size(),
+                    )
+                } {
+                    if let ::core::option::Option::Some(rust) = whole
+                        .rust
+                        .filter(|rust| rust.origin == &raw const __ORIGIN)
+                    {
+                        let entry = rust.chunks;
+                        let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+                        return entry(recv, size);
+                    }
+                }
+                let offset = ::core::mem::offset_of!(__VTable, chunks);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.chunks,
+                        "Chunks",
+                        "chunks",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv, size)
+        }
+    }
+    impl Chunks for ::thin_trait_objects::prelude::ThinMut<dyn Chunks>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Chunks>: 'static,
+    {}
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Chunks >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Chunks >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Chunks {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "chunks"],
+            false => &["drop", "size", "chunks"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Chunks {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Chunks",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "chunks",
+                    mut_receiver: false,
+                    args: &[("size", "usize")],
+                    ret: "Thin < dyn Iterator < Item = u8 > >",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: true,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_CHUNKS: usize = ::core::mem::offset_of!(__VTable, chunks)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Chunks> Chunks for ::std::boxed::Box<__K> {
+    fn chunks(&self, size: usize) -> impl Iterator<Item = u8> + 'static {
+        __K::chunks(&**self, size)
+    }
+}
+impl<__K: Chunks> Chunks for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: 'static,
+{
+    fn chunks(&self, size: usize) -> impl Iterator<Item = u8> + 'static {
+        __K::chunks(&**self, size)
+    }
+}
+impl<__K: Chunks> Chunks for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: 'static,
+{
+    fn chunks(&self, size: usize) -> impl Iterator<Item = u8> + 'static {
+        __K::chunks(&**self, size)
+    }
+}
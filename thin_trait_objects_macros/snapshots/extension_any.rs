@@ -0,0 +1,316 @@
+trait Widget: 'static {
+    fn draw(&self) -> u32;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        draw: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>) -> u32,
+        extension: ::core::option::Option<
+            &'static ::thin_trait_objects::prelude::Extension,
+        >,
+        type_id: ::core::any::TypeId,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::offset_of!(__VTable, extension)
+    }
+    extern "C-unwind" fn __drop<__T: Widget>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Widget");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Widget + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Widget");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn draw<__T: Widget>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Widget", "draw");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Widget", "draw", move || -> u32 { __T::draw(recv) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Widget {
+        type Kind = ::thin_trait_objects::prelude::Own;
+        fn same_type(a: ::core::ptr::NonNull<()>, b: ::core::ptr::NonNull<()>) -> bool {
+            <dyn Widget as ::thin_trait_objects::prelude::ThinAny>::erased_type_id(a)
+                == <dyn Widget as ::thin_trait_objects::prelude::ThinAny>::erased_type_id(
+                    b,
+                )
+        }
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Widget {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    impl ::thin_trait_objects::__private::ExpandedAny for dyn Widget {
+        fn erased_type_id(ptr: ::core::ptr::NonNull<()>) -> ::core::any::TypeId {
+            let offset = ::core::mem::offset_of!(__VTable, type_id);
+            unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable,
+                    _,
+                >(
+                    ptr,
+                    __slots_size(),
+                    offset,
+                    |vtable| &vtable.type_id,
+                    "Widget",
+                    "type_id",
+                )
+            }
+        }
+    }
+    impl ::thin_trait_objects::__private::ExpandedExtensionSlot for dyn Widget {
+        fn erased_extension(
+            ptr: ::core::ptr::NonNull<()>,
+        ) -> ::core::option::Option<&'static ::thin_trait_objects::prelude::Extension> {
+            let offset = ::core::mem::offset_of!(__VTable, extension);
+            unsafe {
+                ::thin_trait_objects::__private::expanded_load_slot::<
+                    __VTable,
+                    _,
+                >(
+                    ptr,
+                    __slots_size(),
+                    offset,
+                    |vtable| &vtable.extension,
+                    "Widget",
+                    "extension",
+                )
+            }
+        }
+    }
+    fn __vtable<__K: Widget + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                draw: draw::<__K>,
+                extension: <__K as ::thin_trait_objects::prelude::ThinExtension<
+                    dyn Widget,
+                >>::EXTENSION,
+                type_id: ::core::any::TypeId::of::<__K>(),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<
+        __K: Widget + ::thin_trait_objects::prelude::PinnedDrop
+            + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>,
+    >() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                draw: draw::<__K>,
+                extension: <__K as ::thin_trait_objects::prelude::ThinExtension<
+                    dyn Widget,
+                >>::EXTENSION,
+                type_id: ::core::any::TypeId::of::<__K>(),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<
+        __K: Widget + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>,
+    > ::thin_trait_objects::__private::ExpandedVTable<__K> for dyn Widget {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<
+        __K: Widget + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>,
+    > ::thin_trait_objects::prelude::ThinExt<dyn Widget, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Widget> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Widget");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Widget + ::thin_trait_objects::prelude::PinnedDrop
+            + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Widget, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Widget> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Widget> {
+            ::thin_trait_objects::__private::count_new::<__K>("Widget");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Widget,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C" fn __clone_bundle<
+        __T: Widget + ::core::clone::Clone
+            + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>,
+    >(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Widget> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Widget,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Widget, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Widget + ::core::clone::Clone
+            + ::thin_trait_objects::prelude::ThinExtension<dyn Widget>,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Widget, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Widget> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Widget> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Widget for ::thin_trait_objects::prelude::Thin<dyn Widget> {
+        #[inline]
+        fn draw(&self) -> u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, draw);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.draw,
+                        "Widget",
+                        "draw",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+    }
+    impl Widget for ::thin_trait_objects::prelude::ThinMut<dyn Widget>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Widget>: 'static,
+    {
+        #[inline]
+        fn draw(&self) -> u32 {
+            <::thin_trait_objects::prelude::Thin<dyn Widget> as Widget>::draw(&**self)
+        }
+    }
+    struct __FnAdapter<__F>(__F);
+    impl<__F> ::thin_trait_objects::prelude::ThinExtension<dyn Widget>
+    for __FnAdapter<__F> {}
+    impl<__F: ::core::ops::Fn() -> u32 + 'static> Widget for __FnAdapter<__F> {
+        fn draw(&self) -> u32 {
+            (self.0)()
+        }
+    }
+    impl<
+        __F: ::core::ops::Fn() -> u32 + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn Widget, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn Widget> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Widget,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn Widget,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Widget >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Widget >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Widget {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "draw"],
+            false => &["drop", "size", "draw"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Widget {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Widget",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "draw",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "u32",
+                },
+            ],
+            any: true,
+            extension: true,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_DRAW: usize = ::core::mem::offset_of!(__VTable, draw)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Widget + ?Sized> Widget for ::std::boxed::Box<__K> {
+    fn draw(&self) -> u32 {
+        __K::draw(&**self)
+    }
+}
+impl<__K: Widget + ?Sized> Widget for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: 'static,
+{
+    fn draw(&self) -> u32 {
+        __K::draw(&**self)
+    }
+}
+impl<__K: Widget + ?Sized> Widget for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: 'static,
+{
+    fn draw(&self) -> u32 {
+        __K::draw(&**self)
+    }
+}
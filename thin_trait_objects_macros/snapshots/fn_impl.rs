@@ -0,0 +1,264 @@
+trait Handler: Send + 'static {
+    fn handle(&mut self, event: u32) -> u64;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        handle: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>, u32) -> u64,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Handler>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Handler");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Handler + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Handler");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn handle<__T: Handler>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+        event: u32,
+    ) -> u64 {
+        ::thin_trait_objects::__private::count_call::<__T>("Handler", "handle");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Handler", "handle", move || -> u64 { __T::handle(recv, event) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Handler {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Handler {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Handler>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                handle: handle::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Handler + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                handle: handle::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Handler> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Handler {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Handler> ::thin_trait_objects::prelude::ThinExt<dyn Handler, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Handler> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Handler");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Handler + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Handler, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Handler> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Handler> {
+            ::thin_trait_objects::__private::count_new::<__K>("Handler");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Handler,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Handler>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Handler>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                handle: handle::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Handler> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Handler {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Handler + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Handler> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Handler,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Handler,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        __K: Handler + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Handler, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Handler> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Handler> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Handler for ::thin_trait_objects::prelude::Thin<dyn Handler> {
+        #[inline]
+        fn handle(&mut self, event: u32) -> u64 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, handle);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.handle,
+                        "Handler",
+                        "handle",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv, event)
+        }
+    }
+    impl Handler for ::thin_trait_objects::prelude::ThinMut<dyn Handler>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Handler>: Send + 'static,
+    {
+        #[inline]
+        fn handle(&mut self, event: u32) -> u64 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Handler,
+            > as Handler>::handle(
+                unsafe { ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self) },
+                event,
+            )
+        }
+    }
+    impl ::thin_trait_objects::prelude::ThinSend for dyn Handler {}
+    struct __FnAdapter<__F>(__F);
+    impl<__F: ::core::ops::FnMut(u32) -> u64 + Send + 'static> Handler
+    for __FnAdapter<__F> {
+        fn handle(&mut self, event: u32) -> u64 {
+            (self.0)(event)
+        }
+    }
+    impl<
+        __F: ::core::ops::FnMut(u32) -> u64 + Send + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn Handler, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn Handler> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Handler,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn Handler,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Handler >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Handler >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Handler {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "handle"],
+            false => &["drop", "size", "handle"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Handler {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Handler",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "handle",
+                    mut_receiver: true,
+                    args: &[("event", "u32")],
+                    ret: "u64",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_HANDLE: usize = ::core::mem::offset_of!(__VTable, handle)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__F: ::core::ops::FnMut(u32) -> u64 + Send + 'static> Handler for __F {
+    fn handle(&mut self, event: u32) -> u64 {
+        self(event)
+    }
+}
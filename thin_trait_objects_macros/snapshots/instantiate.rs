@@ -0,0 +1,339 @@
+trait Sink: 'static {
+    fn send<T: 'static>(&self, value: &T) -> usize
+    where
+        Self: ::core::marker::Sized;
+}
+const _: () = {
+    #[repr(C)]
+    #[allow(non_snake_case)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        send__u8: extern "C" fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            &'_ u8,
+        ) -> usize,
+        send__Vec_u8: extern "C" fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            &'_ Vec<u8>,
+        ) -> usize,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Sink>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Sink");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Sink + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Sink");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    #[allow(non_snake_case)]
+    extern "C" fn send__u8<__T: Sink>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        value: &'_ u8,
+    ) -> usize {
+        ::thin_trait_objects::__private::count_call::<__T>("Sink", "send__u8");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Sink", "send__u8", move || -> usize { __T::send::<u8>(recv, value) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    #[allow(non_snake_case)]
+    extern "C" fn send__Vec_u8<__T: Sink>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        value: &'_ Vec<u8>,
+    ) -> usize {
+        ::thin_trait_objects::__private::count_call::<__T>("Sink", "send__Vec_u8");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Sink", "send__Vec_u8", move || -> usize { __T::send::<Vec<u8>>(recv, value) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Sink {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Sink {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Sink>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                send__u8: send__u8::<__K>,
+                send__Vec_u8: send__Vec_u8::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Sink + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                send__u8: send__u8::<__K>,
+                send__Vec_u8: send__Vec_u8::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Sink> ::thin_trait_objects::__private::ExpandedVTable<__K> for dyn Sink {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Sink> ::thin_trait_objects::prelude::ThinExt<dyn Sink, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Sink> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Sink");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Sink + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Sink, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Sink> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Sink> {
+            ::thin_trait_objects::__private::count_new::<__K>("Sink");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Sink,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Sink>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Sink>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                send__u8: send__u8::<__K>,
+                send__Vec_u8: send__Vec_u8::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Sink> ::thin_trait_objects::__private::ExpandedBatched<__K> for dyn Sink {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Sink + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Sink> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Sink,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Sink, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Sink + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Sink, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Sink> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Sink> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Sink for ::thin_trait_objects::prelude::Thin<dyn Sink> {
+        #[inline]
+        fn send<T: 'static>(&self, value: &T) -> usize
+        where
+            Self: ::core::marker::Sized,
+        {
+            if ::core::any::TypeId::of::<T>() == ::core::any::TypeId::of::<u8>() {
+                let value = unsafe {
+                    ::thin_trait_objects::__private::expanded_cast(value)
+                };
+                let ret = {
+                    let vtable = unsafe {
+                        ::thin_trait_objects::__private::expanded_vtable::<
+                            __VTable,
+                        >(self.ptr)
+                    };
+                    let entry = {
+                        let size = unsafe {
+                            ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                        };
+                        let offset = ::core::mem::offset_of!(__VTable, send__u8);
+                        unsafe {
+                            ::thin_trait_objects::__private::expanded_slot(
+                                vtable,
+                                size,
+                                offset,
+                                |vtable| &vtable.send__u8,
+                                "Sink",
+                                "send__u8",
+                            )
+                        }
+                    };
+                    let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+                    entry(recv, value)
+                };
+                return unsafe { ::thin_trait_objects::__private::expanded_cast(ret) };
+            }
+            if ::core::any::TypeId::of::<T>() == ::core::any::TypeId::of::<Vec<u8>>() {
+                let value = unsafe {
+                    ::thin_trait_objects::__private::expanded_cast(value)
+                };
+                let ret = {
+                    let vtable = unsafe {
+                        ::thin_trait_objects::__private::expanded_vtable::<
+                            __VTable,
+                        >(self.ptr)
+                    };
+                    let entry = {
+                        let size = unsafe {
+                            ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                        };
+                        let offset = ::core::mem::offset_of!(__VTable, send__Vec_u8);
+                        unsafe {
+                            ::thin_trait_objects::__private::expanded_slot(
+                                vtable,
+                                size,
+                                offset,
+                                |vtable| &vtable.send__Vec_u8,
+                                "Sink",
+                                "send__Vec_u8",
+                            )
+                        }
+                    };
+                    let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+                    entry(recv, value)
+                };
+                return unsafe { ::thin_trait_objects::__private::expanded_cast(ret) };
+            }
+            ::thin_trait_objects::__private::missing_instantiation(
+                "Sink",
+                "send",
+                ::core::any::type_name::<T>(),
+            )
+        }
+    }
+    impl Sink for ::thin_trait_objects::prelude::ThinMut<dyn Sink>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Sink>: 'static,
+    {}
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Sink >> () == size_of::
+            < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Sink >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 2usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Sink {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "send__u8", "send__Vec_u8"],
+            false => &["drop", "size", "send__u8", "send__Vec_u8"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Sink {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Sink",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "send__u8",
+                    mut_receiver: false,
+                    args: &[("value", "& u8")],
+                    ret: "usize",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "send__Vec_u8",
+                    mut_receiver: false,
+                    args: &[("value", "& Vec < u8 >")],
+                    ret: "usize",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_SEND__U8: usize = ::core::mem::offset_of!(__VTable, send__u8)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_SEND__VEC_U8: usize = ::core::mem::offset_of!(__VTable, send__Vec_u8)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Sink> Sink for ::std::boxed::Box<__K> {
+    fn send<T: 'static>(&self, value: &T) -> usize
+    where
+        Self: ::core::marker::Sized,
+    {
+        __K::send(&**self, value)
+    }
+}
+impl<__K: Sink> Sink for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: 'static,
+{
+    fn send<T: 'static>(&self, value: &T) -> usize
+    where
+        Self: ::core::marker::Sized,
+    {
+        __K::send(&**self, value)
+    }
+}
+impl<__K: Sink> Sink for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: 'static,
+{
+    fn send<T: 'static>(&self, value: &T) -> usize
+    where
+        Self: ::core::marker::Sized,
+    {
+        __K::send(&**self, value)
+    }
+}
@@ -0,0 +1,458 @@
+trait Lookup: Send + 'static {
+    fn first(&self) -> &u32;
+    fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32;
+    fn nearest(&self, a: &u32, b: &u32) -> &u32;
+    fn fail(&self) -> !;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        first: for<'__self> extern "C" fn(
+            ::thin_trait_objects::prelude::RefSelf<'__self>,
+        ) -> &'__self u32,
+        pick: for<'a> extern "C" fn(
+            ::thin_trait_objects::prelude::RefSelf<'_>,
+            &'a u32,
+            &'a u32,
+        ) -> &'a u32,
+        nearest: for<'__self> extern "C" fn(
+            ::thin_trait_objects::prelude::RefSelf<'__self>,
+            &'_ u32,
+            &'_ u32,
+        ) -> &'__self u32,
+        fail: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>) -> !,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Lookup>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Lookup");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Lookup + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Lookup");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn first<'__self, __T: Lookup>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'__self>,
+    ) -> &'__self u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Lookup", "first");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Lookup", "first", move || -> &'__self u32 { __T::first(recv) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn pick<'a, __T: Lookup>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        a: &'a u32,
+        b: &'a u32,
+    ) -> &'a u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Lookup", "pick");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Lookup", "pick", move || -> &'a u32 { __T::pick(recv, a, b) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn nearest<'__self, __T: Lookup>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'__self>,
+        a: &'_ u32,
+        b: &'_ u32,
+    ) -> &'__self u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Lookup", "nearest");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Lookup", "nearest", move || -> &'__self u32 { __T::nearest(recv, a, b) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn fail<__T: Lookup>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ! {
+        ::thin_trait_objects::__private::count_call::<__T>("Lookup", "fail");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Lookup", "fail", move || -> ! { __T::fail(recv) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Lookup {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Lookup {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Lookup>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                first: first::<__K>,
+                pick: pick::<__K>,
+                nearest: nearest::<__K>,
+                fail: fail::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Lookup + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                first: first::<__K>,
+                pick: pick::<__K>,
+                nearest: nearest::<__K>,
+                fail: fail::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Lookup> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Lookup {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Lookup> ::thin_trait_objects::prelude::ThinExt<dyn Lookup, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Lookup> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Lookup");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Lookup + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Lookup, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Lookup> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Lookup> {
+            ::thin_trait_objects::__private::count_new::<__K>("Lookup");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Lookup,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Lookup>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Lookup>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                first: first::<__K>,
+                pick: pick::<__K>,
+                nearest: nearest::<__K>,
+                fail: fail::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Lookup> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Lookup {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Lookup + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Lookup> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Lookup,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Lookup, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Lookup + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Lookup, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Lookup> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Lookup> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Lookup for ::thin_trait_objects::prelude::Thin<dyn Lookup> {
+        #[inline]
+        fn first(&self) -> &u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, first);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.first,
+                        "Lookup",
+                        "first",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+        #[inline]
+        fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, pick);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.pick,
+                        "Lookup",
+                        "pick",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv, a, b)
+        }
+        #[inline]
+        fn nearest(&self, a: &u32, b: &u32) -> &u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, nearest);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.nearest,
+                        "Lookup",
+                        "nearest",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv, a, b)
+        }
+        #[inline]
+        fn fail(&self) -> ! {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, fail);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.fail,
+                        "Lookup",
+                        "fail",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+    }
+    impl Lookup for ::thin_trait_objects::prelude::ThinMut<dyn Lookup>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Lookup>: Send + 'static,
+    {
+        #[inline]
+        fn first(&self) -> &u32 {
+            <::thin_trait_objects::prelude::Thin<dyn Lookup> as Lookup>::first(&**self)
+        }
+        #[inline]
+        fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Lookup,
+            > as Lookup>::pick(&**self, a, b)
+        }
+        #[inline]
+        fn nearest(&self, a: &u32, b: &u32) -> &u32 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Lookup,
+            > as Lookup>::nearest(&**self, a, b)
+        }
+        #[inline]
+        fn fail(&self) -> ! {
+            <::thin_trait_objects::prelude::Thin<dyn Lookup> as Lookup>::fail(&**self)
+        }
+    }
+    impl ::thin_trait_objects::prelude::ThinSend for dyn Lookup {}
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Lookup >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Lookup >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 4usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Lookup {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "first", "pick", "nearest", "fail"],
+            false => &["drop", "size", "first", "pick", "nearest", "fail"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Lookup {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Lookup",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "first",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "& '__self u32",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "pick",
+                    mut_receiver: false,
+                    args: &[("a", "& 'a u32"), ("b", "& 'a u32")],
+                    ret: "& 'a u32",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "nearest",
+                    mut_receiver: false,
+                    args: &[("a", "& u32"), ("b", "& u32")],
+                    ret: "& '__self u32",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "fail",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "!",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_FIRST: usize = ::core::mem::offset_of!(__VTable, first)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_PICK: usize = ::core::mem::offset_of!(__VTable, pick)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_NEAREST: usize = ::core::mem::offset_of!(__VTable, nearest)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_FAIL: usize = ::core::mem::offset_of!(__VTable, fail)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Lookup + ?Sized> Lookup for ::std::boxed::Box<__K> {
+    fn first(&self) -> &u32 {
+        __K::first(&**self)
+    }
+    fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32 {
+        __K::pick(&**self, a, b)
+    }
+    fn nearest(&self, a: &u32, b: &u32) -> &u32 {
+        __K::nearest(&**self, a, b)
+    }
+    fn fail(&self) -> ! {
+        __K::fail(&**self)
+    }
+}
+impl<__K: Lookup + ?Sized> Lookup for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: Send + 'static,
+{
+    fn first(&self) -> &u32 {
+        __K::first(&**self)
+    }
+    fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32 {
+        __K::pick(&**self, a, b)
+    }
+    fn nearest(&self, a: &u32, b: &u32) -> &u32 {
+        __K::nearest(&**self, a, b)
+    }
+    fn fail(&self) -> ! {
+        __K::fail(&**self)
+    }
+}
+impl<__K: Lookup + ?Sized> Lookup for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: Send + 'static,
+{
+    fn first(&self) -> &u32 {
+        __K::first(&**self)
+    }
+    fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32 {
+        __K::pick(&**self, a, b)
+    }
+    fn nearest(&self, a: &u32, b: &u32) -> &u32 {
+        __K::nearest(&**self, a, b)
+    }
+    fn fail(&self) -> ! {
+        __K::fail(&**self)
+    }
+}
@@ -0,0 +1,273 @@
+trait Exported: 'static {
+    fn double(&self, x: u32) -> u32;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        double: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>, u32) -> u32,
+        rust: ::core::option::Option<&'static __RustEntries>,
+    }
+    #[repr(C)]
+    struct __RustEntries {
+        origin: *const u8,
+        double: fn(::thin_trait_objects::prelude::RefSelf<'_>, u32) -> u32,
+    }
+    static __ORIGIN: u8 = 0;
+    fn __rust_abi_double<__T: Exported>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        x: u32,
+    ) -> u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Exported", "double");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        __T::double(recv, x)
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::offset_of!(__VTable, rust)
+    }
+    extern "C-unwind" fn __drop<__T: Exported>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Exported");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Exported + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Exported");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn double<__T: Exported>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        x: u32,
+    ) -> u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Exported", "double");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Exported", "double", move || -> u32 { __T::double(recv, x) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Exported {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Exported {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Exported>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                double: double::<__K>,
+                rust: ::core::option::Option::Some(
+                    &__RustEntries {
+                        origin: &raw const __ORIGIN,
+                        double: __rust_abi_double::<__K>,
+                    },
+                ),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Exported + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                double: double::<__K>,
+                rust: ::core::option::Option::Some(
+                    &__RustEntries {
+                        origin: &raw const __ORIGIN,
+                        double: __rust_abi_double::<__K>,
+                    },
+                ),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Exported> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Exported {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Exported> ::thin_trait_objects::prelude::ThinExt<dyn Exported, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Exported> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Exported");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Exported + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Exported, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Exported> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Exported> {
+            ::thin_trait_objects::__private::count_new::<__K>("Exported");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Exported,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Exported>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Exported>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                double: double::<__K>,
+                rust: ::core::option::Option::Some(
+                    &__RustEntries {
+                        origin: &raw const __ORIGIN,
+                        double: __rust_abi_double::<__K>,
+                    },
+                ),
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Exported> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Exported {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Exported + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Exported> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Exported,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Exported,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        __K: Exported + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Exported, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Exported> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Exported> {
+            __clone_bundle::<__K>
+        }
+    }
+    struct __FnAdapter<__F>(__F);
+    impl<__F: ::core::ops::Fn(u32) -> u32 + 'static> Exported for __FnAdapter<__F> {
+        fn double(&self, x: u32) -> u32 {
+            (self.0)(x)
+        }
+    }
+    impl<
+        __F: ::core::ops::Fn(u32) -> u32 + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn Exported, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn Exported> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Exported,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn Exported,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Exported >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Exported >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Exported {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "double"],
+            false => &["drop", "size", "double"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Exported {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Exported",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "double",
+                    mut_receiver: false,
+                    args: &[("x", "u32")],
+                    ret: "u32",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: true,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_DOUBLE: usize = ::core::mem::offset_of!(__VTable, double)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Exported + ?Sized> Exported for ::std::boxed::Box<__K> {
+    fn double(&self, x: u32) -> u32 {
+        __K::double(&**self, x)
+    }
+}
+impl<__K: Exported + ?Sized> Exported for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: 'static,
+{
+    fn double(&self, x: u32) -> u32 {
+        __K::double(&**self, x)
+    }
+}
+impl<__K: Exported + ?Sized> Exported for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: 'static,
+{
+    fn double(&self, x: u32) -> u32 {
+        __K::double(&**self, x)
+    }
+}
@@ -0,0 +1,296 @@
+trait Named: 'static {
+    fn name(&self) -> String;
+    fn set(&mut self, name: &str);
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        name: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>) -> String,
+        set: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>, &'_ str),
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Named>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Named");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Named + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Named");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[allow(improper_ctypes_definitions)]
+    extern "C" fn name<__T: Named>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> String {
+        ::thin_trait_objects::__private::count_call::<__T>("Named", "name");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Named", "name", move || -> String { __T::name(recv) })
+    }
+    #[allow(improper_ctypes_definitions)]
+    extern "C" fn set<__T: Named>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+        name: &'_ str,
+    ) {
+        ::thin_trait_objects::__private::count_call::<__T>("Named", "set");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Named", "set", move || { __T::set(recv, name) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Named {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Named {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Named>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                name: name::<__K>,
+                set: set::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Named + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                name: name::<__K>,
+                set: set::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Named> ::thin_trait_objects::__private::ExpandedVTable<__K> for dyn Named {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Named> ::thin_trait_objects::prelude::ThinExt<dyn Named, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Named> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Named");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Named + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Named, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Named> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Named> {
+            ::thin_trait_objects::__private::count_new::<__K>("Named");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Named,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Named>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Named>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                name: name::<__K>,
+                set: set::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Named> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Named {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Named + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Named> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Named,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Named, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Named + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Named, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Named> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Named> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Named for ::thin_trait_objects::prelude::Thin<dyn Named> {
+        #[inline]
+        fn name(&self) -> String {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, name);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.name,
+                        "Named",
+                        "name",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+        #[inline]
+        fn set(&mut self, name: &str) {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, set);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.set,
+                        "Named",
+                        "set",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv, name)
+        }
+    }
+    impl Named for ::thin_trait_objects::prelude::ThinMut<dyn Named>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Named>: 'static,
+    {
+        #[inline]
+        fn name(&self) -> String {
+            <::thin_trait_objects::prelude::Thin<dyn Named> as Named>::name(&**self)
+        }
+        #[inline]
+        fn set(&mut self, name: &str) {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Named,
+            > as Named>::set(
+                unsafe { ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self) },
+                name,
+            )
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Named >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Named >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 2usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Named {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "name", "set"],
+            false => &["drop", "size", "name", "set"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Named {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Named",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "name",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "String",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "set",
+                    mut_receiver: true,
+                    args: &[("name", "& str")],
+                    ret: "()",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_NAME: usize = ::core::mem::offset_of!(__VTable, name)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_SET: usize = ::core::mem::offset_of!(__VTable, set)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Named + ?Sized> Named for ::std::boxed::Box<__K> {
+    fn name(&self) -> String {
+        __K::name(&**self)
+    }
+    fn set(&mut self, name: &str) {
+        __K::set(&mut **self, name)
+    }
+}
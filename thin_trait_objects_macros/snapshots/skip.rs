@@ -0,0 +1,316 @@
+trait Source: 'static {
+    fn next(&mut self) -> u32;
+    fn take<C: FromIterator<u32>>(&mut self, n: usize) -> C
+    where
+        Self: ::core::marker::Sized,
+    {
+        (0..n).map(|_| self.next()).collect()
+    }
+    fn boxed(self) -> Box<dyn Source>
+    where
+        Self: ::core::marker::Sized;
+    fn merge(&mut self, other: Self)
+    where
+        Self: ::core::marker::Sized;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        next: extern "C" fn(::thin_trait_objects::prelude::MutSelf<'_>) -> u32,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Source>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Source");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Source + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Source");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn next<__T: Source>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'_>,
+    ) -> u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Source", "next");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Source", "next", move || -> u32 { __T::next(recv) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Source {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Source {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Source>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                next: next::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Source + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                next: next::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Source> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Source {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Source> ::thin_trait_objects::prelude::ThinExt<dyn Source, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Source> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Source");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Source + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Source, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Source> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Source> {
+            ::thin_trait_objects::__private::count_new::<__K>("Source");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Source,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Source>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Source>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                next: next::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Source> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Source {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Source + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Source> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Source,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Source, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Source + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Source, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Source> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Source> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Source for ::thin_trait_objects::prelude::Thin<dyn Source> {
+        #[inline]
+        fn next(&mut self) -> u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, next);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.next,
+                        "Source",
+                        "next",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv)
+        }
+        #[allow(unused_variables)]
+        fn boxed(self) -> Box<dyn Source>
+        where
+            Self: ::core::marker::Sized,
+        {
+            ::thin_trait_objects::__private::skipped_method("Source", "boxed")
+        }
+        #[allow(unused_variables)]
+        fn merge(&mut self, other: Self)
+        where
+            Self: ::core::marker::Sized,
+        {
+            ::thin_trait_objects::__private::skipped_method("Source", "merge")
+        }
+    }
+    impl Source for ::thin_trait_objects::prelude::ThinMut<dyn Source>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Source>: 'static,
+    {
+        #[inline]
+        fn next(&mut self) -> u32 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Source,
+            > as Source>::next(unsafe {
+                ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self)
+            })
+        }
+    }
+    struct __FnAdapter<__F>(__F);
+    impl<__F: ::core::ops::FnMut() -> u32 + 'static> Source for __FnAdapter<__F> {
+        fn next(&mut self) -> u32 {
+            (self.0)()
+        }
+        #[allow(unused_variables)]
+        fn boxed(self) -> Box<dyn Source>
+        where
+            Self: ::core::marker::Sized,
+        {
+            ::thin_trait_objects::__private::skipped_method("Source", "boxed")
+        }
+        #[allow(unused_variables)]
+        fn merge(&mut self, other: Self)
+        where
+            Self: ::core::marker::Sized,
+        {
+            ::thin_trait_objects::__private::skipped_method("Source", "merge")
+        }
+    }
+    impl<
+        __F: ::core::ops::FnMut() -> u32 + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn Source, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn Source> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Source,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn Source,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Source >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Source >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 1usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Source {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "next"],
+            false => &["drop", "size", "next"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Source {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Source",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "next",
+                    mut_receiver: true,
+                    args: &[],
+                    ret: "u32",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_NEXT: usize = ::core::mem::offset_of!(__VTable, next)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Source> Source for ::std::boxed::Box<__K> {
+    fn next(&mut self) -> u32 {
+        __K::next(&mut **self)
+    }
+    fn take<C: FromIterator<u32>>(&mut self, n: usize) -> C
+    where
+        Self: ::core::marker::Sized,
+    {
+        __K::take(&mut **self, n)
+    }
+    fn boxed(self) -> Box<dyn Source>
+    where
+        Self: ::core::marker::Sized,
+    {
+        __K::boxed(*self)
+    }
+    #[allow(unused_variables)]
+    fn merge(&mut self, other: Self)
+    where
+        Self: ::core::marker::Sized,
+    {
+        ::thin_trait_objects::__private::skipped_method("Source", "merge")
+    }
+}
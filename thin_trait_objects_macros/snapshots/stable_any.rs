@@ -0,0 +1,314 @@
+trait Codec<const V: u8>: Send + 'static {
+    fn decode(&self, byte: u8) -> u8;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable<const V: u8> {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        decode: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>, u8) -> u8,
+    }
+    const fn __slots_size<const V: u8>() -> usize {
+        ::core::mem::size_of::<__VTable<V>>()
+    }
+    extern "C-unwind" fn __drop<const V: u8, __T: Codec<V>>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Codec");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_drop::<__VTable<V>, __T>(ptr)
+        }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        const V: u8,
+        __T: Codec<V> + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Codec");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<
+                __VTable<V>,
+                __T,
+            >(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn decode<const V: u8, __T: Codec<V>>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+        byte: u8,
+    ) -> u8 {
+        ::thin_trait_objects::__private::count_call::<__T>("Codec", "decode");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable<V>, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Codec", "decode", move || -> u8 { __T::decode(recv, byte) })
+    }
+    impl<const V: u8> ::thin_trait_objects::prelude::SpecialAssoc for dyn Codec<V> {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl<const V: u8> ::thin_trait_objects::__private::Expanded for dyn Codec<V> {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<const V: u8, __K: Codec<V>>() -> &'static __VTable<V> {
+        const {
+            use ::core::mem::size_of;
+            ::core::assert!(
+                size_of:: < ::thin_trait_objects::prelude::Thin < dyn Codec < V > >> ()
+                == size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+            );
+            ::core::assert!(
+                size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin
+                < dyn Codec < V > >> > () == size_of:: < * mut () > (),
+                "`Option<Thin<dyn _>>` must be pointer-sized",
+            );
+            ::core::assert!(
+                __slots_size:: < V > () == (2 + 1usize) * size_of:: < extern "C" fn () >
+                () + size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+                "every vtable slot must be a single pointer-sized word",
+            );
+        };
+        let vtable = const {
+            &__VTable::<V> {
+                drop: __drop::<V, __K>,
+                size: __slots_size::<V>(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                decode: decode::<V, __K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<
+        const V: u8,
+        __K: Codec<V> + ::thin_trait_objects::prelude::PinnedDrop,
+    >() -> &'static __VTable<V> {
+        let vtable = const {
+            &__VTable::<V> {
+                drop: __pinned_drop::<V, __K>,
+                size: __slots_size::<V>(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                decode: decode::<V, __K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<const V: u8, __K: Codec<V>> ::thin_trait_objects::__private::ExpandedVTable<__K>
+    for dyn Codec<V> {
+        fn vtable() -> *const () {
+            __vtable::<V, __K>() as *const __VTable<V> as *const ()
+        }
+    }
+    impl<
+        const V: u8,
+        __K: Codec<V>,
+    > ::thin_trait_objects::prelude::ThinExt<dyn Codec<V>, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Codec<V>> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Codec");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(
+                    __vtable::<V, __K>(),
+                    value,
+                )
+            }
+        }
+    }
+    impl<
+        const V: u8,
+        __K: Codec<V> + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Codec<V>, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Codec<V>> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Codec<V>> {
+            ::thin_trait_objects::__private::count_new::<__K>("Codec");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Codec<V>,
+                    _,
+                    _,
+                >(__pinned_vtable::<V, __K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<const V: u8, __T: Codec<V>>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<
+                __VTable<V>,
+                __T,
+            >(ptr)
+        }
+    }
+    fn __batched_vtable<const V: u8, __K: Codec<V>>() -> &'static __VTable<V> {
+        let vtable = const {
+            &__VTable::<V> {
+                drop: __batched_drop::<V, __K>,
+                size: __slots_size::<V>(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                decode: decode::<V, __K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<
+        const V: u8,
+        __K: Codec<V>,
+    > ::thin_trait_objects::__private::ExpandedBatched<__K> for dyn Codec<V> {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<V, __K>() as *const __VTable<V> as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<const V: u8, __T: Codec<V> + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Codec<V>> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable<V>, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Codec<V>,
+        > as ::thin_trait_objects::prelude::ThinExt<
+            dyn Codec<V>,
+            __T,
+        >>::new(value.clone())
+    }
+    impl<
+        const V: u8,
+        __K: Codec<V> + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Codec<V>, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Codec<V>> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Codec<V>> {
+            __clone_bundle::<V, __K>
+        }
+    }
+    impl<const V: u8> Codec<V> for ::thin_trait_objects::prelude::Thin<dyn Codec<V>> {
+        #[inline]
+        fn decode(&self, byte: u8) -> u8 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable<V>>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable < V >, decode);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.decode,
+                        "Codec",
+                        "decode",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv, byte)
+        }
+    }
+    impl<const V: u8> Codec<V> for ::thin_trait_objects::prelude::ThinMut<dyn Codec<V>>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<
+            dyn Codec<V>,
+        >: Send + 'static,
+    {
+        #[inline]
+        fn decode(&self, byte: u8) -> u8 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Codec<V>,
+            > as Codec<V>>::decode(&**self, byte)
+        }
+    }
+    impl<const V: u8> ::thin_trait_objects::prelude::ThinSend for dyn Codec<V> {}
+    struct __FnAdapter<__F>(__F);
+    impl<const V: u8, __F: ::core::ops::Fn(u8) -> u8 + Send + 'static> Codec<V>
+    for __FnAdapter<__F> {
+        fn decode(&self, byte: u8) -> u8 {
+            (self.0)(byte)
+        }
+    }
+    impl<
+        const V: u8,
+        __F: ::core::ops::Fn(u8) -> u8 + Send + 'static,
+    > ::thin_trait_objects::prelude::ThinFromFn<dyn Codec<V>, __F>
+    for ::thin_trait_objects::prelude::Thin<dyn Codec<V>> {
+        fn from_fn(f: __F) -> Self {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Codec<V>,
+            > as ::thin_trait_objects::prelude::ThinExt<
+                dyn Codec<V>,
+                _,
+            >>::new(__FnAdapter(f))
+        }
+    }
+    impl<const V: u8> ::thin_trait_objects::__private::ExpandedUUID for dyn Codec<V> {
+        const UUID: ::thin_trait_objects::prelude::StableTypeId = {
+            let mut hasher = ::thin_trait_objects::prelude::StableHasher::new();
+            hasher.write(::core::env!("CARGO_PKG_VERSION").as_bytes());
+            hasher.write(::core::module_path!().as_bytes());
+            hasher.write("Thin<dyn Codec>".as_bytes());
+            hasher.write(&(V as u128).to_le_bytes());
+            unsafe {
+                ::thin_trait_objects::__private::expanded_stable_id(hasher.finish())
+            }
+        };
+    }
+    impl<const V: u8> ::thin_trait_objects::prelude::SlotLayout for dyn Codec<V> {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "decode"],
+            false => &["drop", "size", "decode"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable<V>;
+    }
+    #[allow(dead_code)]
+    impl<const V: u8> dyn Codec<V> {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Codec",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "decode",
+                    mut_receiver: false,
+                    args: &[("byte", "u8")],
+                    ret: "u8",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_DECODE: usize = ::core::mem::offset_of!(__VTable < V >, decode)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<const V: u8, __K: Codec<V> + ?Sized> Codec<V> for ::std::boxed::Box<__K> {
+    fn decode(&self, byte: u8) -> u8 {
+        __K::decode(&**self, byte)
+    }
+}
+impl<const V: u8, __K: Codec<V> + ?Sized> Codec<V> for ::std::sync::Arc<__K>
+where
+    ::std::sync::Arc<__K>: Send + 'static,
+{
+    fn decode(&self, byte: u8) -> u8 {
+        __K::decode(&**self, byte)
+    }
+}
+impl<const V: u8, __K: Codec<V> + ?Sized> Codec<V> for ::std::rc::Rc<__K>
+where
+    ::std::rc::Rc<__K>: Send + 'static,
+{
+    fn decode(&self, byte: u8) -> u8 {
+        __K::decode(&**self, byte)
+    }
+}
@@ -0,0 +1,296 @@
+trait Typed: 'static {
+    fn len(self: &Self) -> usize;
+    fn last<'a>(self: &'a mut Self) -> &'a mut u32;
+}
+const _: () = {
+    #[repr(C)]
+    pub struct __VTable {
+        drop: extern "C-unwind" fn(*mut ()),
+        size: usize,
+        checksum: ::thin_trait_objects::__private::VTableChecksum,
+        len: extern "C" fn(::thin_trait_objects::prelude::RefSelf<'_>) -> usize,
+        last: for<'a> extern "C" fn(
+            ::thin_trait_objects::prelude::MutSelf<'a>,
+        ) -> &'a mut u32,
+    }
+    const fn __slots_size() -> usize {
+        ::core::mem::size_of::<__VTable>()
+    }
+    extern "C-unwind" fn __drop<__T: Typed>(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Typed");
+        unsafe { ::thin_trait_objects::__private::expanded_drop::<__VTable, __T>(ptr) }
+    }
+    extern "C-unwind" fn __pinned_drop<
+        __T: Typed + ::thin_trait_objects::prelude::PinnedDrop,
+    >(ptr: *mut ()) {
+        ::thin_trait_objects::__private::count_drop::<__T>("Typed");
+        unsafe {
+            ::thin_trait_objects::__private::expanded_pinned_drop::<__VTable, __T>(ptr)
+        }
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn len<__T: Typed>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> usize {
+        ::thin_trait_objects::__private::count_call::<__T>("Typed", "len");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Typed", "len", move || -> usize { __T::len(recv) })
+    }
+    #[warn(improper_ctypes_definitions)]
+    extern "C" fn last<'a, __T: Typed>(
+        recv: ::thin_trait_objects::prelude::MutSelf<'a>,
+    ) -> &'a mut u32 {
+        ::thin_trait_objects::__private::count_call::<__T>("Typed", "last");
+        let recv = unsafe {
+            ::thin_trait_objects::__private::expanded_value_mut::<__VTable, __T>(recv)
+        };
+        ::thin_trait_objects::__private::dispatch::<
+            __T,
+            _,
+        >("Typed", "last", move || -> &'a mut u32 { __T::last(recv) })
+    }
+    impl ::thin_trait_objects::prelude::SpecialAssoc for dyn Typed {
+        type Kind = ::thin_trait_objects::prelude::Own;
+    }
+    impl ::thin_trait_objects::__private::Expanded for dyn Typed {
+        const TOKEN: ::thin_trait_objects::__private::ExpansionToken = unsafe {
+            ::thin_trait_objects::__private::ExpansionToken::new()
+        };
+    }
+    fn __vtable<__K: Typed>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                len: len::<__K>,
+                last: last::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    fn __pinned_vtable<__K: Typed + ::thin_trait_objects::prelude::PinnedDrop>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __pinned_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                len: len::<__K>,
+                last: last::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Typed> ::thin_trait_objects::__private::ExpandedVTable<__K> for dyn Typed {
+        fn vtable() -> *const () {
+            __vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    impl<__K: Typed> ::thin_trait_objects::prelude::ThinExt<dyn Typed, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Typed> {
+        fn new(value: __K) -> Self {
+            ::thin_trait_objects::__private::count_new::<__K>("Typed");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin(__vtable::<__K>(), value)
+            }
+        }
+    }
+    impl<
+        __K: Typed + ::thin_trait_objects::prelude::PinnedDrop,
+    > ::thin_trait_objects::prelude::ThinPinnedDrop<dyn Typed, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Typed> {
+        fn pin_with_drop(
+            value: __K,
+        ) -> ::thin_trait_objects::prelude::ThinPin<dyn Typed> {
+            ::thin_trait_objects::__private::count_new::<__K>("Typed");
+            unsafe {
+                ::thin_trait_objects::__private::expanded_thin::<
+                    dyn Typed,
+                    _,
+                    _,
+                >(__pinned_vtable::<__K>(), value)
+            }
+                .into_pin()
+        }
+    }
+    extern "C-unwind" fn __batched_drop<__T: Typed>(ptr: *mut ()) {
+        unsafe {
+            ::thin_trait_objects::__private::expanded_batched_drop::<__VTable, __T>(ptr)
+        }
+    }
+    fn __batched_vtable<__K: Typed>() -> &'static __VTable {
+        let vtable = const {
+            &__VTable {
+                drop: __batched_drop::<__K>,
+                size: __slots_size(),
+                checksum: ::thin_trait_objects::__private::VTableChecksum::UNSEALED,
+                len: len::<__K>,
+                last: last::<__K>,
+            }
+        };
+        unsafe { ::thin_trait_objects::__private::expanded_seal(vtable) }
+    }
+    impl<__K: Typed> ::thin_trait_objects::__private::ExpandedBatched<__K>
+    for dyn Typed {
+        fn batched_vtable() -> *const () {
+            __batched_vtable::<__K>() as *const __VTable as *const ()
+        }
+    }
+    extern "C" fn __clone_bundle<__T: Typed + ::core::clone::Clone>(
+        recv: ::thin_trait_objects::prelude::RefSelf<'_>,
+    ) -> ::thin_trait_objects::prelude::Thin<dyn Typed> {
+        let value = unsafe {
+            ::thin_trait_objects::__private::expanded_value::<__VTable, __T>(recv)
+        };
+        <::thin_trait_objects::prelude::Thin<
+            dyn Typed,
+        > as ::thin_trait_objects::prelude::ThinExt<dyn Typed, __T>>::new(value.clone())
+    }
+    impl<
+        __K: Typed + ::core::clone::Clone,
+    > ::thin_trait_objects::prelude::ThinClone<dyn Typed, __K>
+    for ::thin_trait_objects::prelude::Thin<dyn Typed> {
+        fn clone_fn() -> ::thin_trait_objects::prelude::CloneFn<dyn Typed> {
+            __clone_bundle::<__K>
+        }
+    }
+    impl Typed for ::thin_trait_objects::prelude::Thin<dyn Typed> {
+        #[inline]
+        fn len(self: &Self) -> usize {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, len);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.len,
+                        "Typed",
+                        "len",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::RefSelf::new(self);
+            entry(recv)
+        }
+        #[inline]
+        fn last<'a>(self: &'a mut Self) -> &'a mut u32 {
+            let vtable = unsafe {
+                ::thin_trait_objects::__private::expanded_vtable::<__VTable>(self.ptr)
+            };
+            let entry = {
+                let size = unsafe {
+                    ::thin_trait_objects::__private::expanded_vtable_size(vtable)
+                };
+                let offset = ::core::mem::offset_of!(__VTable, last);
+                unsafe {
+                    ::thin_trait_objects::__private::expanded_slot(
+                        vtable,
+                        size,
+                        offset,
+                        |vtable| &vtable.last,
+                        "Typed",
+                        "last",
+                    )
+                }
+            };
+            let recv = ::thin_trait_objects::prelude::MutSelf::new(self);
+            entry(recv)
+        }
+    }
+    impl Typed for ::thin_trait_objects::prelude::ThinMut<dyn Typed>
+    where
+        for<'__view> ::thin_trait_objects::prelude::ThinMut<dyn Typed>: 'static,
+    {
+        #[inline]
+        fn len(self: &Self) -> usize {
+            <::thin_trait_objects::prelude::Thin<dyn Typed> as Typed>::len(&**self)
+        }
+        #[inline]
+        fn last<'a>(self: &'a mut Self) -> &'a mut u32 {
+            <::thin_trait_objects::prelude::Thin<
+                dyn Typed,
+            > as Typed>::last(unsafe {
+                ::thin_trait_objects::prelude::ThinMut::as_thin_mut(self)
+            })
+        }
+    }
+    const _: () = {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of:: < ::thin_trait_objects::prelude::Thin < dyn Typed >> () ==
+            size_of:: < * mut () > (), "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of:: < ::core::option::Option < ::thin_trait_objects::prelude::Thin <
+            dyn Typed >> > () == size_of:: < * mut () > (),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size() == (2 + 2usize) * size_of:: < extern "C" fn () > () +
+            size_of:: < ::thin_trait_objects::__private::VTableChecksum > (),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    impl ::thin_trait_objects::prelude::SlotLayout for dyn Typed {
+        const SLOTS: &'static [&'static str] = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => &["drop", "size", "checksum", "len", "last"],
+            false => &["drop", "size", "len", "last"],
+        };
+        const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+        const CHECKSUM_SLOT: ::core::option::Option<usize> = match ::thin_trait_objects::__private::VTABLE_CHECKSUM {
+            true => ::core::option::Option::Some(2),
+            false => ::core::option::Option::None,
+        };
+        type VTable = __VTable;
+    }
+    #[allow(dead_code)]
+    impl dyn Typed {
+        /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+        const DESCRIPTOR: ::thin_trait_objects::__private::TraitDescriptor = ::thin_trait_objects::__private::TraitDescriptor {
+            name: "Typed",
+            version: ::core::env!("CARGO_PKG_VERSION"),
+            methods: &[
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "len",
+                    mut_receiver: false,
+                    args: &[],
+                    ret: "usize",
+                },
+                ::thin_trait_objects::__private::MethodDescriptor {
+                    name: "last",
+                    mut_receiver: true,
+                    args: &[],
+                    ret: "& 'a mut u32",
+                },
+            ],
+            any: false,
+            extension: false,
+            dual_abi: false,
+            free: ::core::option::Option::None,
+        };
+        const SLOT_LEN: usize = ::core::mem::offset_of!(__VTable, len)
+            / ::core::mem::size_of::<*const ()>();
+        const SLOT_LAST: usize = ::core::mem::offset_of!(__VTable, last)
+            / ::core::mem::size_of::<*const ()>();
+    }
+};
+impl<__K: Typed + ?Sized> Typed for ::std::boxed::Box<__K> {
+    fn len(self: &Self) -> usize {
+        __K::len(&**self)
+    }
+    fn last<'a>(self: &'a mut Self) -> &'a mut u32 {
+        __K::last(&mut **self)
+    }
+}
@@ -0,0 +1,84 @@
+//! Property tests feeding `#[thin]` arbitrary trait definitions, assembled from fragments covering
+//! the receivers, types, options and method attributes it handles specially. Every expansion must
+//! either succeed with valid Rust or fail with a diagnostic, never panic.
+//!
+//! Set `PROPTEST_CASES` to run more cases than the default.
+
+use proc_macro2::TokenStream as TokenStream2;
+use proptest::prelude::*;
+use proptest::sample::{select, subsequence};
+
+use crate::expand_thin;
+
+const OPTIONS: &[&str] = &["no_impl", "any", "fn_impl", "dual_abi", "extension", "c_free", "stable_any", "bogus"];
+
+const RECEIVERS: &[&str] = &[
+    "&self", "&mut self", "self", "self: &Self", "self: &'a mut Self", "self: Box<Self>", "mut self", "",
+];
+
+const TYPES: &[&str] = &[
+    "u32", "f64", "bool", "&u8", "&'a u32", "&mut u8", "*const u8", "Self", "&Self", "Box<Self>",
+    "Vec<u8>", "Option<&u8>", "T", "&T", "impl Fn(u32)", "extern \"C\" fn(u32) -> u32", "[u8; 4]",
+    "(u32, u8)", "()", "dyn Fn()",
+];
+
+const RETURNS: &[&str] = &[
+    "", "-> u32", "-> &u32", "-> &'a u32", "-> !", "-> Self", "-> impl Iterator<Item = u8>",
+    "-> impl Send", "-> T", "-> Box<dyn Fn()>", "-> (u32, bool)",
+];
+
+const ATTRS: &[&str] = &[
+    "", "", "#[thin(hot)]", "#[thin(skip)]", "#[thin(instantiate(T = u32, T = u8))]", "#[thin(bogus)]",
+    "#[thin(hot, skip)]", "#[inline]", "#[doc = \"docs\"]",
+];
+
+const GENERICS: &[&str] = &["", "", "<'a>", "<T>", "<T: Clone + 'static>", "<'a, T>", "<const N: usize>"];
+
+const WHERES: &[&str] = &["", "", "where Self: Sized", "where T: Copy", "where Self: Clone"];
+
+const BODIES: &[&str] = &[";", ";", "{ unimplemented!() }", "{ loop {} }"];
+
+const TRAIT_GENERICS: &[&str] = &["", "", "<const N: usize>", "<const N: usize, const B: bool = true>", "<T>", "<'a>"];
+
+const SUPERTRAITS: &[&str] = &["", ": 'static", ": Send + 'static", ": Clone + 'static", ": Sized"];
+
+fn method() -> impl Strategy<Value = String> {
+    (
+        select(ATTRS),
+        select(GENERICS),
+        select(RECEIVERS),
+        prop::collection::vec(select(TYPES), 0..3),
+        select(RETURNS),
+        select(WHERES),
+        select(BODIES),
+    )
+        .prop_map(|(attr, generics, receiver, args, ret, where_clause, body)| {
+            let args = args.iter().enumerate().map(|(i, ty)| format!("arg{}: {}", i, ty));
+            let params = std::iter::once(receiver.to_string()).filter(|receiver| !receiver.is_empty()).chain(args);
+            format!("{} fn method{}({}) {} {} {}", attr, generics, params.collect::<Vec<_>>().join(", "), ret, where_clause, body)
+        })
+}
+
+fn item() -> impl Strategy<Value = String> {
+    (select(TRAIT_GENERICS), select(SUPERTRAITS), prop::collection::vec(method(), 0..4), any::<bool>()).prop_map(|(generics, supertraits, methods, public)| {
+        // methods are numbered to keep their names distinct
+        let methods = methods.iter().enumerate().map(|(i, method)| method.replacen("fn method", &format!("fn method{}", i), 1));
+        let vis = if public { "pub" } else { "" };
+        format!("{} trait Fuzzed{}{} {{ {} }}", vis, generics, supertraits, methods.collect::<Vec<_>>().join("\n"))
+    })
+}
+
+proptest! {
+    #[test]
+    fn expansions_never_panic(options in subsequence(OPTIONS, 0..3), item in item()) {
+        let attr = options.join(", ").parse::<TokenStream2>().unwrap();
+        let tokens = item.parse::<TokenStream2>().unwrap();
+        let expanded = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| expand_thin(attr, tokens)));
+        prop_assert!(expanded.is_ok(), "`#[thin({})]` panicked on `{}`", options.join(", "), item);
+
+        if let Ok(Ok(expanded)) = expanded {
+            let parsed = syn::parse2::<syn::File>(expanded.clone());
+            prop_assert!(parsed.is_ok(), "`#[thin({})]` on `{}` expanded to invalid Rust: {}", options.join(", "), item, expanded);
+        }
+    }
+}
@@ -1,236 +1,1332 @@
 use proc_macro::TokenStream;
-use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
-use syn::{parse_macro_input, parse_quote, AngleBracketedGenericArguments, DeriveInput, FnArg, GenericArgument, GenericParam, Generics, Ident, ItemTrait, Pat, PatIdent, Path, PathArguments, PathSegment, ReturnType, Token, TraitItem, Type, TypeParamBound, TypePath, TypeReference, TypeTuple, WhereClause};
+use syn::visit::Visit;
+use syn::visit_mut::VisitMut;
+use syn::{parse_macro_input, parse_quote, AngleBracketedGenericArguments, Attribute, DeriveInput, FieldsNamed, FnArg, GenericArgument, GenericParam, Generics, Ident, ItemTrait, Lifetime, LitInt, LitStr, Pat, PatIdent, Path, PathArguments, PathSegment, ReturnType, Signature, Token, TraitItem, TraitItemFn, Type, TypeParamBound, TypePath, TypeReference, TypeTuple, Visibility, WhereClause};
 
 //=================//
 
-// TODO: slim this monster down with some helper functions
 #[proc_macro_attribute]
-pub fn thin(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let mut item_trait = parse_macro_input!(item as ItemTrait);
-    let trait_name = &item_trait.ident;
+pub fn thin(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match expand_thin(attr.into(), item.into()) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
 
-    let super_traits = &mut item_trait.supertraits;
+/// `unsafe { #tokens }`, spanned as the macro's own code rather than the caller's: `unsafe_code`
+/// isn't reported in external macros, so the expansion compiles in crates with
+/// `#![forbid(unsafe_code)]`. `tokens` mustn't contain any of the caller's expressions, which the
+/// block would then hide from the lint.
+fn expansion_unsafe(tokens: TokenStream2) -> TokenStream2 {
+    quote_spanned! { Span::mixed_site()=> unsafe { #tokens } }
+}
 
-    let mut is_static = false;
-    let static_bound: TypeParamBound = parse_quote!('static);
-    for super_trait in super_traits.iter() {
-        if *super_trait == static_bound { is_static = true; break }
-    }
-    if !is_static {
-        panic!("Error parsing {}: Traits without a `'static` bound are currently not supported", trait_name);
-    }
+/// The path generated items refer to the crate's items by, so that they don't depend on what's in
+/// scope where the trait is declared.
+fn prelude_path() -> TokenStream2 {
+    quote! { ::thin_trait_objects::prelude }
+}
 
-    let trait_items = &item_trait.items.clone();
+/// The path generated items refer to the crate's internals by, which aren't public API.
+fn private_path() -> TokenStream2 {
+    quote! { ::thin_trait_objects::__private }
+}
+
+/// Expands `#[thin]`, apart from the proc-macro entry point so that expansions can be tested.
+fn expand_thin(attr: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    // `no_impl` skips implementing the trait for `Thin<dyn Trait>`, for thin objects which are only
+    // passed to foreign code
+    // `any` records the `TypeId` of the concrete type in the vtable, for downcasting
+    // `fn_impl` implements a single-method trait for closures, in place of `Box<K>`, `Arc<K>` and `Rc<K>`
+    // `dual_abi` adds a Rust-ABI entry per method, called instead of the `extern "C"` one when the
+    // vtable was created by the same compilation as the caller
+    // `extension` stores per-type static metadata in the vtable, provided through `ThinExtension`
+    // `c_free` exports an `extern "C"` destructor, `{trait_name}_object_free` in snake case, for C
+    // hosts to free objects they were given
+    // `stable_any` gives `Thin<dyn Trait>` a `StableTypeId`, so that it can be nested in
+    // `Thin<dyn StableAny>`s
+    // `rust_only` skips checking that signatures are FFI-safe, for objects only shared between Rust
+    // code built by the same compiler
+    let mut no_impl = false;
+    let mut any = false;
+    let mut fn_impl = false;
+    let mut dual_abi = false;
+    let mut extension = false;
+    let mut c_free = false;
+    let mut stable_any = false;
+    let mut rust_only = false;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("no_impl") {
+            no_impl = true;
+            Ok(())
+        } else if meta.path.is_ident("any") {
+            any = true;
+            Ok(())
+        } else if meta.path.is_ident("fn_impl") {
+            fn_impl = true;
+            Ok(())
+        } else if meta.path.is_ident("dual_abi") {
+            dual_abi = true;
+            Ok(())
+        } else if meta.path.is_ident("extension") {
+            extension = true;
+            Ok(())
+        } else if meta.path.is_ident("c_free") {
+            c_free = true;
+            Ok(())
+        } else if meta.path.is_ident("stable_any") {
+            stable_any = true;
+            Ok(())
+        } else if meta.path.is_ident("rust_only") {
+            rust_only = true;
+            Ok(())
+        } else {
+            Err(meta.error("expected `no_impl`, `any`, `fn_impl`, `dual_abi`, `extension`, `c_free`, `stable_any` or `rust_only`"))
+        }
+    });
+    syn::parse::Parser::parse2(attr_parser, attr)?;
+
+    let mut item_trait = syn::parse2::<ItemTrait>(item)?;
+
+    let trait_name = item_trait.ident.clone();
+    let trait_name = &trait_name;
+    // every generated item is generic over the trait's const parameters, e.g. `Buffered<N>`
+    let generics = TraitGenerics::new(&item_trait)?;
+    let TraitGenerics { generics: trait_generics, params, ty_args, args, turbofish } = &generics;
+    let trait_path = quote! { #trait_name #ty_args };
+    let vtable_ty = quote! { __VTable #ty_args };
+
+    // `#[thin(hot)]` methods are moved to the front of the vtable, next to the header, so their slots
+    // share a cache line with it. the rest keep their declaration order.
+    let mut hot_methods = Vec::new();
+    // `#[thin(instantiate(T = ...))]` methods get a slot per listed type, and calls are dispatched
+    // on the `TypeId` of the parameter, which must therefore be `'static`. they're excluded from
+    // `dyn Trait` (which must stay dyn-compatible), but `Thin<dyn Trait>` is sized
+    let mut instantiated = Vec::new();
+    // `#[thin(skip)]` methods get no slot, and like `where Self: Sized` methods of `dyn Trait`, can't
+    // be called on `Thin<dyn Trait>`, which uses their default body or else a panicking stub
+    let mut skipped = Vec::new();
+    for item in &mut item_trait.items {
+        let TraitItem::Fn(function) = item else { continue };
+        let options = take_method_options(&mut function.attrs)?;
+        if options.skip {
+            if options.hot || options.instantiate.is_some() {
+                let message = format!("Error parsing `{}::{}`: `skip` can't be combined with other options", trait_name, function.sig.ident);
+                return Err(syn::Error::new_spanned(&function.sig.ident, message));
+            }
+            skipped.push(function.clone());
+            function.sig.generics.make_where_clause().predicates.push(parse_quote!(Self: ::core::marker::Sized));
+            continue;
+        }
+        // `impl Trait` return types are erased to `Thin<dyn Trait>` by the shims, so must be `'static`.
+        // like instantiated methods, such methods are excluded from `dyn Trait`
+        if let ReturnType::Type(_, ty) = &mut function.sig.output
+            && let Type::ImplTrait(impl_trait) = &mut **ty
+        {
+            let static_bound: TypeParamBound = parse_quote!('static);
+            if !impl_trait.bounds.iter().any(|bound| *bound == static_bound) {
+                impl_trait.bounds.push(static_bound);
+            }
+            function.sig.generics.make_where_clause().predicates.push(parse_quote!(Self: ::core::marker::Sized));
+        }
+        if options.hot {
+            hot_methods.push(function.sig.ident.clone());
+        }
+        if let Some((param, types)) = options.instantiate {
+            let fn_name = &function.sig.ident;
+            let Some(type_param) = function.sig.generics.type_params_mut().find(|type_param| type_param.ident == param) else {
+                let message = format!("Error parsing `{}::{}`: `{}` is not a type parameter of the method", trait_name, fn_name, param);
+                return Err(syn::Error::new_spanned(&param, message));
+            };
+            if types.is_empty() {
+                let message = format!("Error parsing `{}::{}`: `instantiate` requires at least one type", trait_name, fn_name);
+                return Err(syn::Error::new_spanned(&param, message));
+            }
+            type_param.bounds.push(parse_quote!('static));
+            function.sig.generics.make_where_clause().predicates.push(parse_quote!(Self: ::core::marker::Sized));
+            instantiated.push((fn_name.clone(), param, types));
+        }
+    }
+    let prelude = prelude_path();
+    let private = private_path();
 
-    let mut fn_names = Vec::new();
-    let mut vtable_fields = Vec::new();
-    let mut shims = Vec::new();
-    let mut trait_method_impls = Vec::new();
+    let static_bound: TypeParamBound = parse_quote!('static);
+    if !item_trait.supertraits.iter().any(|super_trait| *super_trait == static_bound) {
+        let message = format!("Error parsing {}: Traits without a `'static` bound are currently not supported", trait_name);
+        return Err(syn::Error::new_spanned(trait_name, message));
+    }
 
-    for item in trait_items {
+    let mut methods = Vec::new();
+    for item in &item_trait.items {
         let TraitItem::Fn(function) = item else {
-            panic!("non-function items are not supported");
+            return Err(syn::Error::new_spanned(item, "non-function items are not supported"));
         };
+        if skipped.iter().any(|skipped| skipped.sig.ident == function.sig.ident) {
+            continue;
+        }
+        match instantiated.iter().find(|(name, ..)| *name == function.sig.ident) {
+            None => methods.push(expand_method(trait_name, &generics, function, dual_abi, rust_only, None)?),
+            Some((_, param, types)) => methods.extend(expand_instantiations(trait_name, &generics, function, param, types, dual_abi, rust_only)?),
+        }
+    }
+    methods.sort_by_key(|method| !hot_methods.contains(&method.group));
+
+    let fn_names = methods.iter().map(|method| &method.name).collect::<Vec<_>>();
+    let vtable_fields = methods.iter().map(|method| &method.vtable_field);
+    let shims = methods.iter().map(|method| &method.shim);
+    let rust_fields = methods.iter().map(|method| &method.rust_field);
+    let rust_shims = methods.iter().map(|method| &method.rust_shim);
+    let rust_shim_names = methods.iter().map(|method| &method.rust_shim_name);
+    let method_descriptors = methods.iter().map(|method| &method.descriptor);
+    let trait_method_impls = methods.iter().map(|method| &method.trait_method_impl);
+    let view_delegations = methods.iter().map(|method| &method.view_delegation);
+    let skipped = skipped.iter().map(|function| expand_skipped(trait_name, function)).collect::<Vec<_>>();
+    let stubs = skipped.iter().map(|skipped| &skipped.stub).collect::<Vec<_>>();
+    let delegations = methods.iter().map(|method| &method.delegation)
+        .chain(skipped.iter().map(|skipped| &skipped.delegation))
+        .collect::<Vec<_>>();
+    let ref_delegations = methods.iter().map(|method| &method.delegation)
+        .chain(skipped.iter().map(|skipped| &skipped.ref_delegation))
+        .collect::<Vec<_>>();
+    let has_mut_receiver = methods.iter().any(|method| method.mut_receiver);
+
+    // the slots of instantiations keep their type's case
+    let allow_case = (!instantiated.is_empty()).then(|| quote! { #[allow(non_snake_case)] });
+
+    // instantiated and skipped methods, and those returning `impl Trait`, can only be delegated to sized types
+    let sized_only = !instantiated.is_empty() || !skipped.is_empty() || methods.iter().any(|method| method.sized_only);
+    let maybe_unsized = match sized_only {
+        false => Some(quote! { + ?Sized }),
+        true => None,
+    };
 
-        let fn_name = &function.sig.ident;
-        fn_names.push(fn_name.clone());
+    // `Arc<K>` and `Rc<K>` can only be delegated to if every method with a slot takes `&self`, for thin
+    // objects shared through them. the auto traits of both depend on those of `K` differently, so
+    // they're bounded by the supertraits themselves, as is
+    // `Box<K>` for `Error` traits, since it isn't an `Error` for unsized `K`
+    let supertraits = &item_trait.supertraits;
+    let has_supertrait = |name: &str| item_trait.supertraits.iter().any(|bound| match bound {
+        TypeParamBound::Trait(bound) => bound.path.segments.last().is_some_and(|segment| segment.ident == name),
+        _ => false,
+    });
+    // `Display`, `Debug` and `Error` supertraits are bridged, as `Thin<dyn Trait>` must implement them as well
+    let error = has_supertrait("Error");
+    let display = error || has_supertrait("Display");
+    let debug = error || has_supertrait("Debug");
+    let ref_delegation = match has_mut_receiver {
+        true => None,
+        false => Some(quote! {
+            impl<#params __K: #trait_path #maybe_unsized> #trait_path for ::std::sync::Arc<__K> where ::std::sync::Arc<__K>: #supertraits {
+                #(#ref_delegations)*
+            }
+
+            impl<#params __K: #trait_path #maybe_unsized> #trait_path for ::std::rc::Rc<__K> where ::std::rc::Rc<__K>: #supertraits {
+                #(#ref_delegations)*
+            }
+        }),
+    };
 
-        let generics = &function.sig.generics;
-        forbid_non_lifetime_generics(generics, trait_name, fn_name);
+    let dyn_trait = quote! { dyn #trait_path };
+    // the types thin objects are created from must provide their extension, if the vtable has one
+    let extension_bound = extension.then(|| quote! { + #prelude::ThinExtension<#dyn_trait> });
+
+    // closures can stand in for the only method of a trait, when they can have the same signature
+    let auto_supertraits = item_trait.supertraits.iter().all(|bound| match bound {
+        TypeParamBound::Lifetime(lt) => lt.ident == "static",
+        TypeParamBound::Trait(bound) => bound.path.is_ident("Send") || bound.path.is_ident("Sync"),
+        _ => false,
+    });
+    let closure_method = match (&methods[..], auto_supertraits) {
+        ([method @ Method { fn_bound: Some(_), .. }], true) => Some(method),
+        _ => None,
+    };
 
-        let args = function.sig.inputs.iter().collect::<Vec<_>>();
-        let mut arg_names = Vec::new();
-        let mut arg_types = Vec::new();
+    let fn_adapter_extension = extension.then(|| quote! {
+        impl<#params __F> #prelude::ThinExtension<#dyn_trait> for __FnAdapter<__F> {}
+    });
 
-        //================//
-        // receiver
+    let from_fn = closure_method.map(|Method { fn_bound, signature, forwarded_args, .. }| quote! {
+        struct __FnAdapter<__F>(__F);
 
-        let Some(FnArg::Receiver(recv)) = args.get(0) else {
-            // the compiler should catch misplaced receivers before we get here
-            // so I reckon this is unnecessary
-            panic!("{}::{} must have a receiver", trait_name, fn_name);
-        };
+        #fn_adapter_extension
 
-        let lt = match recv.lifetime() {
-            Some(lt) => lt.clone(),
-            None => parse_quote!('_),
-        };
+        impl<#params __F: #fn_bound + #supertraits> #trait_path for __FnAdapter<__F> {
+            #signature {
+                (self.0)(#(#forwarded_args),*)
+            }
 
-        let recv_type: Type;
-        let erase_recv: TokenStream2;
-        let un_erase_recv: TokenStream2;
-        match recv.mutability {
-            None => {
-                recv_type = parse_quote!(RefSelf<#lt>);
-                erase_recv = quote! {
-                    let recv = RefSelf::new(self);
-                };
-                un_erase_recv = quote! {
-                    let bundle = unsafe { &*(recv.ptr as *const Bundle<T>) };
-                    let recv = &bundle.value;
-                };
-            },
-            Some(_) => {
-                recv_type = parse_quote!(MutSelf<#lt>);
-                erase_recv = quote! {
-                    let recv = MutSelf::new(self);
-                };
-                un_erase_recv = quote! {
-                    let bundle = unsafe { &mut *(recv.ptr as *mut Bundle<T>) };
-                    let recv = &mut bundle.value;
-                };
-            },
+            #(#stubs)*
         }
 
-        arg_names.push(parse_quote!(recv));
-        arg_types.push(recv_type);
-
-        //================//
-        // non-receiver arguments
+        impl<#params __F: #fn_bound + #supertraits> #prelude::ThinFromFn<#dyn_trait, __F> for #prelude::Thin<#dyn_trait> {
+            fn from_fn(f: __F) -> Self {
+                <#prelude::Thin<#dyn_trait> as #prelude::ThinExt<#dyn_trait, _>>::new(__FnAdapter(f))
+            }
+        }
+    });
+
+    // `Box<F>` is a closure itself (and `Arc<F>` and `Rc<F>` could be), so the delegating impls would overlap
+    let box_bound = error.then(|| quote! { where ::std::boxed::Box<__K>: #supertraits });
+    let delegation_impls = match fn_impl {
+        false => quote! {
+            impl<#params __K: #trait_path #maybe_unsized> #trait_path for ::std::boxed::Box<__K> #box_bound {
+                #(#delegations)*
+            }
 
-        for arg in &args[1..] {
-            let FnArg::Typed(pat_type) = arg else {
-                // SAFETY: the earlier let-else match on the receiver should ensure we never get here
-                unsafe { std::hint::unreachable_unchecked() };
+            #ref_delegation
+        },
+        true => {
+            let Some(Method { fn_bound, signature, forwarded_args, .. }) = closure_method else {
+                let message = format!(
+                    "Error parsing {}: `fn_impl` requires a single method without explicit lifetimes or a borrowed return type, \
+                     and no supertraits other than `'static`, `Send` and `Sync`",
+                    trait_name,
+                );
+                return Err(syn::Error::new_spanned(trait_name, message));
             };
+            quote! {
+                impl<#params __F: #fn_bound + #supertraits> #trait_path for __F {
+                    #signature {
+                        self(#(#forwarded_args),*)
+                    }
 
-            let arg_name = match &*pat_type.pat {
-                Pat::Ident(PatIdent { ident: name, .. }) => name,
-                _ => panic!("Error parsing argument of {}::{}", trait_name, fn_name),
-            };
+                    #(#stubs)*
+                }
+            }
+        }
+    };
 
-            arg_names.push(arg_name.clone());
+    // `Thin<&mut dyn Trait>` derefs to `ThinMut<dyn Trait>`, which is unsized, so needn't implement
+    // the methods excluded from `dyn Trait`. it has the bridged supertraits, but others only if the
+    // trait's crate implements them, hence the (higher-ranked, so not trivially false) bound
+    let thin_impl = match no_impl {
+        true => None,
+        false => Some(quote! {
+            impl #trait_generics #trait_path for #prelude::Thin<#dyn_trait> {
+                #(#trait_method_impls)*
+                #(#stubs)*
+            }
 
-            let mut arg_type = *pat_type.ty.clone();
-            if let Err(ty) = un_elide_lifetimes(&mut arg_type) {
-                panic!("Error parsing `{}::{}`: Arguments of type `{}` not supported", trait_name, fn_name, quote!(#ty));
+            impl #trait_generics #trait_path for #prelude::ThinMut<#dyn_trait> where for<'__view> #prelude::ThinMut<#dyn_trait>: #supertraits {
+                #(#view_delegations)*
             }
+        }),
+    };
 
-            arg_types.push(arg_type);
+    // every value a `Thin<dyn Trait>` is created from implements the trait, so it's as thread-safe as
+    // the trait's supertraits require. `ThinSend` and `ThinSync` themselves require `dyn Trait` to be,
+    // in case `Send` or `Sync` here is some other trait
+    let thin_send = has_supertrait("Send").then(|| quote! {
+        impl #trait_generics #prelude::ThinSend for #dyn_trait {}
+    });
+    let thin_sync = has_supertrait("Sync").then(|| quote! {
+        impl #trait_generics #prelude::ThinSync for #dyn_trait {}
+    });
+
+    let trait_str = trait_name.to_string();
+    // the value a shim's receiver points to
+    let shim_value = expansion_unsafe(quote! { #private::expanded_value::<#vtable_ty, __T>(recv) });
+
+    // the type id follows the slots, so that it doesn't affect their indices
+    let (type_id_field, type_id_init, same_type, any_impl) = match any {
+        false => (None, None, None, None),
+        true => {
+            let load_type_id = expansion_unsafe(quote! {
+                #private::expanded_load_slot::<#vtable_ty, _>(ptr, __slots_size #turbofish(), offset, |vtable| &vtable.type_id, #trait_str, "type_id")
+            });
+            (
+            Some(quote! { type_id: ::core::any::TypeId, }),
+            Some(quote! { type_id: ::core::any::TypeId::of::<__K>(), }),
+            Some(quote! {
+                fn same_type(a: ::core::ptr::NonNull<()>, b: ::core::ptr::NonNull<()>) -> bool {
+                    <#dyn_trait as #prelude::ThinAny>::erased_type_id(a) == <#dyn_trait as #prelude::ThinAny>::erased_type_id(b)
+                }
+            }),
+            Some(quote! {
+                impl #trait_generics #private::ExpandedAny for #dyn_trait {
+                    fn erased_type_id(ptr: ::core::ptr::NonNull<()>) -> ::core::any::TypeId {
+                        let offset = ::core::mem::offset_of!(#vtable_ty, type_id);
+                        #load_type_id
+                    }
+                }
+            }),
+            )
         }
+    };
 
-        //================//
-        // return type
-
-        let mut return_type = function.sig.output.clone();
-        match &mut return_type {
-            ReturnType::Type(_, ty) => {
-                if let Err(ty) = un_elide_lifetimes(ty) {
-                    panic!("Error parsing `{}::{}`: `{}` is not supported in return types", trait_name, fn_name, quote!(#ty));
+    // the extension follows the slots too, before the type id
+    let (extension_field, extension_init, extension_impl) = match extension {
+        false => (None, None, None),
+        true => {
+            let load_extension = expansion_unsafe(quote! {
+                #private::expanded_load_slot::<#vtable_ty, _>(ptr, __slots_size #turbofish(), offset, |vtable| &vtable.extension, #trait_str, "extension")
+            });
+            (
+            Some(quote! { extension: ::core::option::Option<&'static #prelude::Extension>, }),
+            Some(quote! { extension: <__K as #prelude::ThinExtension<#dyn_trait>>::EXTENSION, }),
+            Some(quote! {
+                impl #trait_generics #private::ExpandedExtensionSlot for #dyn_trait {
+                    fn erased_extension(ptr: ::core::ptr::NonNull<()>) -> ::core::option::Option<&'static #prelude::Extension> {
+                        let offset = ::core::mem::offset_of!(#vtable_ty, extension);
+                        #load_extension
+                    }
                 }
-            }
-            _ => {}
+            }),
+            )
         }
+    };
 
-        //================//
-        // for clause
+    // the bridged supertraits get slots between the method slots and the extension, and `Thin` gets
+    // the std impls through the `*Slot` traits, as they can't be written here. the slots are only
+    // called from Rust, so pass a `Formatter` and return whether formatting failed. foreign vtables
+    // may not have them
+    let fmt_bridges = [(display, "DisplaySlot", "Display", "fmt_display"), (debug, "DebugSlot", "Debug", "fmt_debug")].into_iter()
+        .filter(|(bridged, ..)| *bridged)
+        .map(|(_, slot_trait, fmt_trait, field)| {
+            let [slot_trait, fmt_trait, field] = [slot_trait, fmt_trait, field].map(|name| Ident::new(name, Span::call_site()));
+            let shim = Ident::new(&format!("__{}", field), Span::call_site());
+            let field_def = quote! { #field: extern "C-unwind" fn(#prelude::RefSelf<'_>, &mut ::core::fmt::Formatter<'_>) -> bool, };
+            let init = quote! { #field: #shim::<#args __K>, };
+            let load = expansion_unsafe(quote! {
+                #private::expanded_load_slot::<#vtable_ty, _>(thin.ptr, __slots_size #turbofish(), offset, |vtable| &vtable.#field, #trait_str, "fmt")
+            });
+            let items = quote! {
+                extern "C-unwind" fn #shim<#params __T: #trait_path>(recv: #prelude::RefSelf<'_>, f: &mut ::core::fmt::Formatter<'_>) -> bool {
+                    let value = #shim_value;
+                    ::core::fmt::#fmt_trait::fmt(value, f).is_err()
+                }
 
-        let mut lifetimes = generics.lifetimes();
-        let for_clause = match lifetimes.next() {
-            None => None,
-            Some(first) => Some(quote! { for<#first #(, #lifetimes)*> }),
+                impl #trait_generics #prelude::#slot_trait for #dyn_trait {
+                    fn #field(thin: &#prelude::Thin<Self>, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        let offset = ::core::mem::offset_of!(#vtable_ty, #field);
+                        let #field = #load;
+                        match #field(#prelude::RefSelf::new(thin), f) {
+                            false => ::core::result::Result::Ok(()),
+                            true => ::core::result::Result::Err(::core::fmt::Error),
+                        }
+                    }
+                }
+            };
+            (field_def, init, items)
+        });
+    let error_bridge = error.then(|| {
+        let field_def = quote! {
+            error_source: for<'a> extern "C-unwind" fn(#prelude::RefSelf<'a>) -> ::core::option::Option<&'a (dyn ::core::error::Error + 'static)>,
         };
-        
-        //================//
-        // putting it all together
+        let init = quote! { error_source: __error_source::<#args __K>, };
+        let load = expansion_unsafe(quote! {
+            #private::expanded_load_slot::<#vtable_ty, _>(thin.ptr, __slots_size #turbofish(), offset, |vtable| &vtable.error_source, #trait_str, "source")
+        });
+        let items = quote! {
+            // the wide pointer is only ever returned to Rust
+            #[allow(improper_ctypes_definitions)]
+            extern "C-unwind" fn __error_source<'a, #params __T: #trait_path>(recv: #prelude::RefSelf<'a>) -> ::core::option::Option<&'a (dyn ::core::error::Error + 'static)> {
+                let value = #shim_value;
+                ::core::error::Error::source(value)
+            }
 
-        let vtable_field = quote! {
-            #fn_name: #for_clause extern "C" fn (#(#arg_types),*) #return_type,
+            impl #trait_generics #prelude::ErrorSlot for #dyn_trait {
+                fn error_source(thin: &#prelude::Thin<Self>) -> ::core::option::Option<&(dyn ::core::error::Error + 'static)> {
+                    let offset = ::core::mem::offset_of!(#vtable_ty, error_source);
+                    let error_source = #load;
+                    error_source(#prelude::RefSelf::new(thin))
+                }
+            }
         };
+        (field_def, init, items)
+    });
+    let (bridge_fields, bridge_inits, bridge_items): (Vec<_>, Vec<_>, Vec<_>) = fmt_bridges.chain(error_bridge).collect();
+
+    // the Rust-ABI entries are pointed to by the last field, which foreign vtables leave null.
+    // `origin` identifies the compilation which created the vtable, as Rust-ABI functions may only be
+    // called from the same one
+    let (rust_entries, rust_field, rust_init) = match dual_abi {
+        false => (None, None, None),
+        true => (
+            Some(quote! {
+                #[repr(C)]
+                #allow_case
+                struct __RustEntries #trait_generics {
+                    origin: *const u8,
+                    #(#rust_fields)*
+                }
 
-        let lifetimes = generics.lifetimes();
+                static __ORIGIN: u8 = 0;
+
+                #(#rust_shims)*
+            }),
+            Some(quote! { rust: ::core::option::Option<&'static __RustEntries #ty_args>, }),
+            Some(quote! {
+                rust: ::core::option::Option::Some(&__RustEntries #turbofish {
+                    origin: &raw const __ORIGIN,
+                    #(#fn_names: #rust_shim_names::<#args __K>,)*
+                }),
+            }),
+        ),
+    };
 
-        let shim = quote! {
-            extern "C" fn #fn_name<#(#lifetimes,)* T: #trait_name> (#(#arg_names: #arg_types),*) #return_type {
-                // no references to the vtable should exist at this point
-                #un_erase_recv
-                T::#fn_name(#(#arg_names),*)
-            }
-        };
+    let first_extra = [
+        (display, "fmt_display"), (debug, "fmt_debug"), (error, "error_source"),
+        (extension, "extension"), (any, "type_id"), (dual_abi, "rust"),
+    ].into_iter().find(|(present, _)| *present).map(|(_, field)| Ident::new(field, Span::call_site()));
+    // the size slot records the extent of the method slots, so that a vtable created against an older
+    // version of the trait is known to lack the methods added since, rather than have the fields which
+    // follow the methods in their place
+    let slots_size = match first_extra {
+        None => quote! { ::core::mem::size_of::<#vtable_ty>() },
+        Some(field) => quote! { ::core::mem::offset_of!(#vtable_ty, #field) },
+    };
 
-        let mut lifetimes = generics.lifetimes();
-        let lifetimes: TokenStream2 = match lifetimes.next() {
-            Some(first) => quote! { <#first, #(#lifetimes),*> },
-            None => TokenStream2::new(),
-        };
+    // everything but the drop slot
+    let vtable_init = quote! {
+        size: __slots_size #turbofish(),
+        checksum: #private::VTableChecksum::UNSEALED,
+        #(#fn_names: #fn_names::<#args __K>,)*
+        #(#bridge_inits)*
+        #extension_init
+        #type_id_init
+        #rust_init
+    };
 
-        let trait_method_impl = quote! {
-            fn #fn_name #lifetimes (#(#args),*) #return_type {
-                let shim = {
-                    // SAFETY:
-                    // see https://adventures.michaelfbryan.com/posts/ffi-safe-polymorphism-in-rust/?utm_source=user-forums&utm_medium=social&utm_campaign=thin-trait-objects#pointer-to-vtable--object
-                    let vtable = unsafe { &*(self.ptr.as_ptr() as *const VTable) };
-                    vtable.#fn_name
-                    // reference to vtable dropped here?
+    // vtables are sealed on first use, as their checksum covers the slots (see `VTableChecksum`)
+    // the local is hygienic, so that it can't be mistaken for a constant of the same name
+    let vtable = Ident::new("vtable", Span::mixed_site());
+    let seal = expansion_unsafe(quote! { #private::expanded_seal(#vtable) });
+
+    // by-value downcasts free the bundle as a `Box`, so `any` objects can't share an allocation
+    let batched_drop = expansion_unsafe(quote! { #private::expanded_batched_drop::<#vtable_ty, __T>(ptr) });
+    let batched = match any {
+        true => None,
+        false => Some(quote! {
+            extern "C-unwind" fn __batched_drop<#params __T: #trait_path>(ptr: *mut ()) {
+                #batched_drop
+            }
+
+            // batched values get their own vtable too, as their chunk frees them
+            fn __batched_vtable<#params __K: #trait_path #extension_bound>() -> &'static #vtable_ty {
+                let #vtable = const {
+                    &__VTable #turbofish {
+                        drop: __batched_drop::<#args __K>,
+                        #vtable_init
+                    }
                 };
-                #erase_recv
-                shim(#(#arg_names),*)
+                #seal
             }
-        };
 
-        vtable_fields.push(vtable_field);
-        shims.push(shim);
-        trait_method_impls.push(trait_method_impl);
+            impl<#params __K: #trait_path #extension_bound> #private::ExpandedBatched<__K> for #dyn_trait {
+                fn batched_vtable() -> *const () {
+                    __batched_vtable::<#args __K>() as *const #vtable_ty as *const ()
+                }
+            }
+        }),
+    };
+
+    if c_free && !generics.is_empty() {
+        let message = format!("Error parsing {}: `c_free` isn't supported on traits with const parameters", trait_name);
+        return Err(syn::Error::new_spanned(trait_name, message));
     }
+    let free_name = c_free.then(|| Ident::new(&format!("{}_object_free", snake_case(&trait_name.to_string())), trait_name.span()));
+    // ignores null, like `free`
+    let free_fn = free_name.as_ref().map(|free_name| quote! {
+        #[unsafe(no_mangle)]
+        unsafe extern "C" fn #free_name(ptr: *mut ::core::ffi::c_void) {
+            if !ptr.is_null() {
+                ::core::mem::drop(unsafe { #prelude::Thin::<#dyn_trait>::from_c_void(ptr) });
+            }
+        }
+    });
+    let free_descriptor = match &free_name {
+        Some(free_name) => {
+            let free_str = free_name.to_string();
+            quote! { ::core::option::Option::Some(#free_str) }
+        }
+        None => quote! { ::core::option::Option::None },
+    };
 
-    quote! {
+    // hashed like the id of a derived `StableAny`, from the name `Thin<dyn Trait>` and the const arguments
+    let stable_any_impl = stable_any.then(|| {
+        let thin_str = format!("Thin<dyn {}>", trait_name);
+        let const_params = item_trait.generics.const_params().map(|param| &param.ident);
+        let stable_id = expansion_unsafe(quote! { #private::expanded_stable_id(hasher.finish()) });
+        let registration = generics.is_empty().then(|| quote! {
+            #prelude::__register_stable_any!(@named ::core::concat!(::core::module_path!(), "::", #thin_str), #prelude::Thin<#dyn_trait>);
+        });
+        quote! {
+            impl #trait_generics #private::ExpandedUUID for #dyn_trait {
+                const UUID: #prelude::StableTypeId = {
+                    let mut hasher = #prelude::StableHasher::new();
+                    hasher.write(::core::env!("CARGO_PKG_VERSION").as_bytes());
+                    hasher.write(::core::module_path!().as_bytes());
+                    hasher.write(#thin_str.as_bytes());
+                    #(hasher.write(&(#const_params as u128).to_le_bytes());)*
+                    #stable_id
+                };
+            }
+
+            #registration
+        }
+    });
+
+    let slot_count = fn_names.len();
+    // layout guarantees relied upon across the FFI boundary, checked for each instance of a generic trait
+    let assertions = quote! {
+        use ::core::mem::size_of;
+        ::core::assert!(
+            size_of::<#prelude::Thin<#dyn_trait>>() == size_of::<*mut ()>(),
+            "`Thin<dyn _>` must be pointer-sized",
+        );
+        ::core::assert!(
+            size_of::<::core::option::Option<#prelude::Thin<#dyn_trait>>>() == size_of::<*mut ()>(),
+            "`Option<Thin<dyn _>>` must be pointer-sized",
+        );
+        ::core::assert!(
+            __slots_size #turbofish() == (2 + #slot_count) * size_of::<extern "C" fn()>() + size_of::<#private::VTableChecksum>(),
+            "every vtable slot must be a single pointer-sized word",
+        );
+    };
+    let (layout_assertions, generic_layout_assertions) = match generics.is_empty() {
+        true => (Some(quote! { const _: () = { #assertions }; }), None),
+        false => (None, Some(quote! { const { #assertions }; })),
+    };
+    let vis = &item_trait.vis;
+    let slot_names = fn_names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+    let slot_consts = fn_names.iter().map(|name| {
+        Ident::new(&format!("SLOT_{}", name.to_string().to_uppercase()), name.span())
+    });
+
+    let token = expansion_unsafe(quote! { #private::ExpansionToken::new() });
+    let drop = expansion_unsafe(quote! { #private::expanded_drop::<#vtable_ty, __T>(ptr) });
+    let pinned_drop = expansion_unsafe(quote! { #private::expanded_pinned_drop::<#vtable_ty, __T>(ptr) });
+    let new_thin = expansion_unsafe(quote! { #private::expanded_thin(__vtable::<#args __K>(), value) });
+    let new_pinned_thin = expansion_unsafe(quote! {
+        #private::expanded_thin::<#dyn_trait, _, _>(__pinned_vtable::<#args __K>(), value)
+    });
+
+    Ok(quote! {
         #item_trait
 
         const _: () = {
+            // public, though unnameable, as it's `SlotLayout::VTable`
             #[repr(C)]
-            struct VTable {
-                drop: extern "C" fn(*mut ()),
+            #allow_case
+            pub struct __VTable #trait_generics {
+                drop: extern "C-unwind" fn(*mut ()),
+                // the extent of the method slots, which lets callers detect vtables created against
+                // an older version of the trait
+                size: usize,
+                // zero-sized without the `debug-checksum` feature
+                checksum: #private::VTableChecksum,
                 #(#vtable_fields)*
+                #(#bridge_fields)*
+                #extension_field
+                #type_id_field
+                #rust_field
             }
 
-            extern "C" fn drop<T: #trait_name>(ptr: *mut ()) {
-                let bundle = ptr as *mut Bundle<T>;
-                let _ = unsafe { Box::from_raw(bundle) };
+            #rust_entries
+
+            // the fields after the method slots are only read from vtables whose slots end there too
+            const fn __slots_size #trait_generics() -> usize {
+                #slots_size
+            }
+
+            #(#bridge_items)*
+
+            extern "C-unwind" fn __drop<#params __T: #trait_path>(ptr: *mut ()) {
+                #private::count_drop::<__T>(#trait_str);
+                #drop
+            }
+
+            extern "C-unwind" fn __pinned_drop<#params __T: #trait_path + #prelude::PinnedDrop>(ptr: *mut ()) {
+                #private::count_drop::<__T>(#trait_str);
+                #pinned_drop
             }
 
             #(#shims)*
 
-            #[repr(C)]
-            struct Bundle<T> {
-                vtable: VTable,
-                value: T
+            impl #trait_generics #prelude::SpecialAssoc for #dyn_trait {
+                type Kind = #prelude::Own;
+                #same_type
             }
 
-            impl SpecialAssoc for dyn #trait_name {
-                type Kind = Own;
+            // the bundles are `RawBundle`s of `__VTable`, which the `expanded_*` helpers rely on
+            impl #trait_generics #private::Expanded for #dyn_trait {
+                const TOKEN: #private::ExpansionToken = #token;
             }
 
-            impl<K: #trait_name> ThinExt<dyn #trait_name, K> for Thin<dyn #trait_name> {
-                fn new(value: K) -> Self {
-                    let vtable = VTable {
-                        drop: drop::<K>,
-                        #(#fn_names: #fn_names::<K>),*
-                    };
+            #any_impl
 
-                    let bundle = Bundle {
-                        vtable,
-                        value,
-                    };
+            #extension_impl
 
-                    let ptr = Box::into_raw(Box::new(bundle));
+            // one vtable per concrete type
+            fn __vtable<#params __K: #trait_path #extension_bound>() -> &'static #vtable_ty {
+                #generic_layout_assertions
+                let #vtable = const {
+                    &__VTable #turbofish {
+                        drop: __drop::<#args __K>,
+                        #vtable_init
+                    }
+                };
+                #seal
+            }
 
-                    unsafe { Thin::from_raw(ptr as *mut ()) }
+            // and another for pinned values with a `PinnedDrop`, differing only in the drop slot
+            fn __pinned_vtable<#params __K: #trait_path + #prelude::PinnedDrop #extension_bound>() -> &'static #vtable_ty {
+                let #vtable = const {
+                    &__VTable #turbofish {
+                        drop: __pinned_drop::<#args __K>,
+                        #vtable_init
+                    }
+                };
+                #seal
+            }
+
+            impl<#params __K: #trait_path #extension_bound> #private::ExpandedVTable<__K> for #dyn_trait {
+                fn vtable() -> *const () {
+                    __vtable::<#args __K>() as *const #vtable_ty as *const ()
                 }
             }
 
-            impl #trait_name for Thin<dyn #trait_name> {
-                #(#trait_method_impls)*
+            impl<#params __K: #trait_path #extension_bound> #prelude::ThinExt<#dyn_trait, __K> for #prelude::Thin<#dyn_trait> {
+                fn new(value: __K) -> Self {
+                    #private::count_new::<__K>(#trait_str);
+                    #new_thin
+                }
+            }
+
+            impl<#params __K: #trait_path + #prelude::PinnedDrop #extension_bound> #prelude::ThinPinnedDrop<#dyn_trait, __K> for #prelude::Thin<#dyn_trait> {
+                fn pin_with_drop(value: __K) -> #prelude::ThinPin<#dyn_trait> {
+                    #private::count_new::<__K>(#trait_str);
+                    #new_pinned_thin.into_pin()
+                }
+            }
+
+            #batched
+
+            extern "C" fn __clone_bundle<#params __T: #trait_path + ::core::clone::Clone #extension_bound>(recv: #prelude::RefSelf<'_>) -> #prelude::Thin<#dyn_trait> {
+                let value = #shim_value;
+                <#prelude::Thin<#dyn_trait> as #prelude::ThinExt<#dyn_trait, __T>>::new(value.clone())
+            }
+
+            impl<#params __K: #trait_path + ::core::clone::Clone #extension_bound> #prelude::ThinClone<#dyn_trait, __K> for #prelude::Thin<#dyn_trait> {
+                fn clone_fn() -> #prelude::CloneFn<#dyn_trait> {
+                    __clone_bundle::<#args __K>
+                }
+            }
+
+            #thin_impl
+
+            #thin_send
+
+            #thin_sync
+
+            #from_fn
+
+            #free_fn
+
+            #stable_any_impl
+
+            #layout_assertions
+
+            // slot `1` holds the size of the vtable, and slot `2` its checksum if it has one
+            impl #trait_generics #prelude::SlotLayout for #dyn_trait {
+                const SLOTS: &'static [&'static str] = match #private::VTABLE_CHECKSUM {
+                    true => &["drop", "size", "checksum", #(#slot_names),*],
+                    false => &["drop", "size", #(#slot_names),*],
+                };
+                const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+                const CHECKSUM_SLOT: ::core::option::Option<usize> = match #private::VTABLE_CHECKSUM {
+                    true => ::core::option::Option::Some(2),
+                    false => ::core::option::Option::None,
+                };
+                type VTable = #vtable_ty;
+            }
+
+            #[allow(dead_code)]
+            impl #trait_generics #dyn_trait {
+                /// Describes the vtable, for generating C headers (see `thin_trait_objects::codegen`).
+                #vis const DESCRIPTOR: #private::TraitDescriptor = #private::TraitDescriptor {
+                    name: #trait_str,
+                    version: ::core::env!("CARGO_PKG_VERSION"),
+                    methods: &[#(#method_descriptors),*],
+                    any: #any,
+                    extension: #extension,
+                    dual_abi: #dual_abi,
+                    free: #free_descriptor,
+                };
+
+                #(
+                    #vis const #slot_consts: usize =
+                        ::core::mem::offset_of!(#vtable_ty, #fn_names) / ::core::mem::size_of::<*const ()>();
+                )*
             }
         };
-    }.into()
+
+        #delegation_impls
+    })
+}
+
+/// The const parameters of a `#[thin]` trait, in the forms they're spliced into generated items in.
+/// Each is empty for traits without any.
+struct TraitGenerics {
+    /// `<const N: usize>`, declaring them on an item.
+    generics: TokenStream2,
+    /// `const N: usize,`, declaring them before an item's other parameters.
+    params: TokenStream2,
+    /// `<N>`, the arguments of a type.
+    ty_args: TokenStream2,
+    /// `N,`, the arguments before an item's other arguments.
+    args: TokenStream2,
+    /// `::<N>`, the arguments of a call or struct expression.
+    turbofish: TokenStream2,
+}
+
+impl TraitGenerics {
+    fn new(item_trait: &ItemTrait) -> syn::Result<Self> {
+        let generics = &item_trait.generics;
+        if let Some(param) = generics.params.iter().find(|param| !matches!(param, GenericParam::Const(_))) {
+            let message = format!("Error parsing {}: only const generic parameters are supported on `#[thin]` traits", item_trait.ident);
+            return Err(syn::Error::new_spanned(param, message));
+        }
+        let (idents, types): (Vec<_>, Vec<_>) = generics.const_params().map(|param| (&param.ident, &param.ty)).unzip();
+        if idents.is_empty() {
+            let empty = TokenStream2::new();
+            return Ok(TraitGenerics { generics: empty.clone(), params: empty.clone(), ty_args: empty.clone(), args: empty.clone(), turbofish: empty });
+        }
+        // defaults are only allowed on the trait itself
+        Ok(TraitGenerics {
+            generics: quote! { <#(const #idents: #types),*> },
+            params: quote! { #(const #idents: #types,)* },
+            ty_args: quote! { <#(#idents),*> },
+            args: quote! { #(#idents,)* },
+            turbofish: quote! { ::<#(#idents),*> },
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.generics.is_empty()
+    }
+}
+
+/// The generated items for a single method of a `#[thin]` trait.
+struct Method {
+    /// The name of the slot, which is the method's own unless it's an instantiation.
+    name: Ident,
+    /// The name of the method.
+    group: Ident,
+    mut_receiver: bool,
+    /// Whether the method can only be delegated to sized types, as it's excluded from `dyn Trait`.
+    sized_only: bool,
+    vtable_field: TokenStream2,
+    shim: TokenStream2,
+    trait_method_impl: TokenStream2,
+    delegation: TokenStream2,
+    /// The method of the `ThinMut<dyn Trait>` impl, unless it's excluded from `dyn Trait`.
+    view_delegation: TokenStream2,
+    /// The Rust-ABI entry of the method, and the function it points to, for `dual_abi` traits.
+    rust_field: TokenStream2,
+    rust_shim: TokenStream2,
+    rust_shim_name: Ident,
+    /// The `MethodDescriptor` of the method.
+    descriptor: TokenStream2,
+    /// The `Fn`/`FnMut` bound of a closure with the method's signature, if it can have one.
+    fn_bound: Option<TokenStream2>,
+    signature: TokenStream2,
+    forwarded_args: Vec<Ident>,
+    /// For an instantiation, the branch of the generic method's dispatcher calling its slot.
+    dispatch_arm: TokenStream2,
+}
+
+/// Expands the instantiations of a `#[thin(instantiate(...))]` method, the first of which carries
+/// the impls of the generic method itself.
+fn expand_instantiations(trait_name: &Ident, generics: &TraitGenerics, function: &TraitItemFn, param: &Ident, types: &[Type], dual_abi: bool, rust_only: bool) -> syn::Result<Vec<Method>> {
+    let private = private_path();
+    let mut methods = types.iter().map(|ty| {
+        let mut instance = function.clone();
+        instance.sig.generics.params = instance.sig.generics.params.into_iter().filter(|generic| {
+            !matches!(generic, GenericParam::Type(type_param) if type_param.ident == *param)
+        }).collect();
+        SubstituteType { param, ty }.visit_signature_mut(&mut instance.sig);
+        expand_method(trait_name, generics, &instance, dual_abi, rust_only, Some((ty, &function.sig)))
+    }).collect::<syn::Result<Vec<_>>>()?;
+
+    let trait_str = trait_name.to_string();
+    let fn_str = function.sig.ident.to_string();
+    let sig = &function.sig;
+    let arms = methods.iter().map(|method| &method.dispatch_arm);
+    methods[0].trait_method_impl = quote! {
+        #[inline]
+        #sig {
+            #(#arms)*
+            #private::missing_instantiation(#trait_str, #fn_str, ::core::any::type_name::<#param>())
+        }
+    };
+    for method in &mut methods[1..] {
+        method.delegation = TokenStream2::new();
+    }
+    Ok(methods)
+}
+
+/// The generated items for a `#[thin(skip)]` method.
+struct Skipped {
+    /// A panicking stub for impls which can't call the method, unless it has a default body.
+    stub: TokenStream2,
+    /// The methods of the `Box<K>` impl.
+    delegation: TokenStream2,
+    /// The methods of the `Arc<K>` and `Rc<K>` impls.
+    ref_delegation: TokenStream2,
+}
+
+/// Expands a `#[thin(skip)]` method, which has no slot, so can only be called on concrete types.
+fn expand_skipped(trait_name: &Ident, function: &TraitItemFn) -> Skipped {
+    let private = private_path();
+    let fn_name = &function.sig.ident;
+    let trait_str = trait_name.to_string();
+    let fn_str = fn_name.to_string();
+    let mut sig = function.sig.clone();
+    sig.generics.make_where_clause().predicates.push(parse_quote!(Self: ::core::marker::Sized));
+
+    let stub = match function.default {
+        Some(_) => TokenStream2::new(),
+        None => quote! {
+            #[allow(unused_variables)]
+            #sig {
+                #private::skipped_method(#trait_str, #fn_str)
+            }
+        },
+    };
+
+    // delegating to `__K` needs the arguments' names, and can't work if `Self` (which would be the
+    // delegating type rather than `__K`) is mentioned outside the receiver
+    let mut forwarded_args = Vec::new();
+    let mut receiver = None;
+    let mut delegable = !function.sig.generics.where_clause.as_ref().is_some_and(|where_clause| mentions_self(where_clause.to_token_stream()))
+        && !mentions_self(function.sig.output.to_token_stream());
+    for arg in &function.sig.inputs {
+        match arg {
+            FnArg::Receiver(recv) => receiver = Some(recv),
+            FnArg::Typed(pat_type) => match &*pat_type.pat {
+                Pat::Ident(PatIdent { ident, .. }) if !mentions_self(pat_type.ty.to_token_stream()) => forwarded_args.push(ident),
+                _ => delegable = false,
+            },
+        }
+    }
+
+    let (box_recv, ref_recv) = match receiver {
+        None => (Some(quote! {}), Some(quote! {})),
+        Some(recv) if recv.colon_token.is_some() => (None, None),
+        Some(recv) => match &recv.reference {
+            Some(_) if recv.mutability.is_some() => (Some(quote! { &mut **self, }), None),
+            Some(_) => (Some(quote! { &**self, }), Some(quote! { &**self, })),
+            None => (Some(quote! { *self, }), None),
+        },
+    };
+    // methods which can't be delegated fall back to their default body, or the stub
+    let delegate = |recv: Option<TokenStream2>| match recv {
+        Some(recv) if delegable => quote! {
+            #sig {
+                __K::#fn_name(#recv #(#forwarded_args),*)
+            }
+        },
+        _ => stub.clone(),
+    };
+    let delegation = delegate(box_recv);
+    let ref_delegation = delegate(ref_recv);
+
+    Skipped { stub, delegation, ref_delegation }
+}
+
+/// Replaces a type parameter with a type.
+struct SubstituteType<'a> {
+    param: &'a Ident,
+    ty: &'a Type,
+}
+
+impl VisitMut for SubstituteType<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        match ty {
+            Type::Path(TypePath { qself: None, path }) if path.is_ident(self.param) => *ty = self.ty.clone(),
+            _ => syn::visit_mut::visit_type_mut(self, ty),
+        }
+    }
+}
+
+/// Expands a method, or (with `instance`) an instantiation of a generic method, given the type it's
+/// instantiated with and the generic signature.
+fn expand_method(trait_name: &Ident, generics: &TraitGenerics, function: &TraitItemFn, dual_abi: bool, rust_only: bool, instance: Option<(&Type, &Signature)>) -> syn::Result<Method> {
+    let group = &function.sig.ident;
+    let prelude = prelude_path();
+    let private = private_path();
+    let TraitGenerics { params, ty_args, turbofish, .. } = generics;
+    let trait_path = quote! { #trait_name #ty_args };
+    let vtable_ty = quote! { __VTable #ty_args };
+    // instantiations are named after their type, e.g. `put__u8` or `put__Vec_u8`
+    let fn_name = &match instance {
+        None => group.clone(),
+        Some((ty, _)) => Ident::new(&format!("{}__{}", group, type_suffix(ty)), group.span()),
+    };
+    let callee = match instance {
+        None => quote! { #group },
+        Some((ty, _)) => quote! { #group::<#ty> },
+    };
+    // the generated locals can't shadow the method's arguments, nor be shadowed by them
+    let [recv, entry, vtable, size, whole, rust] = ["recv", "entry", "vtable", "size", "whole", "rust"]
+        .map(|name| Ident::new(name, Span::mixed_site()));
+
+    let generics = &function.sig.generics;
+    forbid_non_lifetime_generics(generics, trait_name, fn_name)?;
+    let lifetimes = generics.lifetimes().collect::<Vec<_>>();
+
+    let args = function.sig.inputs.iter().collect::<Vec<_>>();
+    let mut arg_names = Vec::with_capacity(args.len());
+    let mut arg_types = Vec::with_capacity(args.len());
+
+    //================//
+    // receiver
+
+    let Some(FnArg::Receiver(receiver)) = args.first() else {
+        let message = format!("{}::{} must have a receiver", trait_name, fn_name);
+        return Err(syn::Error::new_spanned(&function.sig, message));
+    };
+
+    // `&self` is parsed with a type of `&Self`, so the shorthand and typed forms look the same here
+    let receiver_error = || {
+        let message = format!("Error parsing `{}::{}`: the receiver must be `&self` or `&mut self`", trait_name, fn_name);
+        syn::Error::new_spanned(receiver, message)
+    };
+    let Type::Reference(TypeReference { lifetime, mutability, elem, .. }) = &*receiver.ty else {
+        return Err(receiver_error());
+    };
+    if !matches!(&**elem, Type::Path(TypePath { path, .. }) if path.is_ident("Self")) {
+        return Err(receiver_error());
+    }
+
+    // the lifetimes elided from the return type are the receiver's, so it's named for them if it's
+    // elided as well. those elided from other arguments are each a fresh one, like `'_` in inputs
+    let returns_elided = match &function.sig.output {
+        ReturnType::Type(_, ty) => elides_lifetimes(ty),
+        ReturnType::Default => false,
+    };
+    let (lt, named_receiver): (Lifetime, _) = match lifetime {
+        Some(lt) => (lt.clone(), None),
+        None if returns_elided => (parse_quote!('__self), Some(quote!('__self))),
+        None => (parse_quote!('_), None),
+    };
+    let bound_lifetimes = lifetimes.iter().map(|lt| quote!(#lt)).chain(named_receiver).collect::<Vec<_>>();
+
+    let mut_receiver = mutability.is_some();
+    let (recv_type, erase_recv, un_erase_recv, delegate_recv): (Type, _, _, _) = match mut_receiver {
+        false => (
+            parse_quote!(#prelude::RefSelf<#lt>),
+            quote! { let #recv = #prelude::RefSelf::new(self); },
+            {
+                let value = expansion_unsafe(quote! { #private::expanded_value::<#vtable_ty, __T>(#recv) });
+                quote! { let #recv = #value; }
+            },
+            quote! { &**self },
+        ),
+        true => (
+            parse_quote!(#prelude::MutSelf<#lt>),
+            quote! { let #recv = #prelude::MutSelf::new(self); },
+            {
+                let value = expansion_unsafe(quote! { #private::expanded_value_mut::<#vtable_ty, __T>(#recv) });
+                quote! { let #recv = #value; }
+            },
+            quote! { &mut **self },
+        ),
+    };
+
+    arg_names.push(recv.clone());
+    arg_types.push(recv_type);
+
+    //================//
+    // non-receiver arguments
+
+    for arg in &args[1..] {
+        // syn only accepts a receiver as the first argument
+        let FnArg::Typed(pat_type) = arg else {
+            return Err(syn::Error::new_spanned(arg, format!("Error parsing argument of {}::{}", trait_name, fn_name)));
+        };
+
+        let arg_name = match &*pat_type.pat {
+            Pat::Ident(PatIdent { ident: name, .. }) => name,
+            pat => return Err(syn::Error::new_spanned(pat, format!("Error parsing argument of {}::{}", trait_name, fn_name))),
+        };
+
+        arg_names.push(arg_name.clone());
+
+        let mut arg_type = (*pat_type.ty).clone();
+        if let Err(ty) = un_elide_lifetimes(&mut arg_type, &parse_quote!('_)) {
+            let message = format!("Error parsing `{}::{}`: Arguments of type `{}` not supported", trait_name, fn_name, quote!(#ty));
+            return Err(syn::Error::new_spanned(&pat_type.ty, message));
+        }
+        if !rust_only {
+            check_ffi_safe(&pat_type.ty, trait_name, fn_name)?;
+        }
+
+        arg_types.push(arg_type);
+    }
+
+    //================//
+    // return type
+
+    // `impl Trait` is returned through the slot as a `Thin<dyn Trait>`, which implements the trait
+    let erased_return = match &function.sig.output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::ImplTrait(impl_trait) => {
+                let bounds = impl_trait.bounds.iter().filter(|bound| matches!(bound, TypeParamBound::Trait(_)));
+                Some(quote! { dyn #(#bounds)+* })
+            }
+            _ => None,
+        },
+        ReturnType::Default => None,
+    };
+
+    let mut return_type = function.sig.output.clone();
+    if let Some(dyn_return) = &erased_return {
+        return_type = parse_quote!(-> #prelude::Thin<#dyn_return>);
+    } else if let ReturnType::Type(_, ty) = &mut return_type {
+        match &**ty {
+            // an explicit `-> ()` is the same as no return type
+            Type::Tuple(TypeTuple { elems, .. }) if elems.is_empty() => return_type = ReturnType::Default,
+            // diverging methods need no un-eliding, and `-> !` is allowed in `extern "C"` signatures
+            Type::Never(_) => {}
+            _ => {
+                if let Err(ty) = un_elide_lifetimes(ty, &lt) {
+                    let message = format!("Error parsing `{}::{}`: `{}` is not supported in return types", trait_name, fn_name, quote!(#ty));
+                    return Err(syn::Error::new_spanned(&function.sig.output, message));
+                }
+                if !rust_only {
+                    check_ffi_safe(ty, trait_name, fn_name)?;
+                }
+            }
+        }
+    }
+
+    //================//
+    // putting it all together
+
+    let for_clause = (!bound_lifetimes.is_empty()).then(|| quote! { for<#(#bound_lifetimes),*> });
+    let method_generics = (!lifetimes.is_empty()).then(|| quote! { <#(#lifetimes),*> });
+
+    // the names of instantiations keep their type's case
+    let allow_case = instance.map(|_| quote! { #[allow(non_snake_case)] });
+
+    let vtable_field = quote! {
+        #fn_name: #for_clause extern "C" fn (#(#arg_types),*) #return_type,
+    };
+
+    let trait_str = trait_name.to_string();
+    let fn_str = fn_name.to_string();
+
+    // the signature of the method itself, rather than of its slot, whose lifetimes may be named
+    let method_return_type = &function.sig.output;
+    let call_method = match &erased_return {
+        None => quote! { __T::#callee(#(#arg_names),*) },
+        Some(dyn_return) => quote! {
+            <#prelude::Thin<#dyn_return> as #prelude::ThinExt<#dyn_return, _>>::new(__T::#callee(#(#arg_names),*))
+        },
+    };
+
+    // surfaces non-FFI-safe argument and return types, which are otherwise silenced in macro output
+    let ffi_lint = match rust_only {
+        false => quote! { #[warn(improper_ctypes_definitions)] },
+        true => quote! { #[allow(improper_ctypes_definitions)] },
+    };
+    let shim = quote! {
+        #ffi_lint
+        #allow_case
+        extern "C" fn #fn_name<#(#bound_lifetimes,)* #params __T: #trait_path> (#(#arg_names: #arg_types),*) #return_type {
+            #private::count_call::<__T>(#trait_str, #fn_str);
+            // no references to the vtable should exist at this point
+            #un_erase_recv
+            // panics can't unwind out of the shim, so they're raised again with their context
+            #private::dispatch::<__T, _>(#trait_str, #fn_str, move || #return_type { #call_method })
+        }
+    };
+
+    // the same as the `extern "C"` shim, minus the calling convention
+    let rust_shim_name = Ident::new(&format!("__rust_abi_{}", fn_name), fn_name.span());
+    let rust_field = quote! {
+        #fn_name: #for_clause fn (#(#arg_types),*) #return_type,
+    };
+    let rust_shim = quote! {
+        #allow_case
+        fn #rust_shim_name<#(#bound_lifetimes,)* #params __T: #trait_path> (#(#arg_names: #arg_types),*) #return_type {
+            #private::count_call::<__T>(#trait_str, #fn_str);
+            #un_erase_recv
+            #call_method
+        }
+    };
+
+    // only vtables with the same slots have the pointer to the Rust-ABI entries, so `origin` is read
+    // only from those
+    let load_whole = expansion_unsafe(quote! { #private::expanded_whole_vtable(#vtable, #size, __slots_size #turbofish()) });
+    let fast_path = match dual_abi {
+        false => None,
+        true => Some(quote! {
+            if let ::core::option::Option::Some(#whole) = #load_whole {
+                if let ::core::option::Option::Some(#rust) = #whole.rust.filter(|#rust| #rust.origin == &raw const __ORIGIN) {
+                    let #entry = #rust.#fn_name;
+                    #erase_recv
+                    return #entry(#(#arg_names),*);
+                }
+            }
+        }),
+    };
+
+    // types are recorded as written, with tokens separated by spaces
+    let arg_strs = arg_names[1..].iter().map(|name| name.to_string());
+    let arg_type_strs = args[1..].iter().map(|arg| match arg {
+        FnArg::Typed(pat_type) => pat_type.ty.to_token_stream().to_string(),
+        FnArg::Receiver(_) => unreachable!(),
+    });
+    let return_str = match (&return_type, &erased_return) {
+        (_, Some(dyn_return)) => quote!(Thin<#dyn_return>).to_string(),
+        (ReturnType::Type(_, ty), None) => ty.to_token_stream().to_string(),
+        (ReturnType::Default, None) => "()".to_string(),
+    };
+    let descriptor = quote! {
+        #private::MethodDescriptor {
+            name: #fn_str,
+            mut_receiver: #mut_receiver,
+            args: &[#((#arg_strs, #arg_type_strs)),*],
+            ret: #return_str,
+        }
+    };
+    // the vtable is loaded apart from the slot, so that LLVM can hoist the load out of loops
+    let load_vtable = expansion_unsafe(quote! { #private::expanded_vtable::<#vtable_ty>(self.ptr) });
+    let load_size = expansion_unsafe(quote! { #private::expanded_vtable_size(#vtable) });
+    let load_slot = expansion_unsafe(quote! {
+        #private::expanded_slot(#vtable, #size, offset, |#vtable| &#vtable.#fn_name, #trait_str, #fn_str)
+    });
+    let call = quote! {
+            let #vtable = #load_vtable;
+            let #entry = {
+                let #size = #load_size;
+                #fast_path
+                let offset = ::core::mem::offset_of!(#vtable_ty, #fn_name);
+                #load_slot
+            };
+            #erase_recv
+            #entry(#(#arg_names),*)
+    };
+    let forwarded_args = &arg_names[1..];
+
+    // `ThinMut<dyn Trait>` forwards to the impl for `Thin<dyn Trait>`, which it only lends mutably
+    // for the call
+    let view_recv = match mut_receiver {
+        false => quote! { &**self },
+        true => expansion_unsafe(quote! { #prelude::ThinMut::as_thin_mut(self) }),
+    };
+    let view_delegation = match (instance, &erased_return) {
+        (None, None) => quote! {
+            #[inline]
+            fn #fn_name #method_generics (#(#args),*) #method_return_type {
+                <#prelude::Thin<dyn #trait_path> as #trait_path>::#fn_name(#view_recv, #(#forwarded_args),*)
+            }
+        },
+        _ => TokenStream2::new(),
+    };
+
+    // instantiations are called through the generic method, once its parameter is known to be their type
+    let (trait_method_impl, delegation, dispatch_arm) = match instance {
+        None => (
+            quote! {
+                #[inline]
+                fn #fn_name #method_generics (#(#args),*) #method_return_type {
+                    #call
+                }
+            },
+            quote! {
+                fn #fn_name #method_generics (#(#args),*) #method_return_type {
+                    __K::#fn_name(#delegate_recv, #(#forwarded_args),*)
+                }
+            },
+            TokenStream2::new(),
+        ),
+        Some((ty, generic_sig)) => {
+            let param = generic_sig.generics.type_params().find(|type_param| {
+                !function.sig.generics.type_params().any(|other| other.ident == type_param.ident)
+            }).map(|type_param| &type_param.ident).unwrap();
+            let ret = Ident::new("ret", Span::mixed_site());
+            let cast_args = forwarded_args.iter().map(|arg| expansion_unsafe(quote! { #private::expanded_cast(#arg) }));
+            let cast_ret = expansion_unsafe(quote! { #private::expanded_cast(#ret) });
+            (
+                TokenStream2::new(),
+                quote! {
+                    #generic_sig {
+                        __K::#group(#delegate_recv, #(#forwarded_args),*)
+                    }
+                },
+                quote! {
+                    if ::core::any::TypeId::of::<#param>() == ::core::any::TypeId::of::<#ty>() {
+                        // the parameter is `#ty`, so the generic and instantiated types are the same
+                        #(let #forwarded_args = #cast_args;)*
+                        let #ret = { #call };
+                        return #cast_ret;
+                    }
+                },
+            )
+        }
+    };
+
+    // the receiver's lifetime can't be named in a closure's signature
+    let closure_args = args[1..].iter().map(|arg| match arg {
+        FnArg::Typed(pat_type) => &pat_type.ty,
+        FnArg::Receiver(_) => unreachable!(),
+    });
+    let returns_borrow = match &function.sig.output {
+        ReturnType::Type(_, ty) => mentions_lifetime(quote!(#ty)),
+        ReturnType::Default => false,
+    };
+    let closure_trait = match mut_receiver {
+        false => quote! { ::core::ops::Fn },
+        true => quote! { ::core::ops::FnMut },
+    };
+    let output = &function.sig.output;
+    let fn_bound = match lifetimes.is_empty() && !returns_borrow && instance.is_none() && erased_return.is_none() {
+        true => Some(quote! { #closure_trait(#(#closure_args),*) #output }),
+        false => None,
+    };
+    let signature = quote! { fn #fn_name(#(#args),*) #method_return_type };
+
+    Ok(Method {
+        name: fn_name.clone(),
+        group: group.clone(),
+        mut_receiver,
+        sized_only: erased_return.is_some(),
+        vtable_field,
+        shim,
+        trait_method_impl,
+        delegation,
+        view_delegation,
+        rust_field,
+        rust_shim,
+        rust_shim_name,
+        descriptor,
+        fn_bound,
+        signature,
+        forwarded_args: forwarded_args.to_vec(),
+        dispatch_arm,
+    })
 }
 
-/// Un-elides a `Types`s lifetimes by inserting `'_` where explicit lifetimes would otherwise be.
-fn un_elide_lifetimes(ty: &mut Type) -> Result<(), Type> {
+/// The suffix of the slot of an instantiation, from its type with every run of non-alphanumeric
+/// characters replaced by an underscore.
+fn type_suffix(ty: &Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let words = tokens.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty());
+    words.collect::<Vec<_>>().join("_")
+}
+
+/// Un-elides a `Types`s lifetimes by inserting `lt` where explicit lifetimes would otherwise be, and
+/// in place of `'_`.
+fn un_elide_lifetimes(ty: &mut Type, lt: &Lifetime) -> Result<(), Box<Type>> {
     // TODO: support for more types
     //  - arrays
     //  - slices
@@ -242,72 +1338,313 @@ fn un_elide_lifetimes(ty: &mut Type) -> Result<(), Type> {
                     PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, ..}) => {
                         for arg in args {
                             match arg {
-                                GenericArgument::Type(ty) => un_elide_lifetimes(ty)?,
+                                GenericArgument::Type(ty) => un_elide_lifetimes(ty, lt)?,
+                                GenericArgument::Lifetime(arg) if arg.ident == "_" => *arg = lt.clone(),
                                 _ => {}
                             }
                         }
                     }
                     PathArguments::None => {}
-                    _ => return Err(ty.clone()),
+                    _ => return Err(Box::new(ty.clone())),
                 }
             }
         }
-        Type::Reference(TypeReference { lifetime, .. }) => {
-            match lifetime {
-                None => *lifetime = Some(parse_quote!('_)),
-                _ => {}
-            }
-        }
+        Type::Reference(TypeReference { lifetime, .. }) => match lifetime {
+            Some(lifetime) if lifetime.ident != "_" => {}
+            _ => *lifetime = Some(lt.clone()),
+        },
         Type::Tuple(TypeTuple { elems, .. }) => {
             for elem in elems {
-                un_elide_lifetimes(elem)?
+                un_elide_lifetimes(elem, lt)?
             }
         }
 
-        _ => return Err(ty.clone()),
+        _ => return Err(Box::new(ty.clone())),
     };
 
     Ok(())
 }
 
-fn forbid_non_lifetime_generics(generics: &Generics, trait_name: &Ident, fn_name: &Ident) {
-    let type_generics = generics.type_params();
-    for _ in type_generics {
-        panic!("Error parsing `{}::{}`: type generics are not supported", trait_name, fn_name);
+/// Whether any of a type's lifetimes are elided, or `'_`.
+fn elides_lifetimes(ty: &Type) -> bool {
+    struct Elided(bool);
+
+    impl Visit<'_> for Elided {
+        fn visit_type_reference(&mut self, reference: &TypeReference) {
+            self.0 |= reference.lifetime.is_none();
+            syn::visit::visit_type_reference(self, reference);
+        }
+
+        fn visit_lifetime(&mut self, lifetime: &Lifetime) {
+            self.0 |= lifetime.ident == "_";
+        }
+    }
+
+    let mut elided = Elided(false);
+    elided.visit_type(ty);
+    elided.0
+}
+
+/// Rejects the types which are known not to be FFI-safe, unlike `improper_ctypes_definitions`
+/// which can't see through the generic shims and only warns.
+fn check_ffi_safe(ty: &Type, trait_name: &Ident, fn_name: &Ident) -> syn::Result<()> {
+    match ffi_unsafety(ty) {
+        None => Ok(()),
+        Some((reason, companion)) => {
+            let companion = companion.map(|companion| format!("pass a `{}` instead, or ", companion)).unwrap_or_default();
+            let message = format!(
+                "Error parsing `{}::{}`: `{}` is not FFI-safe, as {} ({}use `#[thin(rust_only)]` if objects are only shared between Rust code built by the same compiler)",
+                trait_name, fn_name, quote!(#ty), reason, companion,
+            );
+            Err(syn::Error::new_spanned(ty, message))
+        }
+    }
+}
+
+/// Why the type can't cross an `extern "C"` boundary, if it's one of those known not to, and the
+/// FFI-safe type of the `ffi` module which may be passed instead, if any.
+fn ffi_unsafety(ty: &Type) -> Option<(&'static str, Option<&'static str>)> {
+    const WIDE: &str = "references to unsized types are wide pointers";
+    match ty {
+        Type::Reference(TypeReference { elem, mutability, .. }) => match &**elem {
+            Type::Slice(_) if mutability.is_none() => Some((WIDE, Some("ThinSlice"))),
+            Type::Slice(_) | Type::TraitObject(_) => Some((WIDE, None)),
+            Type::Path(TypePath { path, .. }) if path.is_ident("str") => Some((WIDE, Some("ThinStr"))),
+            _ => None,
+        },
+        Type::Tuple(TypeTuple { elems, .. }) if !elems.is_empty() => Some(("tuples have no stable layout", None)),
+        Type::Path(TypePath { qself: None, path }) => {
+            let segment = path.segments.last()?;
+            let type_args = || match &segment.arguments {
+                PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) => args.iter().filter_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                }).collect(),
+                _ => Vec::new(),
+            };
+            match segment.ident.to_string().as_str() {
+                "char" => Some(("`char` has no C equivalent", None)),
+                "u128" | "i128" => Some(("128-bit integers have no stable C ABI", None)),
+                "String" => Some(("it has no stable layout", Some("ThinString"))),
+                "Vec" => Some(("it has no stable layout", Some("ThinVecFfi"))),
+                "VecDeque" | "HashMap" | "HashSet" | "BTreeMap" | "BTreeSet" | "Rc" | "Arc" | "Cow" => Some(("it has no stable layout", None)),
+                "Box" => match type_args().first() {
+                    Some(Type::Slice(_)) => Some(("boxes of unsized types are wide pointers", Some("ThinVecFfi"))),
+                    Some(Type::TraitObject(_)) => Some(("boxes of unsized types are wide pointers", None)),
+                    Some(Type::Path(TypePath { path, .. })) if path.is_ident("str") => Some(("boxes of unsized types are wide pointers", Some("ThinString"))),
+                    _ => None,
+                },
+                // only the null pointer optimisation gives `Option`s a defined layout
+                "Option" => match type_args().first() {
+                    Some(Type::Reference(_)) => None,
+                    Some(Type::Path(TypePath { path, .. })) if path.segments.last().is_some_and(|segment| {
+                        ["Box", "NonNull", "Thin", "NonZeroU8", "NonZeroU16", "NonZeroU32", "NonZeroU64", "NonZeroUsize"].iter().any(|name| segment.ident == name)
+                    }) => None,
+                    Some(Type::BareFn(_)) => None,
+                    Some(_) => Some(("`Option` only has a defined layout around references, `Box`, `NonNull`, non-zero integers and thin objects", None)),
+                    None => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
     }
+}
+
+/// The options of the `#[thin(...)]` attributes on a method.
+#[derive(Default)]
+struct MethodOptions {
+    hot: bool,
+    /// The type parameter and the types it's instantiated with, from `#[thin(instantiate(T = u8, u32))]`.
+    instantiate: Option<(Ident, Vec<Type>)>,
+    skip: bool,
+}
+
+/// Removes the `#[thin(...)]` attributes from a method, returning their options.
+fn take_method_options(attrs: &mut Vec<Attribute>) -> syn::Result<MethodOptions> {
+    let mut options = MethodOptions::default();
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("thin") {
+            return true;
+        }
+        if let Err(err) = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("hot") {
+                options.hot = true;
+                Ok(())
+            } else if meta.path.is_ident("instantiate") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let param = content.parse::<Ident>()?;
+                content.parse::<Token![=]>()?;
+                let types = Punctuated::<Type, Token![,]>::parse_terminated(&content)?;
+                options.instantiate = Some((param, types.into_iter().collect()));
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `hot`, `instantiate` or `skip`"))
+            }
+        }) {
+            result = Err(syn::Error::new(err.span(), format!("Error parsing `#[thin(...)]` on a method: {}", err)));
+        }
+        false
+    });
+    result.map(|()| options)
+}
+
+/// Whether `tokens` contain a reference or a lifetime.
+fn mentions_lifetime(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Punct(punct) => punct.as_char() == '&' || punct.as_char() == '\'',
+        TokenTree::Group(group) => mentions_lifetime(group.stream()),
+        _ => false,
+    })
+}
 
-    let const_generics = generics.const_params();
-    for _ in const_generics {
-        panic!("Error parsing `{}::{}`: const generics are not supported", trait_name, fn_name);
+/// Converts a trait name (`FooBar`) to snake case (`foo_bar`).
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.char_indices() {
+        if c.is_uppercase() && i > 0 && !name[..i].ends_with('_') {
+            snake.push('_');
+        }
+        snake.extend(c.to_lowercase());
     }
+    snake
+}
+
+/// Whether `tokens` mention `Self`.
+fn mentions_self(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "Self",
+        TokenTree::Group(group) => mentions_self(group.stream()),
+        _ => false,
+    })
+}
+
+fn forbid_non_lifetime_generics(generics: &Generics, trait_name: &Ident, fn_name: &Ident) -> syn::Result<()> {
+    if let Some(type_param) = generics.type_params().next() {
+        let message = format!("Error parsing `{}::{}`: type generics are only supported with `#[thin(instantiate(...))]`", trait_name, fn_name);
+        return Err(syn::Error::new_spanned(type_param, message));
+    }
+
+    if let Some(const_param) = generics.const_params().next() {
+        let message = format!("Error parsing `{}::{}`: const generics are not supported", trait_name, fn_name);
+        return Err(syn::Error::new_spanned(const_param, message));
+    }
+    Ok(())
 }
 
 //=================//
 
-#[proc_macro_derive(StableAny)]
+#[proc_macro_derive(StableAny, attributes(stable_any))]
 pub fn stable_any_derive(item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as DeriveInput);
+    let item_ident = &item.ident;
+
+    let (seed, implements) = match parse_stable_any_attrs(&item.attrs) {
+        Ok(attrs) => attrs,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let (Some(implemented), false) = (implements.first(), item.generics.params.is_empty()) {
+        let message = "`implements` is only supported on types without generics, as only those are registered";
+        return syn::Error::new_spanned(implemented, message).to_compile_error().into();
+    }
 
-    let mut path = Punctuated::<Ident, Token![::]>::new();
-    path.push_value(item.ident);
+    let (_, ty_generics, _) = item.generics.split_for_impl();
+    let ty = parse_quote!(#item_ident #ty_generics);
+    let name = item.ident.to_string();
 
-    let _impl = generate_uuid_impl(path, item.generics);
+    let _impl = generate_uuid_impl(ty, &name, &item.generics, seed, &implements);
 
     quote! {
         #_impl
     }.into()
 }
 
+/// What the hash of a type's `StableTypeId` starts from.
+enum IdSeed {
+    /// The type's current module path and name.
+    Here,
+    /// A former module path and name, from `#[stable_any(unsafe(rename_from = "path::to::OldName"))]`.
+    Renamed { module: String, name: String },
+    /// A fixed id, from `#[stable_any(unsafe(id = ...))]`, which is the type's id if it has no generics.
+    Pinned(u64),
+}
+
+/// Parses the `#[stable_any(...)]` attributes of a derive: the seed of the type's id, and the thin
+/// traits listed in `implements(...)`.
+///
+/// A pinned or former id may be another type's as well, which would let downcasts reinterpret one as
+/// the other, so they must be written `unsafe(id = ...)` and `unsafe(rename_from = "...")`.
+fn parse_stable_any_attrs(attrs: &[Attribute]) -> syn::Result<(IdSeed, Vec<Path>)> {
+    let mut seed = IdSeed::Here;
+    let mut implements = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("stable_any")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("implements") {
+                return meta.parse_nested_meta(|implemented| {
+                    implements.push(implemented.path);
+                    Ok(())
+                });
+            }
+            if meta.path.is_ident("rename_from") || meta.path.is_ident("id") {
+                let option = meta.path.get_ident().unwrap();
+                let message = format!("`{0}` may give the type another's id, so must be written `unsafe({0} = ...)`", option);
+                return Err(meta.error(message));
+            }
+            if !meta.path.is_ident("unsafe") {
+                return Err(meta.error("expected `unsafe(rename_from = ...)`, `unsafe(id = ...)` or `implements`"));
+            }
+            meta.parse_nested_meta(|meta| {
+                if !matches!(seed, IdSeed::Here) {
+                    return Err(meta.error("only one of `rename_from` and `id` may be given"));
+                }
+                if meta.path.is_ident("rename_from") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    let path = lit.value();
+                    let Some((module, name)) = path.rsplit_once("::") else {
+                        return Err(syn::Error::new(lit.span(), "expected a path like `my_crate::module::Type`"));
+                    };
+                    seed = IdSeed::Renamed { module: module.to_string(), name: name.to_string() };
+                    Ok(())
+                } else if meta.path.is_ident("id") {
+                    let id = meta.value()?.parse::<LitInt>()?;
+                    seed = IdSeed::Pinned(id.base10_parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename_from` or `id`"))
+                }
+            })
+        })?;
+    }
+    Ok((seed, implements))
+}
+
 //=================//
 
-/// Represents an item inside the `impl_stable_any` macro.
+/// Represents an item inside the `impl_stable_any` macro, either the shorthand `path::Name<T, const N: usize>`,
+/// whose parameters are also its arguments, or `impl<T: Bound> Type<T, u8> where ...`, for any other type.
 struct ImplItem {
-    path: Punctuated<Ident, Token![::]>,
-    generics: Punctuated<GenericParam, Token![,]>,
-    where_clause: Option<WhereClause>,
+    ty: Type,
+    /// What the id is hashed from, along with the module path.
+    name: String,
+    generics: Generics,
 }
 impl Parse for ImplItem {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![impl]) {
+            input.parse::<Token![impl]>()?;
+            let mut generics = input.parse::<Generics>()?;
+            let ty = input.parse::<Type>()?;
+            generics.where_clause = input.parse::<Option<WhereClause>>()?;
+            // the arguments needn't be the parameters, so they're part of the name, e.g. `HashMap<K,u8>`
+            let name = ty.to_token_stream().to_string().replace(' ', "");
+            return Ok(ImplItem { ty, name, generics });
+        }
+
         let _ = input.parse::<Token![::]>();
 
         let path = Punctuated::<Ident, Token![::]>::parse_separated_nonempty(input)?;
@@ -322,11 +1659,17 @@ impl Parse for ImplItem {
             Err(_) => (Punctuated::new(), None),
         };
 
-        Ok(ImplItem {
-            path,
-            generics,
+        let generics = Generics {
+            lt_token: Some(parse_quote!(<)),
+            params: generics,
+            gt_token: Some(parse_quote!(>)),
             where_clause,
-        })
+        };
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let ty = parse_quote!(#path #ty_generics);
+        let name = path.last().unwrap().to_string();
+
+        Ok(ImplItem { ty, name, generics })
     }
 }
 
@@ -341,18 +1684,7 @@ impl Parse for ImplItems {
 pub fn impl_stable_any(input: TokenStream) -> TokenStream {
     let type_list = parse_macro_input!(input as ImplItems);
 
-    let mut impls = Vec::new();
-    for item in type_list.0 {
-        let path = item.path;
-        let generics = Generics {
-            lt_token: parse_quote!(<),
-            params: item.generics,
-            gt_token: parse_quote!(>),
-            where_clause: item.where_clause,
-        };
-
-        impls.push(generate_uuid_impl(path, generics));
-    }
+    let impls = type_list.0.iter().map(|item| generate_uuid_impl(item.ty.clone(), &item.name, &item.generics, IdSeed::Here, &[]));
 
     quote! {
         #(#impls)*
@@ -361,9 +1693,9 @@ pub fn impl_stable_any(input: TokenStream) -> TokenStream {
 
 //=================//
 
-/// Generates implementations of `UUID` and `StableAny` for the type given by `path` with the given generics.
-fn generate_uuid_impl(ty: Punctuated<Ident, Token![::]>, generics: Generics) -> TokenStream2 {
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+/// Generates implementations of `UUID` and `StableAny` for `ty`, whose id is hashed from `name`, with the given generics.
+fn generate_uuid_impl(ty: Type, name: &str, generics: &Generics, seed: IdSeed, implements: &[Path]) -> TokenStream2 {
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
 
     let type_params = &generics.type_params().map(
         |param| {
@@ -371,27 +1703,49 @@ fn generate_uuid_impl(ty: Punctuated<Ident, Token![::]>, generics: Generics) ->
         }
     ).collect::<Vec<_>>();
 
-    let where_clause = match where_clause {
-        Some(where_clause) => {
-            quote! { #where_clause, #(#type_params: StableAny),* }
-        },
-        None => {
-            quote! { where #(#type_params: StableAny),* }
-        },
-    };
+    let predicates = where_clause.map(|where_clause| &where_clause.predicates).into_iter().flatten();
+    let where_clause = quote! { where #(#predicates,)* #(#type_params: StableAny),* };
 
     let const_params = generics.const_params().map(|param| { &param.ident }).collect::<Vec<_>>();
 
-    let name_string = ty.last().unwrap().to_string();
-    let name_str = name_string.as_str();
+    // only concrete types can be listed in the registry, along with the thin traits they implement
+    let registration = match generics.params.is_empty() {
+        true => Some(quote! { __register_stable_any!(#ty #(, dyn #implements)*); }),
+        false => None,
+    };
 
-    quote! {
-        unsafe impl #impl_generics UUID for #ty #ty_generics #where_clause {
-            const UUID: StableTypeId = {
-                let mut hasher = const_siphasher::sip::SipHasher13::new();
+    let seed = match seed {
+        IdSeed::Pinned(id) if type_params.is_empty() && const_params.is_empty() => {
+            return quote! {
+                unsafe impl #impl_generics UUID for #ty #where_clause {
+                    const UUID: StableTypeId = unsafe { StableTypeId::new(#id) };
+                }
+
+                #registration
+            };
+        }
+        IdSeed::Pinned(id) => quote! {
+            hasher.write_u64(#id);
+        },
+        IdSeed::Renamed { module, name } => quote! {
+            hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+            hasher.write(#module.as_bytes());
+            hasher.write(#name.as_bytes());
+        },
+        IdSeed::Here => {
+            quote! {
                 hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
                 hasher.write(module_path!().as_bytes());
-                hasher.write(#name_str.as_bytes());
+                hasher.write(#name.as_bytes());
+            }
+        }
+    };
+
+    quote! {
+        unsafe impl #impl_generics UUID for #ty #where_clause {
+            const UUID: StableTypeId = {
+                let mut hasher = StableHasher::new();
+                #seed
                 unsafe { #(
                     hasher.write_u64(#type_params::Inner::UUID.to_u64());
                 )* }
@@ -402,5 +1756,346 @@ fn generate_uuid_impl(ty: Punctuated<Ident, Token![::]>, generics: Generics) ->
                 unsafe { StableTypeId::new(id) }
             };
         }
+
+        #registration
+    }
+}
+//=================//
+
+/// Represents the input of the `export_thin_plugin` macro: `Trait for Type`, optionally followed by
+/// `= constructor`.
+struct PluginExport {
+    trait_path: Path,
+    ty: TypePath,
+    constructor: Option<syn::Expr>,
+}
+impl Parse for PluginExport {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let trait_path = input.parse::<Path>()?;
+        input.parse::<Token![for]>()?;
+        let ty = input.parse::<TypePath>()?;
+        let constructor = match input.parse::<Option<Token![=]>>()? {
+            Some(_) => Some(input.parse::<syn::Expr>()?),
+            None => None,
+        };
+        let _ = input.parse::<Option<Token![;]>>()?;
+        Ok(PluginExport { trait_path, ty, constructor })
+    }
+}
+
+#[proc_macro]
+pub fn export_thin_plugin(input: TokenStream) -> TokenStream {
+    let PluginExport { trait_path, ty, constructor } = parse_macro_input!(input as PluginExport);
+
+    let trait_name = trait_path.segments.last().unwrap().ident.to_string();
+    let type_name = ty.path.segments.last().unwrap().ident.to_string();
+    // must match `thin_trait_objects::plugin::symbol_name`
+    let symbol = Ident::new(&format!("THIN_PLUGIN__{}__{}", trait_name, type_name), Span::call_site());
+    let trait_name = LitStr::new(&format!("{}\0", trait_name), Span::call_site());
+    let type_name = LitStr::new(&format!("{}\0", type_name), Span::call_site());
+    let constructor = constructor.unwrap_or_else(|| parse_quote!(<#ty as ::std::default::Default>::default()));
+    let prelude = prelude_path();
+    let private = private_path();
+
+    // the constructor unwinds into the host if the constructor expression panics, as the drop slot
+    // does; the method slots are `extern "C"`, where `dispatch` turns a panic into an abort
+    quote! {
+        #[allow(non_upper_case_globals)]
+        #[unsafe(no_mangle)]
+        pub static #symbol: #prelude::ThinPluginDescriptor = #prelude::ThinPluginDescriptor {
+            trait_name: #trait_name.as_ptr().cast(),
+            type_name: #type_name.as_ptr().cast(),
+            abi_hash: #private::trait_abi_hash::<dyn #trait_path>(),
+            version: ::core::concat!(::core::env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast(),
+            constructor: {
+                extern "C-unwind" fn construct() -> *mut () {
+                    let value: #ty = #constructor;
+                    #private::__into_raw(<#prelude::Thin<dyn #trait_path> as #prelude::ThinExt<dyn #trait_path, #ty>>::new(value))
+                }
+                construct
+            },
+        };
+    }.into()
+}
+
+//=================//
+
+/// Represents the input of the `thin_vtable` macro: a struct of slots, with the object type it's the
+/// vtable of after `for`, as in `pub struct FooVTable<T> for dyn Foo<T> where ... { slots }`.
+struct ManualVTable {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    name: Ident,
+    generics: Generics,
+    object_ty: Type,
+    slots: FieldsNamed,
+}
+impl Parse for ManualVTable {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse::<Visibility>()?;
+        input.parse::<Token![struct]>()?;
+        let name = input.parse::<Ident>()?;
+        let mut generics = input.parse::<Generics>()?;
+        input.parse::<Token![for]>()?;
+        let object_ty = input.parse::<Type>()?;
+        generics.where_clause = input.parse::<Option<WhereClause>>()?;
+        let slots = input.parse::<FieldsNamed>()?;
+        if let Some(field) = slots.named.iter().find(|field| field.ident.as_ref().is_some_and(|ident| ident == "header")) {
+            return Err(syn::Error::new_spanned(field, "`header` is the vtable's leading `RawVTableHeader`, and can't be a slot"));
+        }
+        Ok(ManualVTable { attrs, vis, name, generics, object_ty, slots })
+    }
+}
+
+#[proc_macro]
+pub fn thin_vtable(input: TokenStream) -> TokenStream {
+    let ManualVTable { attrs, vis, name, generics, object_ty, slots } = parse_macro_input!(input as ManualVTable);
+    let prelude = prelude_path();
+    let private = private_path();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let slot_fields = slots.named.iter();
+    let slot_names = slots.named.iter().map(|field| field.ident.as_ref().unwrap().to_string()).collect::<Vec<_>>();
+    let name_str = name.to_string();
+
+    quote! {
+        #(#attrs)*
+        #[repr(C)]
+        #vis struct #name #generics #where_clause {
+            #vis header: #private::RawVTableHeader,
+            #(#slot_fields,)*
+        }
+
+        impl #impl_generics #prelude::SpecialAssoc for #object_ty #where_clause {
+            type Kind = #prelude::Own;
+        }
+
+        impl #impl_generics #prelude::SlotLayout for #object_ty #where_clause {
+            const SLOTS: &'static [&'static str] = match #private::VTABLE_CHECKSUM {
+                true => &["drop", "size", "checksum", #(#slot_names),*],
+                false => &["drop", "size", #(#slot_names),*],
+            };
+            const SIZE_SLOT: ::core::option::Option<usize> = ::core::option::Option::Some(1);
+            const CHECKSUM_SLOT: ::core::option::Option<usize> = match #private::VTABLE_CHECKSUM {
+                true => ::core::option::Option::Some(2),
+                false => ::core::option::Option::None,
+            };
+            type VTable = #name #ty_generics;
+        }
+
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The header of vtables for thin objects created from `K`s by `new_thin`.
+            #vis const fn header<__K>() -> #private::RawVTableHeader {
+                #private::RawVTableHeader::for_bundle::<Self, __K>()
+            }
+
+            /// Creates a thin object from `value`, pointing to this vtable.
+            ///
+            /// # Safety
+            /// The header must be `header::<K>()`, and the slots must expect the receiver to point to
+            /// a `RawBundle<Self, K>`.
+            #vis unsafe fn new_thin<__K>(&'static self, value: __K) -> #prelude::Thin<#object_ty> {
+                // SAFETY: the struct starts with its header
+                let vtable = unsafe { #private::RawVTableHeader::seal(self) };
+                let ptr = #private::alloc_bundle(#private::RawBundle { vtable, value });
+                unsafe { #prelude::Thin::from_raw(ptr) }
+            }
+
+            /// The vtable of `thin`, which panics if it's shorter than `Self`.
+            #vis fn of(thin: &#prelude::Thin<#object_ty>) -> &'static Self {
+                // SAFETY: the object type's vtables are all `Self`s, unless they're shorter
+                unsafe { #private::RawVTableHeader::load::<Self>(thin.ptr.as_ptr(), #name_str) }
+            }
+        }
+    }.into()
+}
+
+#[cfg(test)]
+mod fuzz;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Compares the expansion of `#[thin(#attr)]` on `item` with `snapshots/{name}.rs`, or overwrites
+    /// the snapshot if `UPDATE_SNAPSHOTS` is set.
+    fn assert_expansion(name: &str, attr: TokenStream2, item: TokenStream2) {
+        let expanded = expand_thin(attr, item).unwrap();
+        let actual = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots").join(format!("{}.rs", name));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_default();
+        if actual != expected {
+            let line = actual.lines().zip(expected.lines()).position(|(a, e)| a != e);
+            panic!(
+                "the expansion of `{}` differs from {} (first at line {}); rerun with UPDATE_SNAPSHOTS=1 if that's intended",
+                name, path.display(), line.map_or(actual.lines().count().min(expected.lines().count()), |line| line) + 1,
+            );
+        }
+    }
+
+    #[test]
+    fn basic() {
+        assert_expansion("basic", quote!(), quote! {
+            pub trait Counter: 'static {
+                fn get(&self) -> u32;
+                fn add(&mut self, n: u32, wrap: bool);
+            }
+        });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn lifetimes() {
+        assert_expansion("lifetimes", quote!(), quote! {
+            trait Lookup: Send + 'static {
+                fn first(&self) -> &u32;
+                fn pick<'a>(&self, a: &'a u32, b: &'a u32) -> &'a u32;
+                fn nearest(&self, a: &u32, b: &u32) -> &u32;
+                fn fail(&self) -> !;
+            }
+        });
+    }
+
+    #[test]
+    fn typed_receivers() {
+        assert_expansion("typed_receivers", quote!(), quote! {
+            trait Typed: 'static {
+                fn len(self: &Self) -> usize;
+                fn last<'a>(self: &'a mut Self) -> &'a mut u32;
+            }
+        });
+    }
+
+    #[test]
+    fn options() {
+        assert_expansion("any_hot", quote!(any), quote! {
+            trait Voice: 'static {
+                fn note_on(&mut self, note: u8);
+                #[thin(hot)]
+                fn sample(&mut self) -> f32;
+            }
+        });
+        assert_expansion("extension_any", quote!(extension, any), quote! {
+            trait Widget: 'static {
+                fn draw(&self) -> u32;
+            }
+        });
+        assert_expansion("instantiate", quote!(), quote! {
+            trait Sink: 'static {
+                #[thin(instantiate(T = u8, Vec<u8>))]
+                fn send<T>(&self, value: &T) -> usize;
+            }
+        });
+        assert_expansion("skip", quote!(), quote! {
+            trait Source: 'static {
+                fn next(&mut self) -> u32;
+                #[thin(skip)]
+                fn take<C: FromIterator<u32>>(&mut self, n: usize) -> C {
+                    (0..n).map(|_| self.next()).collect()
+                }
+                #[thin(skip)]
+                fn boxed(self) -> Box<dyn Source>;
+                #[thin(skip)]
+                fn merge(&mut self, other: Self);
+            }
+        });
+        assert_expansion("erased_return", quote!(dual_abi), quote! {
+            trait Chunks: 'static {
+                fn chunks(&self, size: usize) -> impl Iterator<Item = u8>;
+            }
+        });
+        assert_expansion("fn_impl", quote!(fn_impl), quote! {
+            trait Handler: Send + 'static {
+                fn handle(&mut self, event: u32) -> u64;
+            }
+        });
+        assert_expansion("c_free", quote!(c_free), quote! {
+            trait AudioSink: 'static {
+                fn write(&mut self, sample: f32);
+            }
+        });
+        assert_expansion("stable_any", quote!(stable_any), quote! {
+            trait Codec<const V: u8>: Send + 'static {
+                fn decode(&self, byte: u8) -> u8;
+            }
+        });
+        assert_expansion("const_generics", quote!(any), quote! {
+            trait Buffered<const N: usize>: 'static {
+                fn fill(&mut self, byte: u8);
+            }
+        });
+        assert_expansion("rust_only", quote!(rust_only), quote! {
+            trait Named: 'static {
+                fn name(&self) -> String;
+                fn set(&mut self, name: &str);
+            }
+        });
+        assert_expansion("bridges", quote!(), quote! {
+            trait Failure: ::std::error::Error + Send + 'static {
+                fn code(&self) -> u32;
+            }
+        });
+        assert_expansion("no_impl_dual_abi", quote!(no_impl, dual_abi), quote! {
+            trait Exported: 'static {
+                fn double(&self, x: u32) -> u32;
+            }
+        });
+    }
+
+    #[test]
+    fn errors() {
+        let err = expand_thin(quote!(bogus), quote!(trait Foo: 'static {})).unwrap_err();
+        assert_eq!(err.to_string(), "expected `no_impl`, `any`, `fn_impl`, `dual_abi`, `extension`, `c_free`, `stable_any` or `rust_only`");
+        assert!(expand_thin(quote!(), quote!(struct Foo;)).is_err());
+    }
+
+    #[test]
+    fn unsafe_blocks() {
+        use proc_macro2::{Delimiter, TokenTree};
+
+        // every `unsafe` block calls one of the crate's unsafe helpers, with nothing of the caller's
+        // in its tokens but for argument names
+        fn count_unsafe(tokens: TokenStream2, c_free: bool) -> usize {
+            let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+            let mut count = 0;
+            for (i, token) in tokens.iter().enumerate() {
+                match token {
+                    TokenTree::Ident(ident) if ident == "unsafe" => {
+                        count += 1;
+                        if let Some(TokenTree::Group(block)) = tokens.get(i + 1)
+                            && block.delimiter() == Delimiter::Brace
+                        {
+                            let block = block.stream().to_string();
+                            assert!(
+                                block.starts_with(":: thin_trait_objects :: __private :: expanded_")
+                                    || block.starts_with(":: thin_trait_objects :: __private :: ExpansionToken :: new")
+                                    || block.starts_with(":: thin_trait_objects :: prelude :: ThinMut :: as_thin_mut (self)")
+                                    || c_free && block.contains(":: from_c_void (ptr)"),
+                                "{block}"
+                            );
+                        }
+                    }
+                    TokenTree::Group(group) => count += count_unsafe(group.stream(), c_free),
+                    _ => {}
+                }
+            }
+            count
+        }
+
+        // the expansion compiles under `#![forbid(unsafe_code)]`, but for `c_free`'s exported destructor,
+        // whose `unsafe` is the caller's
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let tokens: TokenStream2 = std::fs::read_to_string(&path).unwrap().parse().unwrap();
+            let c_free = path.file_stem().unwrap() == "c_free";
+            assert!(count_unsafe(tokens, c_free) > 0, "{}", path.display());
+        }
+    }
+
+}